@@ -77,6 +77,7 @@ fn main() {
     }
 
     let mut out_symbols = HashMap::new();
+    let mut weak_externals = Vec::new();
     for in_symbol in in_object.symbols() {
         if in_symbol.kind() == SymbolKind::Null {
             continue;
@@ -115,6 +116,14 @@ fn main() {
                     associative_section,
                 }
             }
+            SymbolFlags::CoffWeakExternal {
+                default_symbol,
+                characteristics,
+            } => {
+                // The default symbol may not have been added yet.
+                weak_externals.push((in_symbol.index(), default_symbol, characteristics));
+                SymbolFlags::None
+            }
             _ => panic!("unknown symbol flags for {:?}", in_symbol),
         };
         let out_symbol = write::Symbol {
@@ -130,6 +139,13 @@ fn main() {
         let symbol_id = out_object.add_symbol(out_symbol);
         out_symbols.insert(in_symbol.index(), symbol_id);
     }
+    for (index, default_symbol, characteristics) in weak_externals {
+        let symbol_id = *out_symbols.get(&index).unwrap();
+        out_object.symbol_mut(symbol_id).flags = SymbolFlags::CoffWeakExternal {
+            default_symbol: *out_symbols.get(&default_symbol).unwrap(),
+            characteristics,
+        };
+    }
 
     for in_section in in_object.sections() {
         if in_section.kind() == SectionKind::Metadata {
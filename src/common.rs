@@ -56,6 +56,45 @@ impl Architecture {
     }
 }
 
+/// A CPU sub-architecture or ABI variant.
+///
+/// This refines an [`Architecture`], and is used to select the correct
+/// instruction set or calling convention for a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SubArchitecture {
+    /// AArch64 with pointer authentication (Mach-O `arm64e`).
+    Arm64E,
+    /// AArch64 code that is compatible with x86-64 emulation (PE `ARM64EC`).
+    Arm64EC,
+    /// ARM code using the Thumb-2 instruction set (PE `ARMNT`).
+    ArmThumb,
+    /// ARM code conforming to the given version of the ARM EABI (ELF `EF_ARM_EABIMASK`).
+    ArmEabi(u8),
+    /// MIPS o32 ABI.
+    MipsO32,
+    /// MIPS o64 ABI.
+    MipsO64,
+    /// MIPS n32 ABI.
+    MipsN32,
+    /// MIPS n64 ABI.
+    MipsN64,
+    /// MIPS 32-bit embedded ABI.
+    MipsEabi32,
+    /// MIPS 64-bit embedded ABI.
+    MipsEabi64,
+    /// RISC-V soft float ABI.
+    RiscvSoftFloat,
+    /// RISC-V single precision hard float ABI.
+    RiscvSingleFloat,
+    /// RISC-V double precision hard float ABI.
+    RiscvDoubleFloat,
+    /// RISC-V quad precision hard float ABI.
+    RiscvQuadFloat,
+    /// x86-64 for Haswell and later processors (Mach-O `x86_64h`).
+    X86_64H,
+}
+
 /// The size of an address value for an architecture.
 ///
 /// This may differ from the address size supported by the file format (such as for COFF).
@@ -425,7 +464,7 @@ pub enum SectionFlags {
 /// Symbol flags that are specific to each file format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
-pub enum SymbolFlags<Section> {
+pub enum SymbolFlags<Section, Symbol> {
     /// No symbol flags.
     None,
     /// ELF symbol flags.
@@ -447,4 +486,15 @@ pub enum SymbolFlags<Section> {
         /// `Number` field in the auxiliary symbol for the section.
         associative_section: Option<Section>,
     },
+    /// COFF flags for a weak external symbol.
+    CoffWeakExternal {
+        /// The symbol that is used if the weak external is not resolved.
+        ///
+        /// This is the `TagIndex` field in the auxiliary symbol.
+        default_symbol: Symbol,
+        /// `Characteristics` field in the auxiliary symbol.
+        ///
+        /// This is one of the `IMAGE_WEAK_EXTERN_SEARCH_*` constants.
+        characteristics: u32,
+    },
 }
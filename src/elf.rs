@@ -707,6 +707,8 @@ pub const SHT_PREINIT_ARRAY: u32 = 16;
 pub const SHT_GROUP: u32 = 17;
 /// Extended section indices for a symbol table.
 pub const SHT_SYMTAB_SHNDX: u32 = 18;
+/// Relative relocation entries.
+pub const SHT_RELR: u32 = 19;
 /// Start of OS-specific section types.
 pub const SHT_LOOS: u32 = 0x6000_0000;
 /// Object attributes.
@@ -1893,6 +1895,26 @@ pub const NT_GNU_GOLD_VERSION: u32 = 4;
 /// Program property.
 pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
 
+/// FDO entries in the note section have this name.
+pub static ELF_NOTE_FDO: &[u8] = b"FDO";
+
+// Note types for `ELF_NOTE_FDO`.
+
+/// Package metadata.
+///
+/// The descriptor is a '\0'-terminated JSON string.
+pub const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe_1a7e;
+
+/// Go entries in the note section have this name.
+pub static ELF_NOTE_GO: &[u8] = b"Go";
+
+// Note types for `ELF_NOTE_GO`.
+
+/// Go build ID.
+///
+/// The descriptor is the build ID string, which is not null-terminated.
+pub const NT_GO_BUILD_ID: u32 = 4;
+
 // TODO: GNU_PROPERTY_*
 // TODO: Elf*_Move
 
@@ -2547,7 +2569,54 @@ pub const STB_MIPS_SPLIT_COMMON: u8 = 13;
 
 // Entries found in sections of type `SHT_MIPS_GPTAB`.
 
-// TODO: Elf32_gptab, Elf32_RegInfo, Elf_Options
+// TODO: Elf32_gptab
+
+/// Register usage information for 32-bit MIPS files.
+///
+/// This is the content of sections of type `SHT_MIPS_REGINFO`, and of
+/// `ODK_REGINFO` entries in sections of type `SHT_MIPS_OPTIONS`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Elf32RegInfo<E: Endian> {
+    /// General registers used.
+    pub ri_gprmask: U32<E>,
+    /// Coprocessor registers used.
+    pub ri_cprmask: [U32<E>; 4],
+    /// `$gp` register value.
+    pub ri_gp_value: I32<E>,
+}
+
+/// Register usage information for 64-bit MIPS files.
+///
+/// This is the content of `ODK_REGINFO` entries in sections of type `SHT_MIPS_OPTIONS`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Elf64RegInfo<E: Endian> {
+    /// General registers used.
+    pub ri_gprmask: U32<E>,
+    #[allow(missing_docs)]
+    pub ri_pad: U32<E>,
+    /// Coprocessor registers used.
+    pub ri_cprmask: [U32<E>; 4],
+    /// `$gp` register value.
+    pub ri_gp_value: I64<E>,
+}
+
+/// The header of an entry in sections of type `SHT_MIPS_OPTIONS`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ElfOptions<E: Endian> {
+    /// Determines interpretation of variable part of descriptor.
+    ///
+    /// One of the `ODK_*` constants.
+    pub kind: u8,
+    /// Byte size of descriptor, including this header.
+    pub size: u8,
+    /// Section header index of section affected, or 0 for the global options.
+    pub section: U16<E>,
+    /// Kind-specific information.
+    pub info: U32<E>,
+}
 
 // Values for `Elf_Options::kind`.
 
@@ -6401,4 +6470,7 @@ unsafe_impl_endian_pod!(
     NoteHeader64,
     HashHeader,
     GnuHashHeader,
+    Elf32RegInfo,
+    Elf64RegInfo,
+    ElfOptions,
 );
@@ -110,3 +110,5 @@ pub mod elf;
 pub mod macho;
 #[cfg(any(feature = "coff", feature = "pe"))]
 pub mod pe;
+#[cfg(feature = "wasm")]
+pub mod wasm;
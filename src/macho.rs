@@ -7,7 +7,7 @@
 
 #![allow(missing_docs)]
 
-use crate::endian::{BigEndian, Endian, U64Bytes, U16, U32, U64};
+use crate::endian::{BigEndian, Endian, U16Bytes, U32Bytes, U64Bytes, U16, U32, U64};
 use crate::pod::Pod;
 
 // Definitions from "/usr/include/mach/machine.h".
@@ -2174,6 +2174,100 @@ pub const EXPORT_SYMBOL_FLAGS_WEAK_DEFINITION: u32 = 0x04;
 pub const EXPORT_SYMBOL_FLAGS_REEXPORT: u32 = 0x08;
 pub const EXPORT_SYMBOL_FLAGS_STUB_AND_RESOLVER: u32 = 0x10;
 
+/*
+ * The LC_DYLD_CHAINED_FIXUPS load command uses a LinkeditDataCommand
+ * to point to a DyldChainedFixupsHeader.
+ */
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedFixupsHeader<E: Endian> {
+    /// 0
+    pub fixups_version: U32<E>,
+    /// offset of DyldChainedStartsInImage in chain_data
+    pub starts_offset: U32<E>,
+    /// offset of imports table in chain_data
+    pub imports_offset: U32<E>,
+    /// offset of symbol strings in chain_data
+    pub symbols_offset: U32<E>,
+    /// number of imported symbol names
+    pub imports_count: U32<E>,
+    /// DYLD_CHAINED_IMPORT*
+    pub imports_format: U32<E>,
+    /// 0 => uncompressed, 1 => zlib compressed
+    pub symbols_format: U32<E>,
+}
+
+/*
+ * This struct is embedded in LC_DYLD_CHAINED_FIXUPS payload
+ */
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedStartsInImage<E: Endian> {
+    pub seg_count: U32<E>,
+    /* seg_info_offset: [U32<E>; seg_count], each entry is offset into this struct for that segment
+    followed by pool of DyldChainedStartsInSegment data */
+}
+
+/*
+ * This struct is embedded in DyldChainedStartsInImage
+ * and passed down to the kernel for page-in linking.
+ *
+ * The fields are unaligned, so this uses the `*Bytes` types.
+ */
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct DyldChainedStartsInSegment<E: Endian> {
+    /// size of this (amount kernel needs to copy)
+    pub size: U32Bytes<E>,
+    /// 0x1000 or 0x4000
+    pub page_size: U16Bytes<E>,
+    /// DYLD_CHAINED_PTR_*
+    pub pointer_format: U16Bytes<E>,
+    /// offset in memory to start of segment
+    pub segment_offset: U64Bytes<E>,
+    /// for 32-bit OS, any value beyond this is not a pointer
+    pub max_valid_pointer: U32Bytes<E>,
+    /// how many pages are in array
+    pub page_count: U16Bytes<E>,
+    /* page_start: [U16<E>; page_count], each entry is offset in each page of first element in chain
+    or DYLD_CHAINED_PTR_START_NONE if no fixups on page */
+}
+
+// values for DyldChainedStartsInSegment.page_start
+/// used in page_start[] to denote a page with no fixups
+pub const DYLD_CHAINED_PTR_START_NONE: u16 = 0xFFFF;
+/// used in page_start[] to denote a page which has multiple starts
+pub const DYLD_CHAINED_PTR_START_MULTI: u16 = 0x8000;
+/// used in chain_starts[] to denote last start in list for page
+pub const DYLD_CHAINED_PTR_START_LAST: u16 = 0x8000;
+
+// values for DyldChainedStartsInSegment.pointer_format
+/// stride 8, unauth target is vmaddr
+pub const DYLD_CHAINED_PTR_ARM64E: u16 = 1;
+/// target is vmaddr
+pub const DYLD_CHAINED_PTR_64: u16 = 2;
+pub const DYLD_CHAINED_PTR_32: u16 = 3;
+pub const DYLD_CHAINED_PTR_32_CACHE: u16 = 4;
+pub const DYLD_CHAINED_PTR_32_FIRMWARE: u16 = 5;
+/// target is vm offset
+pub const DYLD_CHAINED_PTR_64_OFFSET: u16 = 6;
+/// stride 4, unauth target is vm offset
+pub const DYLD_CHAINED_PTR_ARM64E_KERNEL: u16 = 7;
+pub const DYLD_CHAINED_PTR_64_KERNEL_CACHE: u16 = 8;
+/// stride 8, unauth target is vm offset
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND: u16 = 9;
+/// stride 4, unauth target is vmaddr
+pub const DYLD_CHAINED_PTR_ARM64E_FIRMWARE: u16 = 10;
+/// stride 1, x86_64 kernel caches
+pub const DYLD_CHAINED_PTR_X86_64_KERNEL_CACHE: u16 = 11;
+/// stride 8, unauth target is vm offset, 24-bit bind
+pub const DYLD_CHAINED_PTR_ARM64E_USERLAND24: u16 = 12;
+
+// values for DyldChainedFixupsHeader.imports_format
+pub const DYLD_CHAINED_IMPORT: u32 = 1;
+pub const DYLD_CHAINED_IMPORT_ADDEND: u32 = 2;
+pub const DYLD_CHAINED_IMPORT_ADDEND64: u32 = 3;
+
 /*
  * The LinkerOptionCommand contains linker options embedded in object files.
  */
@@ -3246,6 +3340,101 @@ pub const X86_64_RELOC_SIGNED_4: u8 = 8;
 /// for thread local variables
 pub const X86_64_RELOC_TLV: u8 = 9;
 
+// Definitions from "/usr/include/mach-o/compact_unwind_encoding.h".
+
+// Masks for the compact unwind encoding.
+pub const UNWIND_IS_NOT_FUNCTION_START: u32 = 0x8000_0000;
+pub const UNWIND_HAS_LSDA: u32 = 0x4000_0000;
+pub const UNWIND_PERSONALITY_MASK: u32 = 0x3000_0000;
+
+/*
+ * The `__TEXT,__unwind_info` section starts with an UnwindInfoSectionHeader.
+ *
+ * The function offsets in the section are relative to the start of the image.
+ */
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct UnwindInfoSectionHeader<E: Endian> {
+    /// UNWIND_SECTION_VERSION
+    pub version: U32<E>,
+    pub common_encodings_array_section_offset: U32<E>,
+    pub common_encodings_array_count: U32<E>,
+    pub personality_array_section_offset: U32<E>,
+    pub personality_array_count: U32<E>,
+    pub index_section_offset: U32<E>,
+    pub index_count: U32<E>,
+    /* compact_unwind_encoding_t[]
+     * uint32_t personalities[]
+     * unwind_info_section_header_index_entry[]
+     * unwind_info_section_header_lsda_index_entry[] */
+}
+
+pub const UNWIND_SECTION_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct UnwindInfoSectionHeaderIndexEntry<E: Endian> {
+    pub function_offset: U32<E>,
+    /// section offset to start of regular or compress page
+    pub second_level_pages_section_offset: U32<E>,
+    /// section offset to start of lsda_index array for this range
+    pub lsda_index_array_section_offset: U32<E>,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct UnwindInfoSectionHeaderLsdaIndexEntry<E: Endian> {
+    pub function_offset: U32<E>,
+    pub lsda_offset: U32<E>,
+}
+
+/*
+ * A regular second level page is a list of function offsets and encodings.
+ */
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct UnwindInfoRegularSecondLevelEntry<E: Endian> {
+    pub function_offset: U32<E>,
+    pub encoding: U32<E>,
+}
+
+pub const UNWIND_SECOND_LEVEL_REGULAR: u32 = 2;
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct UnwindInfoRegularSecondLevelPageHeader<E: Endian> {
+    /// UNWIND_SECOND_LEVEL_REGULAR
+    pub kind: U32<E>,
+    pub entry_page_offset: U16<E>,
+    pub entry_count: U16<E>,
+    // entry array follows
+}
+
+/*
+ * A compressed second level page has 32-bit entries that contain a 24-bit
+ * function offset relative to the first level index entry, and an 8-bit index
+ * into the common encodings array followed by the encodings array of the page.
+ */
+pub const UNWIND_SECOND_LEVEL_COMPRESSED: u32 = 3;
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct UnwindInfoCompressedSecondLevelPageHeader<E: Endian> {
+    /// UNWIND_SECOND_LEVEL_COMPRESSED
+    pub kind: U32<E>,
+    pub entry_page_offset: U16<E>,
+    pub entry_count: U16<E>,
+    pub encodings_page_offset: U16<E>,
+    pub encodings_count: U16<E>,
+    // 32-bit entry array
+    // encodings array
+}
+
+/// Mask for the function offset in a compressed second level page entry.
+pub const UNWIND_INFO_COMPRESSED_ENTRY_FUNC_OFFSET_MASK: u32 = 0x00FF_FFFF;
+/// Shift for the encoding index in a compressed second level page entry.
+pub const UNWIND_INFO_COMPRESSED_ENTRY_ENCODING_INDEX_SHIFT: u32 = 24;
+
 unsafe_impl_pod!(FatHeader, FatArch32, FatArch64,);
 unsafe_impl_endian_pod!(
     DyldCacheHeader,
@@ -3292,6 +3481,9 @@ unsafe_impl_endian_pod!(
     BuildVersionCommand,
     BuildToolVersion,
     DyldInfoCommand,
+    DyldChainedFixupsHeader,
+    DyldChainedStartsInImage,
+    DyldChainedStartsInSegment,
     LinkerOptionCommand,
     SymsegCommand,
     IdentCommand,
@@ -3304,4 +3496,10 @@ unsafe_impl_endian_pod!(
     Nlist32,
     Nlist64,
     Relocation,
+    UnwindInfoSectionHeader,
+    UnwindInfoSectionHeaderIndexEntry,
+    UnwindInfoSectionHeaderLsdaIndexEntry,
+    UnwindInfoRegularSecondLevelEntry,
+    UnwindInfoRegularSecondLevelPageHeader,
+    UnwindInfoCompressedSecondLevelPageHeader,
 );
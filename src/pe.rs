@@ -357,6 +357,8 @@ pub const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
 pub const IMAGE_FILE_MACHINE_M32R: u16 = 0x9041;
 /// ARM64 Little-Endian
 pub const IMAGE_FILE_MACHINE_ARM64: u16 = 0xAA64;
+/// ARM64EC ("Emulation Compatible")
+pub const IMAGE_FILE_MACHINE_ARM64EC: u16 = 0xA641;
 pub const IMAGE_FILE_MACHINE_CEE: u16 = 0xC0EE;
 /// RISCV32
 pub const IMAGE_FILE_MACHINE_RISCV32: u16 = 0x5032;
@@ -1977,6 +1979,21 @@ pub struct ImageDelayloadDescriptor {
     pub time_date_stamp: U32<LE>,
 }
 
+impl ImageDelayloadDescriptor {
+    /// Tell whether this delay-load import descriptor is the null descriptor
+    /// (used to mark the end of the iterator array in a PE)
+    pub fn is_null(&self) -> bool {
+        self.attributes.get(LE) == 0
+            && self.dll_name_rva.get(LE) == 0
+            && self.module_handle_rva.get(LE) == 0
+            && self.import_address_table_rva.get(LE) == 0
+            && self.import_name_table_rva.get(LE) == 0
+            && self.bound_import_address_table_rva.get(LE) == 0
+            && self.unload_information_table_rva.get(LE) == 0
+            && self.time_date_stamp.get(LE) == 0
+    }
+}
+
 /// Delay load version 2 flag for `ImageDelayloadDescriptor::attributes`.
 pub const IMAGE_DELAYLOAD_RVA_BASED: u32 = 0x8000_0000;
 
@@ -2120,6 +2137,37 @@ pub const RT_HTML: u16 = 23;
 /// ID for: Side-by-Side Assembly Manifest.
 pub const RT_MANIFEST: u16 = 24;
 
+//
+// Version information: https://docs.microsoft.com/en-us/windows/win32/menurc/vs-versioninfo
+//
+
+/// The value of `VsFixedFileInfo::signature`.
+pub const VS_FFI_SIGNATURE: u32 = 0xFEEF_04BD;
+
+/// The fixed part of the version information in a `RT_VERSION` resource.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct VsFixedFileInfo {
+    /// `VS_FFI_SIGNATURE`
+    pub signature: U32<LE>,
+    pub struc_version: U32<LE>,
+    /// The most significant 32 bits of the file version.
+    pub file_version_ms: U32<LE>,
+    /// The least significant 32 bits of the file version.
+    pub file_version_ls: U32<LE>,
+    /// The most significant 32 bits of the product version.
+    pub product_version_ms: U32<LE>,
+    /// The least significant 32 bits of the product version.
+    pub product_version_ls: U32<LE>,
+    pub file_flags_mask: U32<LE>,
+    pub file_flags: U32<LE>,
+    pub file_os: U32<LE>,
+    pub file_type: U32<LE>,
+    pub file_subtype: U32<LE>,
+    pub file_date_ms: U32<LE>,
+    pub file_date_ls: U32<LE>,
+}
+
 //
 // Code Integrity in loadconfig (CI)
 //
@@ -2382,6 +2430,75 @@ pub struct ImageLoadConfigDirectory64 {
     pub volatile_metadata_pointer: U64<LE>,
 }
 
+//
+// Hybrid (CHPE) metadata referenced by the load configuration directory.
+//
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ImageChpeMetadataX86 {
+    pub version: U32<LE>,
+    /// RVA of an array of `ImageChpeRangeEntry`
+    pub chpe_code_address_range_offset: U32<LE>,
+    pub chpe_code_address_range_count: U32<LE>,
+    pub wow_a64_exception_handler_function_pointer: U32<LE>,
+    pub wow_a64_dispatch_call_function_pointer: U32<LE>,
+    pub wow_a64_dispatch_indirect_call_function_pointer: U32<LE>,
+    pub wow_a64_dispatch_indirect_call_cfg_function_pointer: U32<LE>,
+    pub wow_a64_dispatch_ret_function_pointer: U32<LE>,
+    pub wow_a64_dispatch_ret_leaf_function_pointer: U32<LE>,
+    pub wow_a64_dispatch_jump_function_pointer: U32<LE>,
+    /// Version 2 and later
+    pub compiler_iat_pointer: U32<LE>,
+    /// Version 3 and later
+    pub wow_a64_rdtsc_function_pointer: U32<LE>,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ImageArm64EcMetadata {
+    pub version: U32<LE>,
+    /// RVA of an array of `ImageChpeRangeEntry`
+    pub code_map: U32<LE>,
+    pub code_map_count: U32<LE>,
+    pub code_ranges_to_entry_points: U32<LE>,
+    pub redirection_metadata: U32<LE>,
+    pub os_arm64x_dispatch_call_no_redirect: U32<LE>,
+    pub os_arm64x_dispatch_ret: U32<LE>,
+    pub os_arm64x_dispatch_call: U32<LE>,
+    pub os_arm64x_dispatch_icall: U32<LE>,
+    pub os_arm64x_dispatch_icall_cfg: U32<LE>,
+    pub alternate_entry_point: U32<LE>,
+    pub auxiliary_iat: U32<LE>,
+    pub code_ranges_to_entry_points_count: U32<LE>,
+    pub redirection_metadata_count: U32<LE>,
+    pub get_x64_information_function_pointer: U32<LE>,
+    pub set_x64_information_function_pointer: U32<LE>,
+    pub extra_rfe_table: U32<LE>,
+    pub extra_rfe_table_size: U32<LE>,
+    pub os_arm64x_dispatch_fptr: U32<LE>,
+    pub auxiliary_iat_copy: U32<LE>,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct ImageChpeRangeEntry {
+    /// RVA of the start of the range. The low bits contain the code type.
+    pub start_offset: U32<LE>,
+    pub length: U32<LE>,
+}
+
+/// Mask for the code type in `ImageChpeRangeEntry::start_offset` for ARM64EC.
+pub const IMAGE_CHPE_RANGE_ENTRY_TYPE_MASK: u32 = 0x3;
+/// ARM64 code in an ARM64EC image.
+pub const IMAGE_CHPE_RANGE_ENTRY_TYPE_ARM64: u32 = 0x0;
+/// ARM64EC code in an ARM64EC image.
+pub const IMAGE_CHPE_RANGE_ENTRY_TYPE_ARM64EC: u32 = 0x1;
+/// x64 code in an ARM64EC image.
+pub const IMAGE_CHPE_RANGE_ENTRY_TYPE_AMD64: u32 = 0x2;
+/// Native ARM64 code in a CHPE x86 image. Otherwise the code is x86.
+pub const IMAGE_CHPE_RANGE_ENTRY_NATIVE_CODE: u32 = 0x1;
+
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
 pub struct ImageHotPatchInfo {
@@ -2645,6 +2762,25 @@ pub const IMAGE_DEBUG_TYPE_POGO: u32 = 13;
 pub const IMAGE_DEBUG_TYPE_ILTCG: u32 = 14;
 pub const IMAGE_DEBUG_TYPE_MPX: u32 = 15;
 pub const IMAGE_DEBUG_TYPE_REPRO: u32 = 16;
+pub const IMAGE_DEBUG_TYPE_SPGO: u32 = 18;
+pub const IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS: u32 = 20;
+
+// Signatures of `IMAGE_DEBUG_TYPE_POGO` data.
+/// Link time code generation ("LTCG").
+pub const IMAGE_POGO_SIGNATURE_LTCG: u32 = 0x4c54_4347;
+/// Profile guided update ("PGU").
+pub const IMAGE_POGO_SIGNATURE_PGU: u32 = 0x5047_5500;
+
+// Flags in `IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS` data.
+/// Image is CET compatible.
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT: u32 = 0x01;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT_STRICT_MODE: u32 = 0x02;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_SET_CONTEXT_IP_VALIDATION_RELAXED_MODE: u32 = 0x04;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_DYNAMIC_APIS_ALLOW_IN_PROC: u32 = 0x08;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_RESERVED_1: u32 = 0x10;
+pub const IMAGE_DLLCHARACTERISTICS_EX_CET_RESERVED_2: u32 = 0x20;
+pub const IMAGE_DLLCHARACTERISTICS_EX_FORWARD_CFI_COMPAT: u32 = 0x40;
+pub const IMAGE_DLLCHARACTERISTICS_EX_HOTPATCH_COMPATIBLE: u32 = 0x80;
 
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
@@ -2861,6 +2997,12 @@ pub struct ImportObjectHeader {
 pub const IMPORT_OBJECT_CODE: u16 = 0;
 pub const IMPORT_OBJECT_DATA: u16 = 1;
 pub const IMPORT_OBJECT_CONST: u16 = 2;
+/// Mask for the import type in `ImportObjectHeader::name_type`.
+pub const IMPORT_OBJECT_TYPE_MASK: u16 = 0b11;
+/// Shift for the import name type in `ImportObjectHeader::name_type`.
+pub const IMPORT_OBJECT_NAME_SHIFT: u16 = 2;
+/// Mask for the import name type in `ImportObjectHeader::name_type`, after shifting.
+pub const IMPORT_OBJECT_NAME_MASK: u16 = 0b111;
 
 /// Import by ordinal
 pub const IMPORT_OBJECT_ORDINAL: u16 = 0;
@@ -2994,6 +3136,7 @@ unsafe_impl_pod!(
     ImageResourceDirectoryString,
     ImageResourceDirStringU,
     ImageResourceDataEntry,
+    VsFixedFileInfo,
     ImageLoadConfigCodeIntegrity,
     ImageDynamicRelocationTable,
     ImageDynamicRelocation32,
@@ -3007,6 +3150,9 @@ unsafe_impl_pod!(
     //ImageSwitchtableBranchDynamicRelocation,
     ImageLoadConfigDirectory32,
     ImageLoadConfigDirectory64,
+    ImageChpeMetadataX86,
+    ImageArm64EcMetadata,
+    ImageChpeRangeEntry,
     ImageHotPatchInfo,
     ImageHotPatchBase,
     ImageHotPatchHashes,
@@ -0,0 +1,132 @@
+//! Support for describing an address in terms of sections and symbols.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::read::{Object, ObjectSection, SectionIndex, SectionKind, SymbolMap, SymbolMapName};
+
+/// A description of an address, as returned by [`Object::describe_address`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AddressDescription<'data> {
+    /// The name of the section containing the address, and the offset of the
+    /// address within the section.
+    pub section: Option<(String, u64)>,
+    /// The name of the nearest symbol at or below the address, and the offset
+    /// of the address from the start of the symbol.
+    pub symbol: Option<(&'data str, u64)>,
+}
+
+/// A map from addresses to sections and symbols.
+///
+/// Returned by [`Object::address_map`].
+#[derive(Debug, Clone)]
+pub struct AddressMap<'data> {
+    sections: SectionRanges<String>,
+    symbols: SymbolMap<SymbolMapName<'data>>,
+}
+
+impl<'data> AddressMap<'data> {
+    pub(crate) fn new<'file, O>(file: &'file O) -> Self
+    where
+        'data: 'file,
+        O: Object<'data, 'file> + ?Sized,
+    {
+        AddressMap {
+            sections: SectionRanges::new(file, |section| section.name().unwrap_or("").to_string()),
+            symbols: file.symbol_map(),
+        }
+    }
+
+    /// Describe an address using the section that contains it and the nearest
+    /// symbol at or below it.
+    pub fn describe(&self, address: u64) -> AddressDescription<'data> {
+        AddressDescription {
+            section: self
+                .sections
+                .get(address)
+                .map(|(name, offset)| (name.clone(), offset)),
+            symbol: self
+                .symbols
+                .get(address)
+                .map(|symbol| (symbol.name(), address - symbol.address())),
+        }
+    }
+}
+
+/// The address ranges of the sections that are loaded into memory, sorted by address.
+///
+/// Debugging, metadata and string sections are ignored, as are sections with a size of 0.
+#[derive(Debug, Clone)]
+pub(crate) struct SectionRanges<T> {
+    // Sorted by address, then by reverse section index.
+    ranges: Vec<(u64, u64, T)>,
+}
+
+impl<T> SectionRanges<T> {
+    /// Collect the ranges of the sections in a file, along with a value for each section.
+    pub(crate) fn new<'data, 'file, O, F>(file: &'file O, mut f: F) -> Self
+    where
+        'data: 'file,
+        O: Object<'data, 'file> + ?Sized,
+        F: FnMut(&O::Section) -> T,
+    {
+        let mut ranges = Vec::new();
+        for section in file.sections() {
+            match section.kind() {
+                SectionKind::Unknown
+                | SectionKind::OtherString
+                | SectionKind::Debug
+                | SectionKind::Linker
+                | SectionKind::Metadata => continue,
+                _ => {}
+            }
+            if section.size() == 0 {
+                continue;
+            }
+            ranges.push((section.address(), section.size(), f(&section)));
+        }
+        // The sort is stable, so after the reverse the sections with the same
+        // address are in reverse index order.
+        ranges.reverse();
+        ranges.sort_by_key(|range| range.0);
+        SectionRanges { ranges }
+    }
+
+    /// Find the section containing the address, and the offset of the address within it.
+    ///
+    /// If multiple sections contain the address, such as in relocatable files,
+    /// then the section with the highest start address is used. If those sections
+    /// have the same start address, then the first of them is used.
+    pub(crate) fn get(&self, address: u64) -> Option<(&T, u64)> {
+        let end = match self.ranges.binary_search_by_key(&address, |range| range.0) {
+            // Include all of the sections that start at the address.
+            Ok(index) => {
+                index
+                    + self.ranges[index..]
+                        .iter()
+                        .take_while(|range| range.0 == address)
+                        .count()
+            }
+            Err(index) => index,
+        };
+        self.ranges[..end].iter().rev().find_map(|range| {
+            let offset = address - range.0;
+            if offset < range.1 {
+                Some((&range.2, offset))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl SectionRanges<SectionIndex> {
+    /// Collect the ranges of the sections in a file, along with their indices.
+    pub(crate) fn indices<'data, 'file, O>(file: &'file O) -> Self
+    where
+        'data: 'file,
+        O: Object<'data, 'file> + ?Sized,
+    {
+        SectionRanges::new(file, |section| section.index())
+    }
+}
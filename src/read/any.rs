@@ -1,6 +1,7 @@
 use alloc::fmt;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
+use core::result;
 
 #[cfg(feature = "coff")]
 use crate::read::coff;
@@ -14,10 +15,12 @@ use crate::read::pe;
 use crate::read::wasm;
 use crate::read::{
     self, Architecture, BinaryFormat, CodeView, ComdatKind, CompressedData, CompressedFileRange,
-    Error, Export, FileFlags, FileKind, Import, Object, ObjectComdat, ObjectKind, ObjectMap,
-    ObjectSection, ObjectSegment, ObjectSymbol, ObjectSymbolTable, ReadRef, Relocation, Result,
-    SectionFlags, SectionIndex, SectionKind, SegmentFlags, SymbolFlags, SymbolIndex, SymbolKind,
-    SymbolMap, SymbolMapName, SymbolScope, SymbolSection,
+    Error, Export, FileFlags, FileKind, Function, Import, LoadSegmentIterator, Object,
+    ObjectComdat, ObjectKind, ObjectMap, ObjectSection, ObjectSegment, ObjectSymbol,
+    ObjectSymbolTable, ParseError, ParseOptions, ParseStage, PointerFixupIterator, ReadError,
+    ReadRef, Relocation, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+    SubArchitecture, SymbolFlags, SymbolIndex, SymbolKind, SymbolMap, SymbolMapName, SymbolScope,
+    SymbolSection, TlsSymbol,
 };
 #[allow(unused_imports)]
 use crate::{AddressSize, Endian, Endianness};
@@ -172,6 +175,7 @@ macro_rules! next_inner {
 #[derive(Debug)]
 pub struct File<'data, R: ReadRef<'data> = &'data [u8]> {
     inner: FileInternal<'data, R>,
+    base_offset: u64,
 }
 
 #[derive(Debug)]
@@ -196,28 +200,94 @@ enum FileInternal<'data, R: ReadRef<'data>> {
 
 impl<'data, R: ReadRef<'data>> File<'data, R> {
     /// Parse the raw file data.
+    ///
+    /// The file must start at the beginning of `data`. Use [`File::parse_at`] to
+    /// parse a file that is embedded at an offset within a larger buffer.
     pub fn parse(data: R) -> Result<Self> {
-        let inner = match FileKind::parse(data)? {
+        Self::parse_with_kind(data, FileKind::parse(data)?)
+    }
+
+    /// Parse the raw file data, and check that it is within the given limits.
+    ///
+    /// This is the same as [`File::parse`], except that the format specific
+    /// parser is given `options`, so that the counts of sections, symbols and
+    /// relocations, and the PE import tables and resource directory, are
+    /// checked before they are used.
+    ///
+    /// Returns an error if a limit is exceeded, or if the data needed to check
+    /// a limit is invalid.
+    pub fn parse_with_options(data: R, options: &ParseOptions) -> Result<Self> {
+        Self::parse_kind_with_options(data, FileKind::parse(data)?, options)
+    }
+
+    /// Parse the raw file data as the given file kind.
+    ///
+    /// This is the same as [`File::parse`], except that the file kind is not detected.
+    /// This is useful when the kind has already been determined using [`FileKind::parse`].
+    ///
+    /// Returns an error if the data is not valid for the given kind, or if `kind`
+    /// is not supported by `File`, such as archives and fat binaries.
+    /// See [`File::parse_staged`] for a variant that also reports the stage of
+    /// parsing that failed.
+    pub fn parse_with_kind(data: R, kind: FileKind) -> Result<Self> {
+        Self::parse_kind_with_options(data, kind, &ParseOptions::default())
+    }
+
+    /// Parse the raw file data as the given file kind, and include the stage of
+    /// parsing in any error.
+    ///
+    /// This is the same as [`File::parse_with_kind`], except for the error type.
+    /// Errors for an unsupported `kind` are reported at the [`ParseStage::Header`]
+    /// stage. Wasm files do not distinguish stages, so their errors are reported
+    /// at the [`ParseStage::Other`] stage.
+    pub fn parse_staged(data: R, kind: FileKind) -> result::Result<Self, ParseError> {
+        Self::parse_kind_staged(data, kind, &ParseOptions::default())
+    }
+
+    fn parse_kind_with_options(data: R, kind: FileKind, options: &ParseOptions) -> Result<Self> {
+        Self::parse_kind_staged(data, kind, options).map_err(|error| error.detail)
+    }
+
+    fn parse_kind_staged(
+        data: R,
+        kind: FileKind,
+        options: &ParseOptions,
+    ) -> result::Result<Self, ParseError> {
+        let inner = match kind {
             #[cfg(feature = "elf")]
-            FileKind::Elf32 => FileInternal::Elf32(elf::ElfFile32::parse(data)?),
+            FileKind::Elf32 => FileInternal::Elf32(elf::ElfFile32::parse_staged(data, options)?),
             #[cfg(feature = "elf")]
-            FileKind::Elf64 => FileInternal::Elf64(elf::ElfFile64::parse(data)?),
+            FileKind::Elf64 => FileInternal::Elf64(elf::ElfFile64::parse_staged(data, options)?),
             #[cfg(feature = "macho")]
-            FileKind::MachO32 => FileInternal::MachO32(macho::MachOFile32::parse(data)?),
+            FileKind::MachO32 => {
+                FileInternal::MachO32(macho::MachOFile32::parse_staged(data, options)?)
+            }
             #[cfg(feature = "macho")]
-            FileKind::MachO64 => FileInternal::MachO64(macho::MachOFile64::parse(data)?),
+            FileKind::MachO64 => {
+                FileInternal::MachO64(macho::MachOFile64::parse_staged(data, options)?)
+            }
             #[cfg(feature = "wasm")]
-            FileKind::Wasm => FileInternal::Wasm(wasm::WasmFile::parse(data)?),
+            FileKind::Wasm => {
+                FileInternal::Wasm(wasm::WasmFile::parse(data).map_err(ParseStage::Other.error())?)
+            }
             #[cfg(feature = "pe")]
-            FileKind::Pe32 => FileInternal::Pe32(pe::PeFile32::parse(data)?),
+            FileKind::Pe32 => FileInternal::Pe32(pe::PeFile32::parse_staged(data, options)?),
             #[cfg(feature = "pe")]
-            FileKind::Pe64 => FileInternal::Pe64(pe::PeFile64::parse(data)?),
+            FileKind::Pe64 => FileInternal::Pe64(pe::PeFile64::parse_staged(data, options)?),
             #[cfg(feature = "coff")]
-            FileKind::Coff => FileInternal::Coff(coff::CoffFile::parse(data)?),
+            FileKind::Coff => FileInternal::Coff(coff::CoffFile::parse_staged(data, options)?),
             #[allow(unreachable_patterns)]
-            _ => return Err(Error("Unsupported file format")),
+            _ => {
+                return Err(ParseError {
+                    stage: ParseStage::Header,
+                    detail: Error("Unsupported file format"),
+                })
+            }
         };
-        Ok(File { inner })
+        Ok(File {
+            inner,
+            base_offset: 0,
+        })
     }
 
     /// Parse a Mach-O image from the dyld shared cache.
@@ -234,7 +304,10 @@ impl<'data, R: ReadRef<'data>> File<'data, R> {
             }
             _ => return Err(Error("Unsupported file format")),
         };
-        Ok(File { inner })
+        Ok(File {
+            inner,
+            base_offset: 0,
+        })
     }
 
     /// Return the file format.
@@ -252,6 +325,31 @@ impl<'data, R: ReadRef<'data>> File<'data, R> {
             FileInternal::Wasm(_) => BinaryFormat::Wasm,
         }
     }
+
+    /// Return the offset of the start of the file within the data it was parsed from.
+    ///
+    /// This is the offset that was passed to [`File::parse_at`], or 0 otherwise.
+    /// File offsets returned by this file, such as [`ObjectSection::file_range`],
+    /// are relative to this offset.
+    pub fn base_offset(&self) -> u64 {
+        self.base_offset
+    }
+}
+
+impl<'data> File<'data> {
+    /// Parse a file that starts at the given offset within `data`.
+    ///
+    /// The file extends to the end of `data`. Offsets determined by the parser are
+    /// relative to the start of the file, and [`File::base_offset`] can be added to
+    /// these to convert them to offsets within `data`.
+    pub fn parse_at(data: &'data [u8], offset: u64) -> Result<Self> {
+        let file_data = data
+            .read_bytes_at(offset, (data.len() as u64).saturating_sub(offset))
+            .read_error("Invalid file offset")?;
+        let mut file = Self::parse(file_data)?;
+        file.base_offset = offset;
+        Ok(file)
+    }
 }
 
 impl<'data, R: ReadRef<'data>> read::private::Sealed for File<'data, R> {}
@@ -272,10 +370,18 @@ where
     type SymbolTable = SymbolTable<'data, 'file, R>;
     type DynamicRelocationIterator = DynamicRelocationIterator<'data, 'file, R>;
 
+    fn format(&self) -> BinaryFormat {
+        with_inner!(self.inner, FileInternal, |x| x.format())
+    }
+
     fn architecture(&self) -> Architecture {
         with_inner!(self.inner, FileInternal, |x| x.architecture())
     }
 
+    fn sub_architecture(&self) -> Option<SubArchitecture> {
+        with_inner!(self.inner, FileInternal, |x| x.sub_architecture())
+    }
+
     fn is_little_endian(&self) -> bool {
         with_inner!(self.inner, FileInternal, |x| x.is_little_endian())
     }
@@ -295,6 +401,15 @@ where
         }
     }
 
+    fn loadable_segments(&'file self) -> LoadSegmentIterator<SegmentIterator<'data, 'file, R>> {
+        match self.inner {
+            #[cfg(feature = "coff")]
+            FileInternal::Coff(_) => LoadSegmentIterator::empty(),
+            #[allow(unreachable_patterns)]
+            _ => LoadSegmentIterator::new(self.segments()),
+        }
+    }
+
     fn section_by_name_bytes(&'file self, section_name: &[u8]) -> Option<Section<'data, 'file, R>> {
         map_inner_option!(self.inner, FileInternal, SectionInternal, |x| x
             .section_by_name_bytes(section_name))
@@ -321,6 +436,10 @@ where
         }
     }
 
+    fn symbol_count(&'file self) -> usize {
+        with_inner!(self.inner, FileInternal, |x| x.symbol_count())
+    }
+
     fn symbol_by_index(&'file self, index: SymbolIndex) -> Result<Symbol<'data, 'file, R>> {
         map_inner_option!(self.inner, FileInternal, SymbolInternal, |x| x
             .symbol_by_index(index)
@@ -353,6 +472,19 @@ where
         }
     }
 
+    fn symbol_by_name_bytes(&'file self, name: &[u8]) -> Result<Option<Symbol<'data, 'file, R>>> {
+        Ok(
+            map_inner_option!(self.inner, FileInternal, SymbolInternal, |x| x
+                .symbol_by_name_bytes(name)?
+                .map(|x| (x, PhantomData)))
+            .map(|inner| Symbol { inner }),
+        )
+    }
+
+    fn contains_symbol(&'file self, name: &[u8]) -> Result<bool> {
+        with_inner!(self.inner, FileInternal, |x| x.contains_symbol(name))
+    }
+
     fn dynamic_symbol_table(&'file self) -> Option<SymbolTable<'data, 'file, R>> {
         map_inner_option!(self.inner, FileInternal, SymbolTableInternal, |x| x
             .dynamic_symbol_table()
@@ -396,10 +528,38 @@ where
         with_inner!(self.inner, FileInternal, |x| x.exports())
     }
 
+    fn needed_libraries(&self) -> Result<Vec<&'data [u8]>> {
+        with_inner!(self.inner, FileInternal, |x| x.needed_libraries())
+    }
+
+    fn tls_symbols(&'file self) -> Result<Vec<TlsSymbol<'data>>> {
+        with_inner!(self.inner, FileInternal, |x| x.tls_symbols())
+    }
+
+    fn pointer_fixups(&'file self) -> Result<PointerFixupIterator> {
+        with_inner!(self.inner, FileInternal, |x| x.pointer_fixups())
+    }
+
+    fn all_relocations(&'file self) -> Result<Vec<(SectionIndex, u64, Relocation)>> {
+        with_inner!(self.inner, FileInternal, |x| x.all_relocations())
+    }
+
+    fn trailing_data(&self) -> Result<Option<&'data [u8]>> {
+        with_inner!(self.inner, FileInternal, |x| x.trailing_data())
+    }
+
+    fn functions(&'file self) -> Vec<Function> {
+        with_inner!(self.inner, FileInternal, |x| x.functions())
+    }
+
     fn has_debug_symbols(&self) -> bool {
         with_inner!(self.inner, FileInternal, |x| x.has_debug_symbols())
     }
 
+    fn is_stripped(&self) -> bool {
+        with_inner!(self.inner, FileInternal, |x| x.is_stripped())
+    }
+
     #[inline]
     fn mach_uuid(&self) -> Result<Option<[u8; 16]>> {
         with_inner!(self.inner, FileInternal, |x| x.mach_uuid())
@@ -429,10 +589,18 @@ where
         with_inner!(self.inner, FileInternal, |x| x.relative_address_base())
     }
 
+    fn preferred_address(&self) -> u64 {
+        with_inner!(self.inner, FileInternal, |x| x.preferred_address())
+    }
+
     fn entry(&self) -> u64 {
         with_inner!(self.inner, FileInternal, |x| x.entry())
     }
 
+    fn has_entry(&self) -> bool {
+        with_inner!(self.inner, FileInternal, |x| x.has_entry())
+    }
+
     fn flags(&self) -> FileFlags {
         with_inner!(self.inner, FileInternal, |x| x.flags())
     }
@@ -693,6 +861,10 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for Section<'data, 'f
         with_inner!(self.inner, SectionInternal, |x| x.data())
     }
 
+    fn is_bss(&self) -> bool {
+        with_inner!(self.inner, SectionInternal, |x| x.is_bss())
+    }
+
     fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
         with_inner!(self.inner, SectionInternal, |x| x.data_range(address, size))
     }
@@ -1166,7 +1338,7 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSymbol<'data> for Symbol<'data, 'fil
         with_inner!(self.inner, SymbolInternal, |x| x.0.is_local())
     }
 
-    fn flags(&self) -> SymbolFlags<SectionIndex> {
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
         with_inner!(self.inner, SymbolInternal, |x| x.0.flags())
     }
 }
@@ -292,6 +292,49 @@ impl<'data> ArchiveMember<'data> {
         data.read_bytes_at(self.offset, self.size)
             .read_error("Archive member size is too large")
     }
+
+    /// Parse the file data as an object file.
+    ///
+    /// Short import descriptions in Windows import libraries are returned
+    /// as [`ArchiveObject::Import`] instead of an object file.
+    #[cfg(any(
+        feature = "coff",
+        feature = "elf",
+        feature = "macho",
+        feature = "pe",
+        feature = "wasm"
+    ))]
+    pub fn parse_object<R: ReadRef<'data>>(&self, data: R) -> read::Result<ArchiveObject<'data>> {
+        let data = self.data(data)?;
+        #[cfg(feature = "coff")]
+        {
+            if let [0, 0, 0xff, 0xff, 0, 0, ..] = data {
+                return read::coff::ImportFile::parse(data).map(ArchiveObject::Import);
+            }
+        }
+        read::File::parse(data).map(ArchiveObject::File)
+    }
+}
+
+/// The parsed file data of an archive member.
+///
+/// Returned by [`ArchiveMember::parse_object`].
+#[cfg(any(
+    feature = "coff",
+    feature = "elf",
+    feature = "macho",
+    feature = "pe",
+    feature = "wasm"
+))]
+#[derive(Debug)]
+#[non_exhaustive]
+#[allow(clippy::large_enum_variant)]
+pub enum ArchiveObject<'data> {
+    /// An object file.
+    File(read::File<'data>),
+    /// A short import description in a Windows import library.
+    #[cfg(feature = "coff")]
+    Import(read::coff::ImportFile<'data>),
 }
 
 // Ignores bytes starting from the first space.
@@ -1,10 +1,12 @@
 use alloc::vec::Vec;
+use core::result;
 
 use crate::read::{
-    self, Architecture, Export, FileFlags, Import, NoDynamicRelocationIterator, Object, ObjectKind,
-    ObjectSection, ReadError, ReadRef, Result, SectionIndex, SymbolIndex,
+    self, Architecture, BinaryFormat, Export, FileFlags, Import, LoadSegmentIterator,
+    NoDynamicRelocationIterator, Object, ObjectKind, ObjectSection, ParseError, ParseOptions,
+    ParseStage, ReadError, ReadRef, Result, SectionIndex, SubArchitecture, SymbolIndex, TlsSymbol,
 };
-use crate::{pe, LittleEndian as LE};
+use crate::{pe, ByteString, LittleEndian as LE};
 
 use super::{
     CoffComdat, CoffComdatIterator, CoffSection, CoffSectionIterator, CoffSegment,
@@ -21,6 +23,51 @@ pub(crate) struct CoffCommon<'data, R: ReadRef<'data>> {
     pub(crate) image_base: u64,
 }
 
+impl<'data, R: ReadRef<'data>> CoffCommon<'data, R> {
+    /// Return true if there is a section containing DWARF or CodeView debug information.
+    pub(crate) fn has_debug_sections(&self) -> bool {
+        self.sections.iter().any(|section| {
+            section
+                .name(self.symbols.strings())
+                .map(|name| name.starts_with(b".debug"))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Return the symbols that are defined in a `.tls` section.
+    pub(crate) fn tls_symbols(&self) -> Result<Vec<TlsSymbol<'data>>> {
+        let strings = self.symbols.strings();
+        let mut tls = Vec::new();
+        for (index, symbol) in self.symbols.iter() {
+            if !symbol.is_definition() {
+                continue;
+            }
+            let section = match self.sections.section(symbol.section_number.get(LE).into()) {
+                Ok(section) => section,
+                Err(_) => continue,
+            };
+            if !section.name(strings)?.starts_with(b".tls") {
+                continue;
+            }
+            tls.push(TlsSymbol {
+                index: SymbolIndex(index),
+                name: ByteString(symbol.name(strings)?),
+                tls_offset: symbol.value.get(LE).into(),
+            });
+        }
+        Ok(tls)
+    }
+
+    /// Return true if the symbol table contains no local or function definitions.
+    pub(crate) fn is_stripped(&self) -> bool {
+        !self.symbols.iter().any(|(_, symbol)| {
+            symbol.is_definition()
+                && (symbol.storage_class == pe::IMAGE_SYM_CLASS_STATIC
+                    || symbol.derived_type() == pe::IMAGE_SYM_DTYPE_FUNCTION)
+        })
+    }
+}
+
 /// A COFF object file.
 #[derive(Debug)]
 pub struct CoffFile<'data, R: ReadRef<'data> = &'data [u8]> {
@@ -32,10 +79,38 @@ pub struct CoffFile<'data, R: ReadRef<'data> = &'data [u8]> {
 impl<'data, R: ReadRef<'data>> CoffFile<'data, R> {
     /// Parse the raw COFF file data.
     pub fn parse(data: R) -> Result<Self> {
+        Self::parse_with_options(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw COFF file data, and check that it is within the given limits.
+    ///
+    /// The limits are checked using the file header and section headers, before
+    /// the symbol table is parsed.
+    pub fn parse_with_options(data: R, options: &ParseOptions) -> Result<Self> {
+        Self::parse_staged(data, options).map_err(|error| error.detail)
+    }
+
+    /// Parse the raw COFF file data, and include the stage of parsing in any error.
+    pub(crate) fn parse_staged(
+        data: R,
+        options: &ParseOptions,
+    ) -> result::Result<Self, ParseError> {
         let mut offset = 0;
-        let header = pe::ImageFileHeader::parse(data, &mut offset)?;
-        let sections = header.sections(data, offset)?;
-        let symbols = header.symbols(data)?;
+        let header =
+            pe::ImageFileHeader::parse(data, &mut offset).map_err(ParseStage::Header.error())?;
+        options
+            .check_sections(header.number_of_sections.get(LE).into())
+            .map_err(ParseStage::Sections.error())?;
+        let sections = header
+            .sections(data, offset)
+            .map_err(ParseStage::Sections.error())?;
+        options
+            .check_relocations(sections.relocation_count())
+            .map_err(ParseStage::Sections.error())?;
+        options
+            .check_symbols(header.number_of_symbols.get(LE) as usize)
+            .map_err(ParseStage::Symbols.error())?;
+        let symbols = header.symbols(data).map_err(ParseStage::Symbols.error())?;
 
         Ok(CoffFile {
             header,
@@ -67,14 +142,17 @@ where
     type SymbolTable = CoffSymbolTable<'data, 'file, R>;
     type DynamicRelocationIterator = NoDynamicRelocationIterator;
 
+    #[inline]
+    fn format(&self) -> BinaryFormat {
+        BinaryFormat::Coff
+    }
+
     fn architecture(&self) -> Architecture {
-        match self.header.machine.get(LE) {
-            pe::IMAGE_FILE_MACHINE_ARMNT => Architecture::Arm,
-            pe::IMAGE_FILE_MACHINE_ARM64 => Architecture::Aarch64,
-            pe::IMAGE_FILE_MACHINE_I386 => Architecture::I386,
-            pe::IMAGE_FILE_MACHINE_AMD64 => Architecture::X86_64,
-            _ => Architecture::Unknown,
-        }
+        machine_architecture(self.header.machine.get(LE))
+    }
+
+    fn sub_architecture(&self) -> Option<SubArchitecture> {
+        machine_sub_architecture(self.header.machine.get(LE))
     }
 
     #[inline]
@@ -99,6 +177,11 @@ where
         }
     }
 
+    #[inline]
+    fn loadable_segments(&'file self) -> LoadSegmentIterator<Self::SegmentIterator> {
+        LoadSegmentIterator::empty()
+    }
+
     fn section_by_name_bytes(
         &'file self,
         section_name: &[u8],
@@ -130,6 +213,11 @@ where
         }
     }
 
+    #[inline]
+    fn symbol_count(&'file self) -> usize {
+        self.common.symbols.len()
+    }
+
     fn symbol_by_index(&'file self, index: SymbolIndex) -> Result<CoffSymbol<'data, 'file, R>> {
         let symbol = self.common.symbols.symbol(index.0)?;
         Ok(CoffSymbol {
@@ -181,14 +269,26 @@ where
         Ok(Vec::new())
     }
 
+    fn tls_symbols(&'file self) -> Result<Vec<TlsSymbol<'data>>> {
+        self.common.tls_symbols()
+    }
+
     fn has_debug_symbols(&self) -> bool {
-        self.section_by_name(".debug_info").is_some()
+        self.common.has_debug_sections()
+    }
+
+    fn is_stripped(&self) -> bool {
+        self.common.is_stripped()
     }
 
     fn relative_address_base(&self) -> u64 {
         0
     }
 
+    fn preferred_address(&self) -> u64 {
+        0
+    }
+
     #[inline]
     fn entry(&self) -> u64 {
         0
@@ -201,6 +301,26 @@ where
     }
 }
 
+/// Return the architecture for a COFF machine type.
+pub(crate) fn machine_architecture(machine: u16) -> Architecture {
+    match machine {
+        pe::IMAGE_FILE_MACHINE_ARMNT => Architecture::Arm,
+        pe::IMAGE_FILE_MACHINE_ARM64 | pe::IMAGE_FILE_MACHINE_ARM64EC => Architecture::Aarch64,
+        pe::IMAGE_FILE_MACHINE_I386 => Architecture::I386,
+        pe::IMAGE_FILE_MACHINE_AMD64 => Architecture::X86_64,
+        _ => Architecture::Unknown,
+    }
+}
+
+/// Return the sub-architecture for a COFF machine type, if any.
+pub(crate) fn machine_sub_architecture(machine: u16) -> Option<SubArchitecture> {
+    match machine {
+        pe::IMAGE_FILE_MACHINE_ARMNT => Some(SubArchitecture::ArmThumb),
+        pe::IMAGE_FILE_MACHINE_ARM64EC => Some(SubArchitecture::Arm64EC),
+        _ => None,
+    }
+}
+
 impl pe::ImageFileHeader {
     /// Read the file header.
     ///
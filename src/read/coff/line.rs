@@ -0,0 +1,60 @@
+use core::slice;
+
+use crate::endian::LittleEndian as LE;
+use crate::pe;
+use crate::read::SymbolIndex;
+
+/// An iterator over the line number records of a `CoffSection`.
+#[derive(Debug)]
+pub struct CoffLineNumberIterator<'data> {
+    pub(super) iter: slice::Iter<'data, pe::ImageLinenumber>,
+}
+
+impl<'data> Iterator for CoffLineNumberIterator<'data> {
+    type Item = CoffLineNumber;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(CoffLineNumber::parse)
+    }
+}
+
+/// A parsed COFF line number record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoffLineNumber {
+    /// The symbol or address that the record refers to.
+    pub symbol_or_address: CoffLineNumberTarget,
+    /// The line number.
+    ///
+    /// This is 0 for the first record of each function. For other records,
+    /// it is relative to the starting line of the function, which is stored in
+    /// the auxiliary record of the `.bf` symbol.
+    pub line: u16,
+}
+
+impl CoffLineNumber {
+    /// Parse a raw line number record.
+    ///
+    /// The interpretation of the first field depends on whether the line number is 0.
+    pub fn parse(line_number: &pe::ImageLinenumber) -> Self {
+        let line = line_number.linenumber.get(LE);
+        let value = line_number.symbol_table_index_or_virtual_address.get(LE);
+        let symbol_or_address = if line == 0 {
+            CoffLineNumberTarget::Symbol(SymbolIndex(value as usize))
+        } else {
+            CoffLineNumberTarget::Address(value)
+        };
+        CoffLineNumber {
+            symbol_or_address,
+            line,
+        }
+    }
+}
+
+/// The target of a [`CoffLineNumber`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoffLineNumberTarget {
+    /// The symbol table index of the function that the following records belong to.
+    Symbol(SymbolIndex),
+    /// The virtual address of the code for the line.
+    Address(u32),
+}
@@ -14,5 +14,11 @@ pub use symbol::*;
 mod relocation;
 pub use relocation::*;
 
+mod line;
+pub use line::*;
+
 mod comdat;
 pub use comdat::*;
+
+mod import;
+pub use import::*;
@@ -66,6 +66,12 @@ impl<'data, 'file, R: ReadRef<'data>> Iterator for CoffRelocationIterator<'data,
                 },
                 _ => (RelocationKind::Coff(relocation.typ.get(LE)), 0, 0),
             };
+            let size = match kind {
+                RelocationKind::Coff(typ) if size == 0 => {
+                    coff_relocation_size(self.file.header.machine.get(LE), typ)
+                }
+                _ => size,
+            };
             let target = RelocationTarget::Symbol(SymbolIndex(
                 relocation.symbol_table_index.get(LE) as usize,
             ));
@@ -89,3 +95,52 @@ impl<'data, 'file, R: ReadRef<'data>> fmt::Debug for CoffRelocationIterator<'dat
         f.debug_struct("CoffRelocationIterator").finish()
     }
 }
+
+/// Return the size in bits of the place of a relocation type that has no
+/// generic kind.
+///
+/// For instruction relocations, this is the size of the field that is modified,
+/// rather than the size of the instruction.
+///
+/// Returns 0 if the size is unknown, or if the relocation does not modify a place.
+fn coff_relocation_size(machine: u16, typ: u16) -> u8 {
+    match machine {
+        pe::IMAGE_FILE_MACHINE_ARMNT => match typ {
+            pe::IMAGE_REL_ARM_TOKEN | pe::IMAGE_REL_ARM_MOV32 | pe::IMAGE_REL_ARM_MOV32T => 32,
+            pe::IMAGE_REL_ARM_BRANCH24 | pe::IMAGE_REL_ARM_BLX24 | pe::IMAGE_REL_ARM_BRANCH24T => {
+                24
+            }
+            pe::IMAGE_REL_ARM_BLX23T => 23,
+            pe::IMAGE_REL_ARM_BRANCH20T => 20,
+            pe::IMAGE_REL_ARM_GPREL12 => 12,
+            pe::IMAGE_REL_ARM_BRANCH11 | pe::IMAGE_REL_ARM_BLX11 => 11,
+            pe::IMAGE_REL_ARM_GPREL7 => 7,
+            _ => 0,
+        },
+        pe::IMAGE_FILE_MACHINE_ARM64 => match typ {
+            pe::IMAGE_REL_ARM64_TOKEN => 32,
+            pe::IMAGE_REL_ARM64_BRANCH26 => 26,
+            pe::IMAGE_REL_ARM64_PAGEBASE_REL21 | pe::IMAGE_REL_ARM64_REL21 => 21,
+            pe::IMAGE_REL_ARM64_BRANCH19 => 19,
+            pe::IMAGE_REL_ARM64_BRANCH14 => 14,
+            pe::IMAGE_REL_ARM64_PAGEOFFSET_12A
+            | pe::IMAGE_REL_ARM64_PAGEOFFSET_12L
+            | pe::IMAGE_REL_ARM64_SECREL_LOW12A
+            | pe::IMAGE_REL_ARM64_SECREL_HIGH12A
+            | pe::IMAGE_REL_ARM64_SECREL_LOW12L => 12,
+            _ => 0,
+        },
+        pe::IMAGE_FILE_MACHINE_I386 => match typ {
+            pe::IMAGE_REL_I386_TOKEN => 32,
+            pe::IMAGE_REL_I386_SEG12 => 16,
+            _ => 0,
+        },
+        pe::IMAGE_FILE_MACHINE_AMD64 => match typ {
+            pe::IMAGE_REL_AMD64_TOKEN
+            | pe::IMAGE_REL_AMD64_SREL32
+            | pe::IMAGE_REL_AMD64_SSPAN32 => 32,
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
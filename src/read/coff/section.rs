@@ -9,7 +9,7 @@ use crate::read::{
     ReadRef, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
 };
 
-use super::{CoffFile, CoffRelocationIterator};
+use super::{CoffFile, CoffLineNumberIterator, CoffRelocationIterator};
 
 /// The table of section headers in a COFF or PE file.
 #[derive(Debug, Default, Clone, Copy)]
@@ -53,6 +53,14 @@ impl<'data> SectionTable<'data> {
         self.sections.len()
     }
 
+    /// The total number of relocations in the section headers.
+    pub(crate) fn relocation_count(&self) -> u64 {
+        self.sections
+            .iter()
+            .map(|section| u64::from(section.number_of_relocations.get(LE)))
+            .sum()
+    }
+
     /// Return the section header at the given index.
     ///
     /// The index is 1-based.
@@ -230,6 +238,14 @@ impl<'data, 'file, R: ReadRef<'data>> CoffSection<'data, 'file, R> {
             .coff_data(self.file.data)
             .read_error("Invalid COFF section offset or size")
     }
+
+    /// Get an iterator over the line number records for this section.
+    pub fn line_numbers(&self) -> Result<CoffLineNumberIterator<'data>> {
+        let line_numbers = self.section.coff_line_numbers(self.file.data)?;
+        Ok(CoffLineNumberIterator {
+            iter: line_numbers.iter(),
+        })
+    }
 }
 
 impl<'data, 'file, R: ReadRef<'data>> read::private::Sealed for CoffSection<'data, 'file, R> {}
@@ -268,6 +284,11 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSection<'data> for CoffSection<'data
         self.bytes()
     }
 
+    #[inline]
+    fn is_bss(&self) -> bool {
+        self.section.coff_is_bss()
+    }
+
     fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
         Ok(read::util::data_range(
             self.bytes()?,
@@ -424,7 +445,7 @@ impl pe::ImageSectionHeader {
     ///
     /// Returns `None` for sections that have no data in the file.
     pub fn coff_file_range(&self) -> Option<(u32, u32)> {
-        if self.characteristics.get(LE) & pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA != 0 {
+        if self.coff_is_bss() {
             None
         } else {
             let offset = self.pointer_to_raw_data.get(LE);
@@ -434,6 +455,16 @@ impl pe::ImageSectionHeader {
         }
     }
 
+    /// Return true if the section has no data in a COFF file.
+    ///
+    /// This requires the section to be flagged as uninitialized data, and to have
+    /// no pointer to raw data. The size of raw data is the size of the section
+    /// in memory, so it may be non-zero.
+    pub fn coff_is_bss(&self) -> bool {
+        self.characteristics.get(LE) & pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA != 0
+            && self.pointer_to_raw_data.get(LE) == 0
+    }
+
     /// Return the section data in a COFF file.
     ///
     /// Returns `Ok(&[])` if the section has no data.
@@ -471,6 +502,10 @@ impl pe::ImageSectionHeader {
 
     /// Read the relocations in a COFF file.
     ///
+    /// If the section has the `IMAGE_SCN_LNK_NRELOC_OVFL` flag, then the
+    /// relocation count is read from the first relocation, and that
+    /// relocation is not included in the returned slice.
+    ///
     /// `data` must be the entire file data.
     pub fn coff_relocations<'data, R: ReadRef<'data>>(
         &self,
@@ -497,6 +532,19 @@ impl pe::ImageSectionHeader {
         data.read_slice_at(pointer, number)
             .read_error("Invalid COFF relocation offset or number")
     }
+
+    /// Read the line number records in a COFF file.
+    ///
+    /// `data` must be the entire file data.
+    pub fn coff_line_numbers<'data, R: ReadRef<'data>>(
+        &self,
+        data: R,
+    ) -> read::Result<&'data [pe::ImageLinenumber]> {
+        let pointer = self.pointer_to_linenumbers.get(LE).into();
+        let number = self.number_of_linenumbers.get(LE).into();
+        data.read_slice_at(pointer, number)
+            .read_error("Invalid COFF line number offset or number")
+    }
 }
 
 #[cfg(test)]
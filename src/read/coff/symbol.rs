@@ -113,6 +113,14 @@ impl<'data, R: ReadRef<'data>> SymbolTable<'data, R> {
         self.get::<pe::ImageAuxSymbolSection>(index, 1)
     }
 
+    /// Return the auxiliary weak external symbol for the symbol table entry at the given index.
+    ///
+    /// Note that the index is of the symbol, not the first auxiliary record.
+    #[inline]
+    pub fn aux_weak_external(&self, index: usize) -> Result<&'data pe::ImageAuxSymbolWeak> {
+        self.get::<pe::ImageAuxSymbolWeak>(index, 1)
+    }
+
     /// Return the auxiliary file name for the symbol table entry at the given index.
     ///
     /// Note that the index is of the symbol, not the first auxiliary record.
@@ -248,6 +256,11 @@ impl pe::ImageSymbol {
             && self.storage_class == pe::IMAGE_SYM_CLASS_STATIC
             && self.value.get(LE) == 0
     }
+
+    /// Return true if the symbol has an auxiliary weak external symbol.
+    pub fn has_aux_weak_external(&self) -> bool {
+        self.number_of_aux_symbols > 0 && self.storage_class == pe::IMAGE_SYM_CLASS_WEAK_EXTERNAL
+    }
 }
 
 /// A symbol table of a `CoffFile`.
@@ -429,12 +442,20 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSymbol<'data> for CoffSymbol<'data,
     fn section(&self) -> SymbolSection {
         match self.symbol.section_number.get(LE) {
             pe::IMAGE_SYM_UNDEFINED => {
-                if self.symbol.storage_class == pe::IMAGE_SYM_CLASS_EXTERNAL
-                    && self.symbol.value.get(LE) == 0
+                // An undefined external symbol with a nonzero value is a common symbol,
+                // and the value is its size.
+                if self.symbol.storage_class == pe::IMAGE_SYM_CLASS_EXTERNAL {
+                    if self.symbol.value.get(LE) == 0 {
+                        SymbolSection::Undefined
+                    } else {
+                        SymbolSection::Common
+                    }
+                } else if self.symbol.storage_class == pe::IMAGE_SYM_CLASS_SECTION
+                    || self.symbol.storage_class == pe::IMAGE_SYM_CLASS_WEAK_EXTERNAL
                 {
                     SymbolSection::Undefined
                 } else {
-                    SymbolSection::Common
+                    SymbolSection::Unknown
                 }
             }
             pe::IMAGE_SYM_ABSOLUTE => SymbolSection::Absolute,
@@ -498,7 +519,7 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSymbol<'data> for CoffSymbol<'data,
         !self.is_global()
     }
 
-    fn flags(&self) -> SymbolFlags<SectionIndex> {
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
         if self.symbol.has_aux_section() {
             if let Ok(aux) = self.file.symbols.aux_section(self.index.0) {
                 // TODO: use high_number for bigobj
@@ -513,6 +534,14 @@ impl<'data, 'file, R: ReadRef<'data>> ObjectSymbol<'data> for CoffSymbol<'data,
                 };
             }
         }
+        if self.symbol.has_aux_weak_external() {
+            if let Ok(aux) = self.file.symbols.aux_weak_external(self.index.0) {
+                return SymbolFlags::CoffWeakExternal {
+                    default_symbol: SymbolIndex(aux.weak_default_sym_index.get(LE) as usize),
+                    characteristics: aux.weak_search_type.get(LE),
+                };
+            }
+        }
         SymbolFlags::None
     }
 }
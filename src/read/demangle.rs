@@ -0,0 +1,176 @@
+//! Demangling of Rust symbol names that use the legacy mangling scheme.
+//!
+//! Demangling is done while formatting, so no allocation is required.
+
+use core::fmt;
+
+/// A symbol name that has been successfully parsed as a legacy Rust mangled name.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Demangle<'a> {
+    /// The path elements, without the `_ZN` prefix or `E` terminator.
+    inner: &'a str,
+    /// The number of path elements.
+    elements: usize,
+    /// Any suffix after the `E` terminator, such as `.llvm.1234`.
+    suffix: &'a str,
+}
+
+/// Parse a legacy Rust mangled name.
+///
+/// Returns `None` if the name is not mangled, or is not valid.
+pub(crate) fn demangle(name: &str) -> Option<Demangle<'_>> {
+    let inner = if name.starts_with("_ZN") {
+        &name[3..]
+    } else if name.starts_with("__ZN") {
+        // On macOS, symbols have an extra leading underscore.
+        &name[4..]
+    } else if name.starts_with("ZN") {
+        // On Windows, symbols have no leading underscore.
+        &name[2..]
+    } else {
+        return None;
+    };
+    if !inner.is_ascii() {
+        return None;
+    }
+
+    let mut elements = 0;
+    let mut chars = inner;
+    loop {
+        if chars.starts_with('E') {
+            break;
+        }
+        let (ident, rest) = next_element(chars)?;
+        for piece in IdentPieces::new(ident) {
+            piece?;
+        }
+        chars = rest;
+        elements += 1;
+    }
+    if elements == 0 {
+        return None;
+    }
+    let suffix = &chars[1..];
+    if !suffix.is_empty() && !suffix.starts_with('.') {
+        return None;
+    }
+    Some(Demangle {
+        inner: &inner[..inner.len() - chars.len()],
+        elements,
+        suffix,
+    })
+}
+
+/// Split a length prefixed path element from the start of a string.
+fn next_element(chars: &str) -> Option<(&str, &str)> {
+    let digits = chars.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    let len: usize = chars[..digits].parse().ok()?;
+    let chars = &chars[digits..];
+    if len > chars.len() {
+        return None;
+    }
+    Some((&chars[..len], &chars[len..]))
+}
+
+/// Return true if the path element is the hash that rustc appends to the path.
+fn is_rust_hash(ident: &str) -> bool {
+    ident.len() == 17 && ident.starts_with('h') && ident[1..].bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+impl<'a> fmt::Display for Demangle<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut chars = self.inner;
+        for element in 0..self.elements {
+            let (ident, rest) = match next_element(chars) {
+                Some(x) => x,
+                None => break,
+            };
+            chars = rest;
+            if element + 1 == self.elements && element != 0 && is_rust_hash(ident) {
+                break;
+            }
+            if element != 0 {
+                f.write_str("::")?;
+            }
+            for piece in IdentPieces::new(ident) {
+                match piece {
+                    Some(Piece::Str(s)) => f.write_str(s)?,
+                    Some(Piece::Char(c)) => fmt::Write::write_char(f, c)?,
+                    None => break,
+                }
+            }
+        }
+        f.write_str(self.suffix)
+    }
+}
+
+/// A decoded piece of a path element.
+enum Piece<'a> {
+    Str(&'a str),
+    Char(char),
+}
+
+/// An iterator over the decoded pieces of a path element.
+///
+/// Yields `None` for an invalid escape sequence.
+struct IdentPieces<'a>(&'a str);
+
+impl<'a> IdentPieces<'a> {
+    fn new(ident: &'a str) -> Self {
+        // A leading `_` is added to elements that would otherwise start with `$`.
+        if ident.starts_with("_$") {
+            IdentPieces(&ident[1..])
+        } else {
+            IdentPieces(ident)
+        }
+    }
+}
+
+impl<'a> Iterator for IdentPieces<'a> {
+    type Item = Option<Piece<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ident = self.0;
+        if ident.is_empty() {
+            return None;
+        }
+        if ident.starts_with('$') {
+            let end = match ident[1..].find('$') {
+                Some(end) => end + 1,
+                None => {
+                    self.0 = "";
+                    return Some(None);
+                }
+            };
+            let c = match &ident[1..end] {
+                "SP" => Some('@'),
+                "BP" => Some('*'),
+                "RF" => Some('&'),
+                "LT" => Some('<'),
+                "GT" => Some('>'),
+                "LP" => Some('('),
+                "RP" => Some(')'),
+                "C" => Some(','),
+                escape if escape.starts_with('u') => u32::from_str_radix(&escape[1..], 16)
+                    .ok()
+                    .and_then(core::char::from_u32),
+                _ => None,
+            };
+            self.0 = if c.is_some() { &ident[end + 1..] } else { "" };
+            return Some(c.map(Piece::Char));
+        }
+        if ident.starts_with("..") {
+            self.0 = &ident[2..];
+            return Some(Some(Piece::Str("::")));
+        }
+        let len = match ident[1..].find(|c| c == '$' || c == '.') {
+            Some(len) => len + 1,
+            None => ident.len(),
+        };
+        self.0 = &ident[len..];
+        Some(Some(Piece::Str(&ident[..len])))
+    }
+}
@@ -1,5 +1,6 @@
 use core::convert::TryInto;
 use core::fmt::Debug;
+use core::ops;
 
 use crate::elf;
 use crate::endian;
@@ -115,3 +116,127 @@ impl<Endian: endian::Endian> Dyn for elf::Dyn64<Endian> {
         self.d_val.get(endian)
     }
 }
+
+/// The flags of an ELF dynamic object.
+///
+/// This is the value of the `DT_FLAGS` dynamic entry.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DfFlags(pub u32);
+
+impl DfFlags {
+    /// `DF_ORIGIN`
+    pub const ORIGIN: DfFlags = DfFlags(elf::DF_ORIGIN);
+    /// `DF_SYMBOLIC`
+    pub const SYMBOLIC: DfFlags = DfFlags(elf::DF_SYMBOLIC);
+    /// `DF_TEXTREL`
+    pub const TEXTREL: DfFlags = DfFlags(elf::DF_TEXTREL);
+    /// `DF_BIND_NOW`
+    pub const BIND_NOW: DfFlags = DfFlags(elf::DF_BIND_NOW);
+    /// `DF_STATIC_TLS`
+    pub const STATIC_TLS: DfFlags = DfFlags(elf::DF_STATIC_TLS);
+
+    /// Return the raw `DF_*` flags.
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Return true if all of the given flags are set.
+    pub fn contains(self, other: DfFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for DfFlags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        DfFlags(self.0 | other.0)
+    }
+}
+
+impl ops::BitAnd for DfFlags {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        DfFlags(self.0 & other.0)
+    }
+}
+
+/// The state flags of an ELF dynamic object.
+///
+/// This is the value of the `DT_FLAGS_1` dynamic entry.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Df1Flags(pub u32);
+
+impl Df1Flags {
+    /// `DF_1_NOW`
+    pub const NOW: Df1Flags = Df1Flags(elf::DF_1_NOW);
+    /// `DF_1_GLOBAL`
+    pub const GLOBAL: Df1Flags = Df1Flags(elf::DF_1_GLOBAL);
+    /// `DF_1_GROUP`
+    pub const GROUP: Df1Flags = Df1Flags(elf::DF_1_GROUP);
+    /// `DF_1_NODELETE`
+    pub const NODELETE: Df1Flags = Df1Flags(elf::DF_1_NODELETE);
+    /// `DF_1_LOADFLTR`
+    pub const LOADFLTR: Df1Flags = Df1Flags(elf::DF_1_LOADFLTR);
+    /// `DF_1_INITFIRST`
+    pub const INITFIRST: Df1Flags = Df1Flags(elf::DF_1_INITFIRST);
+    /// `DF_1_NOOPEN`
+    pub const NOOPEN: Df1Flags = Df1Flags(elf::DF_1_NOOPEN);
+    /// `DF_1_ORIGIN`
+    pub const ORIGIN: Df1Flags = Df1Flags(elf::DF_1_ORIGIN);
+    /// `DF_1_DIRECT`
+    pub const DIRECT: Df1Flags = Df1Flags(elf::DF_1_DIRECT);
+    /// `DF_1_INTERPOSE`
+    pub const INTERPOSE: Df1Flags = Df1Flags(elf::DF_1_INTERPOSE);
+    /// `DF_1_NODEFLIB`
+    pub const NODEFLIB: Df1Flags = Df1Flags(elf::DF_1_NODEFLIB);
+    /// `DF_1_NODUMP`
+    pub const NODUMP: Df1Flags = Df1Flags(elf::DF_1_NODUMP);
+    /// `DF_1_CONFALT`
+    pub const CONFALT: Df1Flags = Df1Flags(elf::DF_1_CONFALT);
+    /// `DF_1_ENDFILTEE`
+    pub const ENDFILTEE: Df1Flags = Df1Flags(elf::DF_1_ENDFILTEE);
+    /// `DF_1_DISPRELDNE`
+    pub const DISPRELDNE: Df1Flags = Df1Flags(elf::DF_1_DISPRELDNE);
+    /// `DF_1_DISPRELPND`
+    pub const DISPRELPND: Df1Flags = Df1Flags(elf::DF_1_DISPRELPND);
+    /// `DF_1_NODIRECT`
+    pub const NODIRECT: Df1Flags = Df1Flags(elf::DF_1_NODIRECT);
+    /// `DF_1_EDITED`
+    pub const EDITED: Df1Flags = Df1Flags(elf::DF_1_EDITED);
+    /// `DF_1_SYMINTPOSE`
+    pub const SYMINTPOSE: Df1Flags = Df1Flags(elf::DF_1_SYMINTPOSE);
+    /// `DF_1_GLOBAUDIT`
+    pub const GLOBAUDIT: Df1Flags = Df1Flags(elf::DF_1_GLOBAUDIT);
+    /// `DF_1_SINGLETON`
+    pub const SINGLETON: Df1Flags = Df1Flags(elf::DF_1_SINGLETON);
+    /// `DF_1_PIE`
+    pub const PIE: Df1Flags = Df1Flags(elf::DF_1_PIE);
+
+    /// Return the raw `DF_1_*` flags.
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Return true if all of the given flags are set.
+    pub fn contains(self, other: Df1Flags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for Df1Flags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Df1Flags(self.0 | other.0)
+    }
+}
+
+impl ops::BitAnd for Df1Flags {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Df1Flags(self.0 & other.0)
+    }
+}
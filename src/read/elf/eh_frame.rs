@@ -0,0 +1,194 @@
+use alloc::vec::Vec;
+
+use crate::endian::{U16Bytes, U32Bytes, U64Bytes};
+use crate::read::{Bytes, Object, ReadRef};
+
+use super::{ElfFile, FileHeader, SectionHeader};
+
+// The pointer encodings used in `.eh_frame`.
+const DW_EH_PE_ABSPTR: u8 = 0x00;
+const DW_EH_PE_ULEB128: u8 = 0x01;
+const DW_EH_PE_UDATA2: u8 = 0x02;
+const DW_EH_PE_UDATA4: u8 = 0x03;
+const DW_EH_PE_UDATA8: u8 = 0x04;
+const DW_EH_PE_SLEB128: u8 = 0x09;
+const DW_EH_PE_SDATA2: u8 = 0x0a;
+const DW_EH_PE_SDATA4: u8 = 0x0b;
+const DW_EH_PE_SDATA8: u8 = 0x0c;
+const DW_EH_PE_PCREL: u8 = 0x10;
+const DW_EH_PE_INDIRECT: u8 = 0x80;
+const DW_EH_PE_OMIT: u8 = 0xff;
+
+impl<'data, Elf, R> ElfFile<'data, Elf, R>
+where
+    Elf: FileHeader,
+    R: ReadRef<'data>,
+{
+    /// Return the address and size of the function for each FDE in `.eh_frame`.
+    ///
+    /// Invalid entries and FDEs that use unsupported pointer encodings are ignored.
+    pub(super) fn eh_frame_functions(&self) -> Vec<(u64, u64)> {
+        let endian = self.endian;
+        let section = match self.sections.section_by_name(endian, b".eh_frame") {
+            Some((_, section)) => section,
+            None => return Vec::new(),
+        };
+        let data = match section.data(endian, self.data) {
+            Ok(data) => Bytes(data),
+            Err(_) => return Vec::new(),
+        };
+        let parser = EhFrame {
+            data,
+            address: section.sh_addr(endian).into(),
+            address_size: if self.is_64() { 8 } else { 4 },
+            endian,
+        };
+
+        let mut functions = Vec::new();
+        let mut offset = 0;
+        while let Some((id_offset, body, next)) = parser.entry(offset) {
+            offset = next;
+            let mut body = Bytes(body);
+            let id = match body.read::<U32Bytes<Elf::Endian>>() {
+                Ok(id) => id.get(endian),
+                Err(()) => break,
+            };
+            // CIEs are skipped here, and parsed for each FDE that references them.
+            if id == 0 {
+                continue;
+            }
+            let encoding = match (id_offset as u64)
+                .checked_sub(id.into())
+                .and_then(|cie_offset| parser.cie_encoding(cie_offset as usize))
+            {
+                Some(encoding) => encoding,
+                None => continue,
+            };
+            let pc_begin_offset = id_offset + 4;
+            let pc_begin = parser.read_pointer(&mut body, encoding, pc_begin_offset);
+            let pc_range = parser.read_pointer(&mut body, encoding & 0x0f, 0);
+            if let (Some(pc_begin), Some(pc_range)) = (pc_begin, pc_range) {
+                if pc_begin != 0 {
+                    functions.push((pc_begin, pc_range));
+                }
+            }
+        }
+        functions
+    }
+}
+
+/// A parser for the entries in `.eh_frame`.
+struct EhFrame<'data, E: crate::Endian> {
+    data: Bytes<'data>,
+    address: u64,
+    address_size: u8,
+    endian: E,
+}
+
+impl<'data, E: crate::Endian> EhFrame<'data, E> {
+    /// Return the offset of the CIE ID field, the entry data after the length field,
+    /// and the offset of the next entry.
+    ///
+    /// Returns `None` for the terminator or an invalid entry.
+    fn entry(&self, offset: usize) -> Option<(usize, &'data [u8], usize)> {
+        let mut data = self.data;
+        data.skip(offset).ok()?;
+        let length = data.read::<U32Bytes<E>>().ok()?.get(self.endian);
+        let (length, id_offset) = match length {
+            0 => return None,
+            0xffff_ffff => {
+                let length = data.read::<U64Bytes<E>>().ok()?.get(self.endian);
+                (length as usize, offset + 12)
+            }
+            length => (length as usize, offset + 4),
+        };
+        let body = data.read_bytes(length).ok()?;
+        Some((id_offset, body.0, id_offset + length))
+    }
+
+    /// Parse the CIE at the given offset and return the FDE pointer encoding.
+    fn cie_encoding(&self, offset: usize) -> Option<u8> {
+        let (_, body, _) = self.entry(offset)?;
+        let mut body = Bytes(body);
+        if body.read::<U32Bytes<E>>().ok()?.get(self.endian) != 0 {
+            return None;
+        }
+        let version = *body.read::<u8>().ok()?;
+        let augmentation = body.read_string().ok()?;
+        if augmentation.windows(2).any(|w| w == b"eh") {
+            body.skip(self.address_size.into()).ok()?;
+        }
+        // Code alignment factor, data alignment factor and return address register.
+        body.read_uleb128().ok()?;
+        body.read_sleb128().ok()?;
+        if version == 1 {
+            body.read::<u8>().ok()?;
+        } else {
+            body.read_uleb128().ok()?;
+        }
+
+        let mut encoding = DW_EH_PE_ABSPTR;
+        match augmentation.split_first() {
+            None => {}
+            Some((b'z', augmentation)) => {
+                body.read_uleb128().ok()?;
+                for c in augmentation {
+                    match *c {
+                        b'R' => encoding = *body.read::<u8>().ok()?,
+                        b'P' => {
+                            let personality_encoding = *body.read::<u8>().ok()?;
+                            // Only the size of the value matters here.
+                            self.read_pointer(&mut body, personality_encoding & 0x0f, 0)?;
+                        }
+                        b'L' => {
+                            body.read::<u8>().ok()?;
+                        }
+                        b'S' | b'B' | b'G' => {}
+                        _ => return None,
+                    }
+                }
+            }
+            Some(_) => return None,
+        }
+        if encoding == DW_EH_PE_OMIT {
+            return None;
+        }
+        Some(encoding)
+    }
+
+    /// Read a pointer with the given encoding.
+    ///
+    /// `offset` is the offset of the pointer within the section, and is used
+    /// for PC-relative pointers.
+    fn read_pointer(&self, data: &mut Bytes<'data>, encoding: u8, offset: usize) -> Option<u64> {
+        if encoding & DW_EH_PE_INDIRECT != 0 {
+            return None;
+        }
+        let endian = self.endian;
+        let value = match encoding & 0x0f {
+            DW_EH_PE_ABSPTR if self.address_size == 8 => {
+                data.read::<U64Bytes<E>>().ok()?.get(endian)
+            }
+            DW_EH_PE_ABSPTR => data.read::<U32Bytes<E>>().ok()?.get(endian).into(),
+            DW_EH_PE_ULEB128 => data.read_uleb128().ok()?,
+            DW_EH_PE_UDATA2 => data.read::<U16Bytes<E>>().ok()?.get(endian).into(),
+            DW_EH_PE_UDATA4 => data.read::<U32Bytes<E>>().ok()?.get(endian).into(),
+            DW_EH_PE_UDATA8 => data.read::<U64Bytes<E>>().ok()?.get(endian),
+            DW_EH_PE_SLEB128 => data.read_sleb128().ok()? as u64,
+            DW_EH_PE_SDATA2 => {
+                i64::from(data.read::<U16Bytes<E>>().ok()?.get(endian) as i16) as u64
+            }
+            DW_EH_PE_SDATA4 => {
+                i64::from(data.read::<U32Bytes<E>>().ok()?.get(endian) as i32) as u64
+            }
+            DW_EH_PE_SDATA8 => data.read::<U64Bytes<E>>().ok()?.get(endian),
+            _ => return None,
+        };
+        match encoding & 0x70 {
+            0 => Some(value),
+            DW_EH_PE_PCREL => Some(self.address.wrapping_add(offset as u64).wrapping_add(value)),
+            // Text, data and function relative encodings are not supported.
+            _ => None,
+        }
+    }
+}
@@ -1,19 +1,23 @@
 use alloc::vec::Vec;
 use core::convert::TryInto;
 use core::fmt::Debug;
-use core::mem;
+use core::ops::Range;
+use core::{mem, result, slice};
 
 use crate::read::{
-    self, util, Architecture, ByteString, Bytes, Error, Export, FileFlags, Import, Object,
-    ObjectKind, ReadError, ReadRef, SectionIndex, StringTable, SymbolIndex,
+    self, relocate, util, Architecture, BinaryFormat, ByteString, Bytes, Error, Export, FileFlags,
+    Import, Object, ObjectKind, ObjectSymbol, ParseError, ParseOptions, ParseStage, PointerFixup,
+    PointerFixupIterator, ReadError, ReadRef, Relocation, RelocationKind, SectionIndex,
+    StringTable, SubArchitecture, SymbolIndex, TlsSymbol,
 };
-use crate::{elf, endian, Endian, Endianness, Pod, U32};
+use crate::{elf, endian, Endian, Endianness, Pod, U32Bytes, U64Bytes, U32};
 
 use super::{
-    CompressionHeader, Dyn, ElfComdat, ElfComdatIterator, ElfDynamicRelocationIterator, ElfSection,
-    ElfSectionIterator, ElfSegment, ElfSegmentIterator, ElfSymbol, ElfSymbolIterator,
-    ElfSymbolTable, NoteHeader, ProgramHeader, Rel, Rela, RelocationSections, SectionHeader,
-    SectionTable, Sym, SymbolTable,
+    CompressionHeader, Df1Flags, DfFlags, Dyn, ElfComdat, ElfComdatIterator,
+    ElfDynamicRelocationIterator, ElfRelaIterator, ElfSection, ElfSectionIterator, ElfSegment,
+    ElfSegmentIterator, ElfSymbol, ElfSymbolIterator, ElfSymbolTable, GnuHashTable,
+    GotRelocationTypes, HashTable, Note, NoteHeader, ProgramHeader, Rel, Rela, RelocationSections,
+    SectionHeader, SectionTable, Sym, SymbolTable, VersionTable,
 };
 
 /// A 32-bit ELF object file.
@@ -49,15 +53,63 @@ where
 {
     /// Parse the raw ELF file data.
     pub fn parse(data: R) -> read::Result<Self> {
-        let header = Elf::parse(data)?;
-        let endian = header.endian()?;
-        let segments = header.program_headers(endian, data)?;
-        let sections = header.sections(endian, data)?;
-        let symbols = sections.symbols(endian, data, elf::SHT_SYMTAB)?;
+        Self::parse_with_options(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw ELF file data, and check that it is within the given limits.
+    ///
+    /// The limits are checked using the section headers, before the symbol
+    /// tables and relocations are parsed.
+    pub fn parse_with_options(data: R, options: &ParseOptions) -> read::Result<Self> {
+        Self::parse_staged(data, options).map_err(|error| error.detail)
+    }
+
+    /// Parse the raw ELF file data, and include the stage of parsing in any error.
+    pub(crate) fn parse_staged(
+        data: R,
+        options: &ParseOptions,
+    ) -> result::Result<Self, ParseError> {
+        let header = Elf::parse(data).map_err(ParseStage::Header.error())?;
+        let endian = header.endian().map_err(ParseStage::Header.error())?;
+        let segments = header
+            .program_headers(endian, data)
+            .map_err(ParseStage::Segments.error())?;
+        let sections = header
+            .sections(endian, data)
+            .map_err(ParseStage::Sections.error())?;
+        options
+            .check_sections(sections.len())
+            .map_err(ParseStage::Sections.error())?;
+        let mut relocation_count = 0u64;
+        for section in sections.iter() {
+            let entry_size = match section.sh_type(endian) {
+                elf::SHT_REL => mem::size_of::<Elf::Rel>(),
+                elf::SHT_RELA => mem::size_of::<Elf::Rela>(),
+                _ => continue,
+            };
+            relocation_count =
+                relocation_count.saturating_add(section.sh_size(endian).into() / entry_size as u64);
+        }
+        options
+            .check_relocations(relocation_count)
+            .map_err(ParseStage::Sections.error())?;
+        let symbols = sections
+            .symbols(endian, data, elf::SHT_SYMTAB)
+            .map_err(ParseStage::Symbols.error())?;
+        options
+            .check_symbols(symbols.len())
+            .map_err(ParseStage::Symbols.error())?;
         // TODO: get dynamic symbols from DT_SYMTAB if there are no sections
-        let dynamic_symbols = sections.symbols(endian, data, elf::SHT_DYNSYM)?;
+        let dynamic_symbols = sections
+            .symbols(endian, data, elf::SHT_DYNSYM)
+            .map_err(ParseStage::Symbols.error())?;
+        options
+            .check_symbols(dynamic_symbols.len())
+            .map_err(ParseStage::Symbols.error())?;
         // The API we provide requires a mapping from section to relocations, so build it now.
-        let relocations = sections.relocation_sections(endian, symbols.section())?;
+        let relocations = sections
+            .relocation_sections(endian, symbols.section())
+            .map_err(ParseStage::Sections.error())?;
 
         Ok(ElfFile {
             endian,
@@ -91,6 +143,1000 @@ where
         self.segments
     }
 
+    /// Returns an iterator over the relocation sections.
+    ///
+    /// These are the sections with a type of `SHT_REL`, `SHT_RELA`, or `SHT_RELR`.
+    pub fn relocation_sections<'file>(
+        &'file self,
+    ) -> impl Iterator<Item = ElfSection<'data, 'file, Elf, R>> + 'file {
+        ElfSectionIterator {
+            file: self,
+            iter: self.sections.iter().enumerate(),
+        }
+        .filter(|section| section.sh_type_enum().is_relocation())
+    }
+
+    /// Returns an iterator over the dynamic symbols that are imported.
+    ///
+    /// These are the symbols with a section index of `SHN_UNDEF`, excluding
+    /// the null symbol.
+    pub fn dynamic_imports<'file>(
+        &'file self,
+    ) -> impl Iterator<Item = ElfSymbol<'data, 'file, Elf, R>> + 'file {
+        self.dynamic_symbols()
+            .filter(|symbol| symbol.index().0 != 0 && symbol.is_undefined())
+    }
+
+    /// Returns an iterator over the dynamic symbols that are exported.
+    ///
+    /// These are the defined symbols with a binding of `STB_GLOBAL`, `STB_WEAK`
+    /// or `STB_GNU_UNIQUE`, and a visibility of `STV_DEFAULT` or `STV_PROTECTED`.
+    pub fn dynamic_exports<'file>(
+        &'file self,
+    ) -> impl Iterator<Item = ElfSymbol<'data, 'file, Elf, R>> + 'file {
+        self.dynamic_symbols().filter(|symbol| {
+            !symbol.is_undefined()
+                && match symbol.binding() {
+                    elf::STB_GLOBAL | elf::STB_WEAK | elf::STB_GNU_UNIQUE => true,
+                    _ => false,
+                }
+                && match symbol.visibility() {
+                    elf::STV_DEFAULT | elf::STV_PROTECTED => true,
+                    _ => false,
+                }
+        })
+    }
+
+    /// Return the lowest virtual address of the `PT_LOAD` segments, or 0 if there are none.
+    fn lowest_load_address(&self) -> u64 {
+        self.segments
+            .iter()
+            .filter(|segment| segment.p_type(self.endian) == elf::PT_LOAD)
+            .map(|segment| segment.p_vaddr(self.endian).into())
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Decode the pointer fixups in the data of a `SHT_RELR` section.
+    fn relr_fixups(&self, data: &'data [u8], fixups: &mut Vec<PointerFixup>) -> read::Result<()> {
+        let pointer_size = if self.is_64() { 8 } else { 4 };
+        let mut data = Bytes(data);
+        let mut base = 0u64;
+        while !data.is_empty() {
+            let entry = if self.is_64() {
+                data.read::<U64Bytes<Elf::Endian>>()
+                    .read_error("Invalid ELF RELR entry")?
+                    .get(self.endian)
+            } else {
+                data.read::<U32Bytes<Elf::Endian>>()
+                    .read_error("Invalid ELF RELR entry")?
+                    .get(self.endian)
+                    .into()
+            };
+            if entry & 1 == 0 {
+                // An address entry.
+                fixups.push(PointerFixup {
+                    address: entry,
+                    size: pointer_size,
+                });
+                base = entry.wrapping_add(pointer_size.into());
+            } else {
+                // A bitmap entry, where each bit after the first is a pointer
+                // following the base.
+                let bits = u64::from(pointer_size) * 8 - 1;
+                for i in 0..bits {
+                    if (entry >> (i + 1)) & 1 != 0 {
+                        fixups.push(PointerFixup {
+                            address: base.wrapping_add(i * u64::from(pointer_size)),
+                            size: pointer_size,
+                        });
+                    }
+                }
+                base = base.wrapping_add(bits * u64::from(pointer_size));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the OS/ABI from the `EI_OSABI` byte of the file identification.
+    pub fn os_abi(&self) -> OsAbi {
+        OsAbi::from(self.header.e_ident().os_abi)
+    }
+
+    /// Returns the ABI version from the `EI_ABIVERSION` byte of the file identification.
+    ///
+    /// The meaning of this value depends on [`Self::os_abi`].
+    pub fn abi_version(&self) -> u8 {
+        self.header.e_ident().abi_version
+    }
+
+    /// Returns the processor specific `e_flags` field of the file header.
+    ///
+    /// This is named to avoid conflicting with [`Object::flags`].
+    pub fn e_flags(&self) -> u32 {
+        self.header.e_flags(self.endian)
+    }
+
+    /// Returns the ARM EABI version from the `EF_ARM_EABIMASK` bits of `e_flags`.
+    ///
+    /// Returns `None` if the file is not for ARM, or the EABI version is unknown.
+    pub fn arm_eabi_version(&self) -> Option<u8> {
+        if self.header.e_machine(self.endian) != elf::EM_ARM {
+            return None;
+        }
+        match (self.e_flags() & elf::EF_ARM_EABIMASK) >> 24 {
+            0 => None,
+            version => Some(version as u8),
+        }
+    }
+
+    /// Returns the MIPS ABI from the `e_flags` field.
+    ///
+    /// If no ABI flags are set, then the ABI is implied by the ELF class.
+    ///
+    /// Returns `None` if the file is not for MIPS, or the ABI is unknown.
+    pub fn mips_abi(&self) -> Option<MipsAbi> {
+        if self.header.e_machine(self.endian) != elf::EM_MIPS {
+            return None;
+        }
+        let flags = self.e_flags();
+        if flags & elf::EF_MIPS_ABI2 != 0 {
+            return Some(MipsAbi::N32);
+        }
+        match flags & elf::EF_MIPS_ABI {
+            elf::EF_MIPS_ABI_O32 => Some(MipsAbi::O32),
+            elf::EF_MIPS_ABI_O64 => Some(MipsAbi::O64),
+            elf::EF_MIPS_ABI_EABI32 => Some(MipsAbi::Eabi32),
+            elf::EF_MIPS_ABI_EABI64 => Some(MipsAbi::Eabi64),
+            0 if self.header.is_class_64() => Some(MipsAbi::N64),
+            0 => Some(MipsAbi::O32),
+            _ => None,
+        }
+    }
+
+    /// Returns the MIPS register usage information.
+    ///
+    /// This is read from the section of type `SHT_MIPS_REGINFO` if present, or
+    /// otherwise from the first `ODK_REGINFO` entry in the section of type
+    /// `SHT_MIPS_OPTIONS`.
+    ///
+    /// Returns `Ok(None)` if the file is not for MIPS, or neither section contains
+    /// register usage information.
+    pub fn mips_reginfo(&self) -> read::Result<Option<MipsRegInfo>> {
+        let endian = self.endian;
+        if self.header.e_machine(endian) != elf::EM_MIPS {
+            return Ok(None);
+        }
+        if let Some(section) = self
+            .sections
+            .iter()
+            .find(|section| section.sh_type(endian) == elf::SHT_MIPS_REGINFO)
+        {
+            let data = section
+                .data(endian, self.data)
+                .read_error("Invalid ELF .reginfo section offset or size")?;
+            return self.parse_mips_reginfo(Bytes(data)).map(Some);
+        }
+        if let Some(section) = self
+            .sections
+            .iter()
+            .find(|section| section.sh_type(endian) == elf::SHT_MIPS_OPTIONS)
+        {
+            let data = section
+                .data(endian, self.data)
+                .read_error("Invalid ELF .MIPS.options section offset or size")?;
+            let mut data = Bytes(data);
+            while !data.is_empty() {
+                let mut descriptor = data;
+                let option = descriptor
+                    .read::<elf::ElfOptions<Elf::Endian>>()
+                    .read_error("Invalid ELF .MIPS.options descriptor")?;
+                let size = usize::from(option.size);
+                if size < mem::size_of::<elf::ElfOptions<Elf::Endian>>() {
+                    return Err(Error("Invalid ELF .MIPS.options descriptor size"));
+                }
+                if u32::from(option.kind) == elf::ODK_REGINFO {
+                    return self.parse_mips_reginfo(descriptor).map(Some);
+                }
+                data.skip(size)
+                    .read_error("Invalid ELF .MIPS.options descriptor size")?;
+            }
+        }
+        Ok(None)
+    }
+
+    fn parse_mips_reginfo(&self, data: Bytes<'data>) -> read::Result<MipsRegInfo> {
+        let endian = self.endian;
+        if self.header.is_class_64() {
+            let reginfo = data
+                .read_at::<elf::Elf64RegInfo<Elf::Endian>>(0)
+                .read_error("Invalid ELF MIPS register usage information")?;
+            Ok(MipsRegInfo {
+                gprmask: reginfo.ri_gprmask.get(endian),
+                cprmask: [
+                    reginfo.ri_cprmask[0].get(endian),
+                    reginfo.ri_cprmask[1].get(endian),
+                    reginfo.ri_cprmask[2].get(endian),
+                    reginfo.ri_cprmask[3].get(endian),
+                ],
+                gp_value: reginfo.ri_gp_value.get(endian) as u64,
+            })
+        } else {
+            let reginfo = data
+                .read_at::<elf::Elf32RegInfo<Elf::Endian>>(0)
+                .read_error("Invalid ELF MIPS register usage information")?;
+            Ok(MipsRegInfo {
+                gprmask: reginfo.ri_gprmask.get(endian),
+                cprmask: [
+                    reginfo.ri_cprmask[0].get(endian),
+                    reginfo.ri_cprmask[1].get(endian),
+                    reginfo.ri_cprmask[2].get(endian),
+                    reginfo.ri_cprmask[3].get(endian),
+                ],
+                gp_value: reginfo.ri_gp_value.get(endian) as u32 as u64,
+            })
+        }
+    }
+
+    /// Returns the path of the program interpreter from the `PT_INTERP` segment.
+    ///
+    /// This is the dynamic linker that is used to load the file.
+    ///
+    /// Returns `Ok(None)` if there is no `PT_INTERP` segment.
+    pub fn interpreter(&self) -> read::Result<Option<&'data [u8]>> {
+        for segment in self.segments {
+            if let Some(interpreter) = segment.interpreter(self.endian, self.data)? {
+                return Ok(Some(interpreter));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the address range of the `PT_GNU_RELRO` segment.
+    ///
+    /// This is the region that the dynamic linker makes read-only after relocation.
+    ///
+    /// Returns `Ok(None)` if there is no `PT_GNU_RELRO` segment.
+    pub fn relro_range(&self) -> read::Result<Option<Range<u64>>> {
+        for segment in self.segments {
+            if segment.p_type(self.endian) == elf::PT_GNU_RELRO {
+                let start: u64 = segment.p_vaddr(self.endian).into();
+                let size: u64 = segment.p_memsz(self.endian).into();
+                let end = start
+                    .checked_add(size)
+                    .read_error("Invalid ELF PT_GNU_RELRO size")?;
+                return Ok(Some(start..end));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns true if the `PT_GNU_RELRO` segment covers the entire GOT.
+    ///
+    /// This is a heuristic for full RELRO. With partial RELRO, the part of the GOT
+    /// that is used for lazy binding of PLT entries remains writable.
+    ///
+    /// The PLT GOT is located using the `.got.plt` section, or the `DT_PLTGOT` dynamic
+    /// entry if there are no section headers. If there is no PLT GOT, then the file is
+    /// considered to have full RELRO if there are no `DT_JMPREL` relocations.
+    pub fn relro_is_full(&self) -> bool {
+        let relro = match self.relro_range() {
+            Ok(Some(relro)) => relro,
+            _ => return false,
+        };
+        let got = match self.sections.section_by_name(self.endian, b".got.plt") {
+            Some((_, section)) => {
+                let address: u64 = section.sh_addr(self.endian).into();
+                let size: u64 = section.sh_size(self.endian).into();
+                Some((address, address.wrapping_add(size)))
+            }
+            None => match self.dynamic_value(elf::DT_PLTGOT) {
+                Ok(Some(address)) => Some((address, address)),
+                Ok(None) => None,
+                Err(_) => return false,
+            },
+        };
+        match got {
+            Some((start, end)) => {
+                start >= relro.start && start < relro.end && end >= start && end <= relro.end
+            }
+            None => match self.dynamic_value(elf::DT_JMPREL) {
+                Ok(jmprel) => jmprel.is_none(),
+                Err(_) => false,
+            },
+        }
+    }
+
+    /// Returns the producer strings in the `.comment` section.
+    ///
+    /// These identify the tools that produced the file, such as `GCC: (GNU) 13.2.0`.
+    /// Empty strings are skipped, and an unterminated final string is included.
+    ///
+    /// Returns `Ok(None)` if there is no `.comment` section.
+    pub fn comment(&self) -> read::Result<Option<impl Iterator<Item = &'data [u8]>>> {
+        let (_, section) = match self.sections.section_by_name(self.endian, b".comment") {
+            Some(section) => section,
+            None => return Ok(None),
+        };
+        let data = section
+            .data(self.endian, self.data)
+            .read_error("Invalid ELF .comment section offset or size")?;
+        Ok(Some(
+            data.split(|&byte| byte == 0)
+                .filter(|string| !string.is_empty()),
+        ))
+    }
+
+    /// Returns the relocations for the PLT entries.
+    ///
+    /// These are the `R_*_JUMP_SLOT` relocations in the table located by the
+    /// `DT_JMPREL` and `DT_PLTRELSZ` dynamic entries.
+    ///
+    /// Returns the address of each GOT entry and the name of the symbol it is bound to.
+    /// Returns an empty list if there is no PLT relocation table, or if the
+    /// architecture is not supported by [`GotRelocationTypes`].
+    pub fn plt_relocations(&self) -> read::Result<Vec<(u64, &'data [u8])>> {
+        let r_type = match GotRelocationTypes::from_machine(self.header.e_machine(self.endian)) {
+            Some(types) => types.jump_slot,
+            None => return Ok(Vec::new()),
+        };
+        let relocations = match self.dynamic_relocation_table(elf::DT_JMPREL, elf::DT_PLTRELSZ)? {
+            Some(relocations) => relocations,
+            None => return Ok(Vec::new()),
+        };
+        self.got_entries(relocations, r_type)
+    }
+
+    /// Returns the `R_*_GLOB_DAT` relocations for the GOT entries.
+    ///
+    /// These are located using the `DT_RELA` and `DT_RELASZ` dynamic entries,
+    /// or the `DT_REL` and `DT_RELSZ` dynamic entries.
+    ///
+    /// Returns the address of each GOT entry and the name of the symbol it is bound to.
+    /// Returns an empty list if there is no relocation table, or if the
+    /// architecture does not use `R_*_GLOB_DAT` relocations.
+    pub fn got_relocations(&self) -> read::Result<Vec<(u64, &'data [u8])>> {
+        let r_type = match GotRelocationTypes::from_machine(self.header.e_machine(self.endian))
+            .and_then(|types| types.glob_dat)
+        {
+            Some(r_type) => r_type,
+            None => return Ok(Vec::new()),
+        };
+        let mut entries = Vec::new();
+        if let Some(relocations) = self.dynamic_relocation_table(elf::DT_RELA, elf::DT_RELASZ)? {
+            entries.extend(self.got_entries(relocations, r_type)?);
+        }
+        if let Some(relocations) = self.dynamic_relocation_table(elf::DT_REL, elf::DT_RELSZ)? {
+            entries.extend(self.got_entries(relocations, r_type)?);
+        }
+        Ok(entries)
+    }
+
+    /// Returns the `R_*_IRELATIVE` relocations for the GOT entries of indirect functions.
+    ///
+    /// These are located using the `DT_RELA`, `DT_REL` and `DT_JMPREL` dynamic entries.
+    /// If there are no dynamic entries, such as for statically linked executables,
+    /// then the allocated `SHT_RELA` and `SHT_REL` sections are used instead.
+    ///
+    /// Returns the address of each GOT entry and the address of the resolver function
+    /// that is called at load time to determine its value, sorted by GOT entry address.
+    /// The resolver address is the relocation addend, which for `SHT_REL` relocations
+    /// is read from the GOT entry.
+    /// Returns an empty list if there are no relocations, or if the
+    /// architecture does not use `R_*_IRELATIVE` relocations.
+    pub fn ifunc_relocations(&self) -> read::Result<Vec<(u64, u64)>> {
+        let r_type = match GotRelocationTypes::from_machine(self.header.e_machine(self.endian))
+            .and_then(|types| types.irelative)
+        {
+            Some(r_type) => r_type,
+            None => return Ok(Vec::new()),
+        };
+        let mut entries = Vec::new();
+        if self.dynamic_entries()?.is_some() {
+            for &(address_tag, size_tag) in &[
+                (elf::DT_RELA, elf::DT_RELASZ),
+                (elf::DT_REL, elf::DT_RELSZ),
+                (elf::DT_JMPREL, elf::DT_PLTRELSZ),
+            ] {
+                if let Some(relocations) = self.dynamic_relocation_table(address_tag, size_tag)? {
+                    self.ifunc_entries(relocations, r_type, &mut entries)?;
+                }
+            }
+        } else {
+            for section in self.sections.iter() {
+                if section.sh_flags(self.endian).into() & u64::from(elf::SHF_ALLOC) == 0 {
+                    continue;
+                }
+                let relocations = match section.sh_type(self.endian) {
+                    elf::SHT_REL => ElfRelaIterator::Rel(
+                        section
+                            .data_as_array(self.endian, self.data)
+                            .read_error("Invalid ELF relocation section offset or size")?
+                            .iter(),
+                    ),
+                    elf::SHT_RELA => ElfRelaIterator::Rela(
+                        section
+                            .data_as_array(self.endian, self.data)
+                            .read_error("Invalid ELF relocation section offset or size")?
+                            .iter(),
+                    ),
+                    _ => continue,
+                };
+                self.ifunc_entries(relocations, r_type, &mut entries)?;
+            }
+        }
+        // The `DT_RELA` table may include the `DT_JMPREL` table.
+        entries.sort_unstable();
+        entries.dedup();
+        Ok(entries)
+    }
+
+    /// Appends the GOT entry address and resolver address of the relocations with the given type.
+    fn ifunc_entries(
+        &self,
+        relocations: ElfRelaIterator<'data, Elf>,
+        r_type: u32,
+        entries: &mut Vec<(u64, u64)>,
+    ) -> read::Result<()> {
+        let is_mips64el = self.header.is_mips64el(self.endian);
+        let is_rel = relocations.is_rel();
+        let word_size = if self.is_64() { 8 } else { 4 };
+        for reloc in relocations {
+            if reloc.r_type(self.endian, is_mips64el) != r_type {
+                continue;
+            }
+            let address = reloc.r_offset(self.endian).into();
+            let resolver = if is_rel {
+                let mut data = self
+                    .address_data(address, word_size)?
+                    .map(Bytes)
+                    .read_error("Invalid ELF IRELATIVE relocation address")?;
+                if self.is_64() {
+                    data.read::<U64Bytes<Elf::Endian>>()
+                        .read_error("Invalid ELF IRELATIVE relocation address")?
+                        .get(self.endian)
+                } else {
+                    data.read::<U32Bytes<Elf::Endian>>()
+                        .read_error("Invalid ELF IRELATIVE relocation address")?
+                        .get(self.endian)
+                        .into()
+                }
+            } else {
+                reloc.r_addend(self.endian).into() as u64
+            };
+            entries.push((address, resolver));
+        }
+        Ok(())
+    }
+
+    /// Returns the dynamic entries.
+    fn dynamic_entries(&self) -> read::Result<Option<&'data [Elf::Dyn]>> {
+        if let Some((dynamic, _)) = self.sections.dynamic(self.endian, self.data)? {
+            return Ok(Some(dynamic));
+        }
+        for segment in self.segments {
+            if let Some(dynamic) = segment.dynamic(self.endian, self.data)? {
+                return Ok(Some(dynamic));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the relocation table located by the given address and size dynamic tags.
+    ///
+    /// The relocation format is determined by `DT_PLTREL` for `DT_JMPREL`, and by the
+    /// address tag otherwise.
+    fn dynamic_relocation_table(
+        &self,
+        address_tag: u32,
+        size_tag: u32,
+    ) -> read::Result<Option<ElfRelaIterator<'data, Elf>>> {
+        let dynamic = match self.dynamic_entries()? {
+            Some(dynamic) => dynamic,
+            None => return Ok(None),
+        };
+        let mut address = None;
+        let mut size = None;
+        let mut is_rela = address_tag == elf::DT_RELA;
+        for d in dynamic {
+            let tag = match d.tag32(self.endian) {
+                Some(tag) => tag,
+                None => continue,
+            };
+            let val = d.d_val(self.endian).into();
+            if tag == elf::DT_NULL {
+                break;
+            } else if tag == address_tag {
+                address = Some(val);
+            } else if tag == size_tag {
+                size = Some(val);
+            } else if address_tag == elf::DT_JMPREL && tag == elf::DT_PLTREL {
+                is_rela = val == u64::from(elf::DT_RELA);
+            }
+        }
+        let (address, size) = match (address, size) {
+            (Some(address), Some(size)) if size != 0 => (address, size),
+            _ => return Ok(None),
+        };
+        let mut data = self
+            .address_data(address, size)?
+            .map(Bytes)
+            .read_error("Invalid ELF dynamic relocation address")?;
+        let relocations = if is_rela {
+            let count = data.len() / mem::size_of::<Elf::Rela>();
+            ElfRelaIterator::Rela(
+                data.read_slice(count)
+                    .read_error("Invalid ELF dynamic relocation alignment")?
+                    .iter(),
+            )
+        } else {
+            let count = data.len() / mem::size_of::<Elf::Rel>();
+            ElfRelaIterator::Rel(
+                data.read_slice(count)
+                    .read_error("Invalid ELF dynamic relocation alignment")?
+                    .iter(),
+            )
+        };
+        Ok(Some(relocations))
+    }
+
+    /// Returns the file data for the given address range in a `PT_LOAD` segment.
+    ///
+    /// Returns `Ok(None)` if no segment contains the range.
+    fn address_data(&self, address: u64, size: u64) -> read::Result<Option<&'data [u8]>> {
+        for segment in self.segments {
+            if segment.p_type(self.endian) != elf::PT_LOAD {
+                continue;
+            }
+            if let Some(data) = segment
+                .data_range(self.endian, self.data, address, size)
+                .read_error("Invalid ELF segment size or offset")?
+            {
+                return Ok(Some(data));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the file offset for the given address range in a `PT_LOAD` segment.
+    ///
+    /// Returns `None` if no segment contains the range in its file data.
+    fn address_offset(&self, address: u64, size: u64) -> Option<u64> {
+        self.segments.iter().find_map(|segment| {
+            if segment.p_type(self.endian) != elf::PT_LOAD {
+                return None;
+            }
+            let offset = address.checked_sub(segment.p_vaddr(self.endian).into())?;
+            let end = offset.checked_add(size)?;
+            if end > segment.p_filesz(self.endian).into() {
+                return None;
+            }
+            let p_offset: u64 = segment.p_offset(self.endian).into();
+            p_offset.checked_add(offset)
+        })
+    }
+
+    /// Returns the value of the first dynamic entry with the given tag.
+    fn dynamic_value(&self, tag: u32) -> read::Result<Option<u64>> {
+        let dynamic = match self.dynamic_entries()? {
+            Some(dynamic) => dynamic,
+            None => return Ok(None),
+        };
+        for d in dynamic {
+            match d.tag32(self.endian) {
+                Some(elf::DT_NULL) => break,
+                Some(t) if t == tag => return Ok(Some(d.d_val(self.endian).into())),
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the string table used by the dynamic entries.
+    ///
+    /// This uses the section linked by the `SHT_DYNAMIC` section if there is one.
+    /// Otherwise the table is located using the `DT_STRTAB` and `DT_STRSZ` dynamic entries.
+    ///
+    /// Returns `Ok(None)` if there is no dynamic string table.
+    fn dynamic_strings(&self) -> read::Result<Option<StringTable<'data, R>>> {
+        if let Some((_, link)) = self.sections.dynamic(self.endian, self.data)? {
+            return self
+                .sections
+                .strings(self.endian, self.data, link)
+                .map(Some);
+        }
+        let (strtab, strsz) = match (
+            self.dynamic_value(elf::DT_STRTAB)?,
+            self.dynamic_value(elf::DT_STRSZ)?,
+        ) {
+            (Some(strtab), Some(strsz)) => (strtab, strsz),
+            _ => return Ok(None),
+        };
+        let offset = self
+            .address_offset(strtab, strsz)
+            .read_error("Invalid ELF DT_STRTAB address or DT_STRSZ")?;
+        Ok(Some(StringTable::new(
+            self.data,
+            offset,
+            offset.wrapping_add(strsz),
+        )))
+    }
+
+    /// Returns the file data from the given address to the end of its `PT_LOAD` segment.
+    ///
+    /// Returns `Ok(None)` if no segment contains the address.
+    fn address_data_to_end(&self, address: u64) -> read::Result<Option<&'data [u8]>> {
+        for segment in self.segments {
+            if segment.p_type(self.endian) != elf::PT_LOAD {
+                continue;
+            }
+            let data = segment
+                .data(self.endian, self.data)
+                .read_error("Invalid ELF segment size or offset")?;
+            let offset = match address.checked_sub(segment.p_vaddr(self.endian).into()) {
+                Some(offset) => offset,
+                None => continue,
+            };
+            if let Some(data) = data.get(offset as usize..) {
+                if !data.is_empty() {
+                    return Ok(Some(data));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns the number of entries in the dynamic symbol table.
+    ///
+    /// This uses the `SHT_DYNSYM` section if there is one. Otherwise the count
+    /// is determined from the hash table located using the `DT_HASH` dynamic entry,
+    /// or else from the highest symbol index reachable through the chains of the
+    /// GNU hash table located using the `DT_GNU_HASH` dynamic entry.
+    ///
+    /// Returns `Ok(None)` if the count cannot be determined.
+    pub fn dynamic_symbol_count(&self) -> read::Result<Option<usize>> {
+        if !self.dynamic_symbols.is_empty() {
+            return Ok(Some(self.dynamic_symbols.len()));
+        }
+        if let Some(address) = self.dynamic_value(elf::DT_HASH)? {
+            let data = self
+                .address_data_to_end(address)?
+                .read_error("Invalid ELF DT_HASH address")?;
+            let hash = HashTable::<Elf>::parse(self.endian, data)?;
+            return Ok(Some(hash.symbol_table_length() as usize));
+        }
+        if let Some(address) = self.dynamic_value(elf::DT_GNU_HASH)? {
+            let data = self
+                .address_data_to_end(address)?
+                .read_error("Invalid ELF DT_GNU_HASH address")?;
+            let hash = GnuHashTable::<Elf>::parse(self.endian, data)?;
+            return Ok(hash
+                .symbol_table_length(self.endian)
+                .map(|length| length as usize));
+        }
+        Ok(None)
+    }
+
+    /// Returns the dynamic symbol table.
+    ///
+    /// This uses the `SHT_DYNSYM` section if there is one. Otherwise, such as for
+    /// files without section headers, the table is located using the `DT_SYMTAB`,
+    /// `DT_STRTAB` and `DT_STRSZ` dynamic entries, and the number of symbols is
+    /// determined using [`Self::dynamic_symbol_count`]. The section indices of
+    /// a table located this way are 0.
+    ///
+    /// Returns `Ok(None)` if there is no dynamic symbol table or the number of
+    /// symbols cannot be determined.
+    pub fn raw_dynamic_symbol_table(&self) -> read::Result<Option<SymbolTable<'data, Elf, R>>> {
+        if !self.dynamic_symbols.is_empty() {
+            return Ok(Some(self.dynamic_symbols));
+        }
+        let symtab = match self.dynamic_value(elf::DT_SYMTAB)? {
+            Some(symtab) => symtab,
+            None => return Ok(None),
+        };
+        let strings = match self.dynamic_strings()? {
+            Some(strings) => strings,
+            None => return Ok(None),
+        };
+        let count = match self.dynamic_symbol_count()? {
+            Some(count) => count,
+            None => return Ok(None),
+        };
+        let size = (count as u64)
+            .checked_mul(mem::size_of::<Elf::Sym>() as u64)
+            .read_error("Invalid ELF dynamic symbol count")?;
+        let symbols = self
+            .address_data(symtab, size)?
+            .map(Bytes)
+            .and_then(|data| data.read_slice_at(0, count).ok())
+            .read_error("Invalid ELF DT_SYMTAB address")?;
+        Ok(Some(SymbolTable::from_dynamic(symbols, strings)))
+    }
+
+    /// Find the dynamic symbol with the given name.
+    ///
+    /// This uses the hash table that is linked to the dynamic symbol table if there
+    /// is one, and otherwise searches all of the dynamic symbols.
+    fn dynamic_symbol_by_name(
+        &self,
+        name: &[u8],
+    ) -> read::Result<Option<(usize, &'data Elf::Sym)>> {
+        // Symbol versions are ignored, so that any symbol with the name will match.
+        let versions = VersionTable::default();
+        let link = self.dynamic_symbols.section();
+        if let Some((hash, hash_link)) = self.sections.gnu_hash(self.endian, self.data)? {
+            if hash_link == link {
+                let found = hash.find(
+                    self.endian,
+                    name,
+                    elf::gnu_hash(name),
+                    None,
+                    &self.dynamic_symbols,
+                    &versions,
+                );
+                if found.is_some() {
+                    return Ok(found);
+                }
+                // Symbols before the symbol base, such as undefined symbols,
+                // are not included in the GNU hash table.
+                return self.dynamic_symbol_scan(name, hash.symbol_base() as usize);
+            }
+        }
+        if let Some((hash, hash_link)) = self.sections.hash(self.endian, self.data)? {
+            if hash_link == link {
+                return Ok(hash.find(
+                    self.endian,
+                    name,
+                    elf::hash(name),
+                    None,
+                    &self.dynamic_symbols,
+                    &versions,
+                ));
+            }
+        }
+        self.dynamic_symbol_scan(name, self.dynamic_symbols.len())
+    }
+
+    /// Find the dynamic symbol with the given name in the first `count` symbols.
+    fn dynamic_symbol_scan(
+        &self,
+        name: &[u8],
+        count: usize,
+    ) -> read::Result<Option<(usize, &'data Elf::Sym)>> {
+        let strings = self.dynamic_symbols.strings();
+        for (index, symbol) in self.dynamic_symbols.iter().enumerate().take(count) {
+            if symbol.name(self.endian, strings)? == name {
+                return Ok(Some((index, symbol)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns an iterator over the addresses in the `.init_array` section.
+    ///
+    /// If there are no `SHT_INIT_ARRAY` sections, then the array is located using
+    /// the `DT_INIT_ARRAY` and `DT_INIT_ARRAYSZ` dynamic entries instead.
+    ///
+    /// The addresses are read from the file data without applying relocations,
+    /// so for position independent files the load bias must be added to them.
+    /// The iterator is empty if there is no array.
+    pub fn init_array(&self) -> read::Result<FunctionArrayIterator<'data, Elf, R>> {
+        self.function_array(
+            elf::SHT_INIT_ARRAY,
+            elf::DT_INIT_ARRAY,
+            elf::DT_INIT_ARRAYSZ,
+        )
+    }
+
+    /// Returns an iterator over the addresses in the `.fini_array` section.
+    ///
+    /// If there are no `SHT_FINI_ARRAY` sections, then the array is located using
+    /// the `DT_FINI_ARRAY` and `DT_FINI_ARRAYSZ` dynamic entries instead.
+    ///
+    /// The addresses are read from the file data without applying relocations,
+    /// so for position independent files the load bias must be added to them.
+    /// The iterator is empty if there is no array.
+    pub fn fini_array(&self) -> read::Result<FunctionArrayIterator<'data, Elf, R>> {
+        self.function_array(
+            elf::SHT_FINI_ARRAY,
+            elf::DT_FINI_ARRAY,
+            elf::DT_FINI_ARRAYSZ,
+        )
+    }
+
+    /// Returns the flags from the `DT_FLAGS` and `DT_FLAGS_1` dynamic entries.
+    ///
+    /// The flags are empty if the corresponding entry is missing.
+    pub fn dynamic_flags(&self) -> read::Result<(DfFlags, Df1Flags)> {
+        let flags = self.dynamic_value(elf::DT_FLAGS)?.unwrap_or(0);
+        let flags_1 = self.dynamic_value(elf::DT_FLAGS_1)?.unwrap_or(0);
+        Ok((DfFlags(flags as u32), Df1Flags(flags_1 as u32)))
+    }
+
+    /// Returns the address of the initialization function from the `DT_INIT` dynamic entry.
+    pub fn init_function(&self) -> read::Result<Option<u64>> {
+        self.dynamic_value(elf::DT_INIT)
+    }
+
+    /// Returns the address of the termination function from the `DT_FINI` dynamic entry.
+    pub fn fini_function(&self) -> read::Result<Option<u64>> {
+        self.dynamic_value(elf::DT_FINI)
+    }
+
+    /// Returns the package metadata from the `.note.package` note.
+    ///
+    /// This is the descriptor of the `NT_FDO_PACKAGING_METADATA` note, which is
+    /// a JSON object describing the package that the file belongs to.
+    /// The JSON is returned without parsing, and with any trailing null
+    /// bytes removed.
+    ///
+    /// Like the build ID, this searches the note sections, and then the
+    /// `PT_NOTE` segments.
+    pub fn package_note(&self) -> read::Result<Option<&'data [u8]>> {
+        let mut desc = match self.find_note(elf::ELF_NOTE_FDO, elf::NT_FDO_PACKAGING_METADATA)? {
+            Some(desc) => desc,
+            None => return Ok(None),
+        };
+        while let Some((0, rest)) = desc.split_last() {
+            desc = rest;
+        }
+        Ok(Some(desc))
+    }
+
+    /// Returns the build ID from the Go `.note.go.buildid` note.
+    ///
+    /// This is the descriptor of the `NT_GO_BUILD_ID` note with the `Go` name,
+    /// which is an ASCII string identifying the build. It is distinct from the
+    /// GNU build ID returned by [`Object::build_id`].
+    ///
+    /// Like the GNU build ID, this searches the note sections, and then the
+    /// `PT_NOTE` segments.
+    pub fn go_build_id(&self) -> read::Result<Option<&'data [u8]>> {
+        self.find_note(elf::ELF_NOTE_GO, elf::NT_GO_BUILD_ID)
+    }
+
+    /// Returns the descriptor of the first note with the given name and type.
+    ///
+    /// Uses section headers if present, and falls back to program headers
+    /// since the note may not have a section header.
+    ///
+    /// Trailing null bytes of the note name are ignored, since some producers
+    /// pad the name to a multiple of 4 bytes.
+    fn find_note(&self, name: &[u8], n_type: u32) -> read::Result<Option<&'data [u8]>> {
+        let endian = self.endian;
+        let matches = |note: &Note<'data, Elf>| {
+            let mut note_name = note.name();
+            while let Some((0, rest)) = note_name.split_last() {
+                note_name = rest;
+            }
+            note_name == name && note.n_type(endian) == n_type
+        };
+        for section in self.sections.iter() {
+            if let Some(mut notes) = section.notes(endian, self.data)? {
+                while let Some(note) = notes.next()? {
+                    if matches(&note) {
+                        return Ok(Some(note.desc()));
+                    }
+                }
+            }
+        }
+        for segment in self.segments {
+            if let Some(mut notes) = segment.notes(endian, self.data)? {
+                while let Some(note) = notes.next()? {
+                    if matches(&note) {
+                        return Ok(Some(note.desc()));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns a hash of the dynamic symbols of this file.
+    ///
+    /// This is the MD5 hex digest of the names of the global and weak function
+    /// and object symbols in the dynamic symbol table, both imported and exported.
+    /// The names are converted to lowercase, sorted, deduplicated, and joined with commas.
+    /// Names starting with `.` or `_` are excluded because they are usually generated by
+    /// the compiler or toolchain, as are some common libc functions.
+    ///
+    /// Returns `Ok(None)` if there are no such symbols.
+    #[cfg(feature = "hash")]
+    pub fn symhash(&self) -> read::Result<Option<alloc::string::String>> {
+        let mut names = Vec::new();
+        for symbol in self.dynamic_symbols.iter() {
+            match symbol.st_bind() {
+                elf::STB_GLOBAL | elf::STB_WEAK => {}
+                _ => continue,
+            }
+            match symbol.st_type() {
+                elf::STT_FUNC | elf::STT_OBJECT | elf::STT_NOTYPE => {}
+                _ => continue,
+            }
+            let name = symbol.name(self.endian, self.dynamic_symbols.strings())?;
+            if name.is_empty() || name.starts_with(b".") || name.starts_with(b"_") {
+                continue;
+            }
+            let name = name.to_ascii_lowercase();
+            if SYMHASH_EXCLUDED.contains(&&*name) {
+                continue;
+            }
+            names.push(name);
+        }
+        if names.is_empty() {
+            return Ok(None);
+        }
+        names.sort_unstable();
+        names.dedup();
+
+        let mut md5 = read::md5::Md5::new();
+        for (i, name) in names.iter().enumerate() {
+            if i != 0 {
+                md5.update(b",");
+            }
+            md5.update(name);
+        }
+        Ok(Some(md5.finish_hex()))
+    }
+
+    fn function_array(
+        &self,
+        sh_type: u32,
+        address_tag: u32,
+        size_tag: u32,
+    ) -> read::Result<FunctionArrayIterator<'data, Elf, R>> {
+        let mut iter = FunctionArrayIterator {
+            endian: self.endian,
+            is_64: self.is_64(),
+            data: self.data,
+            sections: self.sections.iter(),
+            sh_type,
+            array: Bytes(&[]),
+        };
+        if self
+            .sections
+            .iter()
+            .any(|section| section.sh_type(self.endian) == sh_type)
+        {
+            return Ok(iter);
+        }
+        iter.sections = [].iter();
+        let address = self.dynamic_value(address_tag)?;
+        let size = self.dynamic_value(size_tag)?;
+        if let (Some(address), Some(size)) = (address, size) {
+            let data = self
+                .address_data(address, size)?
+                .read_error("Invalid ELF dynamic function array address")?;
+            iter.array = Bytes(data);
+        }
+        Ok(iter)
+    }
+
+    /// Returns the GOT entry address and symbol name of the relocations with the given type.
+    fn got_entries(
+        &self,
+        relocations: ElfRelaIterator<'data, Elf>,
+        r_type: u32,
+    ) -> read::Result<Vec<(u64, &'data [u8])>> {
+        let is_mips64el = self.header.is_mips64el(self.endian);
+        let mut entries = Vec::new();
+        for reloc in relocations {
+            if reloc.r_type(self.endian, is_mips64el) != r_type {
+                continue;
+            }
+            let name = match reloc.r_sym(self.endian, is_mips64el) {
+                0 => &[][..],
+                index => self
+                    .dynamic_symbols
+                    .symbol(index as usize)?
+                    .name(self.endian, self.dynamic_symbols.strings())?,
+            };
+            entries.push((reloc.r_offset(self.endian).into(), name));
+        }
+        Ok(entries)
+    }
+
     fn raw_section_by_name<'file>(
         &'file self,
         section_name: &[u8],
@@ -127,6 +1173,192 @@ where
     }
 }
 
+/// The OS/ABI of an ELF file.
+///
+/// Returned by [`ElfFile::os_abi`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OsAbi {
+    /// `ELFOSABI_SYSV`
+    SysV,
+    /// `ELFOSABI_HPUX`
+    HpUx,
+    /// `ELFOSABI_NETBSD`
+    NetBsd,
+    /// `ELFOSABI_LINUX`
+    Linux,
+    /// `ELFOSABI_HURD`
+    Hurd,
+    /// `ELFOSABI_SOLARIS`
+    Solaris,
+    /// `ELFOSABI_AIX`
+    Aix,
+    /// `ELFOSABI_IRIX`
+    Irix,
+    /// `ELFOSABI_FREEBSD`
+    FreeBsd,
+    /// `ELFOSABI_TRU64`
+    Tru64,
+    /// `ELFOSABI_MODESTO`
+    Modesto,
+    /// `ELFOSABI_OPENBSD`
+    OpenBsd,
+    /// `ELFOSABI_OPENVMS`
+    OpenVms,
+    /// `ELFOSABI_NSK`
+    Nsk,
+    /// `ELFOSABI_AROS`
+    Aros,
+    /// `ELFOSABI_FENIXOS`
+    FenixOs,
+    /// `ELFOSABI_CLOUDABI`
+    CloudAbi,
+    /// `ELFOSABI_ARM_AEABI`
+    ArmAeabi,
+    /// `ELFOSABI_ARM`
+    Arm,
+    /// `ELFOSABI_STANDALONE`
+    Standalone,
+    /// An unknown OS/ABI value.
+    Unknown(u8),
+}
+
+impl From<u8> for OsAbi {
+    fn from(os_abi: u8) -> Self {
+        match os_abi {
+            elf::ELFOSABI_SYSV => OsAbi::SysV,
+            elf::ELFOSABI_HPUX => OsAbi::HpUx,
+            elf::ELFOSABI_NETBSD => OsAbi::NetBsd,
+            elf::ELFOSABI_LINUX => OsAbi::Linux,
+            elf::ELFOSABI_HURD => OsAbi::Hurd,
+            elf::ELFOSABI_SOLARIS => OsAbi::Solaris,
+            elf::ELFOSABI_AIX => OsAbi::Aix,
+            elf::ELFOSABI_IRIX => OsAbi::Irix,
+            elf::ELFOSABI_FREEBSD => OsAbi::FreeBsd,
+            elf::ELFOSABI_TRU64 => OsAbi::Tru64,
+            elf::ELFOSABI_MODESTO => OsAbi::Modesto,
+            elf::ELFOSABI_OPENBSD => OsAbi::OpenBsd,
+            elf::ELFOSABI_OPENVMS => OsAbi::OpenVms,
+            elf::ELFOSABI_NSK => OsAbi::Nsk,
+            elf::ELFOSABI_AROS => OsAbi::Aros,
+            elf::ELFOSABI_FENIXOS => OsAbi::FenixOs,
+            elf::ELFOSABI_CLOUDABI => OsAbi::CloudAbi,
+            elf::ELFOSABI_ARM_AEABI => OsAbi::ArmAeabi,
+            elf::ELFOSABI_ARM => OsAbi::Arm,
+            elf::ELFOSABI_STANDALONE => OsAbi::Standalone,
+            _ => OsAbi::Unknown(os_abi),
+        }
+    }
+}
+
+/// The ABI of a MIPS ELF file.
+///
+/// Returned by [`ElfFile::mips_abi`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MipsAbi {
+    /// The 32-bit O32 ABI.
+    O32,
+    /// The 64-bit O64 ABI.
+    O64,
+    /// The N32 ABI, using 64-bit registers and 32-bit pointers.
+    N32,
+    /// The 64-bit N64 ABI.
+    N64,
+    /// The 32-bit EABI.
+    Eabi32,
+    /// The 64-bit EABI.
+    Eabi64,
+}
+
+/// The register usage information of a MIPS ELF file.
+///
+/// Returned by [`ElfFile::mips_reginfo`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MipsRegInfo {
+    /// The mask of general registers used (`ri_gprmask`).
+    pub gprmask: u32,
+    /// The masks of coprocessor registers used (`ri_cprmask`).
+    pub cprmask: [u32; 4],
+    /// The value of the `$gp` register (`ri_gp_value`).
+    pub gp_value: u64,
+}
+
+/// An iterator over the addresses in an ELF function pointer array.
+///
+/// Returned by [`ElfFile::init_array`] and [`ElfFile::fini_array`].
+#[derive(Debug, Clone)]
+pub struct FunctionArrayIterator<'data, Elf, R = &'data [u8]>
+where
+    Elf: FileHeader,
+    R: ReadRef<'data>,
+{
+    endian: Elf::Endian,
+    is_64: bool,
+    data: R,
+    /// The remaining sections to search for arrays.
+    sections: slice::Iter<'data, Elf::SectionHeader>,
+    sh_type: u32,
+    /// The remaining data of the current array.
+    array: Bytes<'data>,
+}
+
+impl<'data, Elf, R> FunctionArrayIterator<'data, Elf, R>
+where
+    Elf: FileHeader,
+    R: ReadRef<'data>,
+{
+    /// Return the next address in the array.
+    ///
+    /// Relocatable files may contain multiple array sections, and the addresses
+    /// in all of them are returned in section order. Any trailing bytes of
+    /// a section that are too short for an address are ignored.
+    pub fn next(&mut self) -> read::Result<Option<u64>> {
+        let size = if self.is_64 { 8 } else { 4 };
+        while self.array.len() < size {
+            let section = loop {
+                match self.sections.next() {
+                    Some(section) if section.sh_type(self.endian) == self.sh_type => break section,
+                    Some(_) => {}
+                    None => {
+                        self.array = Bytes(&[]);
+                        return Ok(None);
+                    }
+                }
+            };
+            let data = section
+                .data(self.endian, self.data)
+                .read_error("Invalid ELF function array section offset or size")?;
+            self.array = Bytes(data);
+        }
+        let address = if self.is_64 {
+            self.array
+                .read::<U64Bytes<Elf::Endian>>()
+                .read_error("Invalid ELF function array size")?
+                .get(self.endian)
+        } else {
+            self.array
+                .read::<U32Bytes<Elf::Endian>>()
+                .read_error("Invalid ELF function array size")?
+                .get(self.endian)
+                .into()
+        };
+        Ok(Some(address))
+    }
+}
+
+/// Common libc symbols that are excluded from [`ElfFile::symhash`].
+#[cfg(feature = "hash")]
+const SYMHASH_EXCLUDED: &[&[u8]] = &[
+    b"main",
+    b"abort",
+    b"cachectl",
+    b"cacheflush",
+    b"puts",
+    b"atol",
+    b"malloc_trim",
+];
+
 impl<'data, Elf, R> read::private::Sealed for ElfFile<'data, Elf, R>
 where
     Elf: FileHeader,
@@ -151,6 +1383,11 @@ where
     type SymbolTable = ElfSymbolTable<'data, 'file, Elf, R>;
     type DynamicRelocationIterator = ElfDynamicRelocationIterator<'data, 'file, Elf, R>;
 
+    #[inline]
+    fn format(&self) -> BinaryFormat {
+        BinaryFormat::Elf
+    }
+
     fn architecture(&self) -> Architecture {
         match (
             self.header.e_machine(self.endian),
@@ -180,6 +1417,28 @@ where
         }
     }
 
+    fn sub_architecture(&self) -> Option<SubArchitecture> {
+        let flags = self.header.e_flags(self.endian);
+        match self.header.e_machine(self.endian) {
+            elf::EM_ARM => self.arm_eabi_version().map(SubArchitecture::ArmEabi),
+            elf::EM_MIPS => self.mips_abi().map(|abi| match abi {
+                MipsAbi::O32 => SubArchitecture::MipsO32,
+                MipsAbi::O64 => SubArchitecture::MipsO64,
+                MipsAbi::N32 => SubArchitecture::MipsN32,
+                MipsAbi::N64 => SubArchitecture::MipsN64,
+                MipsAbi::Eabi32 => SubArchitecture::MipsEabi32,
+                MipsAbi::Eabi64 => SubArchitecture::MipsEabi64,
+            }),
+            elf::EM_RISCV => match flags & elf::EF_RISCV_FLOAT_ABI {
+                elf::EF_RISCV_FLOAT_ABI_SOFT => Some(SubArchitecture::RiscvSoftFloat),
+                elf::EF_RISCV_FLOAT_ABI_SINGLE => Some(SubArchitecture::RiscvSingleFloat),
+                elf::EF_RISCV_FLOAT_ABI_DOUBLE => Some(SubArchitecture::RiscvDoubleFloat),
+                _ => Some(SubArchitecture::RiscvQuadFloat),
+            },
+            _ => None,
+        }
+    }
+
     #[inline]
     fn is_little_endian(&self) -> bool {
         self.header.is_little_endian()
@@ -242,6 +1501,11 @@ where
         }
     }
 
+    #[inline]
+    fn symbol_count(&'file self) -> usize {
+        self.symbols.len()
+    }
+
     fn symbol_by_index(
         &'file self,
         index: SymbolIndex,
@@ -281,6 +1545,25 @@ where
         }
     }
 
+    fn symbol_by_name_bytes(
+        &'file self,
+        name: &[u8],
+    ) -> read::Result<Option<ElfSymbol<'data, 'file, Elf, R>>> {
+        for symbol in self.symbols() {
+            if symbol.name_bytes()? == name {
+                return Ok(Some(symbol));
+            }
+        }
+        Ok(self
+            .dynamic_symbol_by_name(name)?
+            .map(|(index, symbol)| ElfSymbol {
+                endian: self.endian,
+                symbols: &self.dynamic_symbols,
+                index: SymbolIndex(index),
+                symbol,
+            }))
+    }
+
     fn dynamic_symbol_table(&'file self) -> Option<ElfSymbolTable<'data, 'file, Elf, R>> {
         if self.dynamic_symbols.is_empty() {
             return None;
@@ -335,46 +1618,149 @@ where
         Ok(exports)
     }
 
-    fn has_debug_symbols(&self) -> bool {
-        for section in self.sections.iter() {
-            if let Ok(name) = self.sections.section_name(self.endian, section) {
-                if name == b".debug_info" || name == b".zdebug_info" {
-                    return true;
+    fn needed_libraries(&self) -> read::Result<Vec<&'data [u8]>> {
+        let mut libraries = Vec::new();
+        let dynamic = match self.dynamic_entries()? {
+            Some(dynamic) => dynamic,
+            None => return Ok(libraries),
+        };
+        let strings = self
+            .dynamic_strings()?
+            .read_error("Missing ELF dynamic string table")?;
+        for d in dynamic {
+            match d.tag32(self.endian) {
+                Some(elf::DT_NULL) => break,
+                Some(elf::DT_NEEDED) => {
+                    let name = d
+                        .val32(self.endian)
+                        .and_then(|val| strings.get(val).ok())
+                        .read_error("Invalid ELF DT_NEEDED string")?;
+                    if !libraries.contains(&name) {
+                        libraries.push(name);
+                    }
                 }
+                _ => {}
             }
         }
-        false
+        Ok(libraries)
     }
 
-    fn build_id(&self) -> read::Result<Option<&'data [u8]>> {
+    fn tls_symbols(&'file self) -> read::Result<Vec<TlsSymbol<'data>>> {
+        let symbols = if self.symbols.is_empty() {
+            &self.dynamic_symbols
+        } else {
+            &self.symbols
+        };
+        let mut tls = Vec::new();
+        for (index, symbol) in symbols.iter().enumerate() {
+            if symbol.st_type() == elf::STT_TLS && !symbol.is_undefined(self.endian) {
+                tls.push(TlsSymbol {
+                    index: SymbolIndex(index),
+                    name: ByteString(symbols.symbol_name(self.endian, symbol)?),
+                    tls_offset: symbol.st_value(self.endian).into(),
+                });
+            }
+        }
+        Ok(tls)
+    }
+
+    fn functions(&'file self) -> Vec<read::Function> {
+        // The FDEs in relocatable files need relocations to be applied.
+        let starts = if self.kind() == ObjectKind::Relocatable {
+            Vec::new()
+        } else {
+            self.eh_frame_functions()
+                .into_iter()
+                .map(|(address, size)| (address, Some(size)))
+                .collect()
+        };
+        read::functions::functions(self, &starts)
+    }
+
+    fn trailing_data(&self) -> read::Result<Option<&'data [u8]>> {
         let endian = self.endian;
-        // Use section headers if present, otherwise use program headers.
+        let mut end = mem::size_of::<Elf>() as u64;
+        if !self.segments.is_empty() {
+            let phoff: u64 = self.header.e_phoff(endian).into();
+            end = end.max(phoff.saturating_add(mem::size_of_val(self.segments) as u64));
+        }
         if !self.sections.is_empty() {
-            for section in self.sections.iter() {
-                if let Some(mut notes) = section.notes(endian, self.data)? {
-                    while let Some(note) = notes.next()? {
-                        if note.name() == elf::ELF_NOTE_GNU
-                            && note.n_type(endian) == elf::NT_GNU_BUILD_ID
-                        {
-                            return Ok(Some(note.desc()));
-                        }
-                    }
-                }
+            let shoff: u64 = self.header.e_shoff(endian).into();
+            let size = self.sections.len() * mem::size_of::<Elf::SectionHeader>();
+            end = end.max(shoff.saturating_add(size as u64));
+        }
+        for section in self.sections.iter() {
+            if let Some((offset, size)) = section.file_range(endian) {
+                end = end.max(offset.saturating_add(size));
             }
-        } else {
-            for segment in self.segments {
-                if let Some(mut notes) = segment.notes(endian, self.data)? {
-                    while let Some(note) = notes.next()? {
-                        if note.name() == elf::ELF_NOTE_GNU
-                            && note.n_type(endian) == elf::NT_GNU_BUILD_ID
-                        {
-                            return Ok(Some(note.desc()));
-                        }
+        }
+        for segment in self.segments {
+            let (offset, size) = segment.file_range(endian);
+            end = end.max(offset.saturating_add(size));
+        }
+        util::trailing_data(self.data, end)
+    }
+
+    fn pointer_fixups(&'file self) -> read::Result<PointerFixupIterator> {
+        let pointer_size = if self.is_64() { 8 } else { 4 };
+        let mut fixups = Vec::new();
+        if let Some(relocations) = self.dynamic_relocations() {
+            for (address, relocation) in relocations {
+                let size = match relocation.kind() {
+                    RelocationKind::Absolute if relocation.size() == pointer_size * 8 => {
+                        pointer_size
+                    }
+                    RelocationKind::Elf(r_type)
+                        if relocate::is_elf_relative(self.architecture(), r_type) =>
+                    {
+                        pointer_size
                     }
+                    _ => continue,
+                };
+                fixups.push(PointerFixup { address, size });
+            }
+        }
+        for section in self.sections.iter() {
+            if section.sh_type(self.endian) == elf::SHT_RELR {
+                let data = section.data(self.endian, self.data)?;
+                self.relr_fixups(data, &mut fixups)?;
+            }
+        }
+        Ok(PointerFixupIterator::new(fixups))
+    }
+
+    fn all_relocations(&'file self) -> read::Result<Vec<(SectionIndex, u64, Relocation)>> {
+        let mut fixups = Vec::new();
+        for section in self.sections.iter() {
+            if section.sh_type(self.endian) == elf::SHT_RELR {
+                let data = section.data(self.endian, self.data)?;
+                self.relr_fixups(data, &mut fixups)?;
+            }
+        }
+        Ok(read::relocations::all_relocations(self, &fixups))
+    }
+
+    fn has_debug_symbols(&self) -> bool {
+        for section in self.sections.iter() {
+            if let Ok(name) = self.sections.section_name(self.endian, section) {
+                if name.starts_with(b".debug_") || name.starts_with(b".zdebug_") {
+                    return true;
                 }
             }
         }
-        Ok(None)
+        false
+    }
+
+    fn is_stripped(&self) -> bool {
+        // The dynamic symbol table is separate, so any definition counts.
+        !self.symbols.iter().any(|symbol| {
+            symbol.is_definition(self.endian)
+                && (symbol.st_bind() == elf::STB_LOCAL || symbol.st_type() == elf::STT_FUNC)
+        })
+    }
+
+    fn build_id(&self) -> read::Result<Option<&'data [u8]>> {
+        self.find_note(elf::ELF_NOTE_GNU, elf::NT_GNU_BUILD_ID)
     }
 
     fn gnu_debuglink(&self) -> read::Result<Option<(&'data [u8], u32)>> {
@@ -416,7 +1802,15 @@ where
     }
 
     fn relative_address_base(&self) -> u64 {
-        0
+        if self.header.e_type(self.endian) == elf::ET_DYN {
+            self.lowest_load_address()
+        } else {
+            0
+        }
+    }
+
+    fn preferred_address(&self) -> u64 {
+        self.lowest_load_address()
     }
 
     fn entry(&self) -> u64 {
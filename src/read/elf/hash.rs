@@ -49,6 +49,10 @@ impl<'data, Elf: FileHeader> HashTable<'data, Elf> {
         symbols: &SymbolTable<'data, Elf, R>,
         versions: &VersionTable<'data, Elf>,
     ) -> Option<(usize, &'data Elf::Sym)> {
+        if self.buckets.is_empty() {
+            return None;
+        }
+
         // Get the chain start from the bucket for this hash.
         let mut index = self.buckets[(hash as usize) % self.buckets.len()].get(endian) as usize;
         // Avoid infinite loop.
@@ -168,6 +172,9 @@ impl<'data, Elf: FileHeader> GnuHashTable<'data, Elf> {
 
         // Test against bloom filter.
         let bloom_count = self.bloom_filters.len() / mem::size_of::<Elf::Word>();
+        if bloom_count == 0 || self.buckets.is_empty() {
+            return None;
+        }
         let offset =
             ((hash / word_bits) & (bloom_count as u32 - 1)) * mem::size_of::<Elf::Word>() as u32;
         let filter = if word_bits == 64 {
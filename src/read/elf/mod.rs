@@ -32,6 +32,11 @@ pub use compression::*;
 mod note;
 pub use note::*;
 
+mod eh_frame;
+
+mod stab;
+pub use stab::*;
+
 mod hash;
 pub use hash::*;
 
@@ -72,7 +72,7 @@ pub(super) enum ElfRelaIterator<'data, Elf: FileHeader> {
 }
 
 impl<'data, Elf: FileHeader> ElfRelaIterator<'data, Elf> {
-    fn is_rel(&self) -> bool {
+    pub(super) fn is_rel(&self) -> bool {
         match self {
             ElfRelaIterator::Rel(_) => true,
             ElfRelaIterator::Rela(_) => false,
@@ -381,6 +381,12 @@ fn parse_relocation<Elf: FileHeader>(
         }
         _ => (RelocationKind::Elf(reloc.r_type(endian, false)), 0),
     };
+    let size = match kind {
+        RelocationKind::Elf(r_type) if size == 0 => {
+            elf_relocation_size(header.e_machine(endian), r_type, header.is_type_64())
+        }
+        _ => size,
+    };
     let sym = reloc.r_sym(endian, is_mips64el) as usize;
     let target = if sym == 0 {
         RelocationTarget::Absolute
@@ -397,6 +403,463 @@ fn parse_relocation<Elf: FileHeader>(
     }
 }
 
+/// Return the size in bits of the place of a relocation type that has no
+/// generic kind.
+///
+/// For instruction relocations, this is the size of the field that is modified,
+/// rather than the size of the instruction. `is_64` selects the size of word
+/// sized relocations.
+///
+/// Returns 0 if the size is unknown, or if the relocation does not modify a place.
+fn elf_relocation_size(e_machine: u16, r_type: u32, is_64: bool) -> u8 {
+    let word = if is_64 { 64 } else { 32 };
+    match e_machine {
+        elf::EM_AARCH64 => match r_type {
+            elf::R_AARCH64_GLOB_DAT
+            | elf::R_AARCH64_JUMP_SLOT
+            | elf::R_AARCH64_RELATIVE
+            | elf::R_AARCH64_TLS_DTPMOD
+            | elf::R_AARCH64_TLS_DTPREL
+            | elf::R_AARCH64_TLS_TPREL
+            | elf::R_AARCH64_IRELATIVE
+            | elf::R_AARCH64_GOTREL64 => 64,
+            elf::R_AARCH64_P32_ABS32
+            | elf::R_AARCH64_P32_GLOB_DAT
+            | elf::R_AARCH64_P32_JUMP_SLOT
+            | elf::R_AARCH64_P32_RELATIVE
+            | elf::R_AARCH64_P32_TLS_DTPMOD
+            | elf::R_AARCH64_P32_TLS_DTPREL
+            | elf::R_AARCH64_P32_TLS_TPREL
+            | elf::R_AARCH64_P32_IRELATIVE
+            | elf::R_AARCH64_GOTREL32 => 32,
+            elf::R_AARCH64_JUMP26 => 26,
+            elf::R_AARCH64_ADR_PREL_LO21
+            | elf::R_AARCH64_ADR_PREL_PG_HI21
+            | elf::R_AARCH64_ADR_PREL_PG_HI21_NC
+            | elf::R_AARCH64_ADR_GOT_PAGE
+            | elf::R_AARCH64_TLSGD_ADR_PAGE21
+            | elf::R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21
+            | elf::R_AARCH64_TLSDESC_ADR_PAGE21 => 21,
+            elf::R_AARCH64_CONDBR19
+            | elf::R_AARCH64_LD_PREL_LO19
+            | elf::R_AARCH64_GOT_LD_PREL19 => 19,
+            elf::R_AARCH64_MOVW_UABS_G0
+            | elf::R_AARCH64_MOVW_UABS_G0_NC
+            | elf::R_AARCH64_MOVW_UABS_G1
+            | elf::R_AARCH64_MOVW_UABS_G1_NC
+            | elf::R_AARCH64_MOVW_UABS_G2
+            | elf::R_AARCH64_MOVW_UABS_G2_NC
+            | elf::R_AARCH64_MOVW_UABS_G3
+            | elf::R_AARCH64_MOVW_SABS_G0
+            | elf::R_AARCH64_MOVW_SABS_G1
+            | elf::R_AARCH64_MOVW_SABS_G2 => 16,
+            elf::R_AARCH64_TSTBR14 => 14,
+            elf::R_AARCH64_ADD_ABS_LO12_NC
+            | elf::R_AARCH64_LDST8_ABS_LO12_NC
+            | elf::R_AARCH64_LDST16_ABS_LO12_NC
+            | elf::R_AARCH64_LDST32_ABS_LO12_NC
+            | elf::R_AARCH64_LDST64_ABS_LO12_NC
+            | elf::R_AARCH64_LDST128_ABS_LO12_NC
+            | elf::R_AARCH64_LD64_GOT_LO12_NC
+            | elf::R_AARCH64_TLSGD_ADD_LO12_NC
+            | elf::R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC
+            | elf::R_AARCH64_TLSLE_ADD_TPREL_HI12
+            | elf::R_AARCH64_TLSLE_ADD_TPREL_LO12
+            | elf::R_AARCH64_TLSLE_ADD_TPREL_LO12_NC
+            | elf::R_AARCH64_TLSDESC_LD64_LO12
+            | elf::R_AARCH64_TLSDESC_ADD_LO12 => 12,
+            _ => 0,
+        },
+        elf::EM_ARM => match r_type {
+            elf::R_ARM_REL32
+            | elf::R_ARM_SBREL32
+            | elf::R_ARM_TLS_DTPMOD32
+            | elf::R_ARM_TLS_DTPOFF32
+            | elf::R_ARM_TLS_TPOFF32
+            | elf::R_ARM_GLOB_DAT
+            | elf::R_ARM_JUMP_SLOT
+            | elf::R_ARM_RELATIVE
+            | elf::R_ARM_GOTOFF
+            | elf::R_ARM_GOTPC
+            | elf::R_ARM_GOT32
+            | elf::R_ARM_BASE_ABS
+            | elf::R_ARM_TARGET1
+            | elf::R_ARM_TARGET2
+            | elf::R_ARM_ABS32_NOI
+            | elf::R_ARM_REL32_NOI
+            | elf::R_ARM_GOT_PREL
+            | elf::R_ARM_TLS_GD32
+            | elf::R_ARM_TLS_LDM32
+            | elf::R_ARM_TLS_LDO32
+            | elf::R_ARM_TLS_IE32
+            | elf::R_ARM_TLS_LE32
+            | elf::R_ARM_IRELATIVE => 32,
+            elf::R_ARM_PREL31 | elf::R_ARM_SBREL31 => 31,
+            elf::R_ARM_PC24 | elf::R_ARM_PLT32 | elf::R_ARM_CALL | elf::R_ARM_JUMP24 => 24,
+            elf::R_ARM_ABS16
+            | elf::R_ARM_MOVW_ABS_NC
+            | elf::R_ARM_MOVT_ABS
+            | elf::R_ARM_MOVW_PREL_NC
+            | elf::R_ARM_MOVT_PREL
+            | elf::R_ARM_THM_MOVW_ABS_NC
+            | elf::R_ARM_THM_MOVT_ABS
+            | elf::R_ARM_THM_MOVW_PREL_NC
+            | elf::R_ARM_THM_MOVT_PREL => 16,
+            elf::R_ARM_ABS12 => 12,
+            elf::R_ARM_ABS8 => 8,
+            _ => 0,
+        },
+        elf::EM_386 => match r_type {
+            elf::R_386_GLOB_DAT
+            | elf::R_386_JMP_SLOT
+            | elf::R_386_RELATIVE
+            | elf::R_386_32PLT
+            | elf::R_386_TLS_TPOFF
+            | elf::R_386_TLS_IE
+            | elf::R_386_TLS_GOTIE
+            | elf::R_386_TLS_LE
+            | elf::R_386_TLS_GD
+            | elf::R_386_TLS_LDM
+            | elf::R_386_TLS_GD_32
+            | elf::R_386_TLS_LDM_32
+            | elf::R_386_TLS_LDO_32
+            | elf::R_386_TLS_IE_32
+            | elf::R_386_TLS_LE_32
+            | elf::R_386_TLS_DTPMOD32
+            | elf::R_386_TLS_DTPOFF32
+            | elf::R_386_TLS_TPOFF32
+            | elf::R_386_SIZE32
+            | elf::R_386_TLS_GOTDESC
+            | elf::R_386_IRELATIVE
+            | elf::R_386_GOT32X => 32,
+            _ => 0,
+        },
+        elf::EM_X86_64 => match r_type {
+            elf::R_X86_64_GLOB_DAT
+            | elf::R_X86_64_JUMP_SLOT
+            | elf::R_X86_64_RELATIVE
+            | elf::R_X86_64_IRELATIVE => word,
+            elf::R_X86_64_DTPMOD64
+            | elf::R_X86_64_DTPOFF64
+            | elf::R_X86_64_TPOFF64
+            | elf::R_X86_64_PC64
+            | elf::R_X86_64_GOTOFF64
+            | elf::R_X86_64_GOT64
+            | elf::R_X86_64_GOTPCREL64
+            | elf::R_X86_64_GOTPC64
+            | elf::R_X86_64_GOTPLT64
+            | elf::R_X86_64_PLTOFF64
+            | elf::R_X86_64_SIZE64
+            | elf::R_X86_64_RELATIVE64 => 64,
+            elf::R_X86_64_TLSGD
+            | elf::R_X86_64_TLSLD
+            | elf::R_X86_64_DTPOFF32
+            | elf::R_X86_64_GOTTPOFF
+            | elf::R_X86_64_TPOFF32
+            | elf::R_X86_64_GOTPC32
+            | elf::R_X86_64_SIZE32
+            | elf::R_X86_64_GOTPC32_TLSDESC
+            | elf::R_X86_64_GOTPCRELX
+            | elf::R_X86_64_REX_GOTPCRELX => 32,
+            _ => 0,
+        },
+        elf::EM_LOONGARCH => match r_type {
+            elf::R_LARCH_RELATIVE | elf::R_LARCH_JUMP_SLOT | elf::R_LARCH_IRELATIVE => word,
+            elf::R_LARCH_TLS_DTPMOD64 | elf::R_LARCH_TLS_DTPREL64 | elf::R_LARCH_TLS_TPREL64 => 64,
+            elf::R_LARCH_TLS_DTPMOD32 | elf::R_LARCH_TLS_DTPREL32 | elf::R_LARCH_TLS_TPREL32 => 32,
+            _ => 0,
+        },
+        elf::EM_MIPS => match r_type {
+            elf::R_MIPS_REL32 | elf::R_MIPS_GLOB_DAT | elf::R_MIPS_JUMP_SLOT => word,
+            elf::R_MIPS_GPREL32
+            | elf::R_MIPS_TLS_DTPMOD32
+            | elf::R_MIPS_TLS_DTPREL32
+            | elf::R_MIPS_TLS_TPREL32 => 32,
+            elf::R_MIPS_TLS_DTPMOD64 | elf::R_MIPS_TLS_DTPREL64 | elf::R_MIPS_TLS_TPREL64 => 64,
+            elf::R_MIPS_26 => 26,
+            elf::R_MIPS_HI16
+            | elf::R_MIPS_LO16
+            | elf::R_MIPS_GPREL16
+            | elf::R_MIPS_LITERAL
+            | elf::R_MIPS_GOT16
+            | elf::R_MIPS_PC16
+            | elf::R_MIPS_CALL16
+            | elf::R_MIPS_GOT_DISP
+            | elf::R_MIPS_GOT_PAGE
+            | elf::R_MIPS_GOT_OFST
+            | elf::R_MIPS_GOT_HI16
+            | elf::R_MIPS_GOT_LO16
+            | elf::R_MIPS_HIGHER
+            | elf::R_MIPS_HIGHEST
+            | elf::R_MIPS_CALL_HI16
+            | elf::R_MIPS_CALL_LO16
+            | elf::R_MIPS_TLS_GD
+            | elf::R_MIPS_TLS_LDM
+            | elf::R_MIPS_TLS_DTPREL_HI16
+            | elf::R_MIPS_TLS_DTPREL_LO16
+            | elf::R_MIPS_TLS_GOTTPREL
+            | elf::R_MIPS_TLS_TPREL_HI16
+            | elf::R_MIPS_TLS_TPREL_LO16 => 16,
+            _ => 0,
+        },
+        elf::EM_PPC => match r_type {
+            elf::R_PPC_REL32
+            | elf::R_PPC_UADDR32
+            | elf::R_PPC_GLOB_DAT
+            | elf::R_PPC_JMP_SLOT
+            | elf::R_PPC_RELATIVE
+            | elf::R_PPC_DTPMOD32
+            | elf::R_PPC_TPREL32
+            | elf::R_PPC_DTPREL32
+            | elf::R_PPC_IRELATIVE => 32,
+            elf::R_PPC_ADDR24 | elf::R_PPC_REL24 | elf::R_PPC_PLTREL24 => 24,
+            elf::R_PPC_ADDR16
+            | elf::R_PPC_ADDR16_LO
+            | elf::R_PPC_ADDR16_HI
+            | elf::R_PPC_ADDR16_HA
+            | elf::R_PPC_UADDR16
+            | elf::R_PPC_GOT16
+            | elf::R_PPC_GOT16_LO
+            | elf::R_PPC_GOT16_HI
+            | elf::R_PPC_GOT16_HA
+            | elf::R_PPC_REL16
+            | elf::R_PPC_REL16_LO
+            | elf::R_PPC_REL16_HI
+            | elf::R_PPC_REL16_HA => 16,
+            elf::R_PPC_ADDR14 | elf::R_PPC_REL14 => 14,
+            _ => 0,
+        },
+        elf::EM_PPC64 => match r_type {
+            elf::R_PPC64_REL64
+            | elf::R_PPC64_UADDR64
+            | elf::R_PPC64_GLOB_DAT
+            | elf::R_PPC64_JMP_SLOT
+            | elf::R_PPC64_RELATIVE
+            | elf::R_PPC64_TOC
+            | elf::R_PPC64_DTPMOD64
+            | elf::R_PPC64_TPREL64
+            | elf::R_PPC64_DTPREL64
+            | elf::R_PPC64_IRELATIVE => 64,
+            elf::R_PPC64_REL32 | elf::R_PPC64_UADDR32 => 32,
+            elf::R_PPC64_ADDR24 | elf::R_PPC64_REL24 => 24,
+            elf::R_PPC64_ADDR16
+            | elf::R_PPC64_ADDR16_LO
+            | elf::R_PPC64_ADDR16_HI
+            | elf::R_PPC64_ADDR16_HA
+            | elf::R_PPC64_ADDR16_HIGHER
+            | elf::R_PPC64_ADDR16_HIGHERA
+            | elf::R_PPC64_ADDR16_HIGHEST
+            | elf::R_PPC64_ADDR16_HIGHESTA
+            | elf::R_PPC64_UADDR16
+            | elf::R_PPC64_TOC16
+            | elf::R_PPC64_TOC16_LO
+            | elf::R_PPC64_TOC16_HI
+            | elf::R_PPC64_TOC16_HA
+            | elf::R_PPC64_ADDR16_DS
+            | elf::R_PPC64_ADDR16_LO_DS
+            | elf::R_PPC64_TOC16_DS
+            | elf::R_PPC64_TOC16_LO_DS
+            | elf::R_PPC64_REL16
+            | elf::R_PPC64_REL16_LO
+            | elf::R_PPC64_REL16_HI
+            | elf::R_PPC64_REL16_HA => 16,
+            elf::R_PPC64_ADDR14 | elf::R_PPC64_REL14 => 14,
+            _ => 0,
+        },
+        elf::EM_RISCV => match r_type {
+            elf::R_RISCV_RELATIVE | elf::R_RISCV_JUMP_SLOT => word,
+            elf::R_RISCV_TLS_DTPMOD64
+            | elf::R_RISCV_TLS_DTPREL64
+            | elf::R_RISCV_TLS_TPREL64
+            | elf::R_RISCV_ADD64
+            | elf::R_RISCV_SUB64 => 64,
+            elf::R_RISCV_TLS_DTPMOD32
+            | elf::R_RISCV_TLS_DTPREL32
+            | elf::R_RISCV_TLS_TPREL32
+            | elf::R_RISCV_ADD32
+            | elf::R_RISCV_SUB32
+            | elf::R_RISCV_SET32
+            | elf::R_RISCV_32_PCREL => 32,
+            elf::R_RISCV_HI20
+            | elf::R_RISCV_PCREL_HI20
+            | elf::R_RISCV_GOT_HI20
+            | elf::R_RISCV_TLS_GOT_HI20
+            | elf::R_RISCV_TLS_GD_HI20
+            | elf::R_RISCV_TPREL_HI20
+            | elf::R_RISCV_JAL => 20,
+            elf::R_RISCV_ADD16 | elf::R_RISCV_SUB16 | elf::R_RISCV_SET16 => 16,
+            elf::R_RISCV_LO12_I
+            | elf::R_RISCV_LO12_S
+            | elf::R_RISCV_PCREL_LO12_I
+            | elf::R_RISCV_PCREL_LO12_S
+            | elf::R_RISCV_TPREL_LO12_I
+            | elf::R_RISCV_TPREL_LO12_S
+            | elf::R_RISCV_BRANCH => 12,
+            elf::R_RISCV_RVC_JUMP => 11,
+            elf::R_RISCV_ADD8 | elf::R_RISCV_SUB8 | elf::R_RISCV_SET8 | elf::R_RISCV_RVC_BRANCH => {
+                8
+            }
+            elf::R_RISCV_SET6 | elf::R_RISCV_SUB6 => 6,
+            _ => 0,
+        },
+        elf::EM_S390 => match r_type {
+            elf::R_390_GLOB_DAT
+            | elf::R_390_JMP_SLOT
+            | elf::R_390_RELATIVE
+            | elf::R_390_IRELATIVE
+            | elf::R_390_TLS_DTPMOD
+            | elf::R_390_TLS_DTPOFF
+            | elf::R_390_TLS_TPOFF => word,
+            elf::R_390_PLT64
+            | elf::R_390_GOTPLT64
+            | elf::R_390_PLTOFF64
+            | elf::R_390_TLS_GD64
+            | elf::R_390_TLS_GOTIE64
+            | elf::R_390_TLS_LDM64
+            | elf::R_390_TLS_IE64
+            | elf::R_390_TLS_LE64
+            | elf::R_390_TLS_LDO64 => 64,
+            elf::R_390_PLT32
+            | elf::R_390_GOTPLT32
+            | elf::R_390_PLTOFF32
+            | elf::R_390_GOTPLTENT
+            | elf::R_390_TLS_GD32
+            | elf::R_390_TLS_GOTIE32
+            | elf::R_390_TLS_LDM32
+            | elf::R_390_TLS_IE32
+            | elf::R_390_TLS_IEENT
+            | elf::R_390_TLS_LE32
+            | elf::R_390_TLS_LDO32 => 32,
+            elf::R_390_20 | elf::R_390_GOT20 | elf::R_390_GOTPLT20 | elf::R_390_TLS_GOTIE20 => 20,
+            elf::R_390_GOTPLT16 | elf::R_390_PLTOFF16 => 16,
+            elf::R_390_12 | elf::R_390_GOT12 | elf::R_390_GOTPLT12 | elf::R_390_TLS_GOTIE12 => 12,
+            _ => 0,
+        },
+        elf::EM_SPARC | elf::EM_SPARC32PLUS | elf::EM_SPARCV9 => match r_type {
+            elf::R_SPARC_GLOB_DAT | elf::R_SPARC_RELATIVE => word,
+            elf::R_SPARC_64
+            | elf::R_SPARC_DISP64
+            | elf::R_SPARC_PLT64
+            | elf::R_SPARC_TLS_DTPMOD64
+            | elf::R_SPARC_TLS_DTPOFF64
+            | elf::R_SPARC_TLS_TPOFF64 => 64,
+            elf::R_SPARC_DISP32
+            | elf::R_SPARC_PLT32
+            | elf::R_SPARC_PCPLT32
+            | elf::R_SPARC_TLS_DTPMOD32
+            | elf::R_SPARC_TLS_DTPOFF32
+            | elf::R_SPARC_TLS_TPOFF32 => 32,
+            elf::R_SPARC_WDISP30 | elf::R_SPARC_WPLT30 => 30,
+            elf::R_SPARC_22
+            | elf::R_SPARC_WDISP22
+            | elf::R_SPARC_HI22
+            | elf::R_SPARC_GOT22
+            | elf::R_SPARC_PC22
+            | elf::R_SPARC_HIPLT22
+            | elf::R_SPARC_PCPLT22 => 22,
+            elf::R_SPARC_WDISP19 => 19,
+            elf::R_SPARC_16 | elf::R_SPARC_UA16 | elf::R_SPARC_DISP16 | elf::R_SPARC_WDISP16 => 16,
+            elf::R_SPARC_13 | elf::R_SPARC_GOT13 => 13,
+            elf::R_SPARC_11 => 11,
+            elf::R_SPARC_10
+            | elf::R_SPARC_LO10
+            | elf::R_SPARC_GOT10
+            | elf::R_SPARC_PC10
+            | elf::R_SPARC_LOPLT10
+            | elf::R_SPARC_PCPLT10 => 10,
+            elf::R_SPARC_8 | elf::R_SPARC_DISP8 => 8,
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+/// The relocation types that bind GOT entries to symbols for an architecture.
+///
+/// The numeric values of these relocation types differ for each architecture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GotRelocationTypes {
+    /// The `R_*_JUMP_SLOT` relocation type used for PLT entries.
+    pub jump_slot: u32,
+    /// The `R_*_GLOB_DAT` relocation type used for GOT entries.
+    ///
+    /// This is `None` for architectures that use a plain absolute relocation instead.
+    pub glob_dat: Option<u32>,
+    /// The `R_*_IRELATIVE` relocation type used for GOT entries that are filled
+    /// by calling an indirect function resolver.
+    ///
+    /// This is `None` for architectures that do not support indirect functions.
+    pub irelative: Option<u32>,
+}
+
+impl GotRelocationTypes {
+    /// Return the relocation types for the given `EM_*` machine.
+    ///
+    /// Returns `None` if the machine is not supported.
+    pub fn from_machine(e_machine: u16) -> Option<Self> {
+        let (jump_slot, glob_dat, irelative) = match e_machine {
+            elf::EM_386 => (
+                elf::R_386_JMP_SLOT,
+                Some(elf::R_386_GLOB_DAT),
+                Some(elf::R_386_IRELATIVE),
+            ),
+            elf::EM_X86_64 => (
+                elf::R_X86_64_JUMP_SLOT,
+                Some(elf::R_X86_64_GLOB_DAT),
+                Some(elf::R_X86_64_IRELATIVE),
+            ),
+            elf::EM_ARM => (
+                elf::R_ARM_JUMP_SLOT,
+                Some(elf::R_ARM_GLOB_DAT),
+                Some(elf::R_ARM_IRELATIVE),
+            ),
+            elf::EM_AARCH64 => (
+                elf::R_AARCH64_JUMP_SLOT,
+                Some(elf::R_AARCH64_GLOB_DAT),
+                Some(elf::R_AARCH64_IRELATIVE),
+            ),
+            elf::EM_68K => (elf::R_68K_JMP_SLOT, Some(elf::R_68K_GLOB_DAT), None),
+            elf::EM_ALPHA => (elf::R_ALPHA_JMP_SLOT, Some(elf::R_ALPHA_GLOB_DAT), None),
+            elf::EM_CSKY => (elf::R_CKCORE_JUMP_SLOT, Some(elf::R_CKCORE_GLOB_DAT), None),
+            elf::EM_LOONGARCH => (elf::R_LARCH_JUMP_SLOT, None, Some(elf::R_LARCH_IRELATIVE)),
+            elf::EM_MICROBLAZE => (
+                elf::R_MICROBLAZE_JUMP_SLOT,
+                Some(elf::R_MICROBLAZE_GLOB_DAT),
+                None,
+            ),
+            elf::EM_MIPS => (elf::R_MIPS_JUMP_SLOT, Some(elf::R_MIPS_GLOB_DAT), None),
+            elf::EM_PPC => (
+                elf::R_PPC_JMP_SLOT,
+                Some(elf::R_PPC_GLOB_DAT),
+                Some(elf::R_PPC_IRELATIVE),
+            ),
+            elf::EM_PPC64 => (
+                elf::R_PPC64_JMP_SLOT,
+                Some(elf::R_PPC64_GLOB_DAT),
+                Some(elf::R_PPC64_IRELATIVE),
+            ),
+            elf::EM_RISCV => (elf::R_RISCV_JUMP_SLOT, None, None),
+            elf::EM_S390 => (
+                elf::R_390_JMP_SLOT,
+                Some(elf::R_390_GLOB_DAT),
+                Some(elf::R_390_IRELATIVE),
+            ),
+            elf::EM_SH => (elf::R_SH_JMP_SLOT, Some(elf::R_SH_GLOB_DAT), None),
+            elf::EM_SPARC | elf::EM_SPARC32PLUS | elf::EM_SPARCV9 => (
+                elf::R_SPARC_JMP_SLOT,
+                Some(elf::R_SPARC_GLOB_DAT),
+                Some(elf::R_SPARC_IRELATIVE),
+            ),
+            _ => return None,
+        };
+        Some(GotRelocationTypes {
+            jump_slot,
+            glob_dat,
+            irelative,
+        })
+    }
+}
+
 /// A trait for generic access to `Rel32` and `Rel64`.
 #[allow(missing_docs)]
 pub trait Rel: Debug + Pod + Clone {
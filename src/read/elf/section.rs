@@ -1,3 +1,5 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
 use core::fmt::Debug;
 use core::{iter, mem, slice, str};
 
@@ -351,6 +353,115 @@ where
     }
 }
 
+/// The type of an ELF section.
+///
+/// This is the decoded `sh_type` field of the section header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SectionType {
+    /// `SHT_NULL`
+    Null,
+    /// `SHT_PROGBITS`
+    Progbits,
+    /// `SHT_SYMTAB`
+    Symtab,
+    /// `SHT_STRTAB`
+    Strtab,
+    /// `SHT_RELA`
+    Rela,
+    /// `SHT_HASH`
+    Hash,
+    /// `SHT_DYNAMIC`
+    Dynamic,
+    /// `SHT_NOTE`
+    Note,
+    /// `SHT_NOBITS`
+    Nobits,
+    /// `SHT_REL`
+    Rel,
+    /// `SHT_SHLIB`
+    Shlib,
+    /// `SHT_DYNSYM`
+    Dynsym,
+    /// `SHT_INIT_ARRAY`
+    InitArray,
+    /// `SHT_FINI_ARRAY`
+    FiniArray,
+    /// `SHT_PREINIT_ARRAY`
+    PreinitArray,
+    /// `SHT_GROUP`
+    Group,
+    /// `SHT_SYMTAB_SHNDX`
+    SymtabShndx,
+    /// `SHT_RELR`
+    Relr,
+    /// `SHT_GNU_ATTRIBUTES`
+    GnuAttributes,
+    /// `SHT_GNU_HASH`
+    GnuHash,
+    /// `SHT_GNU_LIBLIST`
+    GnuLiblist,
+    /// `SHT_GNU_VERDEF`
+    GnuVerdef,
+    /// `SHT_GNU_VERNEED`
+    GnuVerneed,
+    /// `SHT_GNU_VERSYM`
+    GnuVersym,
+    /// Another type in the range `SHT_LOOS` to `SHT_HIOS`.
+    OsSpecific(u32),
+    /// A type in the range `SHT_LOPROC` to `SHT_HIPROC`.
+    ///
+    /// The meaning of these types depends on the architecture.
+    ProcessorSpecific(u32),
+    /// Any other type.
+    Other(u32),
+}
+
+impl SectionType {
+    /// Decode the `sh_type` field of a section header.
+    pub fn from_sh_type(sh_type: u32) -> Self {
+        match sh_type {
+            elf::SHT_NULL => SectionType::Null,
+            elf::SHT_PROGBITS => SectionType::Progbits,
+            elf::SHT_SYMTAB => SectionType::Symtab,
+            elf::SHT_STRTAB => SectionType::Strtab,
+            elf::SHT_RELA => SectionType::Rela,
+            elf::SHT_HASH => SectionType::Hash,
+            elf::SHT_DYNAMIC => SectionType::Dynamic,
+            elf::SHT_NOTE => SectionType::Note,
+            elf::SHT_NOBITS => SectionType::Nobits,
+            elf::SHT_REL => SectionType::Rel,
+            elf::SHT_SHLIB => SectionType::Shlib,
+            elf::SHT_DYNSYM => SectionType::Dynsym,
+            elf::SHT_INIT_ARRAY => SectionType::InitArray,
+            elf::SHT_FINI_ARRAY => SectionType::FiniArray,
+            elf::SHT_PREINIT_ARRAY => SectionType::PreinitArray,
+            elf::SHT_GROUP => SectionType::Group,
+            elf::SHT_SYMTAB_SHNDX => SectionType::SymtabShndx,
+            elf::SHT_RELR => SectionType::Relr,
+            elf::SHT_GNU_ATTRIBUTES => SectionType::GnuAttributes,
+            elf::SHT_GNU_HASH => SectionType::GnuHash,
+            elf::SHT_GNU_LIBLIST => SectionType::GnuLiblist,
+            elf::SHT_GNU_VERDEF => SectionType::GnuVerdef,
+            elf::SHT_GNU_VERNEED => SectionType::GnuVerneed,
+            elf::SHT_GNU_VERSYM => SectionType::GnuVersym,
+            elf::SHT_LOOS..=elf::SHT_HIOS => SectionType::OsSpecific(sh_type),
+            elf::SHT_LOPROC..=elf::SHT_HIPROC => SectionType::ProcessorSpecific(sh_type),
+            _ => SectionType::Other(sh_type),
+        }
+    }
+
+    /// Return true if this is a relocation section type.
+    ///
+    /// This is `SHT_REL`, `SHT_RELA`, or `SHT_RELR`.
+    pub fn is_relocation(self) -> bool {
+        match self {
+            SectionType::Rel | SectionType::Rela | SectionType::Relr => true,
+            _ => false,
+        }
+    }
+}
+
 /// A section of an `ElfFile32`.
 pub type ElfSection32<'data, 'file, Endian = Endianness, R = &'data [u8]> =
     ElfSection<'data, 'file, elf::FileHeader32<Endian>, R>;
@@ -372,6 +483,34 @@ where
 }
 
 impl<'data, 'file, Elf: FileHeader, R: ReadRef<'data>> ElfSection<'data, 'file, Elf, R> {
+    /// Returns the decoded `sh_type` field of the section header.
+    pub fn sh_type_enum(&self) -> SectionType {
+        SectionType::from_sh_type(self.section.sh_type(self.file.endian))
+    }
+
+    /// Returns the name of the section, using the standard name for GNU compressed
+    /// debug sections.
+    ///
+    /// This is the same as [`ObjectSection::name`], except that the `.zdebug_` prefix
+    /// is replaced with `.debug_`. The data of these sections can be decompressed using
+    /// [`ObjectSection::uncompressed_data`].
+    pub fn logical_name(&self) -> read::Result<Cow<'data, str>> {
+        let name = self
+            .file
+            .sections
+            .section_name(self.file.endian, self.section)?;
+        let name = str::from_utf8(name)
+            .ok()
+            .read_error("Non UTF-8 ELF section name")?;
+        if name.starts_with(".zdebug_") {
+            let mut logical = String::from(".debug_");
+            logical.push_str(&name[".zdebug_".len()..]);
+            Ok(Cow::Owned(logical))
+        } else {
+            Ok(Cow::Borrowed(name))
+        }
+    }
+
     fn bytes(&self) -> read::Result<&'data [u8]> {
         self.section
             .data(self.file.endian, self.file.data)
@@ -495,6 +634,11 @@ where
         self.bytes()
     }
 
+    #[inline]
+    fn is_bss(&self) -> bool {
+        self.section.sh_type(self.file.endian) == elf::SHT_NOBITS
+    }
+
     fn data_range(&self, address: u64, size: u64) -> read::Result<Option<&'data [u8]>> {
         Ok(read::util::data_range(
             self.bytes()?,
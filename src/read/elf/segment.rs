@@ -220,6 +220,28 @@ pub trait ProgramHeader: Debug + Pod {
         Ok(Some(dynamic))
     }
 
+    /// Return the path of the program interpreter in a `PT_INTERP` segment.
+    ///
+    /// The returned path does not include the null terminator.
+    ///
+    /// Returns `Ok(None)` if the segment is not `PT_INTERP`.
+    /// Returns `Err` for invalid values, including a missing null terminator.
+    fn interpreter<'data, R: ReadRef<'data>>(
+        &self,
+        endian: Self::Endian,
+        data: R,
+    ) -> read::Result<Option<&'data [u8]>> {
+        if self.p_type(endian) != elf::PT_INTERP {
+            return Ok(None);
+        }
+        let data = self
+            .data(endian, data)
+            .read_error("Invalid ELF interpreter segment offset or size")?;
+        let len =
+            memchr::memchr(b'\0', data).read_error("Missing ELF interpreter null terminator")?;
+        Ok(Some(&data[..len]))
+    }
+
     /// Return a note iterator for the segment data.
     ///
     /// Returns `Ok(None)` if the segment does not contain notes.
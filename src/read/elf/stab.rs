@@ -0,0 +1,151 @@
+use alloc::borrow::Cow;
+
+use crate::endian::{U16Bytes, U32Bytes};
+use crate::read::{self, Bytes, ReadError, ReadRef};
+
+use super::{ElfFile, FileHeader, SectionHeader};
+
+/// The type of the header entry at the start of the entries for each compilation unit.
+const N_UNDF: u8 = 0;
+
+impl<'data, Elf, R> ElfFile<'data, Elf, R>
+where
+    Elf: FileHeader,
+    R: ReadRef<'data>,
+{
+    /// Return an iterator over the STABS debugging entries in the `.stab` section.
+    ///
+    /// The names of the entries are read from the `.stabstr` section.
+    ///
+    /// Returns `Ok(None)` if there is no `.stab` section.
+    pub fn stabs(&self) -> read::Result<Option<StabIterator<'data, Elf::Endian>>> {
+        let endian = self.endian;
+        let section = match self.sections.section_by_name(endian, b".stab") {
+            Some((_, section)) => section,
+            None => return Ok(None),
+        };
+        let data = section
+            .data(endian, self.data)
+            .read_error("Invalid ELF .stab section offset or size")?;
+        let strings = match self.sections.section_by_name(endian, b".stabstr") {
+            Some((_, section)) => section
+                .data(endian, self.data)
+                .read_error("Invalid ELF .stabstr section offset or size")?,
+            None => &[],
+        };
+        Ok(Some(StabIterator {
+            endian,
+            data: Bytes(data),
+            strings: Bytes(strings),
+            strx_base: 0,
+            next_strx_base: 0,
+        }))
+    }
+}
+
+/// A STABS debugging entry in an ELF `.stab` section.
+///
+/// Returned by [`StabIterator::next`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stab<'data> {
+    /// The name of the entry.
+    ///
+    /// A name that is continued in following entries is joined with the
+    /// names of those entries, without the continuation characters.
+    pub name: Cow<'data, [u8]>,
+    /// The `n_type` field.
+    ///
+    /// This is one of the `N_*` stab types, such as `N_FUN` or `N_SLINE`.
+    pub n_type: u8,
+    /// The `n_other` field.
+    pub n_other: u8,
+    /// The `n_desc` field.
+    pub n_desc: u16,
+    /// The `n_value` field.
+    pub n_value: u32,
+}
+
+/// An iterator over the STABS debugging entries in an ELF `.stab` section.
+///
+/// Returned by [`ElfFile::stabs`].
+#[derive(Debug)]
+pub struct StabIterator<'data, E: crate::Endian> {
+    endian: E,
+    data: Bytes<'data>,
+    strings: Bytes<'data>,
+    strx_base: u32,
+    next_strx_base: u32,
+}
+
+impl<'data, E: crate::Endian> StabIterator<'data, E> {
+    /// Return the next entry.
+    ///
+    /// Each compilation unit begins with a header entry with a type of `N_UNDF`,
+    /// which gives the size of the strings for that unit in `n_value`. The names
+    /// of the entries in the unit are relative to the start of those strings.
+    pub fn next(&mut self) -> read::Result<Option<Stab<'data>>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let (n_strx, mut stab) = self.entry()?;
+        if stab.n_type == N_UNDF {
+            self.strx_base = self.next_strx_base;
+            self.next_strx_base = self.next_strx_base.wrapping_add(stab.n_value);
+        }
+        stab.name = Cow::Borrowed(self.name(n_strx)?);
+        // A name ending with a backslash is continued in the next entry.
+        while stab.name.last() == Some(&b'\\') && !self.data.is_empty() {
+            let (n_strx, _) = self.entry()?;
+            let next = self.name(n_strx)?;
+            let name = stab.name.to_mut();
+            name.pop();
+            name.extend_from_slice(next);
+        }
+        Ok(Some(stab))
+    }
+
+    /// Read an entry, and return its `n_strx` field and the other fields.
+    fn entry(&mut self) -> read::Result<(u32, Stab<'data>)> {
+        let endian = self.endian;
+        let n_strx = self
+            .data
+            .read::<U32Bytes<E>>()
+            .read_error("Invalid ELF .stab entry")?
+            .get(endian);
+        let n_type = *self
+            .data
+            .read::<u8>()
+            .read_error("Invalid ELF .stab entry")?;
+        let n_other = *self
+            .data
+            .read::<u8>()
+            .read_error("Invalid ELF .stab entry")?;
+        let n_desc = self
+            .data
+            .read::<U16Bytes<E>>()
+            .read_error("Invalid ELF .stab entry")?
+            .get(endian);
+        let n_value = self
+            .data
+            .read::<U32Bytes<E>>()
+            .read_error("Invalid ELF .stab entry")?
+            .get(endian);
+        let stab = Stab {
+            name: Cow::Borrowed(&[]),
+            n_type,
+            n_other,
+            n_desc,
+            n_value,
+        };
+        Ok((n_strx, stab))
+    }
+
+    fn name(&self, n_strx: u32) -> read::Result<&'data [u8]> {
+        if n_strx == 0 {
+            return Ok(&[]);
+        }
+        self.strings
+            .read_string_at(self.strx_base.wrapping_add(n_strx) as usize)
+            .read_error("Invalid ELF .stab name offset")
+    }
+}
@@ -88,6 +88,17 @@ impl<'data, Elf: FileHeader, R: ReadRef<'data>> SymbolTable<'data, Elf, R> {
         })
     }
 
+    /// Create a symbol table that is not associated with a section.
+    ///
+    /// This is used for dynamic symbol tables located using the dynamic entries.
+    pub(super) fn from_dynamic(symbols: &'data [Elf::Sym], strings: StringTable<'data, R>) -> Self {
+        SymbolTable {
+            symbols,
+            strings,
+            ..Default::default()
+        }
+    }
+
     /// Return the section index of this symbol table.
     #[inline]
     pub fn section(&self) -> SectionIndex {
@@ -312,6 +323,40 @@ where
     pub(super) symbol: &'data Elf::Sym,
 }
 
+impl<'data, 'file, Elf: FileHeader, R: ReadRef<'data>> ElfSymbol<'data, 'file, Elf, R> {
+    /// Return a reference to the raw symbol structure.
+    #[inline]
+    pub fn raw_symbol(&self) -> &'data Elf::Sym {
+        self.symbol
+    }
+
+    /// Return the symbol binding.
+    ///
+    /// This is one of the `STB_*` constants, such as `STB_LOCAL`, `STB_GLOBAL` or `STB_WEAK`.
+    #[inline]
+    pub fn binding(&self) -> u8 {
+        self.symbol.st_bind()
+    }
+
+    /// Return the symbol type.
+    ///
+    /// This is one of the `STT_*` constants, such as `STT_FUNC`, `STT_OBJECT`,
+    /// `STT_TLS` or `STT_GNU_IFUNC`.
+    #[inline]
+    pub fn elf_type(&self) -> u8 {
+        self.symbol.st_type()
+    }
+
+    /// Return the symbol visibility from the low 2 bits of `st_other`.
+    ///
+    /// This is one of the `STV_*` constants, such as `STV_DEFAULT`, `STV_HIDDEN`
+    /// or `STV_PROTECTED`.
+    #[inline]
+    pub fn visibility(&self) -> u8 {
+        self.symbol.st_visibility()
+    }
+}
+
 impl<'data, 'file, Elf: FileHeader, R: ReadRef<'data>> read::private::Sealed
     for ElfSymbol<'data, 'file, Elf, R>
 {
@@ -350,7 +395,7 @@ impl<'data, 'file, Elf: FileHeader, R: ReadRef<'data>> ObjectSymbol<'data>
         match self.symbol.st_type() {
             elf::STT_NOTYPE if self.index.0 == 0 => SymbolKind::Null,
             elf::STT_OBJECT | elf::STT_COMMON => SymbolKind::Data,
-            elf::STT_FUNC => SymbolKind::Text,
+            elf::STT_FUNC | elf::STT_GNU_IFUNC => SymbolKind::Text,
             elf::STT_SECTION => SymbolKind::Section,
             elf::STT_FILE => SymbolKind::File,
             elf::STT_TLS => SymbolKind::Tls,
@@ -429,7 +474,7 @@ impl<'data, 'file, Elf: FileHeader, R: ReadRef<'data>> ObjectSymbol<'data>
     }
 
     #[inline]
-    fn flags(&self) -> SymbolFlags<SectionIndex> {
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
         SymbolFlags::Elf {
             st_info: self.symbol.st_info(),
             st_other: self.symbol.st_other(),
@@ -473,7 +518,10 @@ pub trait Sym: Debug + Pod {
     /// Return true if the symbol is a definition of a function or data object.
     fn is_definition(&self, endian: Self::Endian) -> bool {
         let st_type = self.st_type();
-        (st_type == elf::STT_NOTYPE || st_type == elf::STT_FUNC || st_type == elf::STT_OBJECT)
+        (st_type == elf::STT_NOTYPE
+            || st_type == elf::STT_FUNC
+            || st_type == elf::STT_GNU_IFUNC
+            || st_type == elf::STT_OBJECT)
             && self.st_shndx(endian) != elf::SHN_UNDEF
     }
 }
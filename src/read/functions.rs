@@ -0,0 +1,100 @@
+//! Support for enumerating the functions in a file.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::read::{Object, ObjectSection, ObjectSymbol, SectionKind, SymbolKind};
+
+/// A function, as returned by [`Object::functions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    /// The name of the function, if it has a symbol.
+    pub name: Option<String>,
+    /// The address of the start of the function.
+    pub address: u64,
+    /// The size of the function, if known or inferred.
+    pub size: Option<u64>,
+}
+
+/// Merge the function symbols with the function starts from format specific sources.
+///
+/// `starts` contains the address and optional size of each function.
+///
+/// See [`Object::functions`].
+pub(crate) fn functions<'data, 'file, O>(
+    file: &'file O,
+    starts: &[(u64, Option<u64>)],
+) -> Vec<Function>
+where
+    'data: 'file,
+    O: Object<'data, 'file> + ?Sized,
+{
+    let mut functions = Vec::new();
+    for symbol in file.symbols().chain(file.dynamic_symbols()) {
+        if symbol.kind() != SymbolKind::Text
+            || symbol.is_undefined()
+            || symbol.section_index().is_none()
+        {
+            continue;
+        }
+        let size = symbol.size();
+        functions.push(Function {
+            name: symbol.name().ok().map(ToString::to_string),
+            address: symbol.address(),
+            size: if size != 0 { Some(size) } else { None },
+        });
+    }
+    functions.extend(starts.iter().map(|&(address, size)| Function {
+        name: None,
+        address,
+        size,
+    }));
+
+    // The sort is stable, so symbols come first for each address.
+    functions.sort_by_key(|function| function.address);
+    let mut merged: Vec<Function> = Vec::with_capacity(functions.len());
+    for function in functions {
+        if let Some(last) = merged.last_mut() {
+            if last.address == function.address {
+                if last.name.is_none() {
+                    last.name = function.name;
+                }
+                if last.size.is_none() {
+                    last.size = function.size;
+                }
+                continue;
+            }
+        }
+        merged.push(function);
+    }
+
+    // Infer missing sizes from the start of the next function, limited to the
+    // end of the containing section.
+    let text_ranges = file
+        .sections()
+        .filter(|section| section.kind() == SectionKind::Text)
+        .map(|section| {
+            let address = section.address();
+            (address, address.wrapping_add(section.size()))
+        })
+        .collect::<Vec<_>>();
+    for i in 0..merged.len() {
+        if merged[i].size.is_some() {
+            continue;
+        }
+        let address = merged[i].address;
+        let section_end = match text_ranges
+            .iter()
+            .find(|&&(start, end)| start <= address && address < end)
+        {
+            Some(&(_, end)) => end,
+            None => continue,
+        };
+        let end = match merged.get(i + 1) {
+            Some(next) if next.address < section_end => next.address,
+            _ => section_end,
+        };
+        merged[i].size = Some(end - address);
+    }
+    merged
+}
@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+
+use crate::read::md5::Md5;
+use crate::read::sha::{Sha1, Sha256};
+
+/// A hash algorithm that can be used for [`ObjectSection::hash`](crate::ObjectSection::hash).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HashAlgo {
+    /// MD5.
+    Md5,
+    /// SHA-1.
+    Sha1,
+    /// SHA-256.
+    Sha256,
+}
+
+impl HashAlgo {
+    /// The size in bytes of the digest produced by this algorithm.
+    pub fn digest_size(self) -> usize {
+        match self {
+            HashAlgo::Md5 => 16,
+            HashAlgo::Sha1 => 20,
+            HashAlgo::Sha256 => 32,
+        }
+    }
+
+    /// Compute the digest of the given data.
+    pub fn digest(self, data: &[u8]) -> Vec<u8> {
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finish()
+    }
+
+    /// Return the state for an incremental computation using this algorithm.
+    pub(crate) fn hasher(self) -> Hasher {
+        match self {
+            HashAlgo::Md5 => Hasher::Md5(Md5::new()),
+            HashAlgo::Sha1 => Hasher::Sha1(Sha1::new()),
+            HashAlgo::Sha256 => Hasher::Sha256(Sha256::new()),
+        }
+    }
+}
+
+/// The incremental state of a computation using a [`HashAlgo`].
+#[derive(Debug, Clone)]
+pub(crate) enum Hasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+}
+
+impl Hasher {
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Md5(md5) => md5.update(data),
+            Hasher::Sha1(sha1) => sha1.update(data),
+            Hasher::Sha256(sha256) => sha256.update(data),
+        }
+    }
+
+    /// Finish the computation and return the digest.
+    pub(crate) fn finish(self) -> Vec<u8> {
+        match self {
+            Hasher::Md5(md5) => md5.finish().to_vec(),
+            Hasher::Sha1(sha1) => sha1.finish().to_vec(),
+            Hasher::Sha256(sha256) => sha256.finish().to_vec(),
+        }
+    }
+}
@@ -1,18 +1,19 @@
 use alloc::vec::Vec;
 use core::fmt::Debug;
-use core::{mem, str};
+use core::{mem, result, str};
 
 use crate::read::{
-    self, Architecture, ComdatKind, Error, Export, FileFlags, Import, NoDynamicRelocationIterator,
-    Object, ObjectComdat, ObjectKind, ObjectMap, ObjectSection, ReadError, ReadRef, Result,
-    SectionIndex, SymbolIndex,
+    self, Architecture, BinaryFormat, ComdatKind, Error, Export, FileFlags, Function, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ObjectMap, ObjectSection,
+    ObjectSymbol, ParseError, ParseOptions, ParseStage, PointerFixupIterator, ReadError, ReadRef,
+    Result, SectionIndex, SectionKind, SubArchitecture, SymbolIndex, TlsSymbol,
 };
-use crate::{endian, macho, BigEndian, ByteString, Endian, Endianness, Pod};
+use crate::{endian, macho, BigEndian, ByteString, Bytes, Endian, Endianness, Pod, U32, U64};
 
 use super::{
-    DyldCacheImage, LoadCommandIterator, MachOSection, MachOSectionInternal, MachOSectionIterator,
-    MachOSegment, MachOSegmentInternal, MachOSegmentIterator, MachOSymbol, MachOSymbolIterator,
-    MachOSymbolTable, Nlist, Section, Segment, SymbolTable,
+    DyldCacheImage, LoadCommandIterator, LoadCommandVariant, MachOSection, MachOSectionInternal,
+    MachOSectionIterator, MachOSegment, MachOSegmentInternal, MachOSegmentIterator, MachOSymbol,
+    MachOSymbolIterator, MachOSymbolTable, Nlist, Section, Segment, SymbolTable,
 };
 
 /// A 32-bit Mach-O object file.
@@ -38,6 +39,7 @@ where
     pub(super) segments: Vec<MachOSegmentInternal<'data, Mach, R>>,
     pub(super) sections: Vec<MachOSectionInternal<'data, Mach>>,
     pub(super) symbols: SymbolTable<'data, Mach, R>,
+    pub(super) max_pointer_fixups: usize,
 }
 
 impl<'data, Mach, R> MachOFile<'data, Mach, R>
@@ -47,24 +49,62 @@ where
 {
     /// Parse the raw Mach-O file data.
     pub fn parse(data: R) -> Result<Self> {
-        let header = Mach::parse(data, 0)?;
-        let endian = header.endian()?;
+        Self::parse_with_options(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw Mach-O file data, and check that it is within the given limits.
+    ///
+    /// The limits are checked using the load commands, before the sections
+    /// and symbols they contain are added to the file.
+    pub fn parse_with_options(data: R, options: &ParseOptions) -> Result<Self> {
+        Self::parse_staged(data, options).map_err(|error| error.detail)
+    }
+
+    /// Parse the raw Mach-O file data, and include the stage of parsing in any error.
+    pub(crate) fn parse_staged(
+        data: R,
+        options: &ParseOptions,
+    ) -> result::Result<Self, ParseError> {
+        let header = Mach::parse(data, 0).map_err(ParseStage::Header.error())?;
+        let endian = header.endian().map_err(ParseStage::Header.error())?;
 
         // Build a list of segments and sections to make some operations more efficient.
         let mut segments = Vec::new();
         let mut sections = Vec::new();
         let mut symbols = SymbolTable::default();
+        let mut relocation_count = 0u64;
         if let Ok(mut commands) = header.load_commands(endian, data, 0) {
             while let Ok(Some(command)) = commands.next() {
-                if let Some((segment, section_data)) = Mach::Segment::from_command(command)? {
+                if let Some((segment, section_data)) =
+                    Mach::Segment::from_command(command).map_err(ParseStage::Segments.error())?
+                {
+                    let segment_sections = segment
+                        .sections(endian, section_data)
+                        .map_err(ParseStage::Sections.error())?;
+                    options
+                        .check_sections(sections.len().saturating_add(segment_sections.len()))
+                        .map_err(ParseStage::Sections.error())?;
+                    for section in segment_sections {
+                        relocation_count += u64::from(section.nreloc(endian));
+                    }
+                    options
+                        .check_relocations(relocation_count)
+                        .map_err(ParseStage::Sections.error())?;
                     let segment_index = segments.len();
                     segments.push(MachOSegmentInternal { segment, data });
-                    for section in segment.sections(endian, section_data)? {
+                    for section in segment_sections {
                         let index = SectionIndex(sections.len() + 1);
                         sections.push(MachOSectionInternal::parse(index, segment_index, section));
                     }
-                } else if let Some(symtab) = command.symtab()? {
-                    symbols = symtab.symbols(endian, data)?;
+                } else if let Some(symtab) =
+                    command.symtab().map_err(ParseStage::Symbols.error())?
+                {
+                    options
+                        .check_symbols(symtab.nsyms.get(endian) as usize)
+                        .map_err(ParseStage::Symbols.error())?;
+                    symbols = symtab
+                        .symbols(endian, data)
+                        .map_err(ParseStage::Symbols.error())?;
                 }
             }
         }
@@ -77,6 +117,7 @@ where
             segments,
             sections,
             symbols,
+            max_pointer_fixups: options.max_pointer_fixups,
         })
     }
 
@@ -138,9 +179,455 @@ where
             segments,
             sections,
             symbols,
+            max_pointer_fixups: usize::max_value(),
         })
     }
 
+    /// Return the umbrella framework name from the `LC_SUB_FRAMEWORK` command.
+    ///
+    /// Returns `Ok(None)` if the file is not a subframework of an umbrella framework.
+    pub fn sub_framework(&self) -> Result<Option<&'data [u8]>> {
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let LoadCommandVariant::SubFramework(sub) = command.variant()? {
+                return command.string(self.endian, sub.umbrella).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the client names from the `LC_SUB_CLIENT` commands.
+    ///
+    /// These are the frameworks and bundles that are permitted to link against
+    /// this private framework.
+    pub fn sub_clients(&self) -> Result<Vec<&'data [u8]>> {
+        self.sub_names(macho::LC_SUB_CLIENT)
+    }
+
+    /// Return the subumbrella framework names from the `LC_SUB_UMBRELLA` commands.
+    pub fn sub_umbrellas(&self) -> Result<Vec<&'data [u8]>> {
+        self.sub_names(macho::LC_SUB_UMBRELLA)
+    }
+
+    /// Return the sublibrary names from the `LC_SUB_LIBRARY` commands.
+    pub fn sub_libraries(&self) -> Result<Vec<&'data [u8]>> {
+        self.sub_names(macho::LC_SUB_LIBRARY)
+    }
+
+    /// Return the name of the dylib for the given library ordinal.
+    ///
+    /// The ordinal is a 1-based index into the dylib load commands, such as
+    /// `LC_LOAD_DYLIB` and `LC_LOAD_WEAK_DYLIB`, in the order they appear.
+    /// This is the value returned by [`MachOSymbol::library_ordinal`] for
+    /// undefined symbols in files using two-level namespaces.
+    ///
+    /// Returns `Ok(None)` for `SELF_LIBRARY_ORDINAL`, `DYNAMIC_LOOKUP_ORDINAL`
+    /// and `EXECUTABLE_ORDINAL`, since these do not refer to a dylib.
+    /// Returns `Err` if there is no dylib load command for the ordinal.
+    pub fn library_for_ordinal(&self, ordinal: u16) -> Result<Option<&'data [u8]>> {
+        if ordinal == u16::from(macho::SELF_LIBRARY_ORDINAL)
+            || ordinal == u16::from(macho::DYNAMIC_LOOKUP_ORDINAL)
+            || ordinal == u16::from(macho::EXECUTABLE_ORDINAL)
+        {
+            return Ok(None);
+        }
+        let mut index = 0;
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let Some(dylib) = command.dylib()? {
+                index += 1;
+                if index == ordinal {
+                    return command.string(self.endian, dylib.dylib.name).map(Some);
+                }
+            }
+        }
+        Err(Error("Invalid Mach-O library ordinal"))
+    }
+
+    /// Return the section with the given segment and section names.
+    ///
+    /// Unlike [`Object::section_by_name_bytes`], this does not translate
+    /// the `.` prefix to the `__` prefix, and the segment name must match.
+    pub fn section_by_segment_name<'file>(
+        &'file self,
+        segment_name: &[u8],
+        section_name: &[u8],
+    ) -> Option<MachOSection<'data, 'file, Mach, R>> {
+        self.sections.iter().find_map(|internal| {
+            let section = internal.section;
+            if section.segment_name() == segment_name && section.name() == section_name {
+                Some(MachOSection {
+                    file: self,
+                    internal: *internal,
+                })
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Return the `LC_SYMTAB` command.
+    ///
+    /// This gives the file ranges of the symbol table and the string table.
+    ///
+    /// Returns `Ok(None)` if the command is not present.
+    pub fn symtab_command(&self) -> Result<Option<&'data macho::SymtabCommand<Mach::Endian>>> {
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let Some(symtab) = command.symtab()? {
+                return Ok(Some(symtab));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the `LC_DYSYMTAB` command.
+    ///
+    /// This gives the ranges of the local, external and undefined symbols
+    /// within the symbol table, and the file ranges of the dynamic linking tables.
+    ///
+    /// Returns `Ok(None)` if the command is not present.
+    pub fn dysymtab_command(&self) -> Result<Option<&'data macho::DysymtabCommand<Mach::Endian>>> {
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let Some(dysymtab) = command.dysymtab()? {
+                return Ok(Some(dysymtab));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return an iterator over the local symbols.
+    ///
+    /// These are the symbols in the `ilocalsym` and `nlocalsym` range of the
+    /// `LC_DYSYMTAB` command. Debugging symbols are skipped.
+    ///
+    /// Returns an empty iterator if the command is not present.
+    pub fn local_symbols<'file>(&'file self) -> Result<MachOSymbolIterator<'data, 'file, Mach, R>> {
+        let range = self.dysymtab_command()?.map(|dysymtab| {
+            (
+                dysymtab.ilocalsym.get(self.endian),
+                dysymtab.nlocalsym.get(self.endian),
+            )
+        });
+        self.symbol_range(range)
+    }
+
+    /// Return an iterator over the external symbols that are defined in this file.
+    ///
+    /// These are the symbols in the `iextdefsym` and `nextdefsym` range of the
+    /// `LC_DYSYMTAB` command.
+    ///
+    /// Returns an empty iterator if the command is not present.
+    pub fn external_symbols<'file>(
+        &'file self,
+    ) -> Result<MachOSymbolIterator<'data, 'file, Mach, R>> {
+        let range = self.dysymtab_command()?.map(|dysymtab| {
+            (
+                dysymtab.iextdefsym.get(self.endian),
+                dysymtab.nextdefsym.get(self.endian),
+            )
+        });
+        self.symbol_range(range)
+    }
+
+    /// Return an iterator over the undefined symbols.
+    ///
+    /// These are the symbols in the `iundefsym` and `nundefsym` range of the
+    /// `LC_DYSYMTAB` command.
+    ///
+    /// Returns an empty iterator if the command is not present.
+    pub fn undefined_symbols<'file>(
+        &'file self,
+    ) -> Result<MachOSymbolIterator<'data, 'file, Mach, R>> {
+        let range = self.dysymtab_command()?.map(|dysymtab| {
+            (
+                dysymtab.iundefsym.get(self.endian),
+                dysymtab.nundefsym.get(self.endian),
+            )
+        });
+        self.symbol_range(range)
+    }
+
+    /// Return an iterator over the symbols in the given index and count range.
+    fn symbol_range<'file>(
+        &'file self,
+        range: Option<(u32, u32)>,
+    ) -> Result<MachOSymbolIterator<'data, 'file, Mach, R>> {
+        let (index, end) = match range {
+            Some((index, number)) => {
+                let index = index as usize;
+                let end = index
+                    .checked_add(number as usize)
+                    .filter(|&end| end <= self.symbols.len())
+                    .read_error("Invalid Mach-O dysymtab symbol range")?;
+                (index, end)
+            }
+            None => (0, 0),
+        };
+        Ok(MachOSymbolIterator {
+            file: self,
+            index,
+            end,
+        })
+    }
+
+    /// Return the data of the export trie.
+    ///
+    /// This uses the `LC_DYLD_EXPORTS_TRIE` command if present, and otherwise
+    /// the export range of the `LC_DYLD_INFO` or `LC_DYLD_INFO_ONLY` command.
+    ///
+    /// Returns `Ok(None)` if the file does not contain an export trie.
+    pub fn exports_trie_data(&self) -> Result<Option<&'data [u8]>> {
+        if let Some(data) = self.linkedit_data(macho::LC_DYLD_EXPORTS_TRIE)? {
+            return Ok(Some(data));
+        }
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let LoadCommandVariant::DyldInfo(dyld_info) = command.variant()? {
+                let size = dyld_info.export_size.get(self.endian);
+                if size == 0 {
+                    return Ok(None);
+                }
+                let offset = dyld_info.export_off.get(self.endian);
+                return self
+                    .data
+                    .read_bytes_at(offset.into(), size.into())
+                    .read_error("Invalid Mach-O export trie offset or size")
+                    .map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the data of the `LC_DYLD_CHAINED_FIXUPS` command.
+    ///
+    /// Returns `Ok(None)` if the file does not use chained fixups.
+    pub fn chained_fixups_data(&self) -> Result<Option<&'data [u8]>> {
+        self.linkedit_data(macho::LC_DYLD_CHAINED_FIXUPS)
+    }
+
+    /// Return the data of the `LC_DYLIB_CODE_SIGN_DRS` command.
+    ///
+    /// This contains the code signing designated requirements that were copied
+    /// from the linked dylibs.
+    ///
+    /// Returns `Ok(None)` if the command is not present.
+    pub fn dylib_code_sign_drs_data(&self) -> Result<Option<&'data [u8]>> {
+        self.linkedit_data(macho::LC_DYLIB_CODE_SIGN_DRS)
+    }
+
+    /// Return the version from the `LC_SOURCE_VERSION` command.
+    ///
+    /// The version is `A.B.C.D.E`. It is stored packed into 64 bits, with 24 bits
+    /// for `A` and 10 bits for each of the others.
+    ///
+    /// Returns `Ok(None)` if the command is not present.
+    pub fn source_version(&self) -> Result<Option<(u32, u32, u32, u32, u32)>> {
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let LoadCommandVariant::SourceVersion(source) = command.variant()? {
+                let version = source.version.get(self.endian);
+                return Ok(Some((
+                    (version >> 40) as u32,
+                    (version >> 30) as u32 & 0x3ff,
+                    (version >> 20) as u32 & 0x3ff,
+                    (version >> 10) as u32 & 0x3ff,
+                    version as u32 & 0x3ff,
+                )));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the entries of the `LC_DATA_IN_CODE` command.
+    ///
+    /// Each entry describes a range of data, such as a jump table, that is
+    /// embedded in a code section.
+    ///
+    /// Returns `Ok(None)` if the command is not present.
+    pub fn data_in_code(&self) -> Result<Option<impl Iterator<Item = DataInCodeEntry> + 'data>> {
+        let data = match self.linkedit_data(macho::LC_DATA_IN_CODE)? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        let count = data.len() / mem::size_of::<macho::DataInCodeEntry<Mach::Endian>>();
+        let entries = Bytes(data)
+            .read_slice::<macho::DataInCodeEntry<Mach::Endian>>(count)
+            .read_error("Invalid Mach-O data in code entries")?;
+        let endian = self.endian;
+        Ok(Some(entries.iter().map(move |entry| DataInCodeEntry {
+            offset: entry.offset.get(endian),
+            length: entry.length.get(endian),
+            kind: entry.kind.get(endian),
+        })))
+    }
+
+    /// Return the function start addresses from the `LC_FUNCTION_STARTS` command.
+    ///
+    /// Returns `Ok(None)` if the command is not present.
+    pub fn function_starts(&self) -> Result<Option<Vec<u64>>> {
+        let data = match self.linkedit_data(macho::LC_FUNCTION_STARTS)? {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+        // The addresses are encoded as ULEB128 deltas from the start of `__TEXT`,
+        // and are terminated by a zero delta.
+        let mut data = Bytes(data);
+        let mut address = self.text_address();
+        let mut starts = Vec::new();
+        while !data.is_empty() {
+            let delta = super::fixups::read_uleb128(&mut data)?;
+            if delta == 0 {
+                break;
+            }
+            address = address.wrapping_add(delta);
+            starts.push(address);
+        }
+        Ok(Some(starts))
+    }
+
+    /// Return the `LC_NOTE` commands.
+    ///
+    /// Each note describes a region of data in the file that is tagged with an owner
+    /// name, such as `addrable bits` in core files.
+    ///
+    /// Returns an empty iterator if there are no `LC_NOTE` commands.
+    pub fn notes(&self) -> Result<impl Iterator<Item = MachNote>> {
+        let mut notes = Vec::new();
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let LoadCommandVariant::Note(note) = command.variant()? {
+                notes.push(MachNote {
+                    data_owner: note.data_owner,
+                    offset: note.offset.get(self.endian),
+                    size: note.size.get(self.endian),
+                });
+            }
+        }
+        Ok(notes.into_iter())
+    }
+
+    /// Return the virtual address of the `__TEXT` segment, or 0 if there is none.
+    pub(super) fn text_address(&self) -> u64 {
+        self.segments
+            .iter()
+            .map(|internal| internal.segment)
+            .find(|segment| segment.name() == b"__TEXT")
+            .map(|segment| segment.vmaddr(self.endian).into())
+            .unwrap_or(0)
+    }
+
+    /// Return the data referenced by the first linkedit data command of the given type.
+    fn linkedit_data(&self, cmd: u32) -> Result<Option<&'data [u8]>> {
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if command.cmd() != cmd {
+                continue;
+            }
+            if let LoadCommandVariant::LinkeditData(linkedit) = command.variant()? {
+                let offset = linkedit.dataoff.get(self.endian);
+                let size = linkedit.datasize.get(self.endian);
+                return self
+                    .data
+                    .read_bytes_at(offset.into(), size.into())
+                    .read_error("Invalid Mach-O linkedit data offset or size")
+                    .map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return the names from all of the sub commands of the given type.
+    fn sub_names(&self, cmd: u32) -> Result<Vec<&'data [u8]>> {
+        let mut names = Vec::new();
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if command.cmd() != cmd {
+                continue;
+            }
+            let name = match command.variant()? {
+                LoadCommandVariant::SubFramework(sub) => sub.umbrella,
+                LoadCommandVariant::SubClient(sub) => sub.client,
+                LoadCommandVariant::SubUmbrella(sub) => sub.sub_umbrella,
+                LoadCommandVariant::SubLibrary(sub) => sub.sub_library,
+                _ => continue,
+            };
+            names.push(command.string(self.endian, name)?);
+        }
+        Ok(names)
+    }
+
+    /// Convert a file offset to a virtual address using the segment that contains it.
+    fn offset_to_address(&self, offset: u64) -> Option<u64> {
+        self.segments.iter().find_map(|internal| {
+            let segment = internal.segment;
+            let fileoff = segment.fileoff(self.endian).into();
+            let filesize = segment.filesize(self.endian).into();
+            if offset >= fileoff && offset - fileoff < filesize {
+                Some(segment.vmaddr(self.endian).into() + (offset - fileoff))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Return the program counter from the thread state of a `LC_UNIXTHREAD` command.
+    ///
+    /// `data` is the sequence of flavor, count and state that follows the command header.
+    fn thread_pc(&self, data: &'data [u8]) -> Option<u64> {
+        let endian = self.endian;
+        // The flavor of the thread state, and the byte offset and size of its program counter.
+        let (pc_flavor, pc_offset, pc_is_64) = match self.header.cputype(endian) {
+            // x86_THREAD_STATE32: eax ... eflags, eip
+            macho::CPU_TYPE_X86 => (1, 10 * 4, false),
+            // x86_THREAD_STATE64: rax ... r15, rip
+            macho::CPU_TYPE_X86_64 => (4, 16 * 8, true),
+            // ARM_THREAD_STATE: r0 ... r12, sp, lr, pc
+            macho::CPU_TYPE_ARM => (1, 15 * 4, false),
+            // ARM_THREAD_STATE64: x0 ... x28, fp, lr, sp, pc
+            macho::CPU_TYPE_ARM64 => (6, 32 * 8, true),
+            // PPC_THREAD_STATE: srr0
+            macho::CPU_TYPE_POWERPC => (1, 0, false),
+            // PPC_THREAD_STATE64: srr0
+            macho::CPU_TYPE_POWERPC64 => (5, 0, true),
+            _ => return None,
+        };
+        let mut data = Bytes(data);
+        while !data.is_empty() {
+            let flavor = data.read::<U32<_>>().ok()?.get(endian);
+            let count = data.read::<U32<_>>().ok()?.get(endian);
+            let mut state = data.read_bytes(count as usize * 4).ok()?;
+            if flavor == pc_flavor {
+                state.skip(pc_offset).ok()?;
+                return if pc_is_64 {
+                    Some(state.read::<U64<_>>().ok()?.get(endian))
+                } else {
+                    Some(state.read::<U32<_>>().ok()?.get(endian).into())
+                };
+            }
+        }
+        None
+    }
+
     /// Return the section at the given index.
     #[inline]
     pub(super) fn section_internal(
@@ -164,6 +651,85 @@ where
     }
 }
 
+/// An entry in the `LC_DATA_IN_CODE` table of a Mach-O file.
+///
+/// Returned by [`MachOFile::data_in_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DataInCodeEntry {
+    /// The offset of the data from the start of the Mach-O header.
+    pub offset: u32,
+    /// The size in bytes of the data.
+    pub length: u16,
+    /// The kind of data. This is one of the `DICE_KIND_*` constants.
+    pub kind: u16,
+}
+
+impl DataInCodeEntry {
+    /// Return the kind of data as an enum.
+    pub fn kind_enum(&self) -> DataInCodeKind {
+        match u32::from(self.kind) {
+            macho::DICE_KIND_DATA => DataInCodeKind::Data,
+            macho::DICE_KIND_JUMP_TABLE8 => DataInCodeKind::JumpTable8,
+            macho::DICE_KIND_JUMP_TABLE16 => DataInCodeKind::JumpTable16,
+            macho::DICE_KIND_JUMP_TABLE32 => DataInCodeKind::JumpTable32,
+            macho::DICE_KIND_ABS_JUMP_TABLE32 => DataInCodeKind::AbsJumpTable32,
+            _ => DataInCodeKind::Unknown(self.kind),
+        }
+    }
+}
+
+/// The kind of a [`DataInCodeEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DataInCodeKind {
+    /// `DICE_KIND_DATA`
+    Data,
+    /// `DICE_KIND_JUMP_TABLE8`
+    JumpTable8,
+    /// `DICE_KIND_JUMP_TABLE16`
+    JumpTable16,
+    /// `DICE_KIND_JUMP_TABLE32`
+    JumpTable32,
+    /// `DICE_KIND_ABS_JUMP_TABLE32`
+    AbsJumpTable32,
+    /// An unknown kind.
+    Unknown(u16),
+}
+
+/// A `LC_NOTE` command in a Mach-O file.
+///
+/// Returned by [`MachOFile::notes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MachNote {
+    /// The owner name of the note, padded with zeros.
+    pub data_owner: [u8; 16],
+    /// The file offset of the note data.
+    pub offset: u64,
+    /// The size in bytes of the note data.
+    pub size: u64,
+}
+
+impl MachNote {
+    /// Return the owner name of the note, without the zero padding.
+    pub fn owner(&self) -> &[u8] {
+        match memchr::memchr(b'\0', &self.data_owner) {
+            Some(end) => &self.data_owner[..end],
+            None => &self.data_owner,
+        }
+    }
+
+    /// Return the note data from the file.
+    pub fn data<'data, Mach, R>(&self, file: &MachOFile<'data, Mach, R>) -> Result<&'data [u8]>
+    where
+        Mach: MachHeader,
+        R: ReadRef<'data>,
+    {
+        file.data
+            .read_bytes_at(self.offset, self.size)
+            .read_error("Invalid Mach-O note offset or size")
+    }
+}
+
 impl<'data, Mach, R> read::private::Sealed for MachOFile<'data, Mach, R>
 where
     Mach: MachHeader,
@@ -188,6 +754,11 @@ where
     type SymbolTable = MachOSymbolTable<'data, 'file, Mach, R>;
     type DynamicRelocationIterator = NoDynamicRelocationIterator;
 
+    #[inline]
+    fn format(&self) -> BinaryFormat {
+        BinaryFormat::MachO
+    }
+
     fn architecture(&self) -> Architecture {
         match self.header.cputype(self.endian) {
             macho::CPU_TYPE_ARM => Architecture::Arm,
@@ -199,6 +770,15 @@ where
         }
     }
 
+    fn sub_architecture(&self) -> Option<SubArchitecture> {
+        let cpusubtype = self.header.cpusubtype(self.endian) & !macho::CPU_SUBTYPE_MASK;
+        match (self.header.cputype(self.endian), cpusubtype) {
+            (macho::CPU_TYPE_ARM64, macho::CPU_SUBTYPE_ARM64E) => Some(SubArchitecture::Arm64E),
+            (macho::CPU_TYPE_X86_64, macho::CPU_SUBTYPE_X86_64_H) => Some(SubArchitecture::X86_64H),
+            _ => None,
+        }
+    }
+
     #[inline]
     fn is_little_endian(&self) -> bool {
         self.header.is_little_endian()
@@ -280,6 +860,11 @@ where
         MachOComdatIterator { file: self }
     }
 
+    #[inline]
+    fn symbol_count(&'file self) -> usize {
+        self.symbols.len()
+    }
+
     fn symbol_by_index(
         &'file self,
         index: SymbolIndex,
@@ -292,6 +877,7 @@ where
         MachOSymbolIterator {
             file: self,
             index: 0,
+            end: self.symbols.len(),
         }
     }
 
@@ -304,6 +890,7 @@ where
         MachOSymbolIterator {
             file: self,
             index: self.symbols.len(),
+            end: self.symbols.len(),
         }
     }
 
@@ -361,6 +948,22 @@ where
         Ok(imports)
     }
 
+    fn needed_libraries(&self) -> Result<Vec<&'data [u8]>> {
+        let mut libraries = Vec::new();
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let Some(dylib) = command.dylib()? {
+                let library = command.string(self.endian, dylib.dylib.name)?;
+                if !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+            }
+        }
+        Ok(libraries)
+    }
+
     fn exports(&self) -> Result<Vec<Export<'data>>> {
         let mut dysymtab = None;
         let mut commands = self
@@ -395,8 +998,155 @@ where
         None
     }
 
+    fn pointer_fixups(&'file self) -> Result<PointerFixupIterator> {
+        self.rebase_fixups().map(PointerFixupIterator::new)
+    }
+
+    fn trailing_data(&self) -> Result<Option<&'data [u8]>> {
+        // Images in a dyld shared cache share the file data with other images.
+        if self.header_offset != 0 {
+            return Ok(None);
+        }
+        let endian = self.endian;
+        let mut end = mem::size_of::<Mach>() as u64 + u64::from(self.header.sizeofcmds(endian));
+        let mut add = |offset: u32, size: u64| {
+            if size != 0 {
+                end = end.max(u64::from(offset) + size);
+            }
+        };
+        for internal in &self.sections {
+            let section = internal.section;
+            if section.file_range(endian).is_some() {
+                add(section.offset(endian), section.size(endian).into());
+            }
+            add(
+                section.reloff(endian),
+                u64::from(section.nreloc(endian)) * 8,
+            );
+        }
+        let mut commands = self
+            .header
+            .load_commands(endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            match command.variant()? {
+                LoadCommandVariant::Symtab(symtab) => {
+                    let nlist_size = mem::size_of::<Mach::Nlist>() as u64;
+                    add(
+                        symtab.symoff.get(endian),
+                        u64::from(symtab.nsyms.get(endian)) * nlist_size,
+                    );
+                    add(symtab.stroff.get(endian), symtab.strsize.get(endian).into());
+                }
+                LoadCommandVariant::Dysymtab(dysymtab) => {
+                    add(
+                        dysymtab.indirectsymoff.get(endian),
+                        u64::from(dysymtab.nindirectsyms.get(endian)) * 4,
+                    );
+                    add(
+                        dysymtab.extreloff.get(endian),
+                        u64::from(dysymtab.nextrel.get(endian)) * 8,
+                    );
+                    add(
+                        dysymtab.locreloff.get(endian),
+                        u64::from(dysymtab.nlocrel.get(endian)) * 8,
+                    );
+                }
+                LoadCommandVariant::DyldInfo(info) => {
+                    add(
+                        info.rebase_off.get(endian),
+                        info.rebase_size.get(endian).into(),
+                    );
+                    add(info.bind_off.get(endian), info.bind_size.get(endian).into());
+                    add(
+                        info.weak_bind_off.get(endian),
+                        info.weak_bind_size.get(endian).into(),
+                    );
+                    add(
+                        info.lazy_bind_off.get(endian),
+                        info.lazy_bind_size.get(endian).into(),
+                    );
+                    add(
+                        info.export_off.get(endian),
+                        info.export_size.get(endian).into(),
+                    );
+                }
+                LoadCommandVariant::LinkeditData(linkedit) => {
+                    add(
+                        linkedit.dataoff.get(endian),
+                        linkedit.datasize.get(endian).into(),
+                    );
+                }
+                _ => {}
+            }
+        }
+        // Segments include `__LINKEDIT`, which is usually last.
+        for internal in &self.segments {
+            let (offset, size) = internal.segment.file_range(endian);
+            end = end.max(offset.saturating_add(size));
+        }
+        read::util::trailing_data(self.data, end)
+    }
+
+    fn functions(&'file self) -> Vec<Function> {
+        let starts = match self.function_starts() {
+            Ok(Some(starts)) => starts.into_iter().map(|address| (address, None)).collect(),
+            _ => Vec::new(),
+        };
+        read::functions::functions(self, &starts)
+    }
+
+    fn tls_symbols(&'file self) -> Result<Vec<TlsSymbol<'data>>> {
+        let pointer_size = if self.is_64() { 8 } else { 4 };
+        let mut tls = Vec::new();
+        for symbol in self.symbols() {
+            let section = match symbol.section_index() {
+                Some(index) => self.section_by_index(index)?,
+                None => continue,
+            };
+            if section.kind() != SectionKind::TlsVariables {
+                continue;
+            }
+            // The descriptor is a thunk pointer, a key, and the offset.
+            let address = symbol
+                .address()
+                .checked_add(2 * pointer_size)
+                .read_error("Invalid Mach-O thread-local variable descriptor")?;
+            let offset = section
+                .data_range(address, pointer_size)?
+                .read_error("Invalid Mach-O thread-local variable descriptor")?;
+            let tls_offset = if pointer_size == 8 {
+                Bytes(offset)
+                    .read::<U64<Mach::Endian>>()
+                    .read_error("Invalid Mach-O thread-local variable descriptor")?
+                    .get(self.endian)
+            } else {
+                Bytes(offset)
+                    .read::<U32<Mach::Endian>>()
+                    .read_error("Invalid Mach-O thread-local variable descriptor")?
+                    .get(self.endian)
+                    .into()
+            };
+            tls.push(TlsSymbol {
+                index: symbol.index(),
+                name: ByteString(symbol.name_bytes()?),
+                tls_offset,
+            });
+        }
+        Ok(tls)
+    }
+
     fn has_debug_symbols(&self) -> bool {
-        self.section_by_name(".debug_info").is_some()
+        self.sections
+            .iter()
+            .any(|section| section.section.name().starts_with(b"__debug_"))
+    }
+
+    fn is_stripped(&self) -> bool {
+        // External symbols may be needed for dynamic linking, so only consider
+        // local definitions and debugging symbols.
+        !self.symbols.iter().any(|nlist| {
+            nlist.is_stab() || (nlist.is_definition() && nlist.n_type() & macho::N_EXT == 0)
+        })
     }
 
     fn mach_uuid(&self) -> Result<Option<[u8; 16]>> {
@@ -404,7 +1154,11 @@ where
     }
 
     fn relative_address_base(&self) -> u64 {
-        0
+        self.text_address()
+    }
+
+    fn preferred_address(&self) -> u64 {
+        self.text_address()
     }
 
     fn entry(&self) -> u64 {
@@ -414,7 +1168,16 @@ where
         {
             while let Ok(Some(command)) = commands.next() {
                 if let Ok(Some(command)) = command.entry_point() {
-                    return command.entryoff.get(self.endian);
+                    return self
+                        .offset_to_address(command.entryoff.get(self.endian))
+                        .unwrap_or(0);
+                }
+                if command.cmd() == macho::LC_UNIXTHREAD {
+                    if let Ok(LoadCommandVariant::Thread(_, data)) = command.variant() {
+                        if let Some(pc) = self.thread_pc(data) {
+                            return pc;
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,287 @@
+use alloc::vec::Vec;
+
+use crate::endian::{U16Bytes, U32Bytes, U64Bytes};
+use crate::macho;
+use crate::read::{Bytes, Error, Object, PointerFixup, ReadError, ReadRef, Result};
+
+use super::{LoadCommandVariant, MachHeader, MachOFile, Segment};
+
+impl<'data, Mach, R> MachOFile<'data, Mach, R>
+where
+    Mach: MachHeader,
+    R: ReadRef<'data>,
+{
+    /// Return the pointer fixups from the chained fixups or the rebase opcodes.
+    pub(super) fn rebase_fixups(&self) -> Result<Vec<PointerFixup>> {
+        if let Some(data) = self.chained_fixups_data()? {
+            return self.chained_rebase_fixups(data);
+        }
+        let mut commands = self
+            .header
+            .load_commands(self.endian, self.data, self.header_offset)?;
+        while let Some(command) = commands.next()? {
+            if let LoadCommandVariant::DyldInfo(info) = command.variant()? {
+                let offset = info.rebase_off.get(self.endian);
+                let size = info.rebase_size.get(self.endian);
+                let opcodes = self
+                    .data
+                    .read_bytes_at(offset.into(), size.into())
+                    .read_error("Invalid Mach-O rebase info offset or size")?;
+                return self.opcode_rebase_fixups(opcodes);
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Decode the `REBASE_OPCODE_*` stream of `LC_DYLD_INFO`.
+    fn opcode_rebase_fixups(&self, opcodes: &'data [u8]) -> Result<Vec<PointerFixup>> {
+        let pointer_size = if self.is_64() { 8 } else { 4 };
+        let stride = u64::from(pointer_size);
+        let mut opcodes = Bytes(opcodes);
+        let mut rebases = Rebases {
+            fixups: Vec::new(),
+            limit: self.pointer_fixup_limit(),
+            size: 0,
+            address: 0,
+            segment_end: 0,
+        };
+        while let Ok(&byte) = opcodes.read::<u8>() {
+            let immediate = byte & macho::REBASE_IMMEDIATE_MASK;
+            match byte & macho::REBASE_OPCODE_MASK {
+                macho::REBASE_OPCODE_DONE => break,
+                macho::REBASE_OPCODE_SET_TYPE_IMM => {
+                    rebases.size = match immediate {
+                        macho::REBASE_TYPE_POINTER => pointer_size,
+                        macho::REBASE_TYPE_TEXT_ABSOLUTE32 => 4,
+                        // Relative values are not absolute pointers.
+                        _ => 0,
+                    };
+                }
+                macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB => {
+                    let internal = self
+                        .segments
+                        .get(usize::from(immediate))
+                        .read_error("Invalid Mach-O rebase segment index")?;
+                    let segment = internal.segment;
+                    let vmaddr: u64 = segment.vmaddr(self.endian).into();
+                    let vmsize: u64 = segment.vmsize(self.endian).into();
+                    // Only pointers that are stored in the file can be rebased.
+                    let data = segment
+                        .data(self.endian, internal.data)
+                        .read_error("Invalid Mach-O rebase segment data")?;
+                    let size = vmsize.min(data.len() as u64);
+                    rebases.address = vmaddr.wrapping_add(read_uleb128(&mut opcodes)?);
+                    rebases.segment_end = vmaddr
+                        .checked_add(size)
+                        .read_error("Invalid Mach-O rebase segment address")?;
+                }
+                macho::REBASE_OPCODE_ADD_ADDR_ULEB => {
+                    rebases.skip(read_uleb128(&mut opcodes)?);
+                }
+                macho::REBASE_OPCODE_ADD_ADDR_IMM_SCALED => {
+                    rebases.skip(u64::from(immediate) * stride);
+                }
+                macho::REBASE_OPCODE_DO_REBASE_IMM_TIMES => {
+                    rebases.rebase(immediate.into(), stride)?;
+                }
+                macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES => {
+                    let count = read_uleb128(&mut opcodes)?;
+                    rebases.rebase(count, stride)?;
+                }
+                macho::REBASE_OPCODE_DO_REBASE_ADD_ADDR_ULEB => {
+                    let skip = read_uleb128(&mut opcodes)?;
+                    rebases.rebase(1, rebase_advance(stride, skip)?)?;
+                }
+                macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB => {
+                    let count = read_uleb128(&mut opcodes)?;
+                    let skip = read_uleb128(&mut opcodes)?;
+                    rebases.rebase(count, rebase_advance(stride, skip)?)?;
+                }
+                _ => return Err(Error("Unknown Mach-O rebase opcode")),
+            }
+        }
+        Ok(rebases.fixups)
+    }
+
+    /// Walk the chains of `LC_DYLD_CHAINED_FIXUPS`, and return the rebases.
+    fn chained_rebase_fixups(&self, data: &'data [u8]) -> Result<Vec<PointerFixup>> {
+        let endian = self.endian;
+        let data = Bytes(data);
+        let header = data
+            .read_at::<macho::DyldChainedFixupsHeader<Mach::Endian>>(0)
+            .read_error("Invalid Mach-O chained fixups header")?;
+        let starts_offset = header.starts_offset.get(endian) as usize;
+        let mut starts = data;
+        starts
+            .skip(starts_offset)
+            .read_error("Invalid Mach-O chained fixups starts offset")?;
+        let mut seg_info_offsets = starts;
+        let seg_count = seg_info_offsets
+            .read::<U32Bytes<Mach::Endian>>()
+            .read_error("Invalid Mach-O chained fixups segment count")?
+            .get(endian);
+        let seg_info_offsets = seg_info_offsets
+            .read_slice::<U32Bytes<Mach::Endian>>(seg_count as usize)
+            .read_error("Invalid Mach-O chained fixups segment count")?;
+
+        // Chained pointer addresses are offsets from the start of the image.
+        let image_base = self
+            .segments
+            .iter()
+            .map(|internal| internal.segment)
+            .find(|segment| {
+                segment.fileoff(endian).into() == 0 && segment.filesize(endian).into() != 0
+            })
+            .map(|segment| segment.vmaddr(endian).into())
+            .unwrap_or(0);
+
+        let limit = self.pointer_fixup_limit();
+        let mut fixups = Vec::new();
+        for seg_info_offset in seg_info_offsets {
+            let seg_info_offset = seg_info_offset.get(endian);
+            if seg_info_offset == 0 {
+                continue;
+            }
+            let mut seg_info = starts;
+            seg_info
+                .skip(seg_info_offset as usize)
+                .read_error("Invalid Mach-O chained fixups segment offset")?;
+            let starts_in_segment = seg_info
+                .read::<macho::DyldChainedStartsInSegment<Mach::Endian>>()
+                .read_error("Invalid Mach-O chained fixups segment info")?;
+            let page_count = starts_in_segment.page_count.get(endian);
+            let page_starts = seg_info
+                .read_slice::<U16Bytes<Mach::Endian>>(page_count.into())
+                .read_error("Invalid Mach-O chained fixups page count")?;
+            let page_size = u64::from(starts_in_segment.page_size.get(endian));
+            let segment_offset = starts_in_segment.segment_offset.get(endian);
+
+            // The stride, the mask for the next field, and the bind bit.
+            let (stride, next_mask, bind_bit) = match starts_in_segment.pointer_format.get(endian) {
+                macho::DYLD_CHAINED_PTR_ARM64E
+                | macho::DYLD_CHAINED_PTR_ARM64E_USERLAND
+                | macho::DYLD_CHAINED_PTR_ARM64E_USERLAND24 => (8, 0x7ff, 62),
+                macho::DYLD_CHAINED_PTR_64 | macho::DYLD_CHAINED_PTR_64_OFFSET => (4, 0xfff, 63),
+                _ => return Err(Error("Unsupported Mach-O chained pointer format")),
+            };
+
+            for (page_index, page_start) in page_starts.iter().enumerate() {
+                let page_start = page_start.get(endian);
+                if page_start == macho::DYLD_CHAINED_PTR_START_NONE {
+                    continue;
+                }
+                let mut address = image_base
+                    .wrapping_add(segment_offset)
+                    .wrapping_add(page_index as u64 * page_size)
+                    .wrapping_add(page_start.into());
+                loop {
+                    let value = self.read_u64_at_address(address)?;
+                    if value & (1 << bind_bit) == 0 {
+                        push_fixup(&mut fixups, limit, PointerFixup { address, size: 8 })?;
+                    }
+                    let next = (value >> 51) & next_mask;
+                    if next == 0 {
+                        break;
+                    }
+                    address = address.wrapping_add(next * stride);
+                }
+            }
+        }
+        Ok(fixups)
+    }
+
+    /// Return the maximum number of pointer fixups.
+    ///
+    /// Opcodes and chains can repeat the same pointers, so the number of fixups
+    /// is not otherwise limited by the size of the fixup data.
+    fn pointer_fixup_limit(&self) -> usize {
+        // Each pointer in a valid file is a separate location in the file data,
+        // and pointers are at least 4 bytes.
+        let file_limit = self.data.len().map_or(0, |len| len / 4);
+        if file_limit < self.max_pointer_fixups as u64 {
+            file_limit as usize
+        } else {
+            self.max_pointer_fixups
+        }
+    }
+
+    fn read_u64_at_address(&self, address: u64) -> Result<u64> {
+        let endian = self.endian;
+        self.segments
+            .iter()
+            .find_map(|internal| {
+                let segment = internal.segment;
+                let offset = address.checked_sub(segment.vmaddr(endian).into())?;
+                let data = segment.data(endian, internal.data).ok()?;
+                Bytes(data)
+                    .read_at::<U64Bytes<Mach::Endian>>(offset as usize)
+                    .ok()
+            })
+            .map(|value| value.get(endian))
+            .read_error("Invalid Mach-O chained fixup address")
+    }
+}
+
+/// The state of the rebase opcode decoder.
+struct Rebases {
+    fixups: Vec<PointerFixup>,
+    limit: usize,
+    size: u8,
+    address: u64,
+    segment_end: u64,
+}
+
+impl Rebases {
+    fn skip(&mut self, offset: u64) {
+        self.address = self.address.wrapping_add(offset);
+    }
+
+    /// Rebase `count` pointers starting at the current address, advancing by `advance`
+    /// after each one.
+    ///
+    /// All of the pointers must be within the segment.
+    fn rebase(&mut self, count: u64, advance: u64) -> Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        // The number of pointers that fit in the rest of the segment.
+        let available = match self.segment_end.checked_sub(self.address) {
+            Some(remaining) if remaining != 0 => (remaining - 1) / advance + 1,
+            _ => 0,
+        };
+        if count > available {
+            return Err(Error("Invalid Mach-O rebase address"));
+        }
+        for _ in 0..count {
+            if self.size != 0 {
+                let fixup = PointerFixup {
+                    address: self.address,
+                    size: self.size,
+                };
+                push_fixup(&mut self.fixups, self.limit, fixup)?;
+            }
+            self.skip(advance);
+        }
+        Ok(())
+    }
+}
+
+fn push_fixup(fixups: &mut Vec<PointerFixup>, limit: usize, fixup: PointerFixup) -> Result<()> {
+    if fixups.len() >= limit {
+        return Err(Error("Too many Mach-O pointer fixups"));
+    }
+    fixups.push(fixup);
+    Ok(())
+}
+
+/// Return the address advance after each rebase for a skip of `skip` bytes.
+fn rebase_advance(stride: u64, skip: u64) -> Result<u64> {
+    stride
+        .checked_add(skip)
+        .read_error("Invalid Mach-O rebase skip")
+}
+
+pub(super) fn read_uleb128(data: &mut Bytes<'_>) -> Result<u64> {
+    data.read_uleb128()
+        .read_error("Invalid Mach-O ULEB128 value")
+}
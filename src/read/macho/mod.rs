@@ -28,3 +28,11 @@ pub use symbol::*;
 
 mod relocation;
 pub use relocation::*;
+
+mod objc;
+pub use objc::*;
+
+mod fixups;
+
+mod unwind;
+pub use unwind::*;
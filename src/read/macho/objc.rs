@@ -0,0 +1,370 @@
+use alloc::vec::Vec;
+
+use crate::endian::{U32Bytes, U64Bytes};
+use crate::macho;
+use crate::read::{Bytes, Error, ObjectSection, ReadError, ReadRef, Result};
+
+use super::{MachHeader, MachOFile, MachOSection, Section, Segment};
+
+/// The mask for the `class_ro_t` pointer in the `data` field of a 64-bit `class_t`.
+///
+/// The low bits are flags, such as whether the class is a Swift class.
+const CLASS_DATA_MASK_64: u64 = !7;
+/// The mask for the `class_ro_t` pointer in the `data` field of a 32-bit `class_t`.
+const CLASS_DATA_MASK_32: u64 = !3;
+
+/// The flag in a `method_list_t` for method lists using relative offsets.
+const METHOD_LIST_SMALL: u32 = 0x8000_0000;
+/// The mask for the entry size in the `entsizeAndFlags` field of a `method_list_t`.
+const METHOD_LIST_ENTSIZE_MASK: u32 = 0x0000_fffc;
+
+impl<'data, Mach, R> MachOFile<'data, Mach, R>
+where
+    Mach: MachHeader,
+    R: ReadRef<'data>,
+{
+    /// Return an iterator over the Objective-C classes in the `__objc_classlist` section.
+    ///
+    /// The iterator is empty if the file does not contain this section.
+    ///
+    /// If the file uses chained fixups, then the fixup bits of the pointers are
+    /// masked. Pointers that are binds to other images cannot be followed.
+    pub fn objc_classes<'file>(&'file self) -> Result<ObjcClassIterator<'data, 'file, Mach, R>> {
+        let classlist = self.sections.iter().find(|internal| {
+            let section = internal.section;
+            section.segment_name().starts_with(b"__DATA") && section.name() == b"__objc_classlist"
+        });
+        let data = match classlist {
+            Some(internal) => MachOSection {
+                file: self,
+                internal: *internal,
+            }
+            .data()?,
+            None => &[],
+        };
+        // Pointers using the `*_OFFSET` chained pointer formats are relative
+        // to the address of the start of the file.
+        let image_base = self
+            .segments
+            .iter()
+            .map(|internal| internal.segment)
+            .find(|segment| {
+                segment.fileoff(self.endian).into() == 0
+                    && segment.filesize(self.endian).into() != 0
+            })
+            .map(|segment| segment.vmaddr(self.endian).into())
+            .unwrap_or(0);
+        let cpusubtype = self.header.cpusubtype(self.endian) & !macho::CPU_SUBTYPE_MASK;
+        let pointers = if self.chained_fixups_data()?.is_none() {
+            ObjcPointers::Plain
+        } else if self.header.cputype(self.endian) == macho::CPU_TYPE_ARM64
+            && cpusubtype == macho::CPU_SUBTYPE_ARM64E
+        {
+            ObjcPointers::ChainedArm64e
+        } else {
+            ObjcPointers::Chained
+        };
+        Ok(ObjcClassIterator {
+            reader: ObjcReader {
+                file: self,
+                image_base,
+                pointers,
+            },
+            data: Bytes(data),
+        })
+    }
+}
+
+/// An iterator over the Objective-C classes in a Mach-O file.
+///
+/// Returned by [`MachOFile::objc_classes`].
+#[derive(Debug)]
+pub struct ObjcClassIterator<'data, 'file, Mach, R = &'data [u8]>
+where
+    'data: 'file,
+    Mach: MachHeader,
+    R: ReadRef<'data>,
+{
+    reader: ObjcReader<'data, 'file, Mach, R>,
+    data: Bytes<'data>,
+}
+
+impl<'data, 'file, Mach, R> ObjcClassIterator<'data, 'file, Mach, R>
+where
+    Mach: MachHeader,
+    R: ReadRef<'data>,
+{
+    /// Read the next class.
+    ///
+    /// Returns `Ok(None)` when there are no more classes.
+    pub fn next(&mut self) -> Result<Option<ObjcClass<'data>>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let result = self.parse().map(Some);
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+
+    fn parse(&mut self) -> Result<ObjcClass<'data>> {
+        let reader = &self.reader;
+        let pointer = reader.read_word(&mut self.data)?;
+        let address = reader
+            .pointer(pointer)
+            .read_error("Unsupported Mach-O Objective-C class pointer")?;
+        reader.class(address)
+    }
+}
+
+/// An Objective-C class in a Mach-O file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjcClass<'data> {
+    /// The address of the `class_t` structure.
+    pub address: u64,
+    /// The name of the class.
+    pub name: &'data [u8],
+    /// The instance methods of the class.
+    pub methods: Vec<ObjcMethod<'data>>,
+}
+
+/// A method of an Objective-C class in a Mach-O file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjcMethod<'data> {
+    /// The selector name of the method.
+    pub name: &'data [u8],
+    /// The address of the implementation of the method.
+    ///
+    /// This is 0 if the implementation address is not known.
+    pub implementation: u64,
+}
+
+/// The encoding of pointers in the Objective-C metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ObjcPointers {
+    Plain,
+    /// `DYLD_CHAINED_PTR_64`, `DYLD_CHAINED_PTR_64_OFFSET` or `DYLD_CHAINED_PTR_32`.
+    Chained,
+    /// `DYLD_CHAINED_PTR_ARM64E` and variants.
+    ChainedArm64e,
+}
+
+#[derive(Debug)]
+struct ObjcReader<'data, 'file, Mach, R>
+where
+    'data: 'file,
+    Mach: MachHeader,
+    R: ReadRef<'data>,
+{
+    file: &'file MachOFile<'data, Mach, R>,
+    image_base: u64,
+    pointers: ObjcPointers,
+}
+
+impl<'data, 'file, Mach, R> ObjcReader<'data, 'file, Mach, R>
+where
+    Mach: MachHeader,
+    R: ReadRef<'data>,
+{
+    fn is_64(&self) -> bool {
+        self.file.header.is_type_64()
+    }
+
+    /// Return the data starting at the given address.
+    fn data_at(&self, address: u64) -> Result<Bytes<'data>> {
+        let endian = self.file.endian;
+        self.file
+            .segments
+            .iter()
+            .find_map(|internal| {
+                let segment = internal.segment;
+                let offset = address.checked_sub(segment.vmaddr(endian).into())?;
+                let data = segment.data(endian, internal.data).ok()?;
+                if offset >= data.len() as u64 {
+                    return None;
+                }
+                Some(Bytes(&data[offset as usize..]))
+            })
+            .read_error("Invalid Mach-O Objective-C address")
+    }
+
+    fn read_word(&self, data: &mut Bytes<'data>) -> Result<u64> {
+        let endian = self.file.endian;
+        if self.is_64() {
+            data.read::<U64Bytes<_>>()
+                .map(|value| value.get(endian))
+                .read_error("Invalid Mach-O Objective-C pointer")
+        } else {
+            data.read::<U32Bytes<_>>()
+                .map(|value| value.get(endian).into())
+                .read_error("Invalid Mach-O Objective-C pointer")
+        }
+    }
+
+    fn read_u32(&self, data: &mut Bytes<'data>) -> Result<u32> {
+        data.read::<U32Bytes<_>>()
+            .map(|value| value.get(self.file.endian))
+            .read_error("Invalid Mach-O Objective-C data")
+    }
+
+    fn read_pointer_at(&self, address: u64) -> Result<Option<u64>> {
+        let pointer = self.read_word(&mut self.data_at(address)?)?;
+        Ok(self.pointer(pointer))
+    }
+
+    fn read_string_at(&self, address: u64) -> Result<&'data [u8]> {
+        self.data_at(address)?
+            .read_string()
+            .read_error("Invalid Mach-O Objective-C string")
+    }
+
+    /// Convert a pointer value to an address.
+    ///
+    /// Returns `None` for null pointers and pointers that are binds.
+    fn pointer(&self, value: u64) -> Option<u64> {
+        let address = match self.pointers {
+            ObjcPointers::Plain => value,
+            ObjcPointers::Chained if self.is_64() => {
+                if value & (1 << 63) != 0 {
+                    return None;
+                }
+                let high8 = (value >> 36) & 0xff;
+                (value & 0xf_ffff_ffff) | (high8 << 56)
+            }
+            ObjcPointers::Chained => {
+                if value & (1 << 31) != 0 {
+                    return None;
+                }
+                value & 0x3ff_ffff
+            }
+            ObjcPointers::ChainedArm64e => {
+                if value & (1 << 62) != 0 {
+                    return None;
+                }
+                if value & (1 << 63) != 0 {
+                    value & 0xffff_ffff
+                } else {
+                    let high8 = (value >> 43) & 0xff;
+                    (value & 0x7ff_ffff_ffff) | (high8 << 56)
+                }
+            }
+        };
+        if address == 0 {
+            return None;
+        }
+        if self.pointers != ObjcPointers::Plain && address < self.image_base {
+            // The target is an offset from the start of the image.
+            return address.checked_add(self.image_base);
+        }
+        Some(address)
+    }
+
+    fn class(&self, address: u64) -> Result<ObjcClass<'data>> {
+        // struct class_t { isa, superclass, cache, vtable, data }
+        let word_size = if self.is_64() { 8 } else { 4 };
+        let mut class = self.data_at(address)?;
+        class
+            .skip(4 * word_size)
+            .read_error("Invalid Mach-O Objective-C class")?;
+        let data = self.read_word(&mut class)?;
+        let mask = if self.is_64() {
+            CLASS_DATA_MASK_64
+        } else {
+            CLASS_DATA_MASK_32
+        };
+        let class_ro = self
+            .pointer(data)
+            .read_error("Invalid Mach-O Objective-C class data pointer")?
+            & mask;
+
+        // struct class_ro_t {
+        //     flags, instanceStart, instanceSize, [reserved,]
+        //     ivarLayout, name, baseMethods, ...
+        // }
+        let mut class_ro = self.data_at(class_ro)?;
+        class_ro
+            .skip(if self.is_64() { 16 } else { 12 })
+            .read_error("Invalid Mach-O Objective-C class data")?;
+        self.read_word(&mut class_ro)?;
+        let name = self.read_word(&mut class_ro)?;
+        let name = self
+            .pointer(name)
+            .read_error("Invalid Mach-O Objective-C class name pointer")?;
+        let name = self.read_string_at(name)?;
+        let methods = match self.pointer(self.read_word(&mut class_ro)?) {
+            Some(methods) => self.methods(methods)?,
+            None => Vec::new(),
+        };
+        Ok(ObjcClass {
+            address,
+            name,
+            methods,
+        })
+    }
+
+    fn methods(&self, address: u64) -> Result<Vec<ObjcMethod<'data>>> {
+        // struct method_list_t { entsizeAndFlags, count, methods[] }
+        let mut list = self.data_at(address)?;
+        let flags = self.read_u32(&mut list)?;
+        let count = self.read_u32(&mut list)?;
+        let entsize = flags & METHOD_LIST_ENTSIZE_MASK;
+        let small = flags & METHOD_LIST_SMALL != 0;
+        let word_size = if self.is_64() { 8 } else { 4 };
+        let min_entsize = if small { 12 } else { 3 * word_size };
+        if entsize < min_entsize {
+            return Err(Error("Invalid Mach-O Objective-C method list entry size"));
+        }
+
+        let mut methods = Vec::new();
+        let mut entry_address = address
+            .checked_add(8)
+            .read_error("Invalid Mach-O Objective-C method list address")?;
+        for _ in 0..count {
+            let mut entry = list
+                .read_bytes(entsize as usize)
+                .read_error("Invalid Mach-O Objective-C method list count")?;
+            let method = if small {
+                // struct method_t { int32_t name, types, imp }
+                // The offsets are relative to the address of each field.
+                // The name is an offset to a selector reference.
+                let name = self.read_u32(&mut entry)? as i32;
+                self.read_u32(&mut entry)?;
+                let imp = self.read_u32(&mut entry)? as i32;
+                let selector = entry_address.wrapping_add(name as i64 as u64);
+                let name = self
+                    .read_pointer_at(selector)?
+                    .read_error("Invalid Mach-O Objective-C selector reference")?;
+                let implementation = if imp == 0 {
+                    0
+                } else {
+                    entry_address
+                        .checked_add(8)
+                        .read_error("Invalid Mach-O Objective-C method list address")?
+                        .wrapping_add(imp as i64 as u64)
+                };
+                ObjcMethod {
+                    name: self.read_string_at(name)?,
+                    implementation,
+                }
+            } else {
+                // struct method_t { SEL name; const char *types; IMP imp; }
+                let name = self.read_word(&mut entry)?;
+                let name = self
+                    .pointer(name)
+                    .read_error("Invalid Mach-O Objective-C method name pointer")?;
+                self.read_word(&mut entry)?;
+                let imp = self.read_word(&mut entry)?;
+                ObjcMethod {
+                    name: self.read_string_at(name)?,
+                    implementation: self.pointer(imp).unwrap_or(0),
+                }
+            };
+            methods.push(method);
+            entry_address = entry_address
+                .checked_add(entsize.into())
+                .read_error("Invalid Mach-O Objective-C method list address")?;
+        }
+        Ok(methods)
+    }
+}
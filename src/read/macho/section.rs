@@ -133,6 +133,13 @@ where
         self.bytes()
     }
 
+    fn is_bss(&self) -> bool {
+        let section_type = self.internal.section.flags(self.file.endian) & macho::SECTION_TYPE;
+        section_type == macho::S_ZEROFILL
+            || section_type == macho::S_GB_ZEROFILL
+            || section_type == macho::S_THREAD_LOCAL_ZEROFILL
+    }
+
     fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
         Ok(read::util::data_range(
             self.bytes()?,
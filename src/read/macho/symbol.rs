@@ -179,6 +179,7 @@ where
         MachOSymbolIterator {
             file: self.file,
             index: 0,
+            end: self.file.symbols.len(),
         }
     }
 
@@ -203,6 +204,7 @@ where
 {
     pub(super) file: &'file MachOFile<'data, Mach, R>,
     pub(super) index: usize,
+    pub(super) end: usize,
 }
 
 impl<'data, 'file, Mach, R> fmt::Debug for MachOSymbolIterator<'data, 'file, Mach, R>
@@ -225,6 +227,9 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             let index = self.index;
+            if index >= self.end {
+                return None;
+            }
             let nlist = self.file.symbols.symbols.get(index)?;
             self.index += 1;
             if let Some(symbol) = MachOSymbol::new(self.file, SymbolIndex(index), nlist) {
@@ -268,6 +273,85 @@ where
         }
         Some(MachOSymbol { file, index, nlist })
     }
+
+    /// Return the raw `nlist` entry for this symbol.
+    #[inline]
+    pub fn nlist(&self) -> &'data Mach::Nlist {
+        self.nlist
+    }
+
+    /// Return the raw `n_type` field.
+    #[inline]
+    pub fn n_type(&self) -> u8 {
+        self.nlist.n_type()
+    }
+
+    /// Return the raw `n_sect` field.
+    ///
+    /// This is a 1-based section index, or `NO_SECT`.
+    #[inline]
+    pub fn n_sect(&self) -> u8 {
+        self.nlist.n_sect()
+    }
+
+    /// Return the raw `n_desc` field.
+    #[inline]
+    pub fn n_desc(&self) -> u16 {
+        self.nlist.n_desc(self.file.endian)
+    }
+
+    /// Return true if this is a STAB debugging symbol.
+    ///
+    /// This is currently always false, since STAB symbols are skipped when
+    /// iterating or looking up symbols. Use [`Nlist::is_stab`] with the raw
+    /// symbol table to find them.
+    #[inline]
+    pub fn is_stab(&self) -> bool {
+        self.nlist.is_stab()
+    }
+
+    /// Return true if the `N_EXT` bit is set.
+    #[inline]
+    pub fn is_external(&self) -> bool {
+        self.nlist.is_external()
+    }
+
+    /// Return the symbol type.
+    ///
+    /// This is one of `N_UNDF`, `N_ABS`, `N_SECT`, `N_PBUD` or `N_INDR`.
+    #[inline]
+    pub fn symbol_type(&self) -> u8 {
+        self.nlist.symbol_type()
+    }
+
+    /// Return the library ordinal from the `n_desc` field.
+    ///
+    /// For undefined symbols in files using two-level namespaces, this is either
+    /// a 1-based index into the dylib load commands, or a special ordinal such as
+    /// `SELF_LIBRARY_ORDINAL` or `DYNAMIC_LOOKUP_ORDINAL`.
+    #[inline]
+    pub fn library_ordinal(&self) -> u8 {
+        self.nlist.library_ordinal(self.file.endian)
+    }
+
+    /// Return the size of a common symbol.
+    ///
+    /// Common symbols are undefined external symbols, with the size in `n_value`.
+    ///
+    /// Returns `None` if this is not a common symbol.
+    #[inline]
+    pub fn common_size(&self) -> Option<u64> {
+        self.nlist.common_size(self.file.endian)
+    }
+
+    /// Return the alignment in bytes of a common symbol.
+    ///
+    /// Returns `None` if this is not a common symbol.
+    /// See [`Nlist::common_alignment`].
+    #[inline]
+    pub fn common_alignment(&self) -> Option<u64> {
+        self.nlist.common_alignment(self.file.endian)
+    }
 }
 
 impl<'data, 'file, Mach, R> read::private::Sealed for MachOSymbol<'data, 'file, Mach, R>
@@ -388,7 +472,7 @@ where
     }
 
     #[inline]
-    fn flags(&self) -> SymbolFlags<SectionIndex> {
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
         let n_desc = self.nlist.n_desc(self.file.endian);
         SymbolFlags::MachO { n_desc }
     }
@@ -423,6 +507,20 @@ pub trait Nlist: Debug + Pod {
         self.n_type() & macho::N_STAB != 0
     }
 
+    /// Return true if the `N_EXT` bit is set.
+    ///
+    /// This is only meaningful if this is not a STAB symbol.
+    fn is_external(&self) -> bool {
+        self.n_type() & macho::N_EXT != 0
+    }
+
+    /// Return the `N_TYPE` bits of the `n_type` field.
+    ///
+    /// This is only meaningful if this is not a STAB symbol.
+    fn symbol_type(&self) -> u8 {
+        self.n_type() & macho::N_TYPE
+    }
+
     /// Return true if this is an undefined symbol.
     fn is_undefined(&self) -> bool {
         let n_type = self.n_type();
@@ -443,6 +541,45 @@ pub trait Nlist: Debug + Pod {
     fn library_ordinal(&self, endian: Self::Endian) -> u8 {
         (self.n_desc(endian) >> 8) as u8
     }
+
+    /// Return true if this is a common symbol.
+    ///
+    /// Common symbols are undefined external symbols with a non-zero `n_value`.
+    fn is_common(&self, endian: Self::Endian) -> bool {
+        self.is_undefined() && self.is_external() && self.n_value(endian).into() != 0
+    }
+
+    /// Return the size of a common symbol.
+    ///
+    /// Returns `None` if this is not a common symbol.
+    fn common_size(&self, endian: Self::Endian) -> Option<u64> {
+        if !self.is_common(endian) {
+            return None;
+        }
+        Some(self.n_value(endian).into())
+    }
+
+    /// Return the alignment in bytes of a common symbol.
+    ///
+    /// This is the power of 2 given by `GET_COMM_ALIGN(n_desc)`. If that is zero,
+    /// then the natural alignment for the size is used, rounded up to a power of 2
+    /// and limited to 2^15, which matches the behaviour of `ld64`.
+    ///
+    /// Returns `None` if this is not a common symbol.
+    fn common_alignment(&self, endian: Self::Endian) -> Option<u64> {
+        let size = self.common_size(endian)?;
+        let mut align = u32::from((self.n_desc(endian) >> 8) & 0x0f);
+        if align == 0 {
+            align = 63 - size.leading_zeros();
+            if size != 1 << align {
+                align += 1;
+            }
+            if align > 15 {
+                align = 15;
+            }
+        }
+        Some(1 << align)
+    }
 }
 
 impl<Endian: endian::Endian> Nlist for macho::Nlist32<Endian> {
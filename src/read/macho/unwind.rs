@@ -0,0 +1,278 @@
+use crate::endian::{Endian, U32};
+use crate::macho;
+use crate::read::{Bytes, Error, ReadError, ReadRef, Result};
+
+use super::{MachHeader, MachOFile, Section};
+
+impl<'data, Mach, R> MachOFile<'data, Mach, R>
+where
+    Mach: MachHeader,
+    R: ReadRef<'data>,
+{
+    /// Return the compact unwind information in the `__TEXT,__unwind_info` section.
+    ///
+    /// Returns `Ok(None)` if the section is not present.
+    pub fn compact_unwind(&self) -> Result<Option<CompactUnwind<'data, Mach::Endian>>> {
+        let endian = self.endian;
+        let internal = match self.sections.iter().find(|internal| {
+            internal.section.segment_name() == b"__TEXT"
+                && internal.section.name() == b"__unwind_info"
+        }) {
+            Some(internal) => internal,
+            None => return Ok(None),
+        };
+        let segment = self.segment_internal(internal.segment_index)?;
+        let data = internal
+            .section
+            .data(endian, segment.data)
+            .read_error("Invalid Mach-O section size or offset")?;
+        CompactUnwind::parse(endian, data, self.text_address()).map(Some)
+    }
+
+    /// Return an iterator over the compact unwind entries in the `__TEXT,__unwind_info` section.
+    ///
+    /// Returns an empty iterator if the section is not present.
+    pub fn compact_unwind_entries(&self) -> Result<CompactUnwindIterator<'data, Mach::Endian>> {
+        Ok(match self.compact_unwind()? {
+            Some(unwind) => unwind.entries(),
+            None => CompactUnwindIterator {
+                unwind: None,
+                index: 0,
+                page: CompactUnwindPage::None,
+            },
+        })
+    }
+}
+
+/// The compact unwind information in a Mach-O `__TEXT,__unwind_info` section.
+#[derive(Debug, Clone, Copy)]
+pub struct CompactUnwind<'data, E: Endian> {
+    endian: E,
+    data: Bytes<'data>,
+    address: u64,
+    header: &'data macho::UnwindInfoSectionHeader<E>,
+    common_encodings: &'data [U32<E>],
+    index: &'data [macho::UnwindInfoSectionHeaderIndexEntry<E>],
+}
+
+impl<'data, E: Endian> CompactUnwind<'data, E> {
+    /// Parse the compact unwind information.
+    ///
+    /// `data` must be the data of the `__TEXT,__unwind_info` section, and `address`
+    /// must be the address of the start of the image, which is usually the
+    /// address of the `__TEXT` segment.
+    pub fn parse(endian: E, data: &'data [u8], address: u64) -> Result<Self> {
+        let data = Bytes(data);
+        let header = data
+            .read_at::<macho::UnwindInfoSectionHeader<E>>(0)
+            .read_error("Invalid Mach-O compact unwind header")?;
+        if header.version.get(endian) != macho::UNWIND_SECTION_VERSION {
+            return Err(Error("Unsupported Mach-O compact unwind version"));
+        }
+        let common_encodings = data
+            .read_slice_at(
+                header.common_encodings_array_section_offset.get(endian) as usize,
+                header.common_encodings_array_count.get(endian) as usize,
+            )
+            .read_error("Invalid Mach-O compact unwind common encodings")?;
+        let index = data
+            .read_slice_at(
+                header.index_section_offset.get(endian) as usize,
+                header.index_count.get(endian) as usize,
+            )
+            .read_error("Invalid Mach-O compact unwind index")?;
+        Ok(CompactUnwind {
+            endian,
+            data,
+            address,
+            header,
+            common_encodings,
+            index,
+        })
+    }
+
+    /// Return the section header.
+    pub fn header(&self) -> &'data macho::UnwindInfoSectionHeader<E> {
+        self.header
+    }
+
+    /// Return the common encodings array.
+    pub fn common_encodings(&self) -> &'data [U32<E>] {
+        self.common_encodings
+    }
+
+    /// Return the first level index entries.
+    ///
+    /// The last entry is a sentinel that gives the end of the last range of functions.
+    pub fn index(&self) -> &'data [macho::UnwindInfoSectionHeaderIndexEntry<E>] {
+        self.index
+    }
+
+    /// Return an iterator over the entries of all second level pages.
+    pub fn entries(&self) -> CompactUnwindIterator<'data, E> {
+        CompactUnwindIterator {
+            unwind: Some(*self),
+            index: 0,
+            page: CompactUnwindPage::None,
+        }
+    }
+
+    /// Parse the second level page for a first level index entry.
+    fn page(
+        &self,
+        entry: &macho::UnwindInfoSectionHeaderIndexEntry<E>,
+    ) -> Result<CompactUnwindPage<'data, E>> {
+        let endian = self.endian;
+        let offset = entry.second_level_pages_section_offset.get(endian) as usize;
+        let kind = self
+            .data
+            .read_at::<U32<E>>(offset)
+            .read_error("Invalid Mach-O compact unwind page offset")?
+            .get(endian);
+        match kind {
+            macho::UNWIND_SECOND_LEVEL_REGULAR => {
+                let header = self
+                    .data
+                    .read_at::<macho::UnwindInfoRegularSecondLevelPageHeader<E>>(offset)
+                    .read_error("Invalid Mach-O compact unwind page header")?;
+                let entries = self
+                    .data
+                    .read_slice_at(
+                        offset + usize::from(header.entry_page_offset.get(endian)),
+                        header.entry_count.get(endian).into(),
+                    )
+                    .read_error("Invalid Mach-O compact unwind page entries")?;
+                Ok(CompactUnwindPage::Regular { entries })
+            }
+            macho::UNWIND_SECOND_LEVEL_COMPRESSED => {
+                let header = self
+                    .data
+                    .read_at::<macho::UnwindInfoCompressedSecondLevelPageHeader<E>>(offset)
+                    .read_error("Invalid Mach-O compact unwind page header")?;
+                let entries = self
+                    .data
+                    .read_slice_at(
+                        offset + usize::from(header.entry_page_offset.get(endian)),
+                        header.entry_count.get(endian).into(),
+                    )
+                    .read_error("Invalid Mach-O compact unwind page entries")?;
+                let encodings = self
+                    .data
+                    .read_slice_at(
+                        offset + usize::from(header.encodings_page_offset.get(endian)),
+                        header.encodings_count.get(endian).into(),
+                    )
+                    .read_error("Invalid Mach-O compact unwind page encodings")?;
+                Ok(CompactUnwindPage::Compressed {
+                    function_offset: entry.function_offset.get(endian),
+                    entries,
+                    encodings,
+                })
+            }
+            _ => Err(Error("Unsupported Mach-O compact unwind page kind")),
+        }
+    }
+}
+
+/// An entry in the compact unwind information.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactUnwindEntry {
+    /// The address of the start of the function.
+    pub address: u64,
+    /// The compact unwind encoding for the function.
+    pub encoding: u32,
+}
+
+/// A fallible iterator over the entries in the compact unwind information.
+///
+/// Returned by [`CompactUnwind::entries`] and [`MachOFile::compact_unwind_entries`].
+#[derive(Debug, Clone)]
+pub struct CompactUnwindIterator<'data, E: Endian> {
+    unwind: Option<CompactUnwind<'data, E>>,
+    index: usize,
+    page: CompactUnwindPage<'data, E>,
+}
+
+#[derive(Debug, Clone)]
+enum CompactUnwindPage<'data, E: Endian> {
+    None,
+    Regular {
+        entries: &'data [macho::UnwindInfoRegularSecondLevelEntry<E>],
+    },
+    Compressed {
+        function_offset: u32,
+        entries: &'data [U32<E>],
+        encodings: &'data [U32<E>],
+    },
+}
+
+impl<'data, E: Endian> CompactUnwindIterator<'data, E> {
+    /// Return the next entry.
+    ///
+    /// Entries are returned in the order of the second level pages, which is sorted
+    /// by address.
+    pub fn next(&mut self) -> Result<Option<CompactUnwindEntry>> {
+        let unwind = match self.unwind {
+            Some(unwind) => unwind,
+            None => return Ok(None),
+        };
+        let endian = unwind.endian;
+        loop {
+            match self.page {
+                CompactUnwindPage::None => {}
+                CompactUnwindPage::Regular { ref mut entries } => {
+                    if let Some((entry, rest)) = entries.split_first() {
+                        *entries = rest;
+                        return Ok(Some(CompactUnwindEntry {
+                            address: unwind
+                                .address
+                                .wrapping_add(entry.function_offset.get(endian).into()),
+                            encoding: entry.encoding.get(endian),
+                        }));
+                    }
+                }
+                CompactUnwindPage::Compressed {
+                    function_offset,
+                    ref mut entries,
+                    encodings,
+                } => {
+                    if let Some((entry, rest)) = entries.split_first() {
+                        *entries = rest;
+                        let entry = entry.get(endian);
+                        let offset = function_offset.wrapping_add(
+                            entry & macho::UNWIND_INFO_COMPRESSED_ENTRY_FUNC_OFFSET_MASK,
+                        );
+                        // The encoding index is into the common encodings,
+                        // followed by the encodings of the page.
+                        let index = (entry
+                            >> macho::UNWIND_INFO_COMPRESSED_ENTRY_ENCODING_INDEX_SHIFT)
+                            as usize;
+                        let encoding = match index.checked_sub(unwind.common_encodings.len()) {
+                            None => unwind.common_encodings[index],
+                            Some(index) => *encodings
+                                .get(index)
+                                .read_error("Invalid Mach-O compact unwind encoding index")?,
+                        };
+                        return Ok(Some(CompactUnwindEntry {
+                            address: unwind.address.wrapping_add(offset.into()),
+                            encoding: encoding.get(endian),
+                        }));
+                    }
+                }
+            }
+
+            // Advance to the next second level page.
+            let entry = match unwind.index.get(self.index) {
+                Some(entry) => entry,
+                None => return Ok(None),
+            };
+            self.index += 1;
+            // The sentinel entry at the end of the index has no page.
+            if entry.second_level_pages_section_offset.get(endian) == 0 {
+                self.page = CompactUnwindPage::None;
+                continue;
+            }
+            self.page = unwind.page(entry)?;
+        }
+    }
+}
@@ -17,6 +17,19 @@ pub use read_cache::*;
 mod util;
 pub use util::*;
 
+#[cfg(feature = "demangle")]
+mod demangle;
+
+#[cfg(feature = "hash")]
+mod md5;
+#[cfg(feature = "hash")]
+mod sha;
+
+#[cfg(feature = "hash")]
+mod hash;
+#[cfg(feature = "hash")]
+pub use hash::*;
+
 #[cfg(any(
     feature = "coff",
     feature = "elf",
@@ -49,6 +62,23 @@ pub mod macho;
 #[cfg(feature = "pe")]
 pub mod pe;
 
+mod relocate;
+pub use relocate::{DisassemblyView, ResolvedRef};
+
+mod functions;
+pub use functions::Function;
+
+mod address;
+pub use address::{AddressDescription, AddressMap};
+
+mod relocations;
+
+mod strings;
+pub use strings::*;
+
+mod symbol_diff;
+pub use symbol_diff::*;
+
 mod traits;
 pub use traits::*;
 
@@ -76,6 +106,151 @@ impl std::error::Error for Error {}
 /// The result type used within the read module.
 pub type Result<T> = result::Result<T, Error>;
 
+/// The stage of parsing at which an error occurred.
+///
+/// Used by [`ParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseStage {
+    /// The file header, or other headers at fixed locations.
+    Header,
+    /// The segment table.
+    Segments,
+    /// The section table, or data that is linked from the section table.
+    Sections,
+    /// The symbol table.
+    Symbols,
+    /// Any other stage, or the file format does not distinguish stages.
+    Other,
+}
+
+impl ParseStage {
+    /// Return a function that adds this stage to an error.
+    pub(crate) fn error(self) -> impl Fn(Error) -> ParseError {
+        move |detail| ParseError {
+            stage: self,
+            detail,
+        }
+    }
+}
+
+impl fmt::Display for ParseStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ParseStage::Header => "header",
+            ParseStage::Segments => "segments",
+            ParseStage::Sections => "sections",
+            ParseStage::Symbols => "symbols",
+            ParseStage::Other => "other",
+        })
+    }
+}
+
+/// An error that includes the stage of parsing that failed.
+///
+/// Returned by [`File::parse_staged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    /// The stage of parsing that failed.
+    pub stage: ParseStage,
+    /// The error for the stage.
+    pub detail: Error,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (while parsing {})", self.detail, self.stage)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// Limits on the amount of work done when parsing a file.
+///
+/// Used by [`File::parse_with_options`] and the `parse_with_options` methods of
+/// the format specific file types. The section, symbol and relocation limits are
+/// checked against the counts in the headers before the corresponding tables
+/// are used. The file remembers the PE import descriptor limit for its import
+/// table iterators, and the pointer fixup limit for [`Object::pointer_fixups`].
+///
+/// `ParseOptions::default()` disables all limits, which is the same as using the
+/// `parse` methods. Use [`ParseOptions::strict`] when parsing untrusted input, or
+/// lower individual limits from either of them. These limits are not applied to
+/// Wasm files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// The maximum number of sections.
+    pub max_section_count: usize,
+    /// The maximum number of entries in each symbol table.
+    pub max_symbol_count: usize,
+    /// The maximum number of descriptors in each PE import table.
+    ///
+    /// This applies to both the import table and the delay-load import table.
+    pub max_import_descriptors: usize,
+    /// The maximum number of levels of tables in the PE resource directory.
+    pub max_resource_depth: usize,
+    /// The maximum total number of relocations in the relocation sections.
+    pub max_relocations: usize,
+    /// The maximum number of pointer fixups returned by [`Object::pointer_fixups`].
+    ///
+    /// This is currently only used for Mach-O files. The number of fixups is
+    /// also limited by the size of the file.
+    pub max_pointer_fixups: usize,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            max_section_count: usize::max_value(),
+            max_symbol_count: usize::max_value(),
+            max_import_descriptors: usize::max_value(),
+            max_resource_depth: usize::max_value(),
+            max_relocations: usize::max_value(),
+            max_pointer_fixups: usize::max_value(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Return options with limits that are suitable for parsing untrusted input.
+    ///
+    /// These limits are much larger than what is needed by normal files, but bound
+    /// the memory and time used by a malicious file. The resource directory is
+    /// limited to the 3 levels that are used by Windows.
+    pub fn strict() -> Self {
+        ParseOptions {
+            max_section_count: 0x1_0000,
+            max_symbol_count: 0x100_0000,
+            max_import_descriptors: 0x1000,
+            max_resource_depth: 3,
+            max_relocations: 0x100_0000,
+            max_pointer_fixups: 0x100_0000,
+        }
+    }
+
+    pub(crate) fn check_sections(&self, count: usize) -> Result<()> {
+        if count > self.max_section_count {
+            return Err(Error("Too many sections"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_symbols(&self, count: usize) -> Result<()> {
+        if count > self.max_symbol_count {
+            return Err(Error("Too many symbols"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_relocations(&self, count: u64) -> Result<()> {
+        if count > self.max_relocations as u64 {
+            return Err(Error("Too many relocations"));
+        }
+        Ok(())
+    }
+}
+
 trait ReadError<T> {
     fn read_error(self, error: &'static str) -> Result<T>;
 }
@@ -242,6 +417,61 @@ impl FileKind {
     }
 }
 
+/// The container format of a file.
+///
+/// This is used to determine how to find the objects within a file before
+/// parsing them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Container {
+    /// A Mach-O fat binary.
+    ///
+    /// This gives the architecture of each slice, in the order they are stored.
+    #[cfg(feature = "macho")]
+    FatMachO {
+        /// The architectures of the slices.
+        arches: Vec<Architecture>,
+    },
+    /// A Unix archive.
+    #[cfg(feature = "archive")]
+    Archive {
+        /// The archive format.
+        kind: archive::ArchiveKind,
+    },
+    /// A file containing a single object, or any other file kind.
+    Single,
+}
+
+impl Container {
+    /// Determine the container format by parsing the start of the file.
+    ///
+    /// Returns an error if the file kind is unknown, or if the headers of a
+    /// container are invalid.
+    pub fn parse<'data, R: ReadRef<'data>>(data: R) -> Result<Container> {
+        Ok(match FileKind::parse(data)? {
+            #[cfg(feature = "macho")]
+            FileKind::MachOFat32 => Container::FatMachO {
+                arches: macho::FatHeader::parse_arch32(data)?
+                    .iter()
+                    .map(macho::FatArch::architecture)
+                    .collect(),
+            },
+            #[cfg(feature = "macho")]
+            FileKind::MachOFat64 => Container::FatMachO {
+                arches: macho::FatHeader::parse_arch64(data)?
+                    .iter()
+                    .map(macho::FatArch::architecture)
+                    .collect(),
+            },
+            #[cfg(feature = "archive")]
+            FileKind::Archive => Container::Archive {
+                kind: archive::ArchiveFile::parse(data)?.kind(),
+            },
+            _ => Container::Single,
+        })
+    }
+}
+
 /// An object kind.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -338,17 +568,43 @@ impl<T: SymbolMapEntry> SymbolMap<T> {
     }
 }
 
+impl<'data> SymbolMap<SymbolMapName<'data>> {
+    /// Enable demangling when displaying the symbol names in the map.
+    ///
+    /// Names are demangled as they are formatted, so this does not allocate.
+    /// Names that are not mangled using the legacy Rust scheme are displayed
+    /// unchanged.
+    #[cfg(feature = "demangle")]
+    pub fn with_demangling(mut self) -> Self {
+        for symbol in &mut self.symbols {
+            symbol.demangle = true;
+        }
+        self
+    }
+}
+
 /// A `SymbolMap` entry for symbol names.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// The `Display` implementation writes the demangled name if demangling was
+/// enabled using `SymbolMap::with_demangling`. Demangling does not affect
+/// equality or hashing.
+#[derive(Debug, Clone, Copy)]
 pub struct SymbolMapName<'data> {
     address: u64,
     name: &'data str,
+    #[cfg(feature = "demangle")]
+    demangle: bool,
 }
 
 impl<'data> SymbolMapName<'data> {
     /// Construct a `SymbolMapName`.
     pub fn new(address: u64, name: &'data str) -> Self {
-        SymbolMapName { address, name }
+        SymbolMapName {
+            address,
+            name,
+            #[cfg(feature = "demangle")]
+            demangle: false,
+        }
     }
 
     /// The symbol address.
@@ -362,6 +618,52 @@ impl<'data> SymbolMapName<'data> {
     pub fn name(&self) -> &'data str {
         self.name
     }
+
+    /// The raw symbol name, without demangling.
+    #[inline]
+    pub fn raw(&self) -> &'data str {
+        self.name
+    }
+
+    /// The demangled symbol name.
+    ///
+    /// Only Rust names using the legacy mangling scheme are supported.
+    /// Returns `None` for any other name, including Rust v0 and C++ names.
+    ///
+    /// The name is demangled when it is formatted, so this does not allocate.
+    #[cfg(feature = "demangle")]
+    pub fn demangled(&self) -> Option<impl fmt::Display + 'data> {
+        demangle::demangle(self.name)
+    }
+}
+
+impl<'data> PartialEq for SymbolMapName<'data> {
+    fn eq(&self, other: &Self) -> bool {
+        self.address == other.address && self.name == other.name
+    }
+}
+
+impl<'data> Eq for SymbolMapName<'data> {}
+
+impl<'data> core::hash::Hash for SymbolMapName<'data> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.address.hash(state);
+        self.name.hash(state);
+    }
+}
+
+impl<'data> fmt::Display for SymbolMapName<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "demangle")]
+        {
+            if self.demangle {
+                if let Some(demangled) = self.demangled() {
+                    return fmt::Display::fmt(&demangled, f);
+                }
+            }
+        }
+        f.write_str(self.name)
+    }
 }
 
 impl<'data> SymbolMapEntry for SymbolMapName<'data> {
@@ -495,6 +797,91 @@ impl<'data> Export<'data> {
     }
 }
 
+/// A thread-local storage symbol.
+///
+/// Returned by [`Object::tls_symbols`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TlsSymbol<'data> {
+    index: SymbolIndex,
+    name: ByteString<'data>,
+    tls_offset: u64,
+}
+
+impl<'data> TlsSymbol<'data> {
+    /// The index of the symbol in the symbol table.
+    #[inline]
+    pub fn index(&self) -> SymbolIndex {
+        self.index
+    }
+
+    /// The symbol name.
+    #[inline]
+    pub fn name(&self) -> &'data [u8] {
+        self.name.0
+    }
+
+    /// The offset of the variable within the thread-local storage block.
+    ///
+    /// See [`Object::tls_symbols`] for the meaning of this for each file format.
+    #[inline]
+    pub fn tls_offset(&self) -> u64 {
+        self.tls_offset
+    }
+}
+
+/// A location containing an absolute pointer that must be adjusted by the load bias.
+///
+/// Returned by [`Object::pointer_fixups`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerFixup {
+    address: u64,
+    size: u8,
+}
+
+impl PointerFixup {
+    /// The virtual address of the pointer.
+    #[inline]
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// The size in bytes of the pointer.
+    #[inline]
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+}
+
+/// An iterator over the pointer fixups in a file.
+///
+/// Returned by [`Object::pointer_fixups`].
+#[derive(Debug, Clone)]
+pub struct PointerFixupIterator {
+    iter: alloc::vec::IntoIter<PointerFixup>,
+}
+
+impl PointerFixupIterator {
+    pub(crate) fn new(fixups: Vec<PointerFixup>) -> Self {
+        PointerFixupIterator {
+            iter: fixups.into_iter(),
+        }
+    }
+}
+
+impl Iterator for PointerFixupIterator {
+    type Item = PointerFixup;
+
+    #[inline]
+    fn next(&mut self) -> Option<PointerFixup> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
 /// PDB Information
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CodeView<'data> {
@@ -523,6 +910,110 @@ impl<'data> CodeView<'data> {
     }
 }
 
+/// A region of memory that is mapped when an object file is loaded.
+///
+/// Returned by [`Object::loadable_segments`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadSegment {
+    /// The virtual address of the region.
+    pub address: u64,
+    /// The size in bytes of the region in memory.
+    ///
+    /// This is never less than `file_size`. If it is greater, then the
+    /// remainder of the region is filled with zeros.
+    pub size: u64,
+    /// The offset of the data of the region in the file.
+    pub file_offset: u64,
+    /// The size in bytes of the data of the region in the file.
+    pub file_size: u64,
+    /// The memory protection of the region.
+    pub permissions: SegmentPermissions,
+}
+
+/// The memory protection of a loaded segment.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SegmentPermissions {
+    /// The memory can be read.
+    pub read: bool,
+    /// The memory can be written.
+    pub write: bool,
+    /// The memory can be executed.
+    pub execute: bool,
+}
+
+impl SegmentPermissions {
+    fn from_flags(flags: SegmentFlags) -> Self {
+        match flags {
+            #[cfg(feature = "elf")]
+            SegmentFlags::Elf { p_flags } => SegmentPermissions {
+                read: p_flags & crate::elf::PF_R != 0,
+                write: p_flags & crate::elf::PF_W != 0,
+                execute: p_flags & crate::elf::PF_X != 0,
+            },
+            #[cfg(feature = "macho")]
+            SegmentFlags::MachO { initprot, .. } => SegmentPermissions {
+                read: initprot & crate::macho::VM_PROT_READ != 0,
+                write: initprot & crate::macho::VM_PROT_WRITE != 0,
+                execute: initprot & crate::macho::VM_PROT_EXECUTE != 0,
+            },
+            #[cfg(feature = "coff")]
+            SegmentFlags::Coff { characteristics } => SegmentPermissions {
+                read: characteristics & crate::pe::IMAGE_SCN_MEM_READ != 0,
+                write: characteristics & crate::pe::IMAGE_SCN_MEM_WRITE != 0,
+                execute: characteristics & crate::pe::IMAGE_SCN_MEM_EXECUTE != 0,
+            },
+            _ => SegmentPermissions::default(),
+        }
+    }
+}
+
+/// An iterator over the loadable segments of an object file.
+///
+/// Returned by [`Object::loadable_segments`].
+#[derive(Debug)]
+pub struct LoadSegmentIterator<I> {
+    segments: Option<I>,
+}
+
+impl<I> LoadSegmentIterator<I> {
+    pub(crate) fn new(segments: I) -> Self {
+        LoadSegmentIterator {
+            segments: Some(segments),
+        }
+    }
+
+    pub(crate) fn empty() -> Self {
+        LoadSegmentIterator { segments: None }
+    }
+}
+
+impl<'data, I> Iterator for LoadSegmentIterator<I>
+where
+    I: Iterator,
+    I::Item: ObjectSegment<'data>,
+{
+    type Item = LoadSegment;
+
+    fn next(&mut self) -> Option<LoadSegment> {
+        let segments = self.segments.as_mut()?;
+        for segment in segments {
+            let (file_offset, file_size) = segment.file_range();
+            let size = core::cmp::max(segment.size(), file_size);
+            if size == 0 {
+                continue;
+            }
+            return Some(LoadSegment {
+                address: segment.address(),
+                size,
+                file_offset,
+                file_size,
+                permissions: SegmentPermissions::from_flags(segment.flags()),
+            });
+        }
+        None
+    }
+}
+
 /// The target referenced by a relocation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -0,0 +1,64 @@
+use crate::pe;
+use crate::LittleEndian as LE;
+
+/// The instruction set of a range of code in a hybrid PE image.
+///
+/// This is used for ARM64EC, ARM64X and CHPE x86 images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ChpeCodeType {
+    /// ARM64 code.
+    Arm64,
+    /// ARM64EC code.
+    Arm64Ec,
+    /// x64 code.
+    Amd64,
+    /// x86 code.
+    X86,
+}
+
+/// A range of code in a hybrid PE image.
+///
+/// Returned by [`PeFile::chpe_code_ranges`](super::PeFile::chpe_code_ranges).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChpeCodeRange {
+    /// The RVA of the start of the range.
+    pub start: u32,
+    /// The size in bytes of the range.
+    pub length: u32,
+    /// The instruction set of the code in the range.
+    pub code_type: ChpeCodeType,
+}
+
+impl ChpeCodeRange {
+    /// Decode an entry of the code map of an ARM64EC image.
+    pub(super) fn parse_arm64ec(entry: &pe::ImageChpeRangeEntry) -> Self {
+        let start_offset = entry.start_offset.get(LE);
+        let code_type = match start_offset & pe::IMAGE_CHPE_RANGE_ENTRY_TYPE_MASK {
+            pe::IMAGE_CHPE_RANGE_ENTRY_TYPE_ARM64 => ChpeCodeType::Arm64,
+            pe::IMAGE_CHPE_RANGE_ENTRY_TYPE_ARM64EC => ChpeCodeType::Arm64Ec,
+            // The remaining value is reserved, so treat it as x64 too.
+            _ => ChpeCodeType::Amd64,
+        };
+        ChpeCodeRange {
+            start: start_offset & !pe::IMAGE_CHPE_RANGE_ENTRY_TYPE_MASK,
+            length: entry.length.get(LE),
+            code_type,
+        }
+    }
+
+    /// Decode an entry of the code address ranges of a CHPE x86 image.
+    pub(super) fn parse_x86(entry: &pe::ImageChpeRangeEntry) -> Self {
+        let start_offset = entry.start_offset.get(LE);
+        let code_type = if start_offset & pe::IMAGE_CHPE_RANGE_ENTRY_NATIVE_CODE != 0 {
+            ChpeCodeType::Arm64
+        } else {
+            ChpeCodeType::X86
+        };
+        ChpeCodeRange {
+            start: start_offset & !pe::IMAGE_CHPE_RANGE_ENTRY_NATIVE_CODE,
+            length: entry.length.get(LE),
+            code_type,
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use crate::read::{ReadError, ReadRef, Result};
+use crate::{pe, LittleEndian as LE};
+
+use super::SectionTable;
+
+/// A parsed CLR runtime header for a .NET PE file.
+///
+/// This is located using the `IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR` data directory.
+#[derive(Debug, Clone, Copy)]
+pub struct ClrHeader<'data> {
+    header: &'data pe::ImageCor20Header,
+}
+
+impl<'data> ClrHeader<'data> {
+    /// Parse the CLR runtime header.
+    ///
+    /// `data` must be the data for the `IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR` directory.
+    pub fn parse(data: &'data [u8]) -> Result<Self> {
+        let header = data
+            .read_at::<pe::ImageCor20Header>(0)
+            .read_error("Invalid PE CLR header size or alignment")?;
+        Ok(ClrHeader { header })
+    }
+
+    /// Returns the raw CLR runtime header.
+    pub fn header(&self) -> &'data pe::ImageCor20Header {
+        self.header
+    }
+
+    /// Returns the `COMIMAGE_FLAGS_*` flags.
+    pub fn flags(&self) -> u32 {
+        self.header.flags.get(LE)
+    }
+
+    /// Returns the managed entry point token.
+    ///
+    /// If the `COMIMAGE_FLAGS_NATIVE_ENTRYPOINT` flag is set, then the
+    /// raw value of the token is instead the RVA of a native entry point.
+    pub fn entry_point(&self) -> EntryPointToken {
+        EntryPointToken(self.header.entry_point_token_or_rva.get(LE))
+    }
+
+    /// Returns the data of the strong name signature.
+    ///
+    /// `data` must be the entire file data.
+    ///
+    /// Returns `Ok(None)` if the assembly does not have a strong name signature.
+    pub fn strong_name_signature<R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<Option<&'data [u8]>> {
+        let data_dir = &self.header.strong_name_signature;
+        if data_dir.virtual_address.get(LE) == 0 || data_dir.size.get(LE) == 0 {
+            return Ok(None);
+        }
+        data_dir.data(data, sections).map(Some)
+    }
+}
+
+/// A metadata token for a .NET entry point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryPointToken(pub u32);
+
+impl EntryPointToken {
+    /// Returns the metadata table identifier.
+    ///
+    /// This is the top byte of the token, and is usually `0x06` for the `MethodDef` table.
+    pub fn table(&self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    /// Returns the 1-based row index within the metadata table.
+    ///
+    /// This is the low 24 bits of the token. A row of 0 means there is no entry point.
+    pub fn row(&self) -> u32 {
+        self.0 & 0x00ff_ffff
+    }
+}
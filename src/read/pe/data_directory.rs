@@ -1,9 +1,12 @@
-use core::slice;
+use core::{cmp, slice};
 
-use crate::read::{Error, ReadError, ReadRef, Result};
-use crate::{pe, LittleEndian as LE};
+use crate::read::{Bytes, Error, ReadError, ReadRef, Result};
+use crate::{pe, pod, LittleEndian as LE};
 
-use super::{ExportTable, ImportTable, RelocationBlockIterator, ResourceDirectory, SectionTable};
+use super::{
+    ClrHeader, DelayLoadImportTable, ExceptionDirectory, ExportTable, ImageNtHeaders, ImportTable,
+    RelocationBlockIterator, ResourceDirectory, SectionTable,
+};
 
 /// The table of data directories in a PE file.
 #[derive(Debug, Clone, Copy)]
@@ -18,9 +21,13 @@ impl<'data> DataDirectories<'data> {
     /// [optional header](pe::ImageOptionalHeader64).  `number` must be from the
     /// [`number_of_rva_and_sizes`](pe::ImageOptionalHeader64::number_of_rva_and_sizes)
     /// field of the optional header.
+    ///
+    /// `number` is clamped to [`pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES`], since later
+    /// entries are ignored by the loader.
     pub fn parse(data: &'data [u8], number: u32) -> Result<Self> {
+        let number = cmp::min(number as usize, pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
         let entries = data
-            .read_slice_at(0, number as usize)
+            .read_slice_at(0, number)
             .read_error("Invalid PE number of RVA and sizes")?;
         Ok(DataDirectories { entries })
     }
@@ -31,6 +38,18 @@ impl<'data> DataDirectories<'data> {
         self.entries.len()
     }
 
+    /// Returns the data directories as a slice.
+    ///
+    /// The index of each entry is one of the `IMAGE_DIRECTORY_ENTRY_*` constants.
+    pub fn as_slice(&self) -> &'data [pe::ImageDataDirectory] {
+        self.entries
+    }
+
+    /// Returns the raw bytes of the data directories.
+    pub fn raw_bytes(&self) -> &'data [u8] {
+        pod::bytes_of_slice(self.entries)
+    }
+
     /// Iterator over the data directories.
     pub fn iter(&self) -> slice::Iter<'data, pe::ImageDataDirectory> {
         self.entries.iter()
@@ -105,6 +124,29 @@ impl<'data> DataDirectories<'data> {
         Ok(Some(ImportTable::new(section_data, section_va, import_va)))
     }
 
+    /// Returns the partially parsed delay-load import directory.
+    ///
+    /// `data` must be the entire file data.
+    pub fn delay_load_import_table<R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<Option<DelayLoadImportTable<'data>>> {
+        let data_dir = match self.get(pe::IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT) {
+            Some(data_dir) => data_dir,
+            None => return Ok(None),
+        };
+        let import_va = data_dir.virtual_address.get(LE);
+        let (section_data, section_va) = sections
+            .pe_data_containing(data, import_va)
+            .read_error("Invalid delay-load import data dir virtual address")?;
+        Ok(Some(DelayLoadImportTable::new(
+            section_data,
+            section_va,
+            import_va,
+        )))
+    }
+
     /// Returns the blocks in the base relocation directory.
     ///
     /// `data` must be the entire file data.
@@ -136,6 +178,85 @@ impl<'data> DataDirectories<'data> {
         let rsrc_data = data_dir.data(data, sections)?;
         Ok(Some(ResourceDirectory::new(rsrc_data)))
     }
+
+    /// Returns the function table in the exception directory.
+    ///
+    /// `data` must be the entire file data.
+    pub fn exception_directory<R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<Option<ExceptionDirectory<'data>>> {
+        let data_dir = match self.get(pe::IMAGE_DIRECTORY_ENTRY_EXCEPTION) {
+            Some(data_dir) => data_dir,
+            None => return Ok(None),
+        };
+        let pdata = data_dir.data(data, sections)?;
+        Ok(Some(ExceptionDirectory::new(pdata)))
+    }
+
+    /// Returns the raw data of the import address table.
+    ///
+    /// This is the region described by the `IMAGE_DIRECTORY_ENTRY_IAT` entry.
+    ///
+    /// `data` must be the entire file data.
+    pub fn iat<R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<Option<&'data [u8]>> {
+        let data_dir = match self.get(pe::IMAGE_DIRECTORY_ENTRY_IAT) {
+            Some(data_dir) => data_dir,
+            None => return Ok(None),
+        };
+        data_dir.data(data, sections).map(Some)
+    }
+
+    /// Returns the value of the global pointer register.
+    ///
+    /// This is the RVA in the `IMAGE_DIRECTORY_ENTRY_GLOBALPTR` entry. The size
+    /// of this entry is always zero, so it is not used.
+    ///
+    /// Returns `None` if the entry is missing or has a zero RVA.
+    pub fn global_pointer(&self) -> Option<u32> {
+        self.get(pe::IMAGE_DIRECTORY_ENTRY_GLOBALPTR)
+            .map(|data_dir| data_dir.virtual_address.get(LE))
+    }
+
+    /// Returns the TLS directory.
+    ///
+    /// `data` must be the entire file data.
+    pub fn tls_directory<Pe: ImageNtHeaders, R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<Option<&'data Pe::ImageTlsDirectory>> {
+        let data_dir = match self.get(pe::IMAGE_DIRECTORY_ENTRY_TLS) {
+            Some(data_dir) => data_dir,
+            None => return Ok(None),
+        };
+        let tls_data = data_dir.data(data, sections)?;
+        Bytes(tls_data)
+            .read_at(0)
+            .read_error("Invalid PE TLS directory size")
+            .map(Some)
+    }
+
+    /// Returns the CLR runtime header.
+    ///
+    /// `data` must be the entire file data.
+    pub fn clr_header<R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<Option<ClrHeader<'data>>> {
+        let data_dir = match self.get(pe::IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR) {
+            Some(data_dir) => data_dir,
+            None => return Ok(None),
+        };
+        let clr_data = data_dir.data(data, sections)?;
+        ClrHeader::parse(clr_data).map(Some)
+    }
 }
 
 impl pe::ImageDataDirectory {
@@ -0,0 +1,177 @@
+use crate::endian::{LittleEndian as LE, U32};
+use crate::pe;
+use crate::read::{Bytes, ReadError, Result};
+
+/// The data of an `IMAGE_DEBUG_TYPE_POGO` debug directory entry.
+///
+/// This describes the regions of the image that were laid out using link time
+/// code generation or profile guided optimization.
+#[derive(Debug, Clone, Copy)]
+pub struct PogoInfo<'data> {
+    signature: u32,
+    entries: Bytes<'data>,
+}
+
+impl<'data> PogoInfo<'data> {
+    /// Parse the data of an `IMAGE_DEBUG_TYPE_POGO` debug directory entry.
+    pub fn parse(data: &'data [u8]) -> Result<Self> {
+        let mut entries = Bytes(data);
+        let signature = entries
+            .read::<U32<LE>>()
+            .read_error("Invalid PE POGO signature")?
+            .get(LE);
+        Ok(PogoInfo { signature, entries })
+    }
+
+    /// Returns the signature.
+    ///
+    /// This is usually [`pe::IMAGE_POGO_SIGNATURE_LTCG`](crate::pe::IMAGE_POGO_SIGNATURE_LTCG)
+    /// or [`pe::IMAGE_POGO_SIGNATURE_PGU`](crate::pe::IMAGE_POGO_SIGNATURE_PGU).
+    pub fn signature(&self) -> u32 {
+        self.signature
+    }
+
+    /// Returns an iterator over the entries.
+    pub fn entries(&self) -> PogoEntryIterator<'data> {
+        PogoEntryIterator { data: self.entries }
+    }
+}
+
+/// An iterator over the entries of an `IMAGE_DEBUG_TYPE_POGO` debug directory entry.
+#[derive(Debug, Clone)]
+pub struct PogoEntryIterator<'data> {
+    data: Bytes<'data>,
+}
+
+impl<'data> PogoEntryIterator<'data> {
+    /// Read the next entry.
+    ///
+    /// Returns `Ok(None)` when there are no more entries.
+    pub fn next(&mut self) -> Result<Option<PogoEntry<'data>>> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        let result = self.parse().map(Some);
+        if result.is_err() {
+            self.data = Bytes(&[]);
+        }
+        result
+    }
+
+    fn parse(&mut self) -> Result<PogoEntry<'data>> {
+        let rva = self
+            .data
+            .read::<U32<LE>>()
+            .read_error("Invalid PE POGO entry")?
+            .get(LE);
+        let size = self
+            .data
+            .read::<U32<LE>>()
+            .read_error("Invalid PE POGO entry")?
+            .get(LE);
+        let name = self
+            .data
+            .read_string()
+            .read_error("Invalid PE POGO entry name")?;
+        // The name and its null terminator are padded to a multiple of 4 bytes.
+        // The padding may be omitted for the last entry.
+        let padding = (4 - (name.len() + 1) % 4) % 4;
+        if self.data.skip(padding).is_err() {
+            self.data = Bytes(&[]);
+        }
+        Ok(PogoEntry { rva, size, name })
+    }
+}
+
+/// An entry of an `IMAGE_DEBUG_TYPE_POGO` debug directory entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PogoEntry<'data> {
+    /// The RVA of the start of the region.
+    pub rva: u32,
+    /// The size in bytes of the region.
+    pub size: u32,
+    /// The name of the region, such as `.text$mn`.
+    pub name: &'data [u8],
+}
+
+/// The data of an `IMAGE_DEBUG_TYPE_VC_FEATURE` debug directory entry.
+///
+/// These are counts recorded by the MSVC compiler of the object files that were
+/// built with various features.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VcFeature {
+    /// The number of object files built by a compiler older than Visual C++ 11.
+    pub pre_vc11: u32,
+    /// The number of C and C++ object files.
+    pub c_cpp: u32,
+    /// The number of object files built with `/GS`.
+    pub gs: u32,
+    /// The number of object files built with `/sdl`.
+    pub sdl: u32,
+    /// The number of functions with guard checks, such as for `/guard:cf`.
+    pub guard_n: u32,
+}
+
+impl VcFeature {
+    /// Parse the data of an `IMAGE_DEBUG_TYPE_VC_FEATURE` debug directory entry.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let mut data = Bytes(data);
+        let values = data
+            .read_slice::<U32<LE>>(5)
+            .read_error("Invalid PE VC feature size")?;
+        Ok(VcFeature {
+            pre_vc11: values[0].get(LE),
+            c_cpp: values[1].get(LE),
+            gs: values[2].get(LE),
+            sdl: values[3].get(LE),
+            guard_n: values[4].get(LE),
+        })
+    }
+}
+
+/// The data of an `IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS` debug directory entry.
+///
+/// This contains `IMAGE_DLLCHARACTERISTICS_EX_*` flags that do not fit in the
+/// `dll_characteristics` field of the optional header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExDllCharacteristics(pub u32);
+
+impl ExDllCharacteristics {
+    /// `IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT`
+    pub const CET_COMPAT: ExDllCharacteristics =
+        ExDllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT);
+    /// `IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT_STRICT_MODE`
+    pub const CET_COMPAT_STRICT_MODE: ExDllCharacteristics =
+        ExDllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT_STRICT_MODE);
+    /// `IMAGE_DLLCHARACTERISTICS_EX_FORWARD_CFI_COMPAT`
+    pub const FORWARD_CFI_COMPAT: ExDllCharacteristics =
+        ExDllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_EX_FORWARD_CFI_COMPAT);
+    /// `IMAGE_DLLCHARACTERISTICS_EX_HOTPATCH_COMPATIBLE`
+    pub const HOTPATCH_COMPATIBLE: ExDllCharacteristics =
+        ExDllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_EX_HOTPATCH_COMPATIBLE);
+
+    /// Parse the data of an `IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS` debug directory entry.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let flags = Bytes(data)
+            .read::<U32<LE>>()
+            .read_error("Invalid PE extended DLL characteristics size")?
+            .get(LE);
+        Ok(ExDllCharacteristics(flags))
+    }
+
+    /// Return the raw `IMAGE_DLLCHARACTERISTICS_EX_*` flags.
+    pub fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Return true if all of the given flags are set.
+    pub fn contains(self, other: ExDllCharacteristics) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Return true if the image is compatible with Control-flow Enforcement
+    /// Technology shadow stacks.
+    pub fn cet_compat(self) -> bool {
+        self.contains(Self::CET_COMPAT)
+    }
+}
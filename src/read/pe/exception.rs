@@ -0,0 +1,106 @@
+use core::mem;
+
+use crate::endian::LittleEndian as LE;
+use crate::pe;
+use crate::read::{ReadError, ReadRef, Result};
+
+/// The function table in the exception directory of a PE file.
+///
+/// This is usually the `.pdata` section. The format of the entries depends
+/// on the machine type of the file.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExceptionDirectory<'data> {
+    data: &'data [u8],
+}
+
+impl<'data> ExceptionDirectory<'data> {
+    /// Construct a function table from the data of the exception directory.
+    pub fn new(data: &'data [u8]) -> Self {
+        ExceptionDirectory { data }
+    }
+
+    /// Returns the function table entries for x64 files.
+    ///
+    /// Any trailing bytes that do not form a complete entry are ignored.
+    pub fn functions(&self) -> Result<&'data [pe::ImageRuntimeFunctionEntry]> {
+        let count = self.data.len() / mem::size_of::<pe::ImageRuntimeFunctionEntry>();
+        self.data
+            .read_slice_at(0, count)
+            .read_error("Invalid PE exception directory alignment")
+    }
+
+    /// Returns the function table entries for ARM64 files.
+    ///
+    /// These should only be used if the machine type is `IMAGE_FILE_MACHINE_ARM64`.
+    ///
+    /// Any trailing bytes that do not form a complete entry are ignored.
+    pub fn arm64_functions(&self) -> Result<&'data [pe::ImageArm64RuntimeFunctionEntry]> {
+        let count = self.data.len() / mem::size_of::<pe::ImageArm64RuntimeFunctionEntry>();
+        self.data
+            .read_slice_at(0, count)
+            .read_error("Invalid PE exception directory alignment")
+    }
+}
+
+impl pe::ImageArm64RuntimeFunctionEntry {
+    /// Returns the RVA of the start of the function.
+    pub fn begin_address(&self) -> u32 {
+        self.begin_address.get(LE)
+    }
+
+    /// Decode the unwind data for this function.
+    pub fn unwind_info(&self) -> Arm64UnwindInfo {
+        Arm64UnwindInfo::parse(self.unwind_data.get(LE))
+    }
+}
+
+/// The unwind information for an entry in the ARM64 function table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arm64UnwindInfo {
+    /// The RVA of the `.xdata` record containing the unwind information.
+    Xdata(u32),
+    /// The unwind information is packed into the function table entry.
+    Packed(Arm64PackedUnwindData),
+}
+
+impl Arm64UnwindInfo {
+    /// Decode the unwind data field of an ARM64 function table entry.
+    ///
+    /// The low 2 bits determine whether the field is an `.xdata` RVA or packed unwind data.
+    pub fn parse(unwind_data: u32) -> Self {
+        if unwind_data & 0x3 == 0 {
+            return Arm64UnwindInfo::Xdata(unwind_data);
+        }
+        Arm64UnwindInfo::Packed(Arm64PackedUnwindData {
+            flag: (unwind_data & 0x3) as u8,
+            function_length: ((unwind_data >> 2) & 0x7ff) * 4,
+            reg_f: ((unwind_data >> 13) & 0x7) as u8,
+            reg_i: ((unwind_data >> 16) & 0xf) as u8,
+            h: (unwind_data >> 20) & 0x1 != 0,
+            cr: ((unwind_data >> 21) & 0x3) as u8,
+            frame_size: ((unwind_data >> 23) & 0x1ff) * 16,
+        })
+    }
+}
+
+/// The packed unwind data for an entry in the ARM64 function table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arm64PackedUnwindData {
+    /// The packed format flag.
+    ///
+    /// 1 for packed unwind data for a function with a single prolog and epilog,
+    /// 2 for packed unwind data for a function fragment without a prolog.
+    pub flag: u8,
+    /// The length of the function in bytes.
+    pub function_length: u32,
+    /// The number of non-volatile floating point registers saved, minus one.
+    pub reg_f: u8,
+    /// The number of non-volatile integer registers saved.
+    pub reg_i: u8,
+    /// Whether the integer parameter registers are homed.
+    pub h: bool,
+    /// Whether the function includes extra instructions to set up a frame chain and return link.
+    pub cr: u8,
+    /// The number of bytes of stack that is allocated for this function.
+    pub frame_size: u32,
+}
@@ -1,5 +1,7 @@
+use alloc::borrow::Cow;
 use alloc::vec::Vec;
 use core::fmt::Debug;
+use core::ops::Range;
 
 use crate::read::{ByteString, Bytes, Error, ReadError, ReadRef, Result};
 use crate::{pe, LittleEndian as LE, U16Bytes, U32Bytes};
@@ -49,6 +51,26 @@ pub struct Export<'data> {
     pub target: ExportTarget<'data>,
 }
 
+impl<'data> Export<'data> {
+    /// Returns the name of the export, or a name derived from the ordinal if there is no name.
+    ///
+    /// The derived name is of the form `<dll>.#<ordinal>`, where `<dll>` is `dll_name`
+    /// without its extension. This is the same form as is used for forwarding by ordinal.
+    /// The DLL name is typically obtained from [`ExportTable::dll_name`].
+    pub fn name_or_ordinal(&self, dll_name: &[u8]) -> Cow<'data, [u8]> {
+        if let Some(name) = self.name {
+            return Cow::Borrowed(name);
+        }
+        let stem = match memchr::memrchr(b'.', dll_name) {
+            Some(end) => &dll_name[..end],
+            None => dll_name,
+        };
+        let mut name = stem.to_vec();
+        name.extend_from_slice(format!(".#{}", self.ordinal).as_bytes());
+        Cow::Owned(name)
+    }
+}
+
 impl<'a> Debug for Export<'a> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::result::Result<(), core::fmt::Error> {
         f.debug_struct("Export")
@@ -147,11 +169,26 @@ impl<'data> ExportTable<'data> {
             .read_error("Invalid PE export dir size")
     }
 
+    /// Returns the raw bytes that the export table was parsed from.
+    ///
+    /// This is the data of the `IMAGE_DIRECTORY_ENTRY_EXPORT` data directory.
+    pub fn raw_bytes(&self) -> &'data [u8] {
+        self.data.0
+    }
+
     /// Returns the header of the export table.
     pub fn directory(&self) -> &'data pe::ImageExportDirectory {
         self.directory
     }
 
+    /// Returns the name of the DLL from the export directory.
+    ///
+    /// This is the name that the DLL had when it was linked, which may differ
+    /// from its file name.
+    pub fn dll_name(&self) -> Result<&'data [u8]> {
+        self.name_from_pointer(self.directory.name.get(LE))
+    }
+
     /// Returns the base value of ordinals.
     ///
     /// Adding this to an address index will give an ordinal.
@@ -159,6 +196,15 @@ impl<'data> ExportTable<'data> {
         self.directory.base.get(LE)
     }
 
+    /// Returns the range of ordinals that are covered by the export address table.
+    ///
+    /// This is computed from the `base` and `number_of_functions` fields of the
+    /// export directory. Ordinals in the range may still refer to unused entries.
+    pub fn ordinal_range(&self) -> Range<u32> {
+        let base = self.ordinal_base();
+        base..base.saturating_add(self.directory.number_of_functions.get(LE))
+    }
+
     /// Returns the unparsed address table.
     ///
     /// An address table entry may be a local address, or the address of a forwarded export entry.
@@ -1,19 +1,29 @@
 use alloc::vec::Vec;
 use core::fmt::Debug;
-use core::{mem, str};
+use core::{cmp, mem, result, str};
 
-use core::convert::TryInto;
+use core::convert::{TryFrom, TryInto};
 
-use crate::read::coff::{CoffCommon, CoffSymbol, CoffSymbolIterator, CoffSymbolTable, SymbolTable};
+use crate::endian::{U32Bytes, U64Bytes};
+use crate::read::coff::{
+    machine_architecture, machine_sub_architecture, CoffCommon, CoffSymbol, CoffSymbolIterator,
+    CoffSymbolTable, SymbolTable,
+};
 use crate::read::{
-    self, Architecture, ComdatKind, Error, Export, FileFlags, Import, NoDynamicRelocationIterator,
-    Object, ObjectComdat, ObjectKind, ReadError, ReadRef, Result, SectionIndex, SymbolIndex,
+    self, Architecture, BinaryFormat, ComdatKind, Error, Export, FileFlags, Import,
+    NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ParseError, ParseOptions,
+    ParseStage, PointerFixup, PointerFixupIterator, ReadError, ReadRef, Result, SectionIndex,
+    SubArchitecture, SymbolIndex, TlsSymbol,
 };
-use crate::{pe, ByteString, Bytes, CodeView, LittleEndian as LE, Pod, U32};
+use crate::{pe, pod, ByteString, Bytes, CodeView, LittleEndian as LE, Pod, U32};
 
 use super::{
-    DataDirectories, ExportTable, ImageThunkData, ImportTable, PeSection, PeSectionIterator,
-    PeSegment, PeSegmentIterator, RichHeaderInfo, SectionTable,
+    Arm64UnwindInfo, ChpeCodeRange, ClrHeader, DataDirectories, DelayImport, DelayLoadImportTable,
+    DllCharacteristics, EntryPointInfo, ExDllCharacteristics, ExceptionDirectory, ExportTable,
+    FileCharacteristics, IatEntryIterator, ImageThunkData, ImageTlsDirectory, ImportConsistency,
+    ImportTable, Machine, Packer, PeSection, PeSectionIterator, PeSegment, PeSegmentIterator,
+    PogoInfo, ResourceId, RichHeaderInfo, SectionAnomaly, SectionTable, Subsystem, VcFeature,
+    VersionInfo,
 };
 
 /// A PE32 (32-bit) image file.
@@ -33,6 +43,8 @@ where
     pub(super) data_directories: DataDirectories<'data>,
     pub(super) common: CoffCommon<'data, R>,
     pub(super) data: R,
+    pub(super) max_import_descriptors: usize,
+    pub(super) warnings: Vec<Error>,
 }
 
 impl<'data, Pe, R> PeFile<'data, Pe, R>
@@ -42,26 +54,112 @@ where
 {
     /// Parse the raw PE file data.
     pub fn parse(data: R) -> Result<Self> {
-        let dos_header = pe::ImageDosHeader::parse(data)?;
+        Self::parse_with_options(data, &ParseOptions::default())
+    }
+
+    /// Parse the raw PE file data, and check that it is within the given limits.
+    ///
+    /// The section, symbol and relocation limits are checked using the headers,
+    /// and the resource directory is checked against the depth limit. The import
+    /// descriptor limit is applied by the iterators of [`Self::import_table`] and
+    /// [`Self::delay_load_import_table`].
+    pub fn parse_with_options(data: R, options: &ParseOptions) -> Result<Self> {
+        Self::parse_staged(data, options).map_err(|error| error.detail)
+    }
+
+    /// Parse the raw PE file data, and include the stage of parsing in any error.
+    pub(crate) fn parse_staged(
+        data: R,
+        options: &ParseOptions,
+    ) -> result::Result<Self, ParseError> {
+        let dos_header = pe::ImageDosHeader::parse(data).map_err(ParseStage::Header.error())?;
         let mut offset = dos_header.nt_headers_offset().into();
-        let (nt_headers, data_directories) = Pe::parse(data, &mut offset)?;
-        let sections = nt_headers.sections(data, offset)?;
-        let coff_symbols = nt_headers.symbols(data);
+        let (nt_headers, data_directories) =
+            Pe::parse(data, &mut offset).map_err(ParseStage::Header.error())?;
+        options
+            .check_sections(nt_headers.file_header().number_of_sections.get(LE).into())
+            .map_err(ParseStage::Sections.error())?;
+        let sections = nt_headers
+            .sections(data, offset)
+            .map_err(ParseStage::Sections.error())?;
+        options
+            .check_relocations(sections.relocation_count())
+            .map_err(ParseStage::Sections.error())?;
+        // The PE file format deprecates the COFF symbol table (https://docs.microsoft.com/en-us/windows/win32/debug/pe-format#coff-file-header-object-and-image)
+        // We do not want to prevent parsing the rest of the PE file for a corrupt COFF header, but rather return an empty symbol table
+        let symbols = nt_headers.symbols(data).unwrap_or_default();
+        options
+            .check_symbols(symbols.len())
+            .map_err(ParseStage::Symbols.error())?;
         let image_base = nt_headers.optional_header().image_base();
 
-        Ok(PeFile {
+        if options.max_resource_depth != usize::max_value() {
+            if let Some(resources) = data_directories
+                .resource_directory(data, &sections)
+                .map_err(ParseStage::Other.error())?
+            {
+                resources
+                    .check_depth(options.max_resource_depth)
+                    .map_err(ParseStage::Other.error())?;
+            }
+        }
+
+        let mut file = PeFile {
             dos_header,
             nt_headers,
             data_directories,
             common: CoffCommon {
                 sections,
-                // The PE file format deprecates the COFF symbol table (https://docs.microsoft.com/en-us/windows/win32/debug/pe-format#coff-file-header-object-and-image)
-                // We do not want to prevent parsing the rest of the PE file for a corrupt COFF header, but rather return an empty symbol table
-                symbols: coff_symbols.unwrap_or_default(),
+                symbols,
                 image_base,
             },
             data,
-        })
+            max_import_descriptors: options.max_import_descriptors,
+            warnings: Vec::new(),
+        };
+        if let Err(e) = file.header_consistency() {
+            file.warnings.push(e);
+        }
+        Ok(file)
+    }
+
+    /// Parse the raw PE file data, and check that the headers are consistent.
+    ///
+    /// This is the same as [`Self::parse`], except that it returns the first of
+    /// [`Self::warnings`] as an error.
+    pub fn parse_strict(data: R) -> Result<Self> {
+        let file = Self::parse(data)?;
+        if let Some(warning) = file.warnings.first() {
+            return Err(*warning);
+        }
+        Ok(file)
+    }
+
+    /// Return the problems that were found when parsing the file.
+    ///
+    /// These did not prevent parsing, but mean that some of the file's
+    /// information may be unreliable. Currently this only contains the result
+    /// of [`Self::header_consistency`].
+    pub fn warnings(&self) -> &[Error] {
+        &self.warnings
+    }
+
+    /// Check that the file header is consistent with the optional header.
+    ///
+    /// Returns an error if the word size of the machine in the file header does
+    /// not match the optional header magic (PE32 or PE32+). Such files may be
+    /// accepted by [`Self::parse`], since the optional header is interpreted
+    /// according to its magic, but the machine is then unreliable.
+    ///
+    /// Machines with an unknown word size are not checked.
+    pub fn header_consistency(&self) -> Result<()> {
+        let machine = self.nt_headers.file_header().machine.get(LE);
+        match machine_is_64(machine) {
+            Some(is_64) if is_64 != self.nt_headers.is_type_64() => {
+                Err(Error("PE machine does not match optional header magic"))
+            }
+            _ => Ok(()),
+        }
     }
 
     /// Returns this binary data.
@@ -79,6 +177,115 @@ where
         self.nt_headers
     }
 
+    /// Returns the target machine of this file.
+    pub fn machine(&self) -> Machine {
+        Machine::from(self.nt_headers.file_header().machine.get(LE))
+    }
+
+    /// Returns the subsystem required to run this image.
+    pub fn subsystem(&self) -> Subsystem {
+        Subsystem::from(self.nt_headers.optional_header().subsystem())
+    }
+
+    /// Returns the characteristics from the file header of this image.
+    pub fn file_characteristics(&self) -> FileCharacteristics {
+        FileCharacteristics(self.nt_headers.file_header().characteristics.get(LE))
+    }
+
+    /// Returns the DLL characteristics of this image.
+    pub fn dll_characteristics(&self) -> DllCharacteristics {
+        DllCharacteristics(self.nt_headers.optional_header().dll_characteristics())
+    }
+
+    /// Returns the suspicious properties of the sections of this file.
+    ///
+    /// These do not prevent parsing, but may indicate a packed or malformed file.
+    pub fn section_anomalies(&self) -> Vec<SectionAnomaly> {
+        super::section::section_anomalies(
+            &self.common.sections,
+            self.data,
+            self.common.symbols.strings(),
+        )
+    }
+
+    /// Return true if the name of the section is one of the names used by common linkers,
+    /// such as `.text`, `.rdata` or `.reloc`.
+    ///
+    /// Packers and obfuscators often use other names, or random names.
+    /// This is the same check as
+    /// [`SectionAnomalyKind::NonStandardName`](super::SectionAnomalyKind::NonStandardName).
+    pub fn section_name_is_standard(&self, section: &'data pe::ImageSectionHeader) -> bool {
+        super::section::is_standard_section_name(section, self.common.symbols.strings())
+    }
+
+    /// Return the number of sections with a name that is not standard.
+    ///
+    /// See [`PeFile::section_name_is_standard`].
+    pub fn nonstandard_section_count(&self) -> usize {
+        self.common
+            .sections
+            .iter()
+            .filter(|section| !self.section_name_is_standard(section))
+            .count()
+    }
+
+    /// Attempt to detect whether this file was compressed or encrypted by a packer.
+    ///
+    /// Known packers are detected using the names of the sections that they create.
+    /// Otherwise, [`Packer::Generic`] is returned if the file has a near-empty
+    /// import table and an executable section with high entropy, or if the
+    /// entry point is outside of the first section and the section containing it
+    /// is writable and executable, has no raw data, or has high entropy.
+    ///
+    /// This is only a heuristic, and can have both false positives and false negatives.
+    pub fn detect_packer(&self) -> Option<Packer> {
+        let import_count = self.imports().map(|imports| imports.len()).unwrap_or(0);
+        let entry = self.nt_headers.optional_header().address_of_entry_point();
+        super::packer::detect_packer(&self.common.sections, self.data, entry, import_count)
+    }
+
+    /// Returns the file offsets of PE files that are embedded in this file.
+    ///
+    /// This scans the raw data of each section, and the overlay returned by
+    /// [`Object::trailing_data`], using [`find_pe_headers`]. The header of this
+    /// file is not included, even if a section overlaps it.
+    ///
+    /// The returned offsets are sorted. The data from each offset onwards may be
+    /// passed to [`PeFile::parse`].
+    pub fn embedded_pe_offsets(&self) -> Vec<u64> {
+        let mut ranges = Vec::new();
+        for section in self.common.sections.iter() {
+            let (offset, size) = section.pe_file_range();
+            if let Ok(bytes) = self.data.read_bytes_at(offset.into(), size.into()) {
+                ranges.push((u64::from(offset), bytes));
+            }
+        }
+        if let (Ok(Some(overlay)), Ok(len)) = (self.trailing_data(), self.data.len()) {
+            ranges.push((len - overlay.len() as u64, overlay));
+        }
+
+        let mut offsets = Vec::new();
+        for (offset, bytes) in ranges {
+            offsets.extend(
+                find_pe_headers(bytes)
+                    .map(|pos| offset + pos as u64)
+                    .filter(|&pos| pos != 0),
+            );
+        }
+        offsets.sort_unstable();
+        offsets.dedup();
+        offsets
+    }
+
+    /// Returns information about the location of the entry point.
+    ///
+    /// This reports the section containing the entry point, and some properties
+    /// of that section that may indicate a packed or malformed file.
+    pub fn entry_point_analysis(&self) -> EntryPointInfo {
+        let entry = self.nt_headers.optional_header().address_of_entry_point();
+        super::section::entry_point_analysis(&self.common.sections, entry)
+    }
+
     /// Returns information about the rich header of this file (if any).
     pub fn rich_header_info(&self) -> Option<RichHeaderInfo> {
         RichHeaderInfo::parse(self.data, self.dos_header.nt_headers_offset().into())
@@ -94,6 +301,15 @@ where
         self.data_directories
     }
 
+    /// Returns the entries of the data directories of this file.
+    ///
+    /// The index of each entry is one of the `IMAGE_DIRECTORY_ENTRY_*` constants.
+    /// The number of entries is `number_of_rva_and_sizes`, clamped to
+    /// [`pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES`].
+    pub fn data_directory_entries(&self) -> &'data [pe::ImageDataDirectory] {
+        self.data_directories.as_slice()
+    }
+
     /// Returns the data directory at the given index.
     pub fn data_directory(&self, id: usize) -> Option<&'data pe::ImageDataDirectory> {
         self.data_directories.get(id)
@@ -111,8 +327,461 @@ where
     ///
     /// The import table is located using the data directory.
     pub fn import_table(&self) -> Result<Option<ImportTable<'data>>> {
+        let mut import_table = self
+            .data_directories
+            .import_table(self.data, &self.common.sections)?;
+        if let Some(import_table) = &mut import_table {
+            import_table.set_max_descriptors(self.max_import_descriptors);
+        }
+        Ok(import_table)
+    }
+
+    /// Returns the import hash (imphash) of this file.
+    ///
+    /// This is the MD5 hex digest of the imported function names, normalized
+    /// in the same way as the `pefile` Python module. Functions imported by ordinal
+    /// are named using a lookup table for some common DLLs, or `ord<N>` otherwise.
+    ///
+    /// Returns `Ok(None)` if there is no import table, or if the import table has no
+    /// descriptors. If there are descriptors but none of them have any imports, then
+    /// this is the digest of the empty string, `d41d8cd98f00b204e9800998ecf8427e`,
+    /// which is the same as `pefile`.
+    #[cfg(feature = "hash")]
+    pub fn imphash(&self) -> Result<Option<alloc::string::String>> {
+        match self.import_table()? {
+            Some(import_table) => super::hash::imphash::<Pe>(&import_table),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the Authenticode digest of this file.
+    ///
+    /// This is the digest of the file excluding the checksum field, the certificate
+    /// table entry in the data directory, and the certificate table itself.
+    /// For a signed file that has not been modified, it is equal to the digest
+    /// in the `SpcIndirectDataContent` of the signature.
+    ///
+    /// The certificate table is located using the file offset in its data directory
+    /// entry, and any data after it is included in the digest.
+    ///
+    /// Returns an error if the certificate table is not within the file.
+    #[cfg(feature = "hash")]
+    pub fn authenticode_digest(&self, algo: read::HashAlgo) -> Result<Vec<u8>> {
+        super::hash::authenticode_digest(self, algo)
+    }
+
+    /// Returns the `time_date_stamp` field of the file header.
+    ///
+    /// For reproducible builds, this is derived from a hash of the file contents
+    /// instead of being the build time. See [`Self::is_reproducible_build`].
+    pub fn timestamp(&self) -> u32 {
+        self.nt_headers.file_header().time_date_stamp.get(LE)
+    }
+
+    /// Returns the entries in the debug directory of this file.
+    pub fn debug_directories(&self) -> Result<&'data [pe::ImageDebugDirectory]> {
+        let data_dir = match self.data_directory(pe::IMAGE_DIRECTORY_ENTRY_DEBUG) {
+            Some(data_dir) => data_dir,
+            None => return Ok(&[]),
+        };
+        let debug_data = data_dir.data(self.data, &self.common.sections)?;
+        let count = debug_data.len() / mem::size_of::<pe::ImageDebugDirectory>();
+        debug_data
+            .read_slice_at(0, count)
+            .read_error("Invalid PE debug dir size")
+    }
+
+    /// Returns the hash of a reproducible build.
+    ///
+    /// This is the data of the `IMAGE_DEBUG_TYPE_REPRO` debug directory entry,
+    /// which may be empty if the linker did not record the hash.
+    ///
+    /// Returns `Ok(None)` if there is no such entry.
+    pub fn reproducible_hash(&self) -> Result<Option<&'data [u8]>> {
+        let mut data = match self.debug_data(pe::IMAGE_DEBUG_TYPE_REPRO)? {
+            Some([]) => return Ok(Some(&[])),
+            Some(data) => Bytes(data),
+            None => return Ok(None),
+        };
+        // The data is the hash length followed by the hash.
+        let len = data
+            .read::<U32<LE>>()
+            .read_error("Invalid PE repro hash length")?
+            .get(LE);
+        let hash = data
+            .read_bytes(len as usize)
+            .read_error("Invalid PE repro hash length")?;
+        Ok(Some(hash.0))
+    }
+
+    /// Returns the parsed data of the `IMAGE_DEBUG_TYPE_POGO` debug directory entry.
+    ///
+    /// Returns `Ok(None)` if there is no such entry.
+    pub fn pogo_info(&self) -> Result<Option<PogoInfo<'data>>> {
+        match self.debug_data(pe::IMAGE_DEBUG_TYPE_POGO)? {
+            Some(data) => PogoInfo::parse(data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the parsed data of the `IMAGE_DEBUG_TYPE_VC_FEATURE` debug directory entry.
+    ///
+    /// Returns `Ok(None)` if there is no such entry.
+    pub fn vc_feature(&self) -> Result<Option<VcFeature>> {
+        match self.debug_data(pe::IMAGE_DEBUG_TYPE_VC_FEATURE)? {
+            Some(data) => VcFeature::parse(data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the parsed data of the `IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS` debug
+    /// directory entry.
+    ///
+    /// Returns `Ok(None)` if there is no such entry.
+    pub fn ex_dll_characteristics(&self) -> Result<Option<ExDllCharacteristics>> {
+        match self.debug_data(pe::IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS)? {
+            Some(data) => ExDllCharacteristics::parse(data).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the data of the first debug directory entry with the given type.
+    fn debug_data(&self, typ: u32) -> Result<Option<&'data [u8]>> {
+        let debug_dir = match self
+            .debug_directories()?
+            .iter()
+            .find(|d| d.typ.get(LE) == typ)
+        {
+            Some(debug_dir) => debug_dir,
+            None => return Ok(None),
+        };
+        let size = debug_dir.size_of_data.get(LE);
+        if size == 0 {
+            return Ok(Some(&[]));
+        }
+        self.data
+            .read_bytes_at(debug_dir.pointer_to_raw_data.get(LE).into(), size.into())
+            .read_error("Invalid PE debug directory data")
+            .map(Some)
+    }
+
+    /// Returns true if this file appears to be a reproducible build.
+    ///
+    /// For reproducible builds, the [timestamp](Self::timestamp) is a hash and not
+    /// a time. This returns true if the debug directory contains an
+    /// `IMAGE_DEBUG_TYPE_REPRO` entry. Nothing else is checked; in particular, the
+    /// timestamp is not compared with the hash.
+    ///
+    /// Returns an error if the debug directory is invalid, or if the data of the
+    /// entry is invalid as described for [`Self::reproducible_hash`].
+    pub fn is_reproducible_build(&self) -> Result<bool> {
+        self.reproducible_hash().map(|hash| hash.is_some())
+    }
+
+    /// Returns the delay-load import table of this file.
+    ///
+    /// The delay-load import table is located using the data directory.
+    pub fn delay_load_import_table(&self) -> Result<Option<DelayLoadImportTable<'data>>> {
+        let mut import_table = self
+            .data_directories
+            .delay_load_import_table(self.data, &self.common.sections)?;
+        if let Some(import_table) = &mut import_table {
+            import_table.set_image_base(self.common.image_base as u32);
+            import_table.set_max_descriptors(self.max_import_descriptors);
+        }
+        Ok(import_table)
+    }
+
+    /// Returns the libraries and imports in the delay-load import table of this file.
+    ///
+    /// Descriptors may contain either RVAs or, for the original version of the
+    /// delay-load format, virtual addresses. Both are supported.
+    pub fn delay_load_imports(&self) -> Result<Vec<DelayImport<'data>>> {
+        let mut delay_imports = Vec::new();
+        let import_table = match self.delay_load_import_table()? {
+            Some(import_table) => import_table,
+            None => return Ok(delay_imports),
+        };
+        let mut import_descs = import_table.descriptors()?;
+        while let Some(import_desc) = import_descs.next()? {
+            let base = import_table.address_base(import_desc);
+            let dll = import_table.name(import_desc.dll_name_rva.get(LE).wrapping_sub(base))?;
+            let mut imports = Vec::new();
+            let mut thunks = import_table
+                .thunks(import_desc.import_name_table_rva.get(LE).wrapping_sub(base))?;
+            while let Some(thunk) = thunks.next::<Pe>()? {
+                if thunk.is_ordinal() {
+                    imports.push(super::Import::Ordinal(thunk.ordinal()));
+                } else {
+                    let (hint, name) =
+                        import_table.hint_name(thunk.address().wrapping_sub(base))?;
+                    imports.push(super::Import::Name(hint, name));
+                }
+            }
+            delay_imports.push(DelayImport { dll, imports });
+        }
+        Ok(delay_imports)
+    }
+
+    /// Returns the function table in the exception directory of this file.
+    pub fn exception_directory(&self) -> Result<Option<ExceptionDirectory<'data>>> {
+        self.data_directories
+            .exception_directory(self.data, &self.common.sections)
+    }
+
+    /// Returns the value of the global pointer register for this file.
+    ///
+    /// This is an RVA, and is only used by some architectures, such as IA64.
+    pub fn global_pointer(&self) -> Option<u32> {
+        self.data_directories.global_pointer()
+    }
+
+    /// Returns the TLS directory of this file.
+    pub fn tls_directory(&self) -> Result<Option<&'data Pe::ImageTlsDirectory>> {
         self.data_directories
-            .import_table(self.data, &self.common.sections)
+            .tls_directory::<Pe, _>(self.data, &self.common.sections)
+    }
+
+    /// Returns the version information in the `RT_VERSION` resource of this file.
+    ///
+    /// If there are multiple version resources, then the first one is used.
+    ///
+    /// Returns `Ok(None)` if there is no resource directory or version resource.
+    pub fn version_info(&self) -> Result<Option<VersionInfo<'data>>> {
+        let resources = match self
+            .data_directories
+            .resource_directory(self.data, &self.common.sections)?
+        {
+            Some(resources) => resources,
+            None => return Ok(None),
+        };
+        let entry = match resources.find_path(&[ResourceId::Id(pe::RT_VERSION)])? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let data = entry.data(self.data, &self.common.sections)?;
+        VersionInfo::parse(data).map(Some)
+    }
+
+    /// Returns the application manifest in the `RT_MANIFEST` resource of this file.
+    ///
+    /// This is usually XML. If there are multiple manifest resources, then the
+    /// first one is used.
+    ///
+    /// Returns `Ok(None)` if there is no resource directory or manifest resource.
+    pub fn manifest(&self) -> Result<Option<&'data [u8]>> {
+        let resources = match self
+            .data_directories
+            .resource_directory(self.data, &self.common.sections)?
+        {
+            Some(resources) => resources,
+            None => return Ok(None),
+        };
+        let entry = match resources.find_path(&[ResourceId::Id(pe::RT_MANIFEST)])? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        entry.data(self.data, &self.common.sections).map(Some)
+    }
+
+    /// Returns the value of the `level` attribute of the `requestedExecutionLevel`
+    /// element in the application manifest.
+    ///
+    /// This is usually one of `asInvoker`, `highestAvailable` or `requireAdministrator`.
+    ///
+    /// The manifest is scanned for the attribute without parsing the XML, and
+    /// only UTF-8 manifests are supported.
+    ///
+    /// Returns `Ok(None)` if there is no manifest or the attribute was not found.
+    pub fn requested_execution_level(&self) -> Result<Option<&'data [u8]>> {
+        Ok(self
+            .manifest()?
+            .and_then(super::resource::manifest_execution_level))
+    }
+
+    /// Returns the TLS template data of this file.
+    ///
+    /// See [`ImageTlsDirectory::raw_data`].
+    pub fn tls_raw_data(&self) -> Result<Option<&'data [u8]>> {
+        match self.tls_directory()? {
+            Some(tls) => tls
+                .raw_data(self.data, &self.common.sections, self.common.image_base)
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the RVA of the location that receives the TLS index.
+    ///
+    /// This is the `address_of_index` field of the TLS directory, converted
+    /// from a virtual address to an RVA.
+    pub fn tls_index_address(&self) -> Result<Option<u32>> {
+        let tls = match self.tls_directory()? {
+            Some(tls) => tls,
+            None => return Ok(None),
+        };
+        tls.address_of_index()
+            .checked_sub(self.common.image_base)
+            .and_then(|rva| u32::try_from(rva).ok())
+            .read_error("Invalid PE TLS index address")
+            .map(Some)
+    }
+
+    /// Returns the `CHPEMetadataPointer` field of the load configuration directory.
+    ///
+    /// This is the virtual address of the hybrid metadata of ARM64EC, ARM64X and
+    /// CHPE x86 images.
+    ///
+    /// Returns `Ok(None)` if there is no load configuration directory, if the
+    /// directory is too small to contain the field, or if the field is zero.
+    pub fn chpe_metadata_pointer(&self) -> Result<Option<u64>> {
+        let data_dir = match self.data_directory(pe::IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG) {
+            Some(data_dir) => data_dir,
+            None => return Ok(None),
+        };
+        let data = Bytes(data_dir.data(self.data, &self.common.sections)?);
+        // The load configuration directory grows over time, so use its size
+        // field to check which fields are present.
+        let size = data
+            .read_at::<U32<LE>>(0)
+            .read_error("Invalid PE load config directory size")?
+            .get(LE) as usize;
+        let data = Bytes(&data.0[..cmp::min(size, data.len())]);
+        let pointer = if self.is_64() {
+            // The offset of `ImageLoadConfigDirectory64::chpe_metadata_pointer`.
+            data.read_at::<U64Bytes<LE>>(200)
+                .map(|pointer| pointer.get(LE))
+        } else {
+            // The offset of `ImageLoadConfigDirectory32::chpe_metadata_pointer`.
+            data.read_at::<U32Bytes<LE>>(124)
+                .map(|pointer| pointer.get(LE).into())
+        };
+        Ok(pointer.ok().filter(|&pointer| pointer != 0))
+    }
+
+    /// Returns the ranges of code and their instruction sets for a hybrid image.
+    ///
+    /// For 64-bit images, this decodes the code map of the `IMAGE_ARM64EC_METADATA`
+    /// structure used by ARM64EC and ARM64X images. For 32-bit images, this decodes
+    /// the code address ranges of the `IMAGE_CHPE_METADATA_X86` structure.
+    ///
+    /// Returns an empty list if the image has no hybrid metadata.
+    pub fn chpe_code_ranges(&self) -> Result<Vec<ChpeCodeRange>> {
+        let pointer = match self.chpe_metadata_pointer()? {
+            Some(pointer) => pointer,
+            None => return Ok(Vec::new()),
+        };
+        let metadata = pointer
+            .checked_sub(self.common.image_base)
+            .and_then(|rva| u32::try_from(rva).ok())
+            .and_then(|rva| self.common.sections.pe_data_at(self.data, rva))
+            .map(Bytes)
+            .read_error("Invalid PE CHPE metadata pointer")?;
+        let (entries_rva, count, parse): (_, _, fn(&pe::ImageChpeRangeEntry) -> ChpeCodeRange) =
+            if self.is_64() {
+                let metadata = metadata
+                    .read_at::<pe::ImageArm64EcMetadata>(0)
+                    .read_error("Invalid PE ARM64EC metadata size")?;
+                (
+                    metadata.code_map.get(LE),
+                    metadata.code_map_count.get(LE),
+                    ChpeCodeRange::parse_arm64ec,
+                )
+            } else {
+                let metadata = metadata
+                    .read_at::<pe::ImageChpeMetadataX86>(0)
+                    .read_error("Invalid PE CHPE metadata size")?;
+                (
+                    metadata.chpe_code_address_range_offset.get(LE),
+                    metadata.chpe_code_address_range_count.get(LE),
+                    ChpeCodeRange::parse_x86,
+                )
+            };
+        let entries = self
+            .common
+            .sections
+            .pe_data_at(self.data, entries_rva)
+            .map(Bytes)
+            .and_then(|entries| {
+                entries
+                    .read_slice_at::<pe::ImageChpeRangeEntry>(0, count as usize)
+                    .ok()
+            })
+            .read_error("Invalid PE CHPE code range table")?;
+        Ok(entries.iter().map(parse).collect())
+    }
+
+    /// Returns the raw data of the import address table of this file.
+    ///
+    /// The import address table is located using the `IMAGE_DIRECTORY_ENTRY_IAT`
+    /// data directory, not the import descriptors.
+    pub fn iat(&self) -> Result<Option<&'data [u8]>> {
+        self.data_directories.iat(self.data, &self.common.sections)
+    }
+
+    /// Returns an iterator over the slots of the import address table of this file.
+    ///
+    /// The import address table is located using the `IMAGE_DIRECTORY_ENTRY_IAT`
+    /// data directory. The iterator is empty if there is no such data directory.
+    pub fn iat_entries(&self) -> Result<IatEntryIterator<'data, Pe>> {
+        let data = self.iat()?.unwrap_or(&[]);
+        let address = self
+            .data_directory(pe::IMAGE_DIRECTORY_ENTRY_IAT)
+            .map(|data_dir| data_dir.virtual_address.get(LE))
+            .unwrap_or(0);
+        Ok(IatEntryIterator::new(data, address))
+    }
+
+    /// Compare the `IMAGE_DIRECTORY_ENTRY_IAT` data directory with the import address
+    /// tables of the import descriptors.
+    ///
+    /// Linkers normally emit an IAT data directory that exactly covers the import
+    /// address tables, so a mismatch may indicate that the file was modified by
+    /// a packer. The length of each import address table is determined using the
+    /// import lookup table if present.
+    pub fn import_consistency(&self) -> Result<ImportConsistency> {
+        let iat = self
+            .data_directory(pe::IMAGE_DIRECTORY_ENTRY_IAT)
+            .map(|data_dir| {
+                let start = data_dir.virtual_address.get(LE);
+                start..start.saturating_add(data_dir.size.get(LE))
+            })
+            .filter(|range| range.start < range.end);
+
+        let mut thunks = Vec::new();
+        if let Some(import_table) = self.import_table()? {
+            let size = mem::size_of::<Pe::ImageThunkData>() as u32;
+            let mut import_descs = import_table.descriptors()?;
+            while let Some(import_desc) = import_descs.next()? {
+                let first_thunk = import_desc.first_thunk.get(LE);
+                if first_thunk == 0 {
+                    continue;
+                }
+                let mut lookup_thunk = import_desc.original_first_thunk.get(LE);
+                if lookup_thunk == 0 {
+                    lookup_thunk = first_thunk;
+                }
+                let mut list = import_table.thunks(lookup_thunk)?;
+                // Include the null thunk.
+                let mut count = 1u32;
+                while list.next::<Pe>()?.is_some() {
+                    count += 1;
+                }
+                let end = first_thunk
+                    .checked_add(count.saturating_mul(size))
+                    .read_error("Invalid PE import thunk address")?;
+                thunks.push(first_thunk..end);
+            }
+        }
+        Ok(ImportConsistency::new(iat, thunks))
+    }
+
+    /// Returns the CLR runtime header of this file.
+    ///
+    /// This is only present for .NET assemblies.
+    pub fn clr_header(&self) -> Result<Option<ClrHeader<'data>>> {
+        self.data_directories
+            .clr_header(self.data, &self.common.sections)
     }
 
     pub(super) fn section_alignment(&self) -> u64 {
@@ -144,14 +813,17 @@ where
     type SymbolTable = CoffSymbolTable<'data, 'file, R>;
     type DynamicRelocationIterator = NoDynamicRelocationIterator;
 
+    #[inline]
+    fn format(&self) -> BinaryFormat {
+        BinaryFormat::Pe
+    }
+
     fn architecture(&self) -> Architecture {
-        match self.nt_headers.file_header().machine.get(LE) {
-            pe::IMAGE_FILE_MACHINE_ARMNT => Architecture::Arm,
-            pe::IMAGE_FILE_MACHINE_ARM64 => Architecture::Aarch64,
-            pe::IMAGE_FILE_MACHINE_I386 => Architecture::I386,
-            pe::IMAGE_FILE_MACHINE_AMD64 => Architecture::X86_64,
-            _ => Architecture::Unknown,
-        }
+        machine_architecture(self.nt_headers.file_header().machine.get(LE))
+    }
+
+    fn sub_architecture(&self) -> Option<SubArchitecture> {
+        machine_sub_architecture(self.nt_headers.file_header().machine.get(LE))
     }
 
     #[inline]
@@ -220,6 +892,11 @@ where
         PeComdatIterator { file: self }
     }
 
+    #[inline]
+    fn symbol_count(&'file self) -> usize {
+        self.common.symbols.len()
+    }
+
     fn symbol_by_index(&'file self, index: SymbolIndex) -> Result<CoffSymbol<'data, 'file, R>> {
         let symbol = self.common.symbols.symbol(index.0)?;
         Ok(CoffSymbol {
@@ -281,6 +958,31 @@ where
         Ok(imports)
     }
 
+    fn needed_libraries(&self) -> Result<Vec<&'data [u8]>> {
+        let mut libraries = Vec::new();
+        if let Some(import_table) = self.import_table()? {
+            let mut import_descs = import_table.descriptors()?;
+            while let Some(import_desc) = import_descs.next()? {
+                let library = import_table.name(import_desc.name.get(LE))?;
+                if !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+            }
+        }
+        if let Some(import_table) = self.delay_load_import_table()? {
+            let mut import_descs = import_table.descriptors()?;
+            while let Some(import_desc) = import_descs.next()? {
+                let base = import_table.address_base(import_desc);
+                let library =
+                    import_table.name(import_desc.dll_name_rva.get(LE).wrapping_sub(base))?;
+                if !libraries.contains(&library) {
+                    libraries.push(library);
+                }
+            }
+        }
+        Ok(libraries)
+    }
+
     fn exports(&self) -> Result<Vec<Export<'data>>> {
         let mut exports = Vec::new();
         if let Some(export_table) = self.export_table()? {
@@ -349,17 +1051,107 @@ where
         }))
     }
 
+    fn tls_symbols(&'file self) -> Result<Vec<TlsSymbol<'data>>> {
+        self.common.tls_symbols()
+    }
+
+    fn functions(&'file self) -> Vec<read::Function> {
+        let image_base = self.common.image_base;
+        let mut starts = Vec::new();
+        if let Ok(Some(exception_directory)) = self.exception_directory() {
+            if self.nt_headers.file_header().machine.get(LE) == pe::IMAGE_FILE_MACHINE_ARM64 {
+                for function in exception_directory.arm64_functions().unwrap_or(&[]) {
+                    let size = match function.unwind_info() {
+                        Arm64UnwindInfo::Packed(packed) => Some(packed.function_length.into()),
+                        Arm64UnwindInfo::Xdata(_) => None,
+                    };
+                    let address = image_base.wrapping_add(function.begin_address().into());
+                    starts.push((address, size));
+                }
+            } else {
+                for function in exception_directory.functions().unwrap_or(&[]) {
+                    let begin = function.begin_address.get(LE);
+                    let end = function.end_address.get(LE);
+                    let size = end.checked_sub(begin).map(u64::from);
+                    starts.push((image_base.wrapping_add(begin.into()), size));
+                }
+            }
+        }
+        read::functions::functions(self, &starts)
+    }
+
+    fn trailing_data(&self) -> Result<Option<&'data [u8]>> {
+        let mut end = u64::from(self.nt_headers.optional_header().size_of_headers());
+        end = cmp::max(end, self.common.sections.max_section_file_offset());
+        // The symbol table is followed by the string table. An invalid symbol
+        // table was ignored when parsing, so it is ignored here too.
+        if let Some(strings_end) = self.common.symbols.strings().end_offset() {
+            end = cmp::max(end, strings_end);
+        }
+        if let Some(certificates) = self.data_directory(pe::IMAGE_DIRECTORY_ENTRY_SECURITY) {
+            // The address of the certificate table is a file offset.
+            let (offset, size) = certificates.address_range();
+            end = cmp::max(end, u64::from(offset) + u64::from(size));
+        }
+        read::util::trailing_data(self.data, end)
+    }
+
+    fn all_relocations(&'file self) -> Result<Vec<(SectionIndex, u64, read::Relocation)>> {
+        let fixups = self.pointer_fixups()?.collect::<Vec<_>>();
+        Ok(read::relocations::all_relocations(self, &fixups))
+    }
+
+    fn pointer_fixups(&'file self) -> Result<PointerFixupIterator> {
+        let mut fixups = Vec::new();
+        let mut blocks = match self
+            .data_directories
+            .relocation_blocks(self.data, &self.common.sections)?
+        {
+            Some(blocks) => blocks,
+            None => return Ok(PointerFixupIterator::new(fixups)),
+        };
+        while let Some(block) = blocks.next()? {
+            for relocation in block {
+                let size = match relocation.typ {
+                    pe::IMAGE_REL_BASED_HIGHLOW => 4,
+                    pe::IMAGE_REL_BASED_DIR64 => 8,
+                    _ => continue,
+                };
+                fixups.push(PointerFixup {
+                    address: self
+                        .common
+                        .image_base
+                        .wrapping_add(relocation.virtual_address.into()),
+                    size,
+                });
+            }
+        }
+        Ok(PointerFixupIterator::new(fixups))
+    }
+
     fn has_debug_symbols(&self) -> bool {
-        self.section_by_name(".debug_info").is_some()
+        self.common.has_debug_sections() || matches!(self.pdb_info(), Ok(Some(_)))
+    }
+
+    fn is_stripped(&self) -> bool {
+        self.common.is_stripped()
     }
 
     fn relative_address_base(&self) -> u64 {
         self.common.image_base
     }
 
+    fn preferred_address(&self) -> u64 {
+        self.common.image_base
+    }
+
     fn entry(&self) -> u64 {
-        u64::from(self.nt_headers.optional_header().address_of_entry_point())
-            .wrapping_add(self.common.image_base)
+        let address_of_entry_point = self.nt_headers.optional_header().address_of_entry_point();
+        if address_of_entry_point == 0 {
+            // DLLs may not have an entry point.
+            return 0;
+        }
+        u64::from(address_of_entry_point).wrapping_add(self.common.image_base)
     }
 
     fn flags(&self) -> FileFlags {
@@ -510,6 +1302,34 @@ impl pe::ImageDosHeader {
     }
 }
 
+/// Returns true if the machine uses 64-bit pointers, or `None` if unknown.
+fn machine_is_64(machine: u16) -> Option<bool> {
+    match machine {
+        pe::IMAGE_FILE_MACHINE_AMD64
+        | pe::IMAGE_FILE_MACHINE_ARM64
+        | pe::IMAGE_FILE_MACHINE_ARM64EC
+        | pe::IMAGE_FILE_MACHINE_IA64
+        | pe::IMAGE_FILE_MACHINE_ALPHA64
+        | pe::IMAGE_FILE_MACHINE_RISCV64 => Some(true),
+        pe::IMAGE_FILE_MACHINE_I386
+        | pe::IMAGE_FILE_MACHINE_ARM
+        | pe::IMAGE_FILE_MACHINE_THUMB
+        | pe::IMAGE_FILE_MACHINE_ARMNT
+        | pe::IMAGE_FILE_MACHINE_POWERPC
+        | pe::IMAGE_FILE_MACHINE_POWERPCFP
+        | pe::IMAGE_FILE_MACHINE_R4000
+        | pe::IMAGE_FILE_MACHINE_WCEMIPSV2
+        | pe::IMAGE_FILE_MACHINE_MIPS16
+        | pe::IMAGE_FILE_MACHINE_MIPSFPU
+        | pe::IMAGE_FILE_MACHINE_MIPSFPU16
+        | pe::IMAGE_FILE_MACHINE_SH3
+        | pe::IMAGE_FILE_MACHINE_SH3DSP
+        | pe::IMAGE_FILE_MACHINE_SH4
+        | pe::IMAGE_FILE_MACHINE_RISCV32 => Some(false),
+        _ => None,
+    }
+}
+
 /// Find the optional header and read the `optional_header.magic`.
 ///
 /// It can be useful to know this magic value before trying to
@@ -529,11 +1349,62 @@ pub fn optional_header_magic<'data, R: ReadRef<'data>>(data: R) -> Result<u16> {
     Ok(nt_headers.optional_header().magic())
 }
 
+/// Scan data for the offsets of plausible PE headers.
+///
+/// This is useful for carving PE files out of memory dumps or other data.
+/// A candidate is a DOS header whose `e_lfanew` field points to a PE signature
+/// within the data, followed by an optional header with a PE32 or PE32+ magic.
+///
+/// Each returned offset is the start of the DOS header. The data from that
+/// offset onwards may be passed to [`PeFile::parse`] for a full parse.
+pub fn find_pe_headers(data: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    let mut offset = 0;
+    core::iter::from_fn(move || {
+        while let Some(pos) = memchr::memchr(b'M', data.get(offset..)?) {
+            let start = offset + pos;
+            offset = start + 1;
+            if is_pe_header(&data[start..]) {
+                return Some(start);
+            }
+        }
+        offset = data.len();
+        None
+    })
+}
+
+/// Return true if the data starts with a DOS header for a PE file.
+///
+/// This reads the fields as bytes, since the candidate may not be aligned.
+fn is_pe_header(data: &[u8]) -> bool {
+    // `e_magic` is at offset 0 and `e_lfanew` is at offset 0x3c.
+    let e_lfanew = match data.get(0x3c..0x40) {
+        Some(bytes) if data[..2] == pe::IMAGE_DOS_SIGNATURE.to_le_bytes() => {
+            u32::from_le_bytes(bytes.try_into().unwrap()) as usize
+        }
+        _ => return false,
+    };
+    // The optional header magic follows the signature and the file header.
+    let magic_offset = mem::size_of::<u32>() + mem::size_of::<pe::ImageFileHeader>();
+    let nt_headers = match e_lfanew
+        .checked_add(magic_offset + 2)
+        .and_then(|end| data.get(e_lfanew..end))
+    {
+        Some(nt_headers) => nt_headers,
+        None => return false,
+    };
+    if nt_headers[..4] != pe::IMAGE_NT_SIGNATURE.to_le_bytes() {
+        return false;
+    }
+    let magic = u16::from_le_bytes([nt_headers[magic_offset], nt_headers[magic_offset + 1]]);
+    magic == pe::IMAGE_NT_OPTIONAL_HDR32_MAGIC || magic == pe::IMAGE_NT_OPTIONAL_HDR64_MAGIC
+}
+
 /// A trait for generic access to `ImageNtHeaders32` and `ImageNtHeaders64`.
 #[allow(missing_docs)]
 pub trait ImageNtHeaders: Debug + Pod {
     type ImageOptionalHeader: ImageOptionalHeader;
     type ImageThunkData: ImageThunkData;
+    type ImageTlsDirectory: ImageTlsDirectory;
 
     /// Return true if this type is a 64-bit header.
     ///
@@ -618,6 +1489,13 @@ pub trait ImageNtHeaders: Debug + Pod {
 /// A trait for generic access to `ImageOptionalHeader32` and `ImageOptionalHeader64`.
 #[allow(missing_docs)]
 pub trait ImageOptionalHeader: Debug + Pod {
+    /// Return the raw bytes of the optional header.
+    ///
+    /// This does not include the data directories that follow it.
+    fn raw_bytes(&self) -> &[u8] {
+        pod::bytes_of(self)
+    }
+
     // Standard fields.
     fn magic(&self) -> u16;
     fn major_linker_version(&self) -> u8;
@@ -656,6 +1534,7 @@ pub trait ImageOptionalHeader: Debug + Pod {
 impl ImageNtHeaders for pe::ImageNtHeaders32 {
     type ImageOptionalHeader = pe::ImageOptionalHeader32;
     type ImageThunkData = pe::ImageThunkData32;
+    type ImageTlsDirectory = pe::ImageTlsDirectory32;
 
     #[inline]
     fn is_type_64(&self) -> bool {
@@ -838,6 +1717,7 @@ impl ImageOptionalHeader for pe::ImageOptionalHeader32 {
 impl ImageNtHeaders for pe::ImageNtHeaders64 {
     type ImageOptionalHeader = pe::ImageOptionalHeader64;
     type ImageThunkData = pe::ImageThunkData64;
+    type ImageTlsDirectory = pe::ImageTlsDirectory64;
 
     #[inline]
     fn is_type_64(&self) -> bool {
@@ -0,0 +1,330 @@
+use core::ops;
+
+use crate::pe;
+
+/// The target machine of a PE file.
+///
+/// This is the `machine` field of the file header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Machine {
+    /// `IMAGE_FILE_MACHINE_UNKNOWN`
+    None,
+    /// `IMAGE_FILE_MACHINE_I386`
+    I386,
+    /// `IMAGE_FILE_MACHINE_AMD64`
+    Amd64,
+    /// `IMAGE_FILE_MACHINE_ARM`
+    Arm,
+    /// `IMAGE_FILE_MACHINE_THUMB`
+    Thumb,
+    /// `IMAGE_FILE_MACHINE_ARMNT`
+    ArmNt,
+    /// `IMAGE_FILE_MACHINE_ARM64`
+    Arm64,
+    /// `IMAGE_FILE_MACHINE_IA64`
+    IA64,
+    /// `IMAGE_FILE_MACHINE_EBC`
+    Ebc,
+    /// `IMAGE_FILE_MACHINE_POWERPC`
+    PowerPc,
+    /// `IMAGE_FILE_MACHINE_RISCV32`
+    RiscV32,
+    /// `IMAGE_FILE_MACHINE_RISCV64`
+    RiscV64,
+    /// `IMAGE_FILE_MACHINE_RISCV128`
+    RiscV128,
+    /// Some other `IMAGE_FILE_MACHINE_*` value.
+    Unknown(u16),
+}
+
+impl Machine {
+    /// Return the raw `IMAGE_FILE_MACHINE_*` value.
+    pub fn raw(self) -> u16 {
+        match self {
+            Machine::None => pe::IMAGE_FILE_MACHINE_UNKNOWN,
+            Machine::I386 => pe::IMAGE_FILE_MACHINE_I386,
+            Machine::Amd64 => pe::IMAGE_FILE_MACHINE_AMD64,
+            Machine::Arm => pe::IMAGE_FILE_MACHINE_ARM,
+            Machine::Thumb => pe::IMAGE_FILE_MACHINE_THUMB,
+            Machine::ArmNt => pe::IMAGE_FILE_MACHINE_ARMNT,
+            Machine::Arm64 => pe::IMAGE_FILE_MACHINE_ARM64,
+            Machine::IA64 => pe::IMAGE_FILE_MACHINE_IA64,
+            Machine::Ebc => pe::IMAGE_FILE_MACHINE_EBC,
+            Machine::PowerPc => pe::IMAGE_FILE_MACHINE_POWERPC,
+            Machine::RiscV32 => pe::IMAGE_FILE_MACHINE_RISCV32,
+            Machine::RiscV64 => pe::IMAGE_FILE_MACHINE_RISCV64,
+            Machine::RiscV128 => pe::IMAGE_FILE_MACHINE_RISCV128,
+            Machine::Unknown(machine) => machine,
+        }
+    }
+}
+
+impl From<u16> for Machine {
+    fn from(machine: u16) -> Self {
+        match machine {
+            pe::IMAGE_FILE_MACHINE_UNKNOWN => Machine::None,
+            pe::IMAGE_FILE_MACHINE_I386 => Machine::I386,
+            pe::IMAGE_FILE_MACHINE_AMD64 => Machine::Amd64,
+            pe::IMAGE_FILE_MACHINE_ARM => Machine::Arm,
+            pe::IMAGE_FILE_MACHINE_THUMB => Machine::Thumb,
+            pe::IMAGE_FILE_MACHINE_ARMNT => Machine::ArmNt,
+            pe::IMAGE_FILE_MACHINE_ARM64 => Machine::Arm64,
+            pe::IMAGE_FILE_MACHINE_IA64 => Machine::IA64,
+            pe::IMAGE_FILE_MACHINE_EBC => Machine::Ebc,
+            pe::IMAGE_FILE_MACHINE_POWERPC => Machine::PowerPc,
+            pe::IMAGE_FILE_MACHINE_RISCV32 => Machine::RiscV32,
+            pe::IMAGE_FILE_MACHINE_RISCV64 => Machine::RiscV64,
+            pe::IMAGE_FILE_MACHINE_RISCV128 => Machine::RiscV128,
+            _ => Machine::Unknown(machine),
+        }
+    }
+}
+
+/// The subsystem required to run a PE image.
+///
+/// This is the `subsystem` field of the optional header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Subsystem {
+    /// `IMAGE_SUBSYSTEM_UNKNOWN`
+    None,
+    /// `IMAGE_SUBSYSTEM_NATIVE`
+    Native,
+    /// `IMAGE_SUBSYSTEM_WINDOWS_GUI`
+    WindowsGui,
+    /// `IMAGE_SUBSYSTEM_WINDOWS_CUI`
+    WindowsCui,
+    /// `IMAGE_SUBSYSTEM_OS2_CUI`
+    Os2Cui,
+    /// `IMAGE_SUBSYSTEM_POSIX_CUI`
+    PosixCui,
+    /// `IMAGE_SUBSYSTEM_NATIVE_WINDOWS`
+    NativeWindows,
+    /// `IMAGE_SUBSYSTEM_WINDOWS_CE_GUI`
+    WindowsCeGui,
+    /// `IMAGE_SUBSYSTEM_EFI_APPLICATION`
+    EfiApplication,
+    /// `IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER`
+    EfiBootServiceDriver,
+    /// `IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER`
+    EfiRuntimeDriver,
+    /// `IMAGE_SUBSYSTEM_EFI_ROM`
+    EfiRom,
+    /// `IMAGE_SUBSYSTEM_XBOX`
+    Xbox,
+    /// `IMAGE_SUBSYSTEM_WINDOWS_BOOT_APPLICATION`
+    WindowsBootApplication,
+    /// `IMAGE_SUBSYSTEM_XBOX_CODE_CATALOG`
+    XboxCodeCatalog,
+    /// Some other `IMAGE_SUBSYSTEM_*` value.
+    Unknown(u16),
+}
+
+impl Subsystem {
+    /// Return the raw `IMAGE_SUBSYSTEM_*` value.
+    pub fn raw(self) -> u16 {
+        match self {
+            Subsystem::None => pe::IMAGE_SUBSYSTEM_UNKNOWN,
+            Subsystem::Native => pe::IMAGE_SUBSYSTEM_NATIVE,
+            Subsystem::WindowsGui => pe::IMAGE_SUBSYSTEM_WINDOWS_GUI,
+            Subsystem::WindowsCui => pe::IMAGE_SUBSYSTEM_WINDOWS_CUI,
+            Subsystem::Os2Cui => pe::IMAGE_SUBSYSTEM_OS2_CUI,
+            Subsystem::PosixCui => pe::IMAGE_SUBSYSTEM_POSIX_CUI,
+            Subsystem::NativeWindows => pe::IMAGE_SUBSYSTEM_NATIVE_WINDOWS,
+            Subsystem::WindowsCeGui => pe::IMAGE_SUBSYSTEM_WINDOWS_CE_GUI,
+            Subsystem::EfiApplication => pe::IMAGE_SUBSYSTEM_EFI_APPLICATION,
+            Subsystem::EfiBootServiceDriver => pe::IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER,
+            Subsystem::EfiRuntimeDriver => pe::IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER,
+            Subsystem::EfiRom => pe::IMAGE_SUBSYSTEM_EFI_ROM,
+            Subsystem::Xbox => pe::IMAGE_SUBSYSTEM_XBOX,
+            Subsystem::WindowsBootApplication => pe::IMAGE_SUBSYSTEM_WINDOWS_BOOT_APPLICATION,
+            Subsystem::XboxCodeCatalog => pe::IMAGE_SUBSYSTEM_XBOX_CODE_CATALOG,
+            Subsystem::Unknown(subsystem) => subsystem,
+        }
+    }
+}
+
+impl From<u16> for Subsystem {
+    fn from(subsystem: u16) -> Self {
+        match subsystem {
+            pe::IMAGE_SUBSYSTEM_UNKNOWN => Subsystem::None,
+            pe::IMAGE_SUBSYSTEM_NATIVE => Subsystem::Native,
+            pe::IMAGE_SUBSYSTEM_WINDOWS_GUI => Subsystem::WindowsGui,
+            pe::IMAGE_SUBSYSTEM_WINDOWS_CUI => Subsystem::WindowsCui,
+            pe::IMAGE_SUBSYSTEM_OS2_CUI => Subsystem::Os2Cui,
+            pe::IMAGE_SUBSYSTEM_POSIX_CUI => Subsystem::PosixCui,
+            pe::IMAGE_SUBSYSTEM_NATIVE_WINDOWS => Subsystem::NativeWindows,
+            pe::IMAGE_SUBSYSTEM_WINDOWS_CE_GUI => Subsystem::WindowsCeGui,
+            pe::IMAGE_SUBSYSTEM_EFI_APPLICATION => Subsystem::EfiApplication,
+            pe::IMAGE_SUBSYSTEM_EFI_BOOT_SERVICE_DRIVER => Subsystem::EfiBootServiceDriver,
+            pe::IMAGE_SUBSYSTEM_EFI_RUNTIME_DRIVER => Subsystem::EfiRuntimeDriver,
+            pe::IMAGE_SUBSYSTEM_EFI_ROM => Subsystem::EfiRom,
+            pe::IMAGE_SUBSYSTEM_XBOX => Subsystem::Xbox,
+            pe::IMAGE_SUBSYSTEM_WINDOWS_BOOT_APPLICATION => Subsystem::WindowsBootApplication,
+            pe::IMAGE_SUBSYSTEM_XBOX_CODE_CATALOG => Subsystem::XboxCodeCatalog,
+            _ => Subsystem::Unknown(subsystem),
+        }
+    }
+}
+
+/// The DLL characteristics of a PE image.
+///
+/// This is the `dll_characteristics` field of the optional header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DllCharacteristics(pub u16);
+
+impl DllCharacteristics {
+    /// `IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA`
+    pub const HIGH_ENTROPY_VA: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_HIGH_ENTROPY_VA);
+    /// `IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE`
+    pub const DYNAMIC_BASE: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE);
+    /// `IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY`
+    pub const FORCE_INTEGRITY: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_FORCE_INTEGRITY);
+    /// `IMAGE_DLLCHARACTERISTICS_NX_COMPAT`
+    pub const NX_COMPAT: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_NX_COMPAT);
+    /// `IMAGE_DLLCHARACTERISTICS_NO_ISOLATION`
+    pub const NO_ISOLATION: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_NO_ISOLATION);
+    /// `IMAGE_DLLCHARACTERISTICS_NO_SEH`
+    pub const NO_SEH: DllCharacteristics = DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_NO_SEH);
+    /// `IMAGE_DLLCHARACTERISTICS_NO_BIND`
+    pub const NO_BIND: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_NO_BIND);
+    /// `IMAGE_DLLCHARACTERISTICS_APPCONTAINER`
+    pub const APPCONTAINER: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_APPCONTAINER);
+    /// `IMAGE_DLLCHARACTERISTICS_WDM_DRIVER`
+    pub const WDM_DRIVER: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_WDM_DRIVER);
+    /// `IMAGE_DLLCHARACTERISTICS_GUARD_CF`
+    pub const GUARD_CF: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_GUARD_CF);
+    /// `IMAGE_DLLCHARACTERISTICS_TERMINAL_SERVER_AWARE`
+    pub const TERMINAL_SERVER_AWARE: DllCharacteristics =
+        DllCharacteristics(pe::IMAGE_DLLCHARACTERISTICS_TERMINAL_SERVER_AWARE);
+
+    /// Return the raw `IMAGE_DLLCHARACTERISTICS_*` flags.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Return true if all of the given flags are set.
+    pub fn contains(self, other: DllCharacteristics) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl ops::BitOr for DllCharacteristics {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        DllCharacteristics(self.0 | other.0)
+    }
+}
+
+impl ops::BitAnd for DllCharacteristics {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        DllCharacteristics(self.0 & other.0)
+    }
+}
+
+/// The characteristics of a PE or COFF file.
+///
+/// This is the `characteristics` field of the file header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileCharacteristics(pub u16);
+
+impl FileCharacteristics {
+    /// `IMAGE_FILE_RELOCS_STRIPPED`
+    pub const RELOCS_STRIPPED: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_RELOCS_STRIPPED);
+    /// `IMAGE_FILE_EXECUTABLE_IMAGE`
+    pub const EXECUTABLE_IMAGE: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_EXECUTABLE_IMAGE);
+    /// `IMAGE_FILE_LINE_NUMS_STRIPPED`
+    pub const LINE_NUMS_STRIPPED: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_LINE_NUMS_STRIPPED);
+    /// `IMAGE_FILE_LOCAL_SYMS_STRIPPED`
+    pub const LOCAL_SYMS_STRIPPED: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_LOCAL_SYMS_STRIPPED);
+    /// `IMAGE_FILE_AGGRESIVE_WS_TRIM`
+    pub const AGGRESIVE_WS_TRIM: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_AGGRESIVE_WS_TRIM);
+    /// `IMAGE_FILE_LARGE_ADDRESS_AWARE`
+    pub const LARGE_ADDRESS_AWARE: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_LARGE_ADDRESS_AWARE);
+    /// `IMAGE_FILE_BYTES_REVERSED_LO`
+    pub const BYTES_REVERSED_LO: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_BYTES_REVERSED_LO);
+    /// `IMAGE_FILE_32BIT_MACHINE`
+    pub const MACHINE_32BIT: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_32BIT_MACHINE);
+    /// `IMAGE_FILE_DEBUG_STRIPPED`
+    pub const DEBUG_STRIPPED: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_DEBUG_STRIPPED);
+    /// `IMAGE_FILE_REMOVABLE_RUN_FROM_SWAP`
+    pub const REMOVABLE_RUN_FROM_SWAP: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_REMOVABLE_RUN_FROM_SWAP);
+    /// `IMAGE_FILE_NET_RUN_FROM_SWAP`
+    pub const NET_RUN_FROM_SWAP: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_NET_RUN_FROM_SWAP);
+    /// `IMAGE_FILE_SYSTEM`
+    pub const SYSTEM: FileCharacteristics = FileCharacteristics(pe::IMAGE_FILE_SYSTEM);
+    /// `IMAGE_FILE_DLL`
+    pub const DLL: FileCharacteristics = FileCharacteristics(pe::IMAGE_FILE_DLL);
+    /// `IMAGE_FILE_UP_SYSTEM_ONLY`
+    pub const UP_SYSTEM_ONLY: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_UP_SYSTEM_ONLY);
+    /// `IMAGE_FILE_BYTES_REVERSED_HI`
+    pub const BYTES_REVERSED_HI: FileCharacteristics =
+        FileCharacteristics(pe::IMAGE_FILE_BYTES_REVERSED_HI);
+    /// Return the raw `IMAGE_FILE_*` flags.
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    /// Return true if all of the given flags are set.
+    pub fn contains(self, other: FileCharacteristics) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Return true if the file is a DLL.
+    pub fn is_dll(self) -> bool {
+        self.contains(Self::DLL)
+    }
+
+    /// Return true if the file is an image that can be run.
+    pub fn is_executable(self) -> bool {
+        self.contains(Self::EXECUTABLE_IMAGE)
+    }
+
+    /// Return true if the base relocations have been removed.
+    ///
+    /// Such an image must be loaded at its preferred base address.
+    pub fn relocs_stripped(self) -> bool {
+        self.contains(Self::RELOCS_STRIPPED)
+    }
+}
+
+impl ops::BitOr for FileCharacteristics {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        FileCharacteristics(self.0 | other.0)
+    }
+}
+
+impl ops::BitAnd for FileCharacteristics {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        FileCharacteristics(self.0 & other.0)
+    }
+}
@@ -1,7 +1,10 @@
+use alloc::vec::Vec;
 use core::fmt::Debug;
+use core::marker::PhantomData;
 use core::mem;
+use core::ops::Range;
 
-use crate::read::{Bytes, ReadError, Result};
+use crate::read::{Bytes, Error, ReadError, Result};
 use crate::{pe, LittleEndian as LE, Pod, U16Bytes};
 
 use super::ImageNtHeaders;
@@ -12,6 +15,7 @@ pub struct ImportTable<'data> {
     section_data: Bytes<'data>,
     section_address: u32,
     import_address: u32,
+    max_descriptors: usize,
 }
 
 impl<'data> ImportTable<'data> {
@@ -29,16 +33,48 @@ impl<'data> ImportTable<'data> {
             section_data: Bytes(section_data),
             section_address,
             import_address,
+            max_descriptors: usize::max_value(),
         }
     }
 
+    /// Set the maximum number of descriptors that will be read by the
+    /// iterators returned by [`Self::descriptors`].
+    ///
+    /// By default, there is no limit.
+    pub fn set_max_descriptors(&mut self, max_descriptors: usize) {
+        self.max_descriptors = max_descriptors;
+    }
+
     /// Return an iterator for the import descriptors.
     pub fn descriptors(&self) -> Result<ImportDescriptorIterator<'data>> {
         let offset = self.import_address.wrapping_sub(self.section_address);
         let mut data = self.section_data;
         data.skip(offset as usize)
             .read_error("Invalid PE import descriptor address")?;
-        Ok(ImportDescriptorIterator { data })
+        Ok(ImportDescriptorIterator {
+            data,
+            count: 0,
+            max_descriptors: self.max_descriptors,
+        })
+    }
+
+    /// Return the raw bytes of the import descriptor array.
+    ///
+    /// This includes the null descriptor that terminates the array.
+    pub fn raw_bytes(&self) -> Result<&'data [u8]> {
+        let mut descriptors = self.descriptors()?;
+        let mut count = 1;
+        while descriptors.next()?.is_some() {
+            count += 1;
+        }
+        let offset = self.import_address.wrapping_sub(self.section_address);
+        self.section_data
+            .read_bytes_at(
+                offset as usize,
+                count * mem::size_of::<pe::ImageImportDescriptor>(),
+            )
+            .map(|bytes| bytes.0)
+            .read_error("Invalid PE import descriptor address")
     }
 
     /// Return a library name given its address.
@@ -97,21 +133,299 @@ impl<'data> ImportTable<'data> {
 #[derive(Debug, Clone)]
 pub struct ImportDescriptorIterator<'data> {
     data: Bytes<'data>,
+    count: usize,
+    max_descriptors: usize,
 }
 
 impl<'data> ImportDescriptorIterator<'data> {
+    /// Set the maximum number of descriptors that will be read.
+    ///
+    /// Reading more descriptors than this before finding a null descriptor is an error.
+    pub fn set_max_descriptors(&mut self, max_descriptors: usize) {
+        self.max_descriptors = max_descriptors;
+    }
+
     /// Return the next descriptor.
     ///
     /// Returns `Ok(None)` when a null descriptor is found.
+    ///
+    /// Returns an error if the maximum number of descriptors is exceeded.
     pub fn next(&mut self) -> Result<Option<&'data pe::ImageImportDescriptor>> {
         let import_desc = self
             .data
             .read::<pe::ImageImportDescriptor>()
             .read_error("Missing PE null import descriptor")?;
         if import_desc.is_null() {
-            Ok(None)
+            return Ok(None);
+        }
+        if self.count >= self.max_descriptors {
+            return Err(Error("Too many PE import descriptors"));
+        }
+        self.count += 1;
+        Ok(Some(import_desc))
+    }
+}
+
+/// Information for parsing a PE delay-load import table.
+#[derive(Debug, Clone)]
+pub struct DelayLoadImportTable<'data> {
+    section_data: Bytes<'data>,
+    section_address: u32,
+    import_address: u32,
+    image_base: u32,
+    max_descriptors: usize,
+}
+
+impl<'data> DelayLoadImportTable<'data> {
+    /// Create a new delay load import table parser.
+    ///
+    /// The delay load import descriptors start at `import_address`.
+    /// This table works in the same way the import table does: descriptors will be
+    /// parsed until a null entry.
+    ///
+    /// `section_data` should be from the section containing `import_address`, and
+    /// `section_address` should be the address of that section. Pointers within the
+    /// descriptors and thunks may point to anywhere within the section data.
+    pub fn new(section_data: &'data [u8], section_address: u32, import_address: u32) -> Self {
+        DelayLoadImportTable {
+            section_data: Bytes(section_data),
+            section_address,
+            import_address,
+            image_base: 0,
+            max_descriptors: usize::max_value(),
+        }
+    }
+
+    /// Set the maximum number of descriptors that will be read by the
+    /// iterators returned by [`Self::descriptors`].
+    ///
+    /// By default, there is no limit.
+    pub fn set_max_descriptors(&mut self, max_descriptors: usize) {
+        self.max_descriptors = max_descriptors;
+    }
+
+    /// Set the image base that is used for descriptors containing virtual addresses.
+    pub(super) fn set_image_base(&mut self, image_base: u32) {
+        self.image_base = image_base;
+    }
+
+    /// Return the value to subtract from the addresses in a descriptor to obtain RVAs.
+    ///
+    /// This is zero unless the descriptor is a version 1 descriptor, which uses
+    /// virtual addresses instead of RVAs.
+    pub(super) fn address_base(&self, import_desc: &pe::ImageDelayloadDescriptor) -> u32 {
+        if import_desc.attributes.get(LE) & pe::IMAGE_DELAYLOAD_RVA_BASED != 0 {
+            0
         } else {
-            Ok(Some(import_desc))
+            self.image_base
+        }
+    }
+
+    /// Return an iterator for the delay load import descriptors.
+    pub fn descriptors(&self) -> Result<DelayLoadDescriptorIterator<'data>> {
+        let offset = self.import_address.wrapping_sub(self.section_address);
+        let mut data = self.section_data;
+        data.skip(offset as usize)
+            .read_error("Invalid PE delay-load import descriptor address")?;
+        Ok(DelayLoadDescriptorIterator {
+            data,
+            table: self.clone(),
+            count: 0,
+            max_descriptors: self.max_descriptors,
+        })
+    }
+
+    /// Return a library name given its address.
+    ///
+    /// This address may be from [`pe::ImageDelayloadDescriptor::dll_name_rva`].
+    pub fn name(&self, address: u32) -> Result<&'data [u8]> {
+        self.section_data
+            .read_string_at(address.wrapping_sub(self.section_address) as usize)
+            .read_error("Invalid PE delay-load import descriptor name")
+    }
+
+    /// Return a list of thunks given its address.
+    ///
+    /// This address may be from the INT, i.e. from
+    /// [`pe::ImageDelayloadDescriptor::import_name_table_rva`].
+    ///
+    /// Please note that others RVA values from [`pe::ImageDelayloadDescriptor`] are used
+    /// by the delay loader at runtime to store values, and thus do not point inside the same
+    /// section as the INT. Calling this function on those addresses will fail.
+    pub fn thunks(&self, address: u32) -> Result<ImportThunkList<'data>> {
+        let offset = address.wrapping_sub(self.section_address);
+        let mut data = self.section_data;
+        data.skip(offset as usize)
+            .read_error("Invalid PE delay load import thunk table address")?;
+        Ok(ImportThunkList { data })
+    }
+
+    /// Parse a thunk.
+    pub fn import<Pe: ImageNtHeaders>(&self, thunk: Pe::ImageThunkData) -> Result<Import<'data>> {
+        if thunk.is_ordinal() {
+            Ok(Import::Ordinal(thunk.ordinal()))
+        } else {
+            let (hint, name) = self.hint_name(thunk.address())?;
+            Ok(Import::Name(hint, name))
+        }
+    }
+
+    /// Return the hint and name at the given address.
+    ///
+    /// This address may be from [`pe::ImageThunkData32`] or [`pe::ImageThunkData64`].
+    ///
+    /// The hint is an index into the export name pointer table in the target library.
+    pub fn hint_name(&self, address: u32) -> Result<(u16, &'data [u8])> {
+        let offset = address.wrapping_sub(self.section_address);
+        let mut data = self.section_data;
+        data.skip(offset as usize)
+            .read_error("Invalid PE delay load import thunk address")?;
+        let hint = data
+            .read::<U16Bytes<LE>>()
+            .read_error("Missing PE delay load import thunk hint")?
+            .get(LE);
+        let name = data
+            .read_string()
+            .read_error("Missing PE delay load import thunk name")?;
+        Ok((hint, name))
+    }
+}
+
+/// The imports from a single library in the delay-load import table.
+///
+/// Returned by [`PeFile::delay_load_imports`](super::PeFile::delay_load_imports).
+#[derive(Debug, Clone)]
+pub struct DelayImport<'data> {
+    /// The name of the library.
+    pub dll: &'data [u8],
+    /// The imports from the library, in the order of the import name table.
+    pub imports: Vec<Import<'data>>,
+}
+
+/// A fallible iterator for the descriptors in the delay-load data directory.
+#[derive(Debug, Clone)]
+pub struct DelayLoadDescriptorIterator<'data> {
+    data: Bytes<'data>,
+    table: DelayLoadImportTable<'data>,
+    count: usize,
+    max_descriptors: usize,
+}
+
+impl<'data> DelayLoadDescriptorIterator<'data> {
+    /// Set the maximum number of descriptors that will be read.
+    ///
+    /// Reading more descriptors than this before finding a null descriptor is an error.
+    pub fn set_max_descriptors(&mut self, max_descriptors: usize) {
+        self.max_descriptors = max_descriptors;
+    }
+
+    /// Return the next descriptor.
+    ///
+    /// Returns `Ok(None)` when a null descriptor is found.
+    ///
+    /// Returns an error if the descriptor's library name is not within the section,
+    /// or if the maximum number of descriptors is exceeded.
+    pub fn next(&mut self) -> Result<Option<&'data pe::ImageDelayloadDescriptor>> {
+        let import_desc = self
+            .data
+            .read::<pe::ImageDelayloadDescriptor>()
+            .read_error("Missing PE null delay-load import descriptor")?;
+        if import_desc.is_null() {
+            return Ok(None);
+        }
+        if self.count >= self.max_descriptors {
+            return Err(Error("Too many PE delay-load import descriptors"));
+        }
+        self.count += 1;
+        let name_offset = import_desc
+            .dll_name_rva
+            .get(LE)
+            .wrapping_sub(self.table.address_base(import_desc))
+            .wrapping_sub(self.table.section_address);
+        if name_offset as usize >= self.table.section_data.len() {
+            return Err(Error("Invalid PE delay-load import descriptor name"));
+        }
+        Ok(Some(import_desc))
+    }
+}
+
+/// A comparison of the import address table with the import descriptors.
+///
+/// Returned by [`PeFile::import_consistency`](super::PeFile::import_consistency).
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportConsistency {
+    /// The RVA range of the `IMAGE_DIRECTORY_ENTRY_IAT` data directory.
+    ///
+    /// This is `None` if the data directory is missing or empty.
+    pub iat: Option<Range<u32>>,
+    /// The RVA ranges of the import address tables of the import descriptors,
+    /// including the null thunks.
+    ///
+    /// The ranges are sorted, and overlapping or adjacent ranges are merged.
+    pub thunks: Vec<Range<u32>>,
+    /// The RVA ranges within the IAT data directory that are not used by any
+    /// import descriptor.
+    pub unused: Vec<Range<u32>>,
+    /// The RVA ranges used by the import descriptors that are outside of the
+    /// IAT data directory.
+    ///
+    /// If there is no IAT data directory, then this is the same as `thunks`.
+    pub outside: Vec<Range<u32>>,
+}
+
+impl ImportConsistency {
+    /// Return true if the IAT data directory exactly covers the import address
+    /// tables of the import descriptors.
+    pub fn is_consistent(&self) -> bool {
+        self.unused.is_empty() && self.outside.is_empty()
+    }
+
+    pub(super) fn new(iat: Option<Range<u32>>, mut thunks: Vec<Range<u32>>) -> Self {
+        thunks.sort_unstable_by_key(|range| (range.start, range.end));
+        let mut merged: Vec<Range<u32>> = Vec::with_capacity(thunks.len());
+        for range in thunks {
+            if let Some(last) = merged.last_mut() {
+                if range.start <= last.end {
+                    last.end = last.end.max(range.end);
+                    continue;
+                }
+            }
+            merged.push(range);
+        }
+
+        let mut unused = Vec::new();
+        let mut outside = Vec::new();
+        match iat {
+            Some(ref iat) => {
+                let mut next = iat.start;
+                for range in &merged {
+                    if range.start < iat.start {
+                        outside.push(range.start..range.end.min(iat.start));
+                    }
+                    if range.end > iat.end {
+                        outside.push(range.start.max(iat.end)..range.end);
+                    }
+                    let start = range.start.max(iat.start);
+                    let end = range.end.min(iat.end);
+                    if start >= end {
+                        continue;
+                    }
+                    if start > next {
+                        unused.push(next..start);
+                    }
+                    next = next.max(end);
+                }
+                if next < iat.end {
+                    unused.push(next..iat.end);
+                }
+            }
+            None => outside = merged.clone(),
+        }
+        ImportConsistency {
+            iat,
+            thunks: merged,
+            unused,
+            outside,
         }
     }
 }
@@ -150,6 +464,49 @@ impl<'data> ImportThunkList<'data> {
     }
 }
 
+/// An iterator over the slots of an import address table.
+///
+/// Returned by [`PeFile::iat_entries`](super::PeFile::iat_entries).
+#[derive(Debug, Clone)]
+pub struct IatEntryIterator<'data, Pe: ImageNtHeaders> {
+    data: Bytes<'data>,
+    address: u32,
+    marker: PhantomData<Pe>,
+}
+
+impl<'data, Pe: ImageNtHeaders> IatEntryIterator<'data, Pe> {
+    /// Create an iterator over the slots in the given data.
+    ///
+    /// `address` is the RVA of the start of the data.
+    pub fn new(data: &'data [u8], address: u32) -> Self {
+        IatEntryIterator {
+            data: Bytes(data),
+            address,
+            marker: PhantomData,
+        }
+    }
+
+    /// Return the RVA and the pointer sized value of the next slot.
+    ///
+    /// This includes the null entries that terminate the thunks of each DLL.
+    /// Returns `Ok(None)` when there are no more slots. Any trailing bytes
+    /// that are too short for a slot are ignored.
+    pub fn next(&mut self) -> Result<Option<(u32, u64)>> {
+        let size = mem::size_of::<Pe::ImageThunkData>();
+        if self.data.len() < size {
+            self.data = Bytes(&[]);
+            return Ok(None);
+        }
+        let thunk = self
+            .data
+            .read::<Pe::ImageThunkData>()
+            .read_error("Invalid PE IAT size")?;
+        let address = self.address;
+        self.address = address.wrapping_add(size as u32);
+        Ok(Some((address, thunk.raw())))
+    }
+}
+
 /// A parsed import thunk.
 #[derive(Debug, Clone, Copy)]
 pub enum Import<'data> {
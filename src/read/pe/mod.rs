@@ -10,6 +10,9 @@
 mod file;
 pub use file::*;
 
+mod header;
+pub use header::*;
+
 mod section;
 pub use section::*;
 
@@ -28,7 +31,31 @@ pub use relocation::*;
 mod resource;
 pub use resource::*;
 
+mod version;
+pub use version::*;
+
 mod rich;
 pub use rich::*;
 
+mod clr;
+pub use clr::*;
+
+mod exception;
+pub use exception::*;
+
+mod debug;
+pub use debug::*;
+
+mod packer;
+pub use packer::*;
+
+mod tls;
+pub use tls::*;
+
+mod chpe;
+pub use chpe::*;
+
+#[cfg(feature = "hash")]
+mod hash;
+
 pub use super::coff::{SectionTable, SymbolTable};
@@ -0,0 +1,145 @@
+use core::cmp;
+
+use crate::endian::LittleEndian as LE;
+use crate::pe;
+use crate::read::ReadRef;
+
+use super::SectionTable;
+
+/// A packer that was detected by [`PeFile::detect_packer`](super::PeFile::detect_packer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Packer {
+    /// UPX.
+    Upx,
+    /// ASPack.
+    Aspack,
+    /// Petite.
+    Petite,
+    /// NsPack.
+    Nspack,
+    /// An unknown packer that was detected using heuristics.
+    Generic,
+}
+
+/// Section name prefixes that are created by known packers.
+static PACKER_SECTION_NAMES: &[(&[u8], Packer)] = &[
+    (b"UPX", Packer::Upx),
+    (b".UPX", Packer::Upx),
+    (b".aspack", Packer::Aspack),
+    (b".adata", Packer::Aspack),
+    (b".petite", Packer::Petite),
+    (b".nsp", Packer::Nspack),
+];
+
+/// The maximum number of imports for an import table to be considered near-empty.
+const PACKED_MAX_IMPORTS: usize = 4;
+
+/// The minimum entropy in bits per byte for section data to be considered
+/// compressed or encrypted.
+const PACKED_MIN_ENTROPY: f64 = 7.0;
+
+pub(super) fn detect_packer<'data, R: ReadRef<'data>>(
+    sections: &SectionTable<'data>,
+    data: R,
+    entry: u32,
+    import_count: usize,
+) -> Option<Packer> {
+    for section in sections.iter() {
+        let name = section.raw_name();
+        if let Some(&(_, packer)) = PACKER_SECTION_NAMES
+            .iter()
+            .find(|(prefix, _)| name.starts_with(prefix))
+        {
+            return Some(packer);
+        }
+    }
+
+    if import_count <= PACKED_MAX_IMPORTS {
+        let high_entropy = sections.iter().any(|section| {
+            section.characteristics.get(LE) & pe::IMAGE_SCN_MEM_EXECUTE != 0
+                && section
+                    .pe_data(data)
+                    .map(|data| entropy(data) >= PACKED_MIN_ENTROPY)
+                    .unwrap_or(false)
+        });
+        if high_entropy {
+            return Some(Packer::Generic);
+        }
+    }
+
+    // An entry point outside of the first section is common for packed files, but
+    // also occurs in normal files, so also require the entry section to look like
+    // it is unpacked at runtime.
+    if entry != 0 {
+        if let Some(first) = sections.iter().next() {
+            let address = first.virtual_address.get(LE);
+            let size = cmp::max(first.virtual_size.get(LE), first.size_of_raw_data.get(LE));
+            if entry.wrapping_sub(address) >= size {
+                if let Some(section) = sections.section_containing(entry) {
+                    if is_unpacked_at_runtime(section, data) {
+                        return Some(Packer::Generic);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Return true if the section containing the entry point appears to be
+/// written to at runtime, or to contain compressed or encrypted data.
+fn is_unpacked_at_runtime<'data, R: ReadRef<'data>>(
+    section: &pe::ImageSectionHeader,
+    data: R,
+) -> bool {
+    let characteristics = section.characteristics.get(LE);
+    let writable_code = pe::IMAGE_SCN_MEM_WRITE | pe::IMAGE_SCN_MEM_EXECUTE;
+    characteristics & writable_code == writable_code
+        || section.size_of_raw_data.get(LE) == 0
+        || section
+            .pe_data(data)
+            .map(|data| entropy(data) >= PACKED_MIN_ENTROPY)
+            .unwrap_or(false)
+}
+
+/// Calculate the Shannon entropy of the data in bits per byte.
+fn entropy(data: &[u8]) -> f64 {
+    if data.is_empty() {
+        return 0.0;
+    }
+    let mut counts = [0u32; 256];
+    for &byte in data {
+        counts[usize::from(byte)] += 1;
+    }
+    let len = data.len() as f64;
+    let sum: f64 = counts
+        .iter()
+        .filter(|&&count| count != 0)
+        .map(|&count| {
+            let count = f64::from(count);
+            count * log2(count)
+        })
+        .sum();
+    log2(len) - sum / len
+}
+
+/// Calculate the base 2 logarithm of a positive normal number.
+///
+/// This is needed because `f64::log2` is not available without `std`.
+fn log2(x: f64) -> f64 {
+    let bits = x.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    // The mantissa with a zero exponent, in the range [1, 2).
+    let mantissa = f64::from_bits((bits & ((1 << 52) - 1)) | (1023 << 52));
+    // ln(m) = 2 * atanh(z) where z = (m - 1) / (m + 1), which is in the range [0, 1/3).
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut ln = 0.0;
+    for i in 0..12 {
+        ln += term / f64::from(2 * i + 1);
+        term *= z2;
+    }
+    exponent as f64 + 2.0 * ln / core::f64::consts::LN_2
+}
@@ -88,3 +88,18 @@ pub struct Relocation {
     /// One of the `pe::IMAGE_REL_BASED_*` constants.
     pub typ: u16,
 }
+
+impl Relocation {
+    /// Return the size in bits of the place that is modified by the relocation.
+    ///
+    /// Returns 0 for `IMAGE_REL_BASED_ABSOLUTE`, which is used for padding, and
+    /// for machine specific types.
+    pub fn size(&self) -> u8 {
+        match self.typ {
+            pe::IMAGE_REL_BASED_HIGH | pe::IMAGE_REL_BASED_LOW | pe::IMAGE_REL_BASED_HIGHADJ => 16,
+            pe::IMAGE_REL_BASED_HIGHLOW => 32,
+            pe::IMAGE_REL_BASED_DIR64 => 64,
+            _ => 0,
+        }
+    }
+}
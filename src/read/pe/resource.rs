@@ -1,8 +1,13 @@
+use alloc::collections::BTreeSet;
 use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp;
 
-use crate::read::{ReadError, ReadRef, Result};
+use crate::read::{Error, ReadError, ReadRef, Result};
 use crate::{pe, LittleEndian as LE, U16};
 
+use super::SectionTable;
+
 /// The `.rsrc` section of a PE file.
 #[derive(Debug, Clone, Copy)]
 pub struct ResourceDirectory<'data> {
@@ -19,6 +24,126 @@ impl<'data> ResourceDirectory<'data> {
     pub fn root(&self) -> Result<ResourceDirectoryTable<'data>> {
         ResourceDirectoryTable::parse(&self.data, 0)
     }
+
+    /// Find the data entry for a resource using its type, name and language.
+    ///
+    /// This walks the conventional three level directory tree. If a data entry
+    /// is found before the language level, then it is returned without checking
+    /// the remaining levels.
+    ///
+    /// The data entry gives the RVA and size of the resource data, which can be
+    /// read using [`pe::ImageResourceDataEntry::data`].
+    ///
+    /// Returns `Ok(None)` if the resource is not found.
+    pub fn find(
+        &self,
+        type_id: ResourceId,
+        name: ResourceId,
+        lang: ResourceId,
+    ) -> Result<Option<&'data pe::ImageResourceDataEntry>> {
+        self.find_path(&[type_id, name, lang])
+    }
+
+    /// Find the data entry for a resource using its type and name, for any language.
+    ///
+    /// If there are multiple languages, then the first one is returned.
+    ///
+    /// Returns `Ok(None)` if the resource is not found.
+    pub fn find_first_language(
+        &self,
+        type_id: ResourceId,
+        name: ResourceId,
+    ) -> Result<Option<&'data pe::ImageResourceDataEntry>> {
+        self.find_path(&[type_id, name])
+    }
+
+    /// Return the number of levels of tables in the directory tree.
+    ///
+    /// The root table is at the first level. A table that is referenced more than
+    /// once is counted at the deepest level it occurs.
+    ///
+    /// Returns an error if the tree contains a cycle.
+    pub fn depth(&self) -> Result<usize> {
+        self.depth_limited(usize::max_value())
+    }
+
+    /// Check that the directory tree has no more than `max_depth` levels of tables.
+    ///
+    /// Unlike [`Self::depth`], this stops walking the tree once the limit is exceeded.
+    pub fn check_depth(&self, max_depth: usize) -> Result<()> {
+        self.depth_limited(max_depth).map(|_| ())
+    }
+
+    fn depth_limited(&self, max_depth: usize) -> Result<usize> {
+        // Walk the tree one level at a time. Each level contains the distinct tables
+        // that can be reached by a path of that length, so the walk takes at most one
+        // step per table unless there is a cycle.
+        let mut tables = BTreeSet::new();
+        tables.insert(0);
+        let mut level = tables.clone();
+        let mut depth = 0;
+        while !level.is_empty() {
+            depth += 1;
+            if depth > max_depth {
+                return Err(Error("Too many PE resource directory levels"));
+            }
+            if depth > tables.len() {
+                return Err(Error("Invalid resource directory nesting"));
+            }
+            let mut next = BTreeSet::new();
+            for offset in level {
+                let table = ResourceDirectoryTable::parse(self.data, offset)?;
+                for entry in table.entries {
+                    if entry.is_table() {
+                        tables.insert(entry.data_offset());
+                        next.insert(entry.data_offset());
+                    }
+                }
+            }
+            level = next;
+        }
+        Ok(depth)
+    }
+
+    /// Follow a path of IDs from the root table to a data entry.
+    ///
+    /// If the path ends before a data entry is found, then the first entry of each
+    /// remaining table is used. The walk is limited to the three conventional levels,
+    /// or to the length of the path if that is greater, and returns an error if it
+    /// goes deeper or revisits a table.
+    pub(super) fn find_path(
+        &self,
+        path: &[ResourceId],
+    ) -> Result<Option<&'data pe::ImageResourceDataEntry>> {
+        let max_depth = cmp::max(path.len(), 3);
+        let mut offsets = Vec::with_capacity(max_depth);
+        offsets.push(0);
+        let mut table = self.root()?;
+        let mut path = path.iter();
+        loop {
+            let entry = match path.next() {
+                Some(id) => match table.find(*self, *id)? {
+                    Some(entry) => entry,
+                    None => return Ok(None),
+                },
+                None => match table.entries.first() {
+                    Some(entry) => entry,
+                    None => return Ok(None),
+                },
+            };
+            if entry.is_table() {
+                let offset = entry.data_offset();
+                if offsets.len() >= max_depth || offsets.contains(&offset) {
+                    return Err(Error("Invalid resource directory nesting"));
+                }
+                offsets.push(offset);
+            }
+            match entry.data(*self)? {
+                ResourceDirectoryEntryData::Table(t) => table = t,
+                ResourceDirectoryEntryData::Data(d) => return Ok(Some(d)),
+            }
+        }
+    }
 }
 
 /// A table of resource entries.
@@ -43,6 +168,27 @@ impl<'data> ResourceDirectoryTable<'data> {
             .read_error("Invalid resource table entries")?;
         Ok(Self { header, entries })
     }
+
+    /// Find the entry with the given name or ID.
+    ///
+    /// Returns `Ok(None)` if there is no matching entry.
+    pub fn find(
+        &self,
+        directory: ResourceDirectory<'data>,
+        id: ResourceId,
+    ) -> Result<Option<&'data pe::ImageResourceDirectoryEntry>> {
+        for entry in self.entries {
+            let matches = match (entry.name_or_id(), id) {
+                (ResourceNameOrId::Id(a), ResourceId::Id(b)) => a == b,
+                (ResourceNameOrId::Name(a), ResourceId::Name(b)) => a.data(directory)? == b,
+                _ => false,
+            };
+            if matches {
+                return Ok(Some(entry));
+            }
+        }
+        Ok(None)
+    }
 }
 
 impl pe::ImageResourceDirectoryEntry {
@@ -103,6 +249,23 @@ impl pe::ImageResourceDirectoryEntry {
     }
 }
 
+impl pe::ImageResourceDataEntry {
+    /// Returns the data of the resource.
+    ///
+    /// `data` must be the entire file data.
+    pub fn data<'data, R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+    ) -> Result<&'data [u8]> {
+        let size = self.size.get(LE) as usize;
+        sections
+            .pe_data_at(data, self.offset_to_data.get(LE))
+            .and_then(|data| data.get(..size))
+            .read_error("Invalid resource data entry")
+    }
+}
+
 /// Data associated with a resource directory entry.
 #[derive(Debug, Clone)]
 pub enum ResourceDirectoryEntryData<'data> {
@@ -193,3 +356,53 @@ impl ResourceNameOrId {
         }
     }
 }
+
+/// A resource name or ID used to find an entry in a resource directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceId<'a> {
+    /// A resource name, as UTF-16 code units.
+    Name(&'a [u16]),
+    /// A resource ID.
+    Id(u16),
+}
+
+/// Find the value of the `level` attribute of the `requestedExecutionLevel` element
+/// in a manifest.
+pub(super) fn manifest_execution_level(manifest: &[u8]) -> Option<&[u8]> {
+    const ELEMENT: &[u8] = b"requestedExecutionLevel";
+    let start = manifest
+        .windows(ELEMENT.len())
+        .position(|window| window == ELEMENT)?
+        + ELEMENT.len();
+    let mut tag = &manifest[start..];
+    if let Some(end) = tag.iter().position(|&byte| byte == b'>') {
+        tag = &tag[..end];
+    }
+
+    // Parse the `name="value"` attributes of the element.
+    loop {
+        let name_start = tag.iter().position(|byte| !byte.is_ascii_whitespace())?;
+        tag = &tag[name_start..];
+        let name_end = tag
+            .iter()
+            .position(|&byte| byte == b'=' || byte.is_ascii_whitespace())?;
+        let name = &tag[..name_end];
+        tag = &tag[name_end..];
+        let equals = tag.iter().position(|byte| !byte.is_ascii_whitespace())?;
+        if tag[equals] != b'=' {
+            return None;
+        }
+        tag = &tag[equals + 1..];
+        let quote_start = tag.iter().position(|byte| !byte.is_ascii_whitespace())?;
+        let quote = tag[quote_start];
+        if quote != b'"' && quote != b'\'' {
+            return None;
+        }
+        tag = &tag[quote_start + 1..];
+        let value_end = tag.iter().position(|&byte| byte == quote)?;
+        if name == b"level" {
+            return Some(&tag[..value_end]);
+        }
+        tag = &tag[value_end + 1..];
+    }
+}
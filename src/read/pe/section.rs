@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 use core::{cmp, iter, slice, str};
 
@@ -6,7 +7,7 @@ use crate::pe;
 use crate::pe::ImageSectionHeader;
 use crate::read::{
     self, CompressedData, CompressedFileRange, ObjectSection, ObjectSegment, ReadError, ReadRef,
-    Relocation, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags,
+    Relocation, Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags, StringTable,
 };
 
 use super::{ImageNtHeaders, PeFile, SectionTable};
@@ -235,6 +236,13 @@ where
         self.section.pe_data(self.file.data)
     }
 
+    #[inline]
+    fn is_bss(&self) -> bool {
+        // The flag alone is not enough, since the section may still have raw data.
+        self.section.characteristics.get(LE) & pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA != 0
+            && self.section.size_of_raw_data.get(LE) == 0
+    }
+
     fn data_range(&self, address: u64, size: u64) -> Result<Option<&'data [u8]>> {
         Ok(read::util::data_range(
             self.data()?,
@@ -434,3 +442,195 @@ impl<'data, 'file, R> Iterator for PeRelocationIterator<'data, 'file, R> {
         None
     }
 }
+
+/// A suspicious property of a section in a PE file.
+///
+/// Returned by [`PeFile::section_anomalies`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionAnomaly {
+    /// The index of the section.
+    pub index: SectionIndex,
+    /// The kind of anomaly.
+    pub kind: SectionAnomalyKind,
+}
+
+/// The kind of a [`SectionAnomaly`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SectionAnomalyKind {
+    /// The raw data extends past the end of the file.
+    RawDataPastEnd,
+    /// The raw data overlaps the raw data of the given section.
+    ///
+    /// This is reported at most once for each section. The given section is the
+    /// earlier section, in file order, whose raw data extends the furthest.
+    OverlappingRawData(SectionIndex),
+    /// The virtual size is much larger than the raw data size.
+    ///
+    /// Sections that only contain uninitialized data are not reported.
+    LargeVirtualSize,
+    /// Both the virtual size and the raw data size are zero.
+    ZeroSize,
+    /// The section name is not one of the names used by common linkers.
+    NonStandardName,
+}
+
+/// The factor by which the virtual size must exceed the raw data size
+/// for [`SectionAnomalyKind::LargeVirtualSize`].
+const LARGE_VIRTUAL_SIZE_FACTOR: u64 = 16;
+
+/// Section names that are used by common linkers.
+const STANDARD_SECTION_NAMES: &[&[u8]] = &[
+    b".text",
+    b".textbss",
+    b".data",
+    b".rdata",
+    b".bss",
+    b".idata",
+    b".edata",
+    b".pdata",
+    b".xdata",
+    b".reloc",
+    b".rsrc",
+    b".tls",
+    b".CRT",
+    b".didat",
+    b".gfids",
+    b".00cfg",
+    b".sxdata",
+    b".buildid",
+    b".eh_frame",
+    b".debug",
+];
+
+/// Return true if the name of the section is one of the names used by common linkers.
+///
+/// Names that can't be read, or that contain non-printable bytes, are never standard.
+pub(super) fn is_standard_section_name<'data, R: ReadRef<'data>>(
+    section: &'data ImageSectionHeader,
+    strings: StringTable<'data, R>,
+) -> bool {
+    match section.name(strings) {
+        Ok(name) => name.starts_with(b".debug_") || STANDARD_SECTION_NAMES.contains(&name),
+        Err(_) => false,
+    }
+}
+
+/// Return the anomalies in the sections of a PE file.
+///
+/// `data` must be the entire file data, and `strings` must be the COFF string table.
+pub(super) fn section_anomalies<'data, R: ReadRef<'data>>(
+    sections: &SectionTable<'data>,
+    data: R,
+    strings: StringTable<'data, R>,
+) -> Vec<SectionAnomaly> {
+    let mut anomalies = Vec::new();
+    let file_len = data.len().unwrap_or(0);
+    let mut raw_ranges = Vec::new();
+    for (i, section) in sections.iter().enumerate() {
+        let index = SectionIndex(i + 1);
+        let mut anomaly = |kind| anomalies.push(SectionAnomaly { index, kind });
+
+        let offset = u64::from(section.pointer_to_raw_data.get(LE));
+        let raw_size = u64::from(section.size_of_raw_data.get(LE));
+        let virtual_size = u64::from(section.virtual_size.get(LE));
+        if raw_size != 0 && offset + raw_size > file_len {
+            anomaly(SectionAnomalyKind::RawDataPastEnd);
+        }
+        let characteristics = section.characteristics.get(LE);
+        let uninitialized = characteristics
+            & (pe::IMAGE_SCN_CNT_CODE
+                | pe::IMAGE_SCN_CNT_INITIALIZED_DATA
+                | pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA
+                | pe::IMAGE_SCN_MEM_EXECUTE)
+            == pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA;
+        if !uninitialized && virtual_size > raw_size.saturating_mul(LARGE_VIRTUAL_SIZE_FACTOR) {
+            anomaly(SectionAnomalyKind::LargeVirtualSize);
+        }
+        if raw_size == 0 && virtual_size == 0 {
+            anomaly(SectionAnomalyKind::ZeroSize);
+        }
+        if !is_standard_section_name(section, strings) {
+            anomaly(SectionAnomalyKind::NonStandardName);
+        }
+        if raw_size != 0 {
+            raw_ranges.push((offset, offset + raw_size, index));
+        }
+    }
+
+    raw_ranges.sort_unstable_by_key(|&(start, end, index)| (start, end, index.0));
+    // Report each section that starts within the raw data of an earlier section,
+    // using the earlier section that extends the furthest.
+    let mut furthest: Option<(u64, SectionIndex)> = None;
+    for &(start, end, index) in &raw_ranges {
+        if let Some((furthest_end, other)) = furthest {
+            if start < furthest_end {
+                anomalies.push(SectionAnomaly {
+                    index,
+                    kind: SectionAnomalyKind::OverlappingRawData(other),
+                });
+            }
+            if end <= furthest_end {
+                continue;
+            }
+        }
+        furthest = Some((end, index));
+    }
+    anomalies
+}
+
+/// Information about the location of the entry point of a PE file.
+///
+/// Returned by [`PeFile::entry_point_analysis`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryPointInfo {
+    /// The RVA of the entry point.
+    pub address: u32,
+    /// The index of the section containing the entry point.
+    ///
+    /// This is `None` if the entry point is not within any section.
+    pub section: Option<SectionIndex>,
+    /// The section containing the entry point is not executable.
+    pub entry_section_not_executable: bool,
+    /// The entry point is at the start of its section.
+    pub entry_at_section_start: bool,
+    /// The entry point is within the last few bytes of its section.
+    pub entry_at_section_end: bool,
+    /// The entry point is in the last section of the file.
+    pub entry_in_last_section: bool,
+}
+
+/// The number of bytes at the end of a section for [`EntryPointInfo::entry_at_section_end`].
+const ENTRY_SECTION_END_SIZE: u32 = 16;
+
+/// Return information about the section containing the entry point.
+pub(super) fn entry_point_analysis(sections: &SectionTable<'_>, entry: u32) -> EntryPointInfo {
+    let mut info = EntryPointInfo {
+        address: entry,
+        section: None,
+        entry_section_not_executable: false,
+        entry_at_section_start: false,
+        entry_at_section_end: false,
+        entry_in_last_section: false,
+    };
+    let count = sections.len();
+    for (i, section) in sections.iter().enumerate() {
+        let address = section.virtual_address.get(LE);
+        let size = cmp::max(
+            section.virtual_size.get(LE),
+            section.size_of_raw_data.get(LE),
+        );
+        let offset = entry.wrapping_sub(address);
+        if offset >= size {
+            continue;
+        }
+        let characteristics = section.characteristics.get(LE);
+        info.section = Some(SectionIndex(i + 1));
+        info.entry_section_not_executable = characteristics & pe::IMAGE_SCN_MEM_EXECUTE == 0;
+        info.entry_at_section_start = offset == 0;
+        info.entry_at_section_end = size - offset <= ENTRY_SECTION_END_SIZE;
+        info.entry_in_last_section = i + 1 == count;
+        break;
+    }
+    info
+}
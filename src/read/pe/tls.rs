@@ -0,0 +1,118 @@
+use core::convert::TryFrom;
+use core::fmt::Debug;
+
+use crate::pe;
+use crate::pod::Pod;
+use crate::read::{ReadError, ReadRef, Result};
+use crate::LittleEndian as LE;
+
+use super::SectionTable;
+
+/// A trait for generic access to [`pe::ImageTlsDirectory32`] and [`pe::ImageTlsDirectory64`].
+///
+/// The addresses in the TLS directory are virtual addresses, not RVAs.
+pub trait ImageTlsDirectory: Debug + Pod {
+    /// The virtual address of the start of the TLS template data.
+    fn start_address_of_raw_data(&self) -> u64;
+
+    /// The virtual address of the end of the TLS template data.
+    fn end_address_of_raw_data(&self) -> u64;
+
+    /// The virtual address of the location that receives the TLS index.
+    fn address_of_index(&self) -> u64;
+
+    /// The virtual address of the null-terminated array of TLS callbacks.
+    fn address_of_call_backs(&self) -> u64;
+
+    /// The size in bytes of the zero fill following the TLS template data.
+    fn size_of_zero_fill(&self) -> u32;
+
+    /// The `IMAGE_SCN_ALIGN_*` alignment characteristics.
+    fn characteristics(&self) -> u32;
+
+    /// Return the TLS template data.
+    ///
+    /// This is the initial image of the TLS block, excluding the zero fill.
+    /// Returns an empty slice if the start and end addresses are equal.
+    ///
+    /// `data` must be the entire file data, and `image_base` is used to
+    /// convert the virtual addresses to RVAs.
+    fn raw_data<'data, R: ReadRef<'data>>(
+        &self,
+        data: R,
+        sections: &SectionTable<'data>,
+        image_base: u64,
+    ) -> Result<&'data [u8]> {
+        let start = self.start_address_of_raw_data();
+        let end = self.end_address_of_raw_data();
+        if start == end {
+            return Ok(&[]);
+        }
+        let size = end
+            .checked_sub(start)
+            .read_error("Invalid PE TLS template data range")?;
+        let rva = start
+            .checked_sub(image_base)
+            .and_then(|rva| u32::try_from(rva).ok())
+            .read_error("Invalid PE TLS template data address")?;
+        let data = sections
+            .pe_data_at(data, rva)
+            .read_error("Invalid PE TLS template data address")?;
+        usize::try_from(size)
+            .ok()
+            .and_then(|size| data.get(..size))
+            .read_error("Invalid PE TLS template data size")
+    }
+}
+
+impl ImageTlsDirectory for pe::ImageTlsDirectory64 {
+    fn start_address_of_raw_data(&self) -> u64 {
+        self.start_address_of_raw_data.get(LE)
+    }
+
+    fn end_address_of_raw_data(&self) -> u64 {
+        self.end_address_of_raw_data.get(LE)
+    }
+
+    fn address_of_index(&self) -> u64 {
+        self.address_of_index.get(LE)
+    }
+
+    fn address_of_call_backs(&self) -> u64 {
+        self.address_of_call_backs.get(LE)
+    }
+
+    fn size_of_zero_fill(&self) -> u32 {
+        self.size_of_zero_fill.get(LE)
+    }
+
+    fn characteristics(&self) -> u32 {
+        self.characteristics.get(LE)
+    }
+}
+
+impl ImageTlsDirectory for pe::ImageTlsDirectory32 {
+    fn start_address_of_raw_data(&self) -> u64 {
+        self.start_address_of_raw_data.get(LE).into()
+    }
+
+    fn end_address_of_raw_data(&self) -> u64 {
+        self.end_address_of_raw_data.get(LE).into()
+    }
+
+    fn address_of_index(&self) -> u64 {
+        self.address_of_index.get(LE).into()
+    }
+
+    fn address_of_call_backs(&self) -> u64 {
+        self.address_of_call_backs.get(LE).into()
+    }
+
+    fn size_of_zero_fill(&self) -> u32 {
+        self.size_of_zero_fill.get(LE)
+    }
+
+    fn characteristics(&self) -> u32 {
+        self.characteristics.get(LE)
+    }
+}
@@ -0,0 +1,71 @@
+use core::mem;
+
+use crate::read::{Bytes, Error, ReadError, Result};
+use crate::{pe, LittleEndian as LE, U16};
+
+/// The version information in a `RT_VERSION` resource.
+///
+/// This is the `VS_VERSIONINFO` structure.
+#[derive(Debug, Clone, Copy)]
+pub struct VersionInfo<'data> {
+    fixed: &'data pe::VsFixedFileInfo,
+}
+
+impl<'data> VersionInfo<'data> {
+    /// Parse the version information from the data of a `RT_VERSION` resource.
+    pub fn parse(data: &'data [u8]) -> Result<Self> {
+        // The header is the length, the value length and the type, followed by
+        // the null terminated key.
+        let data = Bytes(data);
+        let value_length = data
+            .read_at::<U16<LE>>(2)
+            .read_error("Invalid PE version info header")?
+            .get(LE);
+        let key = data
+            .read_slice_at::<U16<LE>>(6, 16)
+            .read_error("Invalid PE version info key")?;
+        if !key
+            .iter()
+            .map(|c| c.get(LE))
+            .eq("VS_VERSION_INFO\0".encode_utf16())
+        {
+            return Err(Error("Invalid PE version info key"));
+        }
+        if usize::from(value_length) < mem::size_of::<pe::VsFixedFileInfo>() {
+            return Err(Error("Missing PE version fixed file info"));
+        }
+        // The value follows the key, aligned to a 32-bit boundary.
+        let fixed = data
+            .read_at::<pe::VsFixedFileInfo>(40)
+            .read_error("Invalid PE version fixed file info")?;
+        if fixed.signature.get(LE) != pe::VS_FFI_SIGNATURE {
+            return Err(Error("Invalid PE version fixed file info signature"));
+        }
+        Ok(VersionInfo { fixed })
+    }
+
+    /// Return the fixed file information.
+    pub fn fixed_file_info(&self) -> &'data pe::VsFixedFileInfo {
+        self.fixed
+    }
+
+    /// Return the components of the file version, most significant first.
+    pub fn file_version(&self) -> (u16, u16, u16, u16) {
+        split_version(
+            self.fixed.file_version_ms.get(LE),
+            self.fixed.file_version_ls.get(LE),
+        )
+    }
+
+    /// Return the components of the product version, most significant first.
+    pub fn product_version(&self) -> (u16, u16, u16, u16) {
+        split_version(
+            self.fixed.product_version_ms.get(LE),
+            self.fixed.product_version_ls.get(LE),
+        )
+    }
+}
+
+fn split_version(ms: u32, ls: u32) -> (u16, u16, u16, u16) {
+    ((ms >> 16) as u16, ms as u16, (ls >> 16) as u16, ls as u16)
+}
@@ -2,7 +2,7 @@ use core::ops::Range;
 use std::boxed::Box;
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::convert::TryInto;
 use std::io::{Read, Seek, SeekFrom};
 use std::mem;
@@ -16,7 +16,9 @@ use crate::read::ReadRef;
 /// Contains a cache of read-only blocks of data, allowing references to
 /// them to be returned. Entries in the cache are never removed.
 /// Entries are keyed on the offset and size of the read.
-/// Currently overlapping reads are considered separate reads.
+/// A read that is contained within the most recent block at or before
+/// its offset is served from that block. Blocks are never merged, since
+/// that would invalidate references that have already been returned.
 #[derive(Debug)]
 pub struct ReadCache<R: Read + Seek> {
     cache: RefCell<ReadCacheInternal<R>>,
@@ -25,7 +27,7 @@ pub struct ReadCache<R: Read + Seek> {
 #[derive(Debug)]
 struct ReadCacheInternal<R: Read + Seek> {
     read: R,
-    bufs: HashMap<(u64, u64), Box<[u8]>>,
+    bufs: BTreeMap<(u64, u64), Box<[u8]>>,
     strings: HashMap<(u64, u8), Box<[u8]>>,
 }
 
@@ -35,7 +37,7 @@ impl<R: Read + Seek> ReadCache<R> {
         ReadCache {
             cache: RefCell::new(ReadCacheInternal {
                 read,
-                bufs: HashMap::new(),
+                bufs: BTreeMap::new(),
                 strings: HashMap::new(),
             }),
         }
@@ -72,20 +74,28 @@ impl<'a, R: Read + Seek> ReadRef<'a> for &'a ReadCache<R> {
         if size == 0 {
             return Ok(&[]);
         }
+        let end = offset.checked_add(size).ok_or(())?;
         let cache = &mut *self.cache.borrow_mut();
-        let buf = match cache.bufs.entry((offset, size)) {
-            Entry::Occupied(entry) => entry.into_mut(),
-            Entry::Vacant(entry) => {
-                let size = size.try_into().map_err(|_| ())?;
-                cache
-                    .read
-                    .seek(SeekFrom::Start(offset as u64))
-                    .map_err(|_| ())?;
-                let mut bytes = vec![0; size].into_boxed_slice();
-                cache.read.read_exact(&mut bytes).map_err(|_| ())?;
-                entry.insert(bytes)
+        // Check the block with the largest offset (and then size) not after this read.
+        if let Some((&(block_offset, block_size), block)) =
+            cache.bufs.range(..=(offset, u64::max_value())).next_back()
+        {
+            if end <= block_offset + block_size {
+                let start = (offset - block_offset) as usize;
+                let buf = &block[start..][..size as usize];
+                // Extend the lifetime to that of self.
+                // This is OK because we never mutate or remove entries.
+                return Ok(unsafe { mem::transmute::<&[u8], &[u8]>(buf) });
             }
-        };
+        }
+        let len = size.try_into().map_err(|_| ())?;
+        cache
+            .read
+            .seek(SeekFrom::Start(offset as u64))
+            .map_err(|_| ())?;
+        let mut bytes = vec![0; len].into_boxed_slice();
+        cache.read.read_exact(&mut bytes).map_err(|_| ())?;
+        let buf = cache.bufs.entry((offset, size)).or_insert(bytes);
         // Extend the lifetime to that of self.
         // This is OK because we never mutate or remove entries.
         Ok(unsafe { mem::transmute::<&[u8], &[u8]>(buf) })
@@ -0,0 +1,453 @@
+//! Support for applying relocations to section data.
+
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::read::{
+    Architecture, Error, Object, ObjectSection, ObjectSymbol, ReadError, Relocation,
+    RelocationEncoding, RelocationKind, RelocationTarget, Result, SectionIndex, SymbolIndex,
+};
+use crate::{elf, pe};
+
+/// Return the data of a section with its relocations applied.
+///
+/// See [`Object::relocated_section_data`].
+pub(crate) fn relocated_section_data<'data, 'file, O>(
+    file: &'file O,
+    section: &O::Section,
+    load_bias: u64,
+) -> Result<Cow<'data, [u8]>>
+where
+    'data: 'file,
+    O: Object<'data, 'file> + ?Sized,
+{
+    let data = section.data()?;
+    let mut relocations = section.relocations().peekable();
+    if relocations.peek().is_none() {
+        return Ok(Cow::Borrowed(data));
+    }
+
+    let mut data = data.to_vec();
+    let section_address = section.address().wrapping_add(load_bias);
+    for (offset, relocation) in relocations {
+        let size = place_size(file.architecture(), &relocation)?;
+        let start = offset as usize;
+        let place = start
+            .checked_add(size / 8)
+            .and_then(|end| data.get_mut(start..end))
+            .read_error("Invalid relocation offset")?;
+
+        let mut addend = relocation.addend();
+        if relocation.has_implicit_addend() {
+            addend = addend.wrapping_add(read_place(place, file.is_little_endian()));
+        }
+        let address = section_address.wrapping_add(offset);
+        let value = match relocation.kind() {
+            RelocationKind::Absolute => {
+                target_address(file, &relocation, load_bias)?.wrapping_add(addend as u64)
+            }
+            RelocationKind::Relative => target_address(file, &relocation, load_bias)?
+                .wrapping_add(addend as u64)
+                .wrapping_sub(address),
+            RelocationKind::Elf(r_type) if is_elf_relative(file.architecture(), r_type) => {
+                load_bias.wrapping_add(addend as u64)
+            }
+            kind => return Err(unsupported_kind(kind)),
+        };
+        write_place(place, value, file.is_little_endian());
+    }
+    Ok(Cow::Owned(data))
+}
+
+/// Return the relocations of a section with their implicit addends included.
+///
+/// See [`Object::relocations_with_addends`].
+pub(crate) fn relocations_with_addends<'data, 'file, O>(
+    file: &'file O,
+    section: &O::Section,
+) -> Result<Vec<(u64, Relocation)>>
+where
+    'data: 'file,
+    O: Object<'data, 'file> + ?Sized,
+{
+    let mut data = None;
+    let mut relocations = Vec::new();
+    for (offset, mut relocation) in section.relocations() {
+        if relocation.has_implicit_addend() {
+            if let Ok(size) = place_size(file.architecture(), &relocation) {
+                let data = match data {
+                    Some(data) => data,
+                    None => *data.get_or_insert(section.data()?),
+                };
+                let start = offset as usize;
+                let place = start
+                    .checked_add(size / 8)
+                    .and_then(|end| data.get(start..end))
+                    .read_error("Invalid relocation offset")?;
+                relocation.addend = relocation
+                    .addend
+                    .wrapping_add(read_place(place, file.is_little_endian()));
+                relocation.implicit_addend = false;
+            }
+        }
+        relocations.push((offset, relocation));
+    }
+    Ok(relocations)
+}
+
+/// The data of a section together with an index of its relocations.
+///
+/// Returned by [`Object::disassembly_view`].
+#[derive(Debug, Clone)]
+pub struct DisassemblyView<'data> {
+    data: &'data [u8],
+    // Sorted, and parallel to `relocations`.
+    offsets: Vec<u64>,
+    relocations: Vec<(RelocationKind, RelocationTarget, i64)>,
+}
+
+impl<'data> DisassemblyView<'data> {
+    pub(crate) fn new<S: ObjectSection<'data>>(section: &S) -> Result<Self> {
+        let data = section.data()?;
+        let mut relocations = section.relocations().collect::<Vec<_>>();
+        // The sort is stable, so relocations at the same offset stay in file order.
+        relocations.sort_by_key(|(offset, _)| *offset);
+        let offsets = relocations.iter().map(|(offset, _)| *offset).collect();
+        let relocations = relocations
+            .iter()
+            .map(|(_, relocation)| (relocation.kind(), relocation.target(), relocation.addend()))
+            .collect();
+        Ok(DisassemblyView {
+            data,
+            offsets,
+            relocations,
+        })
+    }
+
+    /// Return the unrelocated section data.
+    #[inline]
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Return the first relocation that starts at the given offset within the section.
+    ///
+    /// See [`Self::relocations_at`].
+    pub fn relocation_at(&self, offset: u64) -> Option<(RelocationKind, RelocationTarget, i64)> {
+        self.relocations_at(offset).first().copied()
+    }
+
+    /// Return all of the relocations that start at the given offset within the section.
+    ///
+    /// Returns the kind, target and explicit addend of each relocation, in the order
+    /// they occur in the file. Some formats use multiple relocations for one place,
+    /// such as Mach-O subtractor pairs. If a relocation has an implicit addend, then
+    /// the addend is stored in the section data and is not included.
+    pub fn relocations_at(&self, offset: u64) -> &[(RelocationKind, RelocationTarget, i64)] {
+        let start = match self.offsets.binary_search_by(|o| {
+            if *o < offset {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }) {
+            Ok(index) | Err(index) => index,
+        };
+        let end = match self.offsets.binary_search_by(|o| {
+            if *o <= offset {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        }) {
+            Ok(index) | Err(index) => index,
+        };
+        &self.relocations[start..end]
+    }
+}
+
+/// The target of a relocation, as resolved by [`Object::resolve_reference`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResolvedRef {
+    /// The index of the symbol that is the target of the relocation, if any.
+    pub symbol: Option<SymbolIndex>,
+    /// The index of the section containing the target, if known.
+    ///
+    /// For symbol targets, this is the section of the symbol.
+    pub section: Option<SectionIndex>,
+    /// The addend of the relocation.
+    ///
+    /// This includes any implicit addend that is stored in the section data.
+    pub addend: i64,
+    /// The biased address of the target plus the addend.
+    ///
+    /// This is `None` if the relocation kind is not supported, or if the
+    /// target is an undefined symbol.
+    pub target_address: Option<u64>,
+}
+
+/// Resolve the target of the relocation at an offset within a section.
+///
+/// See [`Object::resolve_reference`].
+pub(crate) fn resolve_reference<'data, 'file, O>(
+    file: &'file O,
+    section: &O::Section,
+    offset: u64,
+    load_bias: u64,
+) -> Result<Option<ResolvedRef>>
+where
+    'data: 'file,
+    O: Object<'data, 'file> + ?Sized,
+{
+    let relocation = match section.relocations().find(|(o, _)| *o == offset) {
+        Some((_, relocation)) => relocation,
+        None => return Ok(None),
+    };
+
+    let mut addend = Some(relocation.addend());
+    if relocation.has_implicit_addend() {
+        // The implicit addend is unknown if the size is not supported.
+        addend = match place_size(file.architecture(), &relocation) {
+            Ok(size) => {
+                let data = section.data()?;
+                let start = offset as usize;
+                let place = start
+                    .checked_add(size / 8)
+                    .and_then(|end| data.get(start..end))
+                    .read_error("Invalid relocation offset")?;
+                Some(
+                    relocation
+                        .addend()
+                        .wrapping_add(read_place(place, file.is_little_endian())),
+                )
+            }
+            Err(_) => None,
+        };
+    }
+
+    let (symbol, target_section, base) = match relocation.target() {
+        RelocationTarget::Symbol(index) => {
+            let symbol = file.symbol_by_index(index)?;
+            let base = if !symbol.is_undefined() {
+                Some(symbol.address().wrapping_add(load_bias))
+            } else if symbol.is_weak() {
+                // Undefined weak symbols resolve to zero, and are not biased.
+                Some(0)
+            } else {
+                None
+            };
+            (Some(index), symbol.section_index(), base)
+        }
+        RelocationTarget::Section(index) => {
+            let base = file.section_by_index(index)?.address();
+            (None, Some(index), Some(base.wrapping_add(load_bias)))
+        }
+        RelocationTarget::Absolute => (None, None, Some(0)),
+    };
+
+    let target_address = addend.and_then(|addend| match relocation.kind() {
+        RelocationKind::Absolute | RelocationKind::Relative | RelocationKind::PltRelative => {
+            base.map(|base| base.wrapping_add(addend as u64))
+        }
+        RelocationKind::Elf(r_type) if is_elf_relative(file.architecture(), r_type) => {
+            Some(load_bias.wrapping_add(addend as u64))
+        }
+        _ => None,
+    });
+    Ok(Some(ResolvedRef {
+        symbol,
+        section: target_section,
+        addend: addend.unwrap_or_else(|| relocation.addend()),
+        target_address,
+    }))
+}
+
+/// Return the size in bits of the place of a relocation.
+///
+/// This is [`Relocation::size`], which includes the sizes of format specific
+/// relocation types for ELF and COFF.
+///
+/// Returns an error if the place does not contain a complete data word, such as
+/// for instruction immediates, or if the size is not supported. Format specific
+/// relocation types are only supported if they are known to modify a data word.
+fn place_size(architecture: Architecture, relocation: &Relocation) -> Result<usize> {
+    match relocation.encoding() {
+        // The x86 encodings still modify a complete 32-bit field.
+        RelocationEncoding::Generic
+        | RelocationEncoding::X86Signed
+        | RelocationEncoding::X86RipRelative
+        | RelocationEncoding::X86RipRelativeMovq
+        | RelocationEncoding::X86Branch => {}
+        RelocationEncoding::S390xDbl => {
+            return Err(Error("Unsupported S390xDbl relocation encoding"))
+        }
+        RelocationEncoding::AArch64Call => {
+            return Err(Error("Unsupported AArch64Call relocation encoding"))
+        }
+    }
+    let size = match relocation.size() {
+        8 | 16 | 32 | 64 => usize::from(relocation.size()),
+        _ => return Err(Error("Unsupported relocation size")),
+    };
+    let is_data = match relocation.kind() {
+        RelocationKind::Absolute
+        | RelocationKind::Relative
+        | RelocationKind::ImageOffset
+        | RelocationKind::SectionOffset => true,
+        RelocationKind::Elf(r_type) => is_elf_data(architecture, r_type),
+        RelocationKind::Coff(typ) => is_coff_data(architecture, typ),
+        // The place does not contain an addend for section indices, and the
+        // remaining kinds may modify instructions.
+        _ => false,
+    };
+    if !is_data {
+        return Err(Error("Unsupported relocation place"));
+    }
+    Ok(size)
+}
+
+/// Return the error for a relocation kind that can't be applied.
+fn unsupported_kind(kind: RelocationKind) -> Error {
+    Error(match kind {
+        RelocationKind::ImageOffset => "Unsupported image offset relocation",
+        RelocationKind::SectionOffset => "Unsupported section offset relocation",
+        RelocationKind::Elf(_) => "Unsupported ELF relocation type",
+        RelocationKind::MachO { .. } => "Unsupported Mach-O relocation type",
+        RelocationKind::Coff(_) => "Unsupported COFF relocation type",
+        _ => "Unsupported relocation kind",
+    })
+}
+
+/// Return true if the ELF relocation type modifies a data word.
+fn is_elf_data(architecture: Architecture, r_type: u32) -> bool {
+    match architecture {
+        Architecture::Aarch64 => match r_type {
+            elf::R_AARCH64_ABS64
+            | elf::R_AARCH64_ABS32
+            | elf::R_AARCH64_GLOB_DAT
+            | elf::R_AARCH64_RELATIVE
+            | elf::R_AARCH64_P32_ABS32
+            | elf::R_AARCH64_P32_GLOB_DAT
+            | elf::R_AARCH64_P32_RELATIVE => true,
+            _ => false,
+        },
+        Architecture::Arm => match r_type {
+            elf::R_ARM_ABS32 | elf::R_ARM_GLOB_DAT | elf::R_ARM_RELATIVE => true,
+            _ => false,
+        },
+        Architecture::I386 => match r_type {
+            elf::R_386_32 | elf::R_386_GLOB_DAT | elf::R_386_RELATIVE => true,
+            _ => false,
+        },
+        Architecture::X86_64 => match r_type {
+            elf::R_X86_64_64
+            | elf::R_X86_64_32
+            | elf::R_X86_64_GLOB_DAT
+            | elf::R_X86_64_RELATIVE => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Return true if the COFF relocation type modifies a data word.
+fn is_coff_data(architecture: Architecture, typ: u16) -> bool {
+    match architecture {
+        Architecture::X86_64 => typ == pe::IMAGE_REL_AMD64_SREL32,
+        _ => false,
+    }
+}
+
+/// Return the biased address of the target of a relocation.
+fn target_address<'data, 'file, O>(
+    file: &'file O,
+    relocation: &Relocation,
+    load_bias: u64,
+) -> Result<u64>
+where
+    'data: 'file,
+    O: Object<'data, 'file> + ?Sized,
+{
+    let address = match relocation.target() {
+        RelocationTarget::Symbol(index) => {
+            let symbol = file.symbol_by_index(index)?;
+            if symbol.is_undefined() {
+                if !symbol.is_weak() {
+                    return Err(Error("Unsupported relocation for undefined symbol"));
+                }
+                // Undefined weak symbols resolve to zero, and are not biased.
+                return Ok(0);
+            }
+            symbol.address()
+        }
+        RelocationTarget::Section(index) => file.section_by_index(index)?.address(),
+        RelocationTarget::Absolute => return Ok(0),
+    };
+    Ok(address.wrapping_add(load_bias))
+}
+
+/// Return true if the ELF relocation type adds the load bias to the addend.
+pub(crate) fn is_elf_relative(architecture: Architecture, r_type: u32) -> bool {
+    match architecture {
+        Architecture::Aarch64 => r_type == elf::R_AARCH64_RELATIVE,
+        Architecture::Arm => r_type == elf::R_ARM_RELATIVE,
+        Architecture::I386 => r_type == elf::R_386_RELATIVE,
+        Architecture::X86_64 => r_type == elf::R_X86_64_RELATIVE,
+        _ => false,
+    }
+}
+
+fn read_place(place: &[u8], little_endian: bool) -> i64 {
+    if place.len() == 1 {
+        return i64::from(place[0] as i8);
+    }
+    if place.len() == 2 {
+        let value = [place[0], place[1]];
+        return if little_endian {
+            i64::from(i16::from_le_bytes(value))
+        } else {
+            i64::from(i16::from_be_bytes(value))
+        };
+    }
+    if place.len() == 4 {
+        let mut value = [0; 4];
+        value.copy_from_slice(place);
+        return if little_endian {
+            i64::from(i32::from_le_bytes(value))
+        } else {
+            i64::from(i32::from_be_bytes(value))
+        };
+    }
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(place);
+    if little_endian {
+        i64::from_le_bytes(bytes)
+    } else {
+        i64::from_be_bytes(bytes)
+    }
+}
+
+fn write_place(place: &mut [u8], value: u64, little_endian: bool) {
+    if place.len() == 1 {
+        place[0] = value as u8;
+    } else if place.len() == 2 {
+        let value = value as u16;
+        place.copy_from_slice(&if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        });
+    } else if place.len() == 4 {
+        let value = value as u32;
+        place.copy_from_slice(&if little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        });
+    } else if little_endian {
+        place.copy_from_slice(&value.to_le_bytes());
+    } else {
+        place.copy_from_slice(&value.to_be_bytes());
+    }
+}
@@ -0,0 +1,57 @@
+//! Support for enumerating all of the relocations in a file.
+
+use alloc::vec::Vec;
+
+use crate::read::address::SectionRanges;
+use crate::read::{
+    Object, ObjectSection, PointerFixup, Relocation, RelocationEncoding, RelocationKind,
+    RelocationTarget, SectionIndex,
+};
+
+/// Collect the relocations of each section, the dynamic relocations, and the given
+/// pointer fixups.
+///
+/// See [`Object::all_relocations`].
+pub(crate) fn all_relocations<'data, 'file, O>(
+    file: &'file O,
+    fixups: &[PointerFixup],
+) -> Vec<(SectionIndex, u64, Relocation)>
+where
+    'data: 'file,
+    O: Object<'data, 'file> + ?Sized,
+{
+    let mut relocations = Vec::new();
+    for section in file.sections() {
+        let index = section.index();
+        relocations.extend(
+            section
+                .relocations()
+                .map(|(offset, relocation)| (index, offset, relocation)),
+        );
+    }
+
+    // The remaining relocations are located by address.
+    let ranges = SectionRanges::indices(file);
+    let section_offset = |address: u64| ranges.get(address).map(|(index, offset)| (*index, offset));
+    if let Some(dynamic) = file.dynamic_relocations() {
+        for (address, relocation) in dynamic {
+            if let Some((index, offset)) = section_offset(address) {
+                relocations.push((index, offset, relocation));
+            }
+        }
+    }
+    for fixup in fixups {
+        if let Some((index, offset)) = section_offset(fixup.address()) {
+            let relocation = Relocation {
+                kind: RelocationKind::Absolute,
+                encoding: RelocationEncoding::Generic,
+                size: fixup.size() * 8,
+                target: RelocationTarget::Absolute,
+                addend: 0,
+                implicit_addend: true,
+            };
+            relocations.push((index, offset, relocation));
+        }
+    }
+    relocations
+}
@@ -0,0 +1,231 @@
+use core::{fmt, ops};
+
+use crate::read::{ObjectSection, SectionIndex, SectionKind};
+
+/// A set of section kinds.
+///
+/// This is used to select the sections to search in [`Object::extract_strings`](crate::read::Object::extract_strings).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SectionKindMask(u32);
+
+impl SectionKindMask {
+    /// No section kinds.
+    pub const NONE: SectionKindMask = SectionKindMask(0);
+    /// All section kinds.
+    pub const ALL: SectionKindMask = SectionKindMask(!0);
+    /// [`SectionKind::Unknown`].
+    pub const UNKNOWN: SectionKindMask = SectionKindMask(1 << 0);
+    /// [`SectionKind::Text`].
+    pub const TEXT: SectionKindMask = SectionKindMask(1 << 1);
+    /// [`SectionKind::Data`].
+    pub const DATA: SectionKindMask = SectionKindMask(1 << 2);
+    /// [`SectionKind::ReadOnlyData`].
+    pub const READ_ONLY_DATA: SectionKindMask = SectionKindMask(1 << 3);
+    /// [`SectionKind::ReadOnlyString`].
+    pub const READ_ONLY_STRING: SectionKindMask = SectionKindMask(1 << 4);
+    /// [`SectionKind::UninitializedData`].
+    pub const UNINITIALIZED_DATA: SectionKindMask = SectionKindMask(1 << 5);
+    /// [`SectionKind::Common`].
+    pub const COMMON: SectionKindMask = SectionKindMask(1 << 6);
+    /// [`SectionKind::Tls`].
+    pub const TLS: SectionKindMask = SectionKindMask(1 << 7);
+    /// [`SectionKind::UninitializedTls`].
+    pub const UNINITIALIZED_TLS: SectionKindMask = SectionKindMask(1 << 8);
+    /// [`SectionKind::TlsVariables`].
+    pub const TLS_VARIABLES: SectionKindMask = SectionKindMask(1 << 9);
+    /// [`SectionKind::OtherString`].
+    pub const OTHER_STRING: SectionKindMask = SectionKindMask(1 << 10);
+    /// [`SectionKind::Other`].
+    pub const OTHER: SectionKindMask = SectionKindMask(1 << 11);
+    /// [`SectionKind::Debug`].
+    pub const DEBUG: SectionKindMask = SectionKindMask(1 << 12);
+    /// [`SectionKind::Linker`].
+    pub const LINKER: SectionKindMask = SectionKindMask(1 << 13);
+    /// [`SectionKind::Note`].
+    pub const NOTE: SectionKindMask = SectionKindMask(1 << 14);
+    /// [`SectionKind::Metadata`].
+    pub const METADATA: SectionKindMask = SectionKindMask(1 << 15);
+    /// [`SectionKind::Elf`].
+    pub const ELF: SectionKindMask = SectionKindMask(1 << 16);
+
+    /// Return the mask containing only the given section kind.
+    pub fn from_kind(kind: SectionKind) -> Self {
+        match kind {
+            SectionKind::Unknown => Self::UNKNOWN,
+            SectionKind::Text => Self::TEXT,
+            SectionKind::Data => Self::DATA,
+            SectionKind::ReadOnlyData => Self::READ_ONLY_DATA,
+            SectionKind::ReadOnlyString => Self::READ_ONLY_STRING,
+            SectionKind::UninitializedData => Self::UNINITIALIZED_DATA,
+            SectionKind::Common => Self::COMMON,
+            SectionKind::Tls => Self::TLS,
+            SectionKind::UninitializedTls => Self::UNINITIALIZED_TLS,
+            SectionKind::TlsVariables => Self::TLS_VARIABLES,
+            SectionKind::OtherString => Self::OTHER_STRING,
+            SectionKind::Other => Self::OTHER,
+            SectionKind::Debug => Self::DEBUG,
+            SectionKind::Linker => Self::LINKER,
+            SectionKind::Note => Self::NOTE,
+            SectionKind::Metadata => Self::METADATA,
+            SectionKind::Elf(_) => Self::ELF,
+        }
+    }
+
+    /// Return true if the mask contains the given section kind.
+    pub fn contains(self, kind: SectionKind) -> bool {
+        self.0 & Self::from_kind(kind).0 != 0
+    }
+}
+
+impl ops::BitOr for SectionKindMask {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        SectionKindMask(self.0 | other.0)
+    }
+}
+
+impl ops::BitOrAssign for SectionKindMask {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// The encoding of an [`ExtractedString`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringEncoding {
+    /// Printable ASCII characters.
+    Ascii,
+    /// Printable ASCII characters encoded as UTF-16LE.
+    Utf16Le,
+}
+
+/// A string found by [`Object::extract_strings`](crate::read::Object::extract_strings).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractedString<'data> {
+    /// The bytes of the string.
+    ///
+    /// For UTF-16LE strings, this includes the zero high byte of each character.
+    pub data: &'data [u8],
+    /// The encoding of the string.
+    pub encoding: StringEncoding,
+    /// The index of the section containing the string.
+    pub section: SectionIndex,
+    /// The offset of the string within the section data.
+    pub offset: u64,
+}
+
+#[inline]
+fn is_printable(byte: u8) -> bool {
+    byte == b'\t' || (0x20..0x7f).contains(&byte)
+}
+
+/// An iterator over the strings in the data of a sequence of sections.
+///
+/// Returned by [`Object::extract_strings`](crate::read::Object::extract_strings).
+pub struct StringIterator<'data, I> {
+    sections: I,
+    min_len: usize,
+    kinds: SectionKindMask,
+    section: SectionIndex,
+    data: &'data [u8],
+    offset: usize,
+}
+
+impl<'data, I> StringIterator<'data, I>
+where
+    I: Iterator,
+    I::Item: ObjectSection<'data>,
+{
+    /// Construct a new iterator over the strings in the given sections.
+    ///
+    /// Only sections with a kind contained in `kinds` are searched, and strings
+    /// must contain at least `min_len` characters.
+    pub fn new(sections: I, min_len: usize, kinds: SectionKindMask) -> Self {
+        StringIterator {
+            sections,
+            min_len: if min_len == 0 { 1 } else { min_len },
+            kinds,
+            section: SectionIndex(0),
+            data: &[],
+            offset: 0,
+        }
+    }
+
+    /// Move to the next section that matches the section kinds.
+    ///
+    /// Returns false if there are no more sections.
+    fn next_section(&mut self) -> bool {
+        for section in &mut self.sections {
+            if !self.kinds.contains(section.kind()) {
+                continue;
+            }
+            if let Ok(data) = section.data() {
+                self.section = section.index();
+                self.data = data;
+                self.offset = 0;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<'data, I> Iterator for StringIterator<'data, I>
+where
+    I: Iterator,
+    I::Item: ObjectSection<'data>,
+{
+    type Item = ExtractedString<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while self.offset < self.data.len() {
+                let start = self.offset;
+                let rest = &self.data[start..];
+
+                let ascii_len = rest.iter().take_while(|&&b| is_printable(b)).count();
+                if ascii_len >= self.min_len {
+                    self.offset += ascii_len;
+                    return Some(ExtractedString {
+                        data: &rest[..ascii_len],
+                        encoding: StringEncoding::Ascii,
+                        section: self.section,
+                        offset: start as u64,
+                    });
+                }
+
+                let utf16_len = rest
+                    .chunks_exact(2)
+                    .take_while(|c| is_printable(c[0]) && c[1] == 0)
+                    .count();
+                if utf16_len >= self.min_len {
+                    self.offset += utf16_len * 2;
+                    return Some(ExtractedString {
+                        data: &rest[..utf16_len * 2],
+                        encoding: StringEncoding::Utf16Le,
+                        section: self.section,
+                        offset: start as u64,
+                    });
+                }
+
+                // A UTF-16 string can only start at the last character of a short ASCII run.
+                self.offset += if ascii_len > 1 { ascii_len - 1 } else { 1 };
+            }
+            if !self.next_section() {
+                return None;
+            }
+        }
+    }
+}
+
+impl<'data, I> fmt::Debug for StringIterator<'data, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StringIterator")
+            .field("min_len", &self.min_len)
+            .field("kinds", &self.kinds)
+            .field("section", &self.section)
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
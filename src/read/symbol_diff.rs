@@ -0,0 +1,93 @@
+use alloc::collections::btree_map::{BTreeMap, Entry};
+use alloc::vec::Vec;
+
+use crate::read::{Object, ObjectSymbol, SymbolKind};
+
+/// The differences between the symbols of two files.
+///
+/// This is returned by [`symbol_diff`]. Symbols are matched by name, and each
+/// list is sorted by name.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SymbolDiff<'data> {
+    /// The name and address of symbols that are only in the second file.
+    pub added: Vec<(&'data [u8], u64)>,
+    /// The name and address of symbols that are only in the first file.
+    pub removed: Vec<(&'data [u8], u64)>,
+    /// The name, old address, and new address of symbols with a different address.
+    pub changed: Vec<(&'data [u8], u64, u64)>,
+}
+
+impl<'data> SymbolDiff<'data> {
+    /// Return true if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compare the defined symbols of two files.
+///
+/// By default, only global symbols are compared. Local symbols are also
+/// compared if `include_locals` is true. Section and file symbols are never
+/// compared. If a file has no symbol table, then its dynamic symbols are used.
+///
+/// If multiple symbols have the same name, then the first one is used.
+pub fn symbol_diff<'data, 'a, 'b, A, B>(
+    a: &'a A,
+    b: &'b B,
+    include_locals: bool,
+) -> SymbolDiff<'data>
+where
+    'data: 'a + 'b,
+    A: Object<'data, 'a>,
+    B: Object<'data, 'b>,
+{
+    let old = defined_symbols(a, include_locals);
+    let mut new = defined_symbols(b, include_locals);
+
+    let mut diff = SymbolDiff::default();
+    for (name, old_address) in old {
+        match new.remove(name) {
+            Some(new_address) => {
+                if old_address != new_address {
+                    diff.changed.push((name, old_address, new_address));
+                }
+            }
+            None => diff.removed.push((name, old_address)),
+        }
+    }
+    diff.added.extend(new);
+    diff
+}
+
+fn defined_symbols<'data, 'file, O>(
+    object: &'file O,
+    include_locals: bool,
+) -> BTreeMap<&'data [u8], u64>
+where
+    'data: 'file,
+    O: Object<'data, 'file>,
+{
+    let mut symbols = BTreeMap::new();
+    let mut add = |symbol: O::Symbol| {
+        if !symbol.is_definition()
+            || (!include_locals && !symbol.is_global())
+            || symbol.kind() == SymbolKind::Section
+            || symbol.kind() == SymbolKind::File
+        {
+            return;
+        }
+        let name = match symbol.name_bytes() {
+            Ok(name) if !name.is_empty() => name,
+            _ => return,
+        };
+        if let Entry::Vacant(entry) = symbols.entry(name) {
+            entry.insert(symbol.address());
+        }
+    };
+    if object.symbol_table().is_some() {
+        object.symbols().for_each(&mut add);
+    } else {
+        object.dynamic_symbols().for_each(&mut add);
+    }
+    symbols
+}
@@ -1,13 +1,20 @@
 use alloc::borrow::Cow;
+#[cfg(feature = "hash")]
+use alloc::string::String;
 use alloc::vec::Vec;
+use core::convert::TryFrom;
 
+#[cfg(feature = "hash")]
+use crate::read::HashAlgo;
 use crate::read::{
-    self, Architecture, CodeView, ComdatKind, CompressedData, CompressedFileRange, Export,
-    FileFlags, Import, ObjectKind, ObjectMap, Relocation, Result, SectionFlags, SectionIndex,
-    SectionKind, SegmentFlags, SymbolFlags, SymbolIndex, SymbolKind, SymbolMap, SymbolMapName,
-    SymbolScope, SymbolSection,
+    self, functions, relocate, relocations, AddressDescription, AddressMap, Architecture,
+    BinaryFormat, CodeView, ComdatKind, CompressedData, CompressedFileRange, DisassemblyView,
+    Export, FileFlags, Function, Import, LoadSegmentIterator, ObjectKind, ObjectMap,
+    PointerFixupIterator, ReadError, Relocation, ResolvedRef, Result, SectionFlags, SectionIndex,
+    SectionKind, SectionKindMask, SegmentFlags, StringIterator, SubArchitecture, SymbolFlags,
+    SymbolIndex, SymbolKind, SymbolMap, SymbolMapName, SymbolScope, SymbolSection, TlsSymbol,
 };
-use crate::Endianness;
+use crate::{pod, Endianness, Pod};
 
 /// An object file.
 pub trait Object<'data: 'file, 'file>: read::private::Sealed {
@@ -48,9 +55,25 @@ pub trait Object<'data: 'file, 'file>: read::private::Sealed {
     /// that the relocation applies to.
     type DynamicRelocationIterator: Iterator<Item = (u64, Relocation)>;
 
+    /// Get the binary format of the file.
+    fn format(&self) -> BinaryFormat;
+
     /// Get the architecture type of the file.
+    ///
+    /// Variants of an architecture are reported as the base architecture. For example,
+    /// PE `ARM64EC` files are reported as [`Architecture::Aarch64`]. Use
+    /// [`Self::sub_architecture`] to distinguish them.
     fn architecture(&self) -> Architecture;
 
+    /// Get the sub-architecture or ABI variant of the file, if known.
+    ///
+    /// This is determined from the machine type for PE and COFF files,
+    /// the `e_flags` field for ELF files, and the CPU subtype for Mach-O files.
+    #[inline]
+    fn sub_architecture(&self) -> Option<SubArchitecture> {
+        None
+    }
+
     /// Get the endianness of the file.
     #[inline]
     fn endianness(&self) -> Endianness {
@@ -106,12 +129,182 @@ pub trait Object<'data: 'file, 'file>: read::private::Sealed {
     /// Get an iterator over the sections in the file.
     fn sections(&'file self) -> Self::SectionIterator;
 
+    /// Compute the hash of the data of each section in the file.
+    ///
+    /// Returns the section name and hash for each section, in section order.
+    /// Names that are not UTF-8 are converted lossily.
+    #[cfg(feature = "hash")]
+    fn section_hashes(&'file self, algo: HashAlgo) -> Result<Vec<(String, Vec<u8>)>> {
+        let mut hashes = Vec::new();
+        for section in self.sections() {
+            let name = String::from_utf8_lossy(section.name_bytes()?).into_owned();
+            hashes.push((name, section.hash(algo)?));
+        }
+        Ok(hashes)
+    }
+
+    /// Get an iterator over the ASCII and UTF-16LE strings in the file.
+    ///
+    /// Only sections with a kind contained in `kinds` are searched.
+    /// Strings must contain at least `min_len` characters.
+    ///
+    /// The section data is scanned as the iterator advances.
+    fn extract_strings(
+        &'file self,
+        min_len: usize,
+        kinds: SectionKindMask,
+    ) -> StringIterator<'data, Self::SectionIterator> {
+        StringIterator::new(self.sections(), min_len, kinds)
+    }
+
+    /// Get an iterator over the regions that are mapped when the file is loaded.
+    ///
+    /// These are derived from the ELF `PT_LOAD` program headers, the Mach-O
+    /// `LC_SEGMENT` commands, and the PE section headers.
+    /// COFF object files have no loadable segments.
+    fn loadable_segments(&'file self) -> LoadSegmentIterator<Self::SegmentIterator> {
+        LoadSegmentIterator::new(self.segments())
+    }
+
+    /// Get the data of a section with its relocations applied.
+    ///
+    /// The data is relocated as if the file was loaded at `load_bias`, which is
+    /// added to the address of each symbol and section.
+    ///
+    /// Returns borrowed data if the section has no relocations.
+    ///
+    /// Only absolute and PC-relative relocations of 32 or 64 bits, and
+    /// ELF `R_*_RELATIVE` relocations, are supported. Other relocations
+    /// return an error.
+    fn relocated_section_data(
+        &'file self,
+        section: &Self::Section,
+        load_bias: u64,
+    ) -> Result<Cow<'data, [u8]>> {
+        relocate::relocated_section_data(self, section, load_bias)
+    }
+
+    /// Get the relocations of a section with their implicit addends included.
+    ///
+    /// For relocations that store the addend in the section data, such as ELF `REL`
+    /// relocations and COFF and Mach-O relocations, the value in the place is read
+    /// and added to [`Relocation::addend`], and [`Relocation::has_implicit_addend`]
+    /// is cleared. This allows the addend to be used in the same way for all formats.
+    /// The addends of PC-relative COFF and Mach-O relocations already include the
+    /// adjustment for the position of the field within the instruction.
+    ///
+    /// The implicit addend is only read for relocations of 8, 16, 32 or 64 bits
+    /// that have a generic kind and an encoding that stores the value unchanged,
+    /// and for ELF `R_*_RELATIVE` relocations. Other relocations are returned
+    /// unchanged.
+    fn relocations_with_addends(
+        &'file self,
+        section: &Self::Section,
+    ) -> Result<Vec<(u64, Relocation)>> {
+        relocate::relocations_with_addends(self, section)
+    }
+
+    /// Resolve the target of the relocation at the given offset within a section.
+    ///
+    /// This combines the relocation with the symbol table to determine the
+    /// symbol or section that is referenced, and the address of the target.
+    /// The address is biased by `load_bias`, which is added to the address of
+    /// each symbol and section.
+    ///
+    /// For PC-relative relocations, the target address is the address of the
+    /// symbol plus the addend, so it may include an adjustment for the position
+    /// of the relocated field within the instruction.
+    ///
+    /// Returns `Ok(None)` if there is no relocation at the offset.
+    fn resolve_reference(
+        &'file self,
+        section: &Self::Section,
+        offset: u64,
+        load_bias: u64,
+    ) -> Result<Option<ResolvedRef>> {
+        relocate::resolve_reference(self, section, offset, load_bias)
+    }
+
+    /// Get a view of the data of a section together with its relocations.
+    ///
+    /// This does not apply the relocations. Instead, the view allows looking up
+    /// the relocation at a given offset, such as when annotating decoded instructions.
+    fn disassembly_view(&'file self, section: &Self::Section) -> Result<DisassemblyView<'data>> {
+        DisassemblyView::new(section)
+    }
+
+    /// Read a value of a `Pod` type at the given virtual address.
+    ///
+    /// The address is located using the segments, or the sections if no
+    /// segment contains it. Segments and sections that have no data in the file
+    /// are ignored.
+    ///
+    /// Returns `Ok(None)` if no section or segment contains all of the bytes of the value.
+    /// Returns an error if the value is not correctly aligned in memory.
+    fn read_pod_at_address<T: Pod>(&'file self, address: u64) -> Result<Option<&'data T>> {
+        let size = core::mem::size_of::<T>() as u64;
+        let data = self
+            .segments()
+            .find_map(|segment| segment.data_range(address, size).ok().flatten())
+            .or_else(|| {
+                self.sections()
+                    .find_map(|section| section.data_range(address, size).ok().flatten())
+            });
+        match data {
+            Some(data) => pod::from_bytes(data)
+                .map(|(value, _)| Some(value))
+                .read_error("Invalid alignment for value at address"),
+            None => Ok(None),
+        }
+    }
+
+    /// Read a null terminated string at the given virtual address.
+    ///
+    /// The address is located in the same way as for [`Self::read_pod_at_address`].
+    /// The string is read up to the first null byte, but no more than `max_len` bytes
+    /// are read, and the string does not extend past the end of the data of the
+    /// segment or section. The returned bytes do not include the null terminator.
+    ///
+    /// Returns `Ok(None)` if no section or segment contains the address.
+    fn read_cstr_at_address(
+        &'file self,
+        address: u64,
+        max_len: usize,
+    ) -> Result<Option<&'data [u8]>> {
+        fn data_from(data: &[u8], base: u64, address: u64) -> Option<&[u8]> {
+            let offset = usize::try_from(address.checked_sub(base)?).ok()?;
+            data.get(offset..).filter(|data| !data.is_empty())
+        }
+        let data = self
+            .segments()
+            .find_map(|segment| data_from(segment.data().ok()?, segment.address(), address))
+            .or_else(|| {
+                self.sections()
+                    .find_map(|section| data_from(section.data().ok()?, section.address(), address))
+            });
+        Ok(data.map(|data| {
+            let data = &data[..data.len().min(max_len)];
+            match memchr::memchr(0, data) {
+                Some(end) => &data[..end],
+                None => data,
+            }
+        }))
+    }
+
     /// Get an iterator over the COMDAT section groups in the file.
     fn comdats(&'file self) -> Self::ComdatIterator;
 
     /// Get the symbol table, if any.
     fn symbol_table(&'file self) -> Option<Self::SymbolTable>;
 
+    /// Return the number of entries in the debugging symbol table.
+    ///
+    /// Indices passed to [`Self::symbol_by_index`] must be less than this value.
+    ///
+    /// For COFF files, this includes auxiliary symbol table entries, so
+    /// some indices below this value do not correspond to a symbol.
+    fn symbol_count(&'file self) -> usize;
+
     /// Get the debugging symbol at the given index.
     ///
     /// The meaning of the index depends on the object file.
@@ -139,6 +332,30 @@ pub trait Object<'data: 'file, 'file>: read::private::Sealed {
     /// Other file formats will return an empty iterator.
     fn dynamic_symbols(&'file self) -> Self::SymbolIterator;
 
+    /// Find the first symbol with the given name.
+    ///
+    /// This searches the debugging symbols followed by the dynamic linking symbols,
+    /// and stops at the first match. For ELF files, the dynamic linking symbols are
+    /// searched using the hash table if there is one.
+    ///
+    /// This is cheaper than constructing a [`SymbolMap`] when only a few lookups are needed.
+    fn symbol_by_name_bytes(&'file self, name: &[u8]) -> Result<Option<Self::Symbol>> {
+        for symbol in self.symbols().chain(self.dynamic_symbols()) {
+            if symbol.name_bytes()? == name {
+                return Ok(Some(symbol));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return true if the file contains a symbol with the given name.
+    ///
+    /// See [`Self::symbol_by_name_bytes`].
+    fn contains_symbol(&'file self, name: &[u8]) -> Result<bool> {
+        self.symbol_by_name_bytes(name)
+            .map(|symbol| symbol.is_some())
+    }
+
     /// Get the dynamic relocations for this file.
     ///
     /// Symbol indices in these relocations refer to the dynamic symbol table.
@@ -181,9 +398,141 @@ pub trait Object<'data: 'file, 'file>: read::private::Sealed {
     /// the lower-level API.
     fn exports(&self) -> Result<Vec<Export<'data>>>;
 
-    /// Return true if the file contains debug information sections, false if not.
+    /// Get the names of the libraries that this file depends on.
+    ///
+    /// The names are found using:
+    /// - ELF: the `DT_NEEDED` dynamic entries.
+    /// - Mach-O: the install names of the dylib load commands, such as
+    ///   `LC_LOAD_DYLIB`, `LC_LOAD_WEAK_DYLIB` and `LC_REEXPORT_DYLIB`.
+    /// - PE: the DLL names in the import table and the delay-load import table.
+    ///
+    /// Duplicate names are removed, keeping the first occurrence.
+    ///
+    /// Returns an empty list for file formats that do not support this.
+    fn needed_libraries(&self) -> Result<Vec<&'data [u8]>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the thread-local storage symbols that are defined in the file.
+    ///
+    /// The TLS offset of each symbol depends on the file format:
+    /// - ELF: the value of the `STT_TLS` symbol. This is the offset within the `PT_TLS`
+    ///   segment for executables and shared objects, and the offset within the
+    ///   section for relocatable objects.
+    /// - Mach-O: the offset field of the thread-local variable descriptor for symbols
+    ///   in `__thread_vars`. This is usually zero in relocatable objects, where it is
+    ///   set by a relocation.
+    /// - COFF and PE: the offset of the symbol within its `.tls` section.
+    ///
+    /// Returns an empty list for file formats that do not support this.
+    fn tls_symbols(&'file self) -> Result<Vec<TlsSymbol<'data>>> {
+        Ok(Vec::new())
+    }
+
+    /// Get the locations of the absolute pointers that must be adjusted when the
+    /// file is loaded at an address other than its preferred address.
+    ///
+    /// The locations are found using:
+    /// - ELF: the `R_*_RELATIVE` and absolute word dynamic relocations, and the
+    ///   `SHT_RELR` sections.
+    /// - Mach-O: the rebase opcodes of `LC_DYLD_INFO`, or the rebases in the
+    ///   `LC_DYLD_CHAINED_FIXUPS` chains. Only 64-bit chained pointer formats
+    ///   are supported.
+    /// - PE: the `IMAGE_REL_BASED_HIGHLOW` and `IMAGE_REL_BASED_DIR64` base relocations.
+    ///
+    /// Symbol binds are not included.
+    ///
+    /// Returns an empty list for file formats that do not support this.
+    fn pointer_fixups(&'file self) -> Result<PointerFixupIterator> {
+        Ok(PointerFixupIterator::new(Vec::new()))
+    }
+
+    /// Get all of the relocations in the file.
+    ///
+    /// Each relocation is returned with the index of the section that it applies to,
+    /// and its offset. The relocations are found using:
+    /// - the relocations of each section, as returned by [`ObjectSection::relocations`]
+    /// - ELF: the dynamic relocations, and the `SHT_RELR` sections
+    /// - PE: the `IMAGE_REL_BASED_HIGHLOW` and `IMAGE_REL_BASED_DIR64` base relocations
+    ///
+    /// The offsets of the relocations of each section are the same as returned by
+    /// [`ObjectSection::relocations`]. The other relocations are located by address,
+    /// and are omitted if the address is not within a section. `SHT_RELR` entries and
+    /// base relocations are returned as absolute relocations with an implicit addend.
+    fn all_relocations(&'file self) -> Result<Vec<(SectionIndex, u64, Relocation)>> {
+        Ok(relocations::all_relocations(self, &[]))
+    }
+
+    /// Get the data following the end of the last region of the file that is
+    /// described by the headers.
+    ///
+    /// The regions that are considered are:
+    /// - ELF: the file header, the program and section headers, and the file
+    ///   ranges of the sections and segments.
+    /// - Mach-O: the header and load commands, the file ranges of the segments
+    ///   and sections, the section relocations, and the `__LINKEDIT` data referenced by
+    ///   the load commands, such as the symbol table and the code signature.
+    /// - PE: the headers, the raw data of the sections, the COFF symbol table,
+    ///   and the attribute certificate table.
+    ///
+    /// This is sometimes called the overlay. Returns `Ok(None)` if there is no
+    /// data after the last region, or for file formats that do not support this.
+    fn trailing_data(&self) -> Result<Option<&'data [u8]>> {
+        Ok(None)
+    }
+
+    /// Get a best-effort list of the functions in the file, sorted by address.
+    ///
+    /// This combines the function symbols with the function starts from format
+    /// specific sources, when available:
+    /// - the `.eh_frame` FDEs for ELF files,
+    /// - `LC_FUNCTION_STARTS` for Mach-O files,
+    /// - the function table in the exception directory for PE files.
+    ///
+    /// Functions at the same address are merged. If the size of a function is not
+    /// known, then it is inferred from the start of the next function or the end
+    /// of the containing section. Errors in the format specific sources are ignored.
+    ///
+    /// Functions are identified by their address, so this is intended for
+    /// executables and shared libraries.
+    fn functions(&'file self) -> Vec<Function> {
+        functions::functions(self, &[])
+    }
+
+    /// Construct a map from addresses to sections and symbols.
+    ///
+    /// The sections are those whose address range is loaded into memory, ignoring
+    /// debugging, metadata and string sections. The symbols are those of
+    /// [`Self::symbol_map`].
+    fn address_map(&'file self) -> AddressMap<'data> {
+        AddressMap::new(self)
+    }
+
+    /// Describe an address using the section that contains it and the nearest
+    /// symbol at or below it.
+    ///
+    /// This constructs an [`AddressMap`] for each call. Use [`Self::address_map`]
+    /// instead when looking up many addresses.
+    fn describe_address(&'file self, address: u64) -> AddressDescription<'data> {
+        self.address_map().describe(address)
+    }
+
+    /// Return true if the file contains debug information, false if not.
+    ///
+    /// This is true if the file contains DWARF sections, such as `.debug_*` or
+    /// `__debug_*` sections, or COFF CodeView `.debug$*` sections. For PE files,
+    /// this is also true if the debug directory references a PDB.
+    ///
+    /// The symbol table is not considered. Use [`Self::is_stripped`] to check for
+    /// local symbols.
     fn has_debug_symbols(&self) -> bool;
 
+    /// Return true if the symbol table has been stripped.
+    ///
+    /// This is true if the symbol table contains no local or function symbols.
+    /// Symbols that are only used for dynamic linking are not considered.
+    fn is_stripped(&self) -> bool;
+
     /// The UUID from a Mach-O `LC_UUID` load command.
     #[inline]
     fn mach_uuid(&self) -> Result<Option<[u8; 16]>> {
@@ -216,12 +565,38 @@ pub trait Object<'data: 'file, 'file>: read::private::Sealed {
 
     /// Get the base address used for relative virtual addresses.
     ///
-    /// Currently this is only non-zero for PE.
+    /// Relative addresses are offsets from this base. The base depends on the
+    /// file format:
+    /// - PE: the image base.
+    /// - ELF: the lowest `PT_LOAD` virtual address for `ET_DYN` files, which are
+    ///   shared objects and position independent executables. 0 for other types.
+    /// - Mach-O: the virtual address of the `__TEXT` segment, or 0 if there is none.
+    /// - Other formats: 0.
     fn relative_address_base(&'file self) -> u64;
 
-    /// Get the virtual address of the entry point of the binary
+    /// Get the address that the file expects to be loaded at.
+    ///
+    /// Unlike [`Self::relative_address_base`], this is always the preferred
+    /// load address:
+    /// - PE: the image base.
+    /// - ELF: the lowest `PT_LOAD` virtual address.
+    /// - Mach-O: the virtual address of the `__TEXT` segment.
+    /// - Other formats: 0.
+    fn preferred_address(&'file self) -> u64;
+
+    /// Get the virtual address of the entry point of the binary.
+    ///
+    /// Returns 0 if the file does not have an entry point.
     fn entry(&'file self) -> u64;
 
+    /// Return true if the file has an entry point.
+    ///
+    /// Relocatable object files do not have an entry point.
+    #[inline]
+    fn has_entry(&'file self) -> bool {
+        self.entry() != 0
+    }
+
     /// File flags that are specific to each file format.
     fn flags(&self) -> FileFlags;
 }
@@ -297,6 +672,24 @@ pub trait ObjectSection<'data>: read::private::Sealed {
     /// This does not do any decompression.
     fn data(&self) -> Result<&'data [u8]>;
 
+    /// Return true if the section has no data in the file.
+    ///
+    /// This is true for ELF `SHT_NOBITS` sections, Mach-O zerofill sections,
+    /// and COFF uninitialized data sections that have no raw data. The section
+    /// is filled with zeros when loaded.
+    fn is_bss(&self) -> bool;
+
+    /// Return the size of the section in memory that has no data in the file.
+    ///
+    /// Returns 0 if [`Self::is_bss`] is false.
+    fn uninitialized_size(&self) -> u64 {
+        if self.is_bss() {
+            self.size()
+        } else {
+            0
+        }
+    }
+
     /// Return the raw contents of the section data in the given range.
     ///
     /// This does not do any decompression.
@@ -347,6 +740,18 @@ pub trait ObjectSection<'data>: read::private::Sealed {
 
     /// Section flags that are specific to each file format.
     fn flags(&self) -> SectionFlags;
+
+    /// Compute the hash of the section data.
+    ///
+    /// Sections that occupy no space in the file, such as `.bss`, are hashed
+    /// as empty data.
+    #[cfg(feature = "hash")]
+    fn hash(&self, algo: HashAlgo) -> Result<Vec<u8>> {
+        match self.kind() {
+            SectionKind::UninitializedData | SectionKind::UninitializedTls => Ok(algo.digest(&[])),
+            _ => Ok(algo.digest(self.data()?)),
+        }
+    }
 }
 
 /// A COMDAT section group defined in an object file.
@@ -452,7 +857,7 @@ pub trait ObjectSymbol<'data>: read::private::Sealed {
     fn is_local(&self) -> bool;
 
     /// Symbol flags that are specific to each file format.
-    fn flags(&self) -> SymbolFlags<SectionIndex>;
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex>;
 }
 
 /// An iterator for files that don't have dynamic relocations.
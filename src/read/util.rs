@@ -4,6 +4,7 @@ use core::fmt;
 use core::marker::PhantomData;
 
 use crate::pod::{from_bytes, slice_from_bytes, Pod};
+use crate::read::ReadError;
 use crate::ReadRef;
 
 /// A newtype for byte slices.
@@ -165,6 +166,48 @@ impl<'data> Bytes<'data> {
         self.skip(offset)?;
         self.read_string()
     }
+
+    /// Read an unsigned LEB128 number.
+    pub fn read_uleb128(&mut self) -> Result<u64, ()> {
+        let mut result = 0;
+        let mut shift = 0;
+
+        loop {
+            let byte = *self.read::<u8>()?;
+            if shift == 63 && byte != 0x00 && byte != 0x01 {
+                return Err(());
+            }
+            result |= u64::from(byte & 0x7f) << shift;
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+        }
+    }
+
+    /// Read a signed LEB128 number.
+    pub fn read_sleb128(&mut self) -> Result<i64, ()> {
+        let mut result = 0;
+        let mut shift = 0;
+
+        loop {
+            let byte = *self.read::<u8>()?;
+            if shift == 63 && byte != 0x00 && byte != 0x7f {
+                return Err(());
+            }
+            result |= i64::from(byte & 0x7f) << shift;
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                if shift < 64 && (byte & 0x40) != 0 {
+                    // Sign extend the result.
+                    result |= !0 << shift;
+                }
+                return Ok(result);
+            }
+        }
+    }
 }
 
 // Only for Debug impl of `Bytes`.
@@ -225,6 +268,22 @@ pub(crate) fn data_range(
         .get(..size.try_into().ok()?)
 }
 
+/// Return the data from `end` to the end of `data`.
+///
+/// Returns `Ok(None)` if `end` is at or beyond the end of `data`.
+pub(crate) fn trailing_data<'data, R: ReadRef<'data>>(
+    data: R,
+    end: u64,
+) -> crate::read::Result<Option<&'data [u8]>> {
+    let len = data.len().read_error("Unknown file length")?;
+    if end >= len {
+        return Ok(None);
+    }
+    data.read_bytes_at(end, len - end)
+        .read_error("Invalid trailing data offset")
+        .map(Some)
+}
+
 /// A table of zero-terminated strings.
 ///
 /// This is used for most file formats.
@@ -260,6 +319,13 @@ impl<'data, R: ReadRef<'data>> StringTable<'data, R> {
             None => Err(()),
         }
     }
+
+    /// Return the offset of the end of the string table within the data.
+    ///
+    /// Returns `None` if the string table is empty.
+    pub(crate) fn end_offset(&self) -> Option<u64> {
+        self.data.map(|_| self.end)
+    }
 }
 
 impl<'data, R: ReadRef<'data>> Default for StringTable<'data, R> {
@@ -344,6 +410,24 @@ mod tests {
         assert_eq!(data.read_slice_at::<u8>(0, 4), Ok(data.0));
         assert_eq!(data.read_slice_at::<u8>(1, 4), Err(()));
 
+        let mut bytes = data;
+        assert_eq!(
+            bytes.read_slice::<u16>(2),
+            Ok(&[u16::to_be(0x0123), u16::to_be(0x4567)][..])
+        );
+        assert_eq!(bytes, Bytes(&[]));
+
+        let mut bytes = data;
+        assert_eq!(bytes.read_slice::<u16>(usize::MAX), Err(()));
+        assert_eq!(bytes, Bytes(&[]));
+
+        assert_eq!(
+            data.read_slice_at::<u16>(2, 1),
+            Ok(&[u16::to_be(0x4567)][..])
+        );
+        assert_eq!(data.read_slice_at::<u16>(1, 1), Err(()));
+        assert_eq!(data.read_slice_at::<u16>(2, 2), Err(()));
+
         let data = Bytes(&[0x01, 0x02, 0x00, 0x04]);
 
         let mut bytes = data;
@@ -361,6 +445,28 @@ mod tests {
         assert_eq!(data.read_string_at(3), Err(()));
     }
 
+    #[test]
+    fn leb128() {
+        let mut bytes = Bytes(&[0xe5, 0x8e, 0x26, 0x7f]);
+        assert_eq!(bytes.read_uleb128(), Ok(624_485));
+        assert_eq!(bytes.read_sleb128(), Ok(-1));
+        assert_eq!(bytes, Bytes(&[]));
+
+        let mut bytes = Bytes(&[0xc0, 0xbb, 0x78]);
+        assert_eq!(bytes.read_sleb128(), Ok(-123_456));
+
+        let mut max = [0xff; 10];
+        max[9] = 0x01;
+        assert_eq!(Bytes(&max).read_uleb128(), Ok(u64::max_value()));
+        // Overflow.
+        max[9] = 0x02;
+        assert_eq!(Bytes(&max).read_uleb128(), Err(()));
+
+        // Missing the end of the value.
+        let mut bytes = Bytes(&[0x80]);
+        assert_eq!(bytes.read_uleb128(), Err(()));
+    }
+
     #[test]
     fn bytes_debug() {
         assert_eq!(format!("{:?}", Bytes(&[])), "[]");
@@ -6,15 +6,17 @@
 use alloc::boxed::Box;
 use alloc::vec::Vec;
 use core::marker::PhantomData;
-use core::{slice, str};
+use core::{fmt, slice, str};
 use wasmparser as wp;
 
+use crate::wasm;
+
 use crate::read::{
-    self, Architecture, ComdatKind, CompressedData, CompressedFileRange, Error, Export, FileFlags,
-    Import, NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind, ObjectSection,
-    ObjectSegment, ObjectSymbol, ObjectSymbolTable, ReadError, ReadRef, Relocation, Result,
-    SectionFlags, SectionIndex, SectionKind, SegmentFlags, SymbolFlags, SymbolIndex, SymbolKind,
-    SymbolScope, SymbolSection,
+    self, Architecture, BinaryFormat, ComdatKind, CompressedData, CompressedFileRange, Error,
+    Export, FileFlags, Import, NoDynamicRelocationIterator, Object, ObjectComdat, ObjectKind,
+    ObjectSection, ObjectSegment, ObjectSymbol, ObjectSymbolTable, ReadError, ReadRef, Relocation,
+    Result, SectionFlags, SectionIndex, SectionKind, SegmentFlags, SymbolFlags, SymbolIndex,
+    SymbolKind, SymbolScope, SymbolSection,
 };
 
 const SECTION_CUSTOM: usize = 0;
@@ -46,6 +48,8 @@ pub struct WasmFile<'data, R = &'data [u8]> {
     symbols: Vec<WasmSymbolInternal<'data>>,
     // Address of the function body for the entry point.
     entry: u64,
+    // The id and payload of each top-level section, if this is a component.
+    component_sections: Option<Vec<(u8, &'data [u8])>>,
     marker: PhantomData<R>,
 }
 
@@ -67,7 +71,6 @@ impl<'data, R: ReadRef<'data>> WasmFile<'data, R> {
     pub fn parse(data: R) -> Result<Self> {
         let len = data.len().read_error("Unknown Wasm file size")?;
         let data = data.read_bytes_at(0, len).read_error("Wasm read failed")?;
-        let module = wp::ModuleReader::new(data).read_error("Invalid Wasm header")?;
 
         let mut file = WasmFile {
             sections: Vec::new(),
@@ -75,9 +78,19 @@ impl<'data, R: ReadRef<'data>> WasmFile<'data, R> {
             has_debug_symbols: false,
             symbols: Vec::new(),
             entry: 0,
+            component_sections: None,
             marker: PhantomData,
         };
 
+        // Components use a different section layout, which is not supported
+        // by the module reader.
+        if let Some(sections) = parse_component_sections(data)? {
+            file.component_sections = Some(sections);
+            return Ok(file);
+        }
+
+        let module = wp::ModuleReader::new(data).read_error("Invalid Wasm header")?;
+
         let mut main_file_symbol = Some(WasmSymbolInternal {
             name: "",
             address: 0,
@@ -217,7 +230,8 @@ impl<'data, R: ReadRef<'data>> WasmFile<'data, R> {
                         let address = range.start as u64 - section.range().start as u64;
                         let size = (range.end - range.start) as u64;
 
-                        if entry_func_id == Some(i as u32) {
+                        // Function indices include the imported functions.
+                        if entry_func_id == Some(imported_funcs_count + i as u32) {
                             file.entry = address;
                         }
 
@@ -297,6 +311,539 @@ impl<'data, R: ReadRef<'data>> WasmFile<'data, R> {
 
         Ok(file)
     }
+
+    /// Return true if this file is a component instead of a core module.
+    ///
+    /// Components are only partially supported. They have no sections or
+    /// symbols, but their top-level sections can be obtained using
+    /// [`Self::component_sections`].
+    pub fn is_component(&self) -> bool {
+        self.component_sections.is_some()
+    }
+
+    /// Return the top-level sections of a component.
+    ///
+    /// Each item is the section id and the raw section payload. The section id
+    /// is one of the `WASM_COMPONENT_SECTION_*` constants.
+    ///
+    /// Returns an empty iterator if this file is not a component.
+    pub fn component_sections(&self) -> impl Iterator<Item = (u8, &'data [u8])> + '_ {
+        self.component_sections.iter().flatten().copied()
+    }
+
+    /// Return the section with the given id, if present.
+    fn id_section(&self, id: usize) -> Option<&wp::Section<'data>> {
+        self.id_sections[id].map(|index| &self.sections[index])
+    }
+
+    /// Return an iterator over the memories defined in the memory section.
+    ///
+    /// Imported memories are not included.
+    pub fn memories(&self) -> Result<WasmMemoryIterator<'data>> {
+        let reader = match self.id_section(SECTION_MEMORY) {
+            Some(section) => Some(
+                section
+                    .get_memory_section_reader()
+                    .read_error("Couldn't read header of the memory section")?,
+            ),
+            None => None,
+        };
+        let remaining = reader.as_ref().map_or(0, |reader| reader.get_count());
+        Ok(WasmMemoryIterator { reader, remaining })
+    }
+
+    /// Return an iterator over the tables defined in the table section.
+    ///
+    /// Imported tables are not included.
+    pub fn tables(&self) -> Result<WasmTableIterator<'data>> {
+        let reader = match self.id_section(SECTION_TABLE) {
+            Some(section) => Some(
+                section
+                    .get_table_section_reader()
+                    .read_error("Couldn't read header of the table section")?,
+            ),
+            None => None,
+        };
+        let remaining = reader.as_ref().map_or(0, |reader| reader.get_count());
+        Ok(WasmTableIterator { reader, remaining })
+    }
+
+    /// Return an iterator over the globals defined in the global section.
+    ///
+    /// Imported globals are not included.
+    pub fn globals(&self) -> Result<WasmGlobalIterator<'data>> {
+        let reader = match self.id_section(SECTION_GLOBAL) {
+            Some(section) => Some(
+                section
+                    .get_global_section_reader()
+                    .read_error("Couldn't read header of the global section")?,
+            ),
+            None => None,
+        };
+        let remaining = reader.as_ref().map_or(0, |reader| reader.get_count());
+        Ok(WasmGlobalIterator { reader, remaining })
+    }
+
+    /// Return the relocations from the `reloc.*` custom sections.
+    ///
+    /// These are only present in object files that have not been linked.
+    pub fn relocations(&self) -> Result<Vec<WasmRelocation>> {
+        let mut relocations = Vec::new();
+        for section in &self.sections {
+            if let wp::SectionCode::Custom {
+                kind: wp::CustomSectionKind::Reloc,
+                ..
+            } = section.code
+            {
+                let mut reader = section.get_binary_reader();
+                let target = reader
+                    .read_var_u32()
+                    .read_error("Couldn't read Wasm relocation section index")?;
+                if target as usize >= self.sections.len() {
+                    return Err(Error("Invalid Wasm relocation section index"));
+                }
+                let section_index = SectionIndex(target as usize);
+                let count = reader
+                    .read_var_u32()
+                    .read_error("Couldn't read Wasm relocation count")?;
+                for _ in 0..count {
+                    let r_type = reader
+                        .read_u8()
+                        .read_error("Couldn't read Wasm relocation type")?
+                        as u8;
+                    let offset = reader
+                        .read_var_u32()
+                        .read_error("Couldn't read Wasm relocation offset")?;
+                    let index = reader
+                        .read_var_u32()
+                        .read_error("Couldn't read Wasm relocation index")?;
+                    let addend = if wasm::r_wasm_has_addend(r_type) {
+                        reader
+                            .read_var_i64()
+                            .read_error("Couldn't read Wasm relocation addend")?
+                    } else {
+                        0
+                    };
+                    relocations.push(WasmRelocation {
+                        section: section_index,
+                        offset,
+                        r_type,
+                        index,
+                        addend,
+                    });
+                }
+            }
+        }
+        Ok(relocations)
+    }
+
+    /// Return the symbols from the symbol table in the `linking` custom section.
+    ///
+    /// These are only present in object files that have not been linked.
+    /// Returns an empty list if there is no `linking` section.
+    pub fn linking_symbols(&self) -> Result<Vec<WasmLinkingSymbol<'data>>> {
+        let mut symbols = Vec::new();
+        let section = match self.sections.iter().find(|section| match section.code {
+            wp::SectionCode::Custom {
+                kind: wp::CustomSectionKind::Linking,
+                ..
+            } => true,
+            _ => false,
+        }) {
+            Some(section) => section,
+            None => return Ok(symbols),
+        };
+        let mut reader = section.get_binary_reader();
+        let version = reader
+            .read_var_u32()
+            .read_error("Couldn't read Wasm linking section version")?;
+        if version != wasm::WASM_LINKING_VERSION {
+            return Err(Error("Unsupported Wasm linking section version"));
+        }
+        while !reader.eof() {
+            let kind = reader
+                .read_u8()
+                .read_error("Couldn't read Wasm linking subsection type")?;
+            let len = reader
+                .read_var_u32()
+                .read_error("Couldn't read Wasm linking subsection size")?;
+            let data = reader
+                .read_bytes(len as usize)
+                .read_error("Invalid Wasm linking subsection size")?;
+            if kind != u32::from(wasm::WASM_SYMBOL_TABLE) {
+                continue;
+            }
+            let mut reader = wp::BinaryReader::new(data);
+            let count = reader
+                .read_var_u32()
+                .read_error("Couldn't read Wasm symbol count")?;
+            for _ in 0..count {
+                symbols.push(WasmLinkingSymbol::parse(&mut reader)?);
+            }
+        }
+        Ok(symbols)
+    }
+}
+
+/// A relocation in a Wasm object file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmRelocation {
+    /// The index of the section that the relocation applies to.
+    ///
+    /// This is the position of the section in the file, as used by the `reloc.*`
+    /// custom section. It is not the same as [`ObjectSection::index`], which is
+    /// based on the section ID.
+    pub section: SectionIndex,
+    /// The offset of the relocated value within the section data.
+    pub offset: u32,
+    /// The relocation type. One of the `R_WASM_*` constants.
+    pub r_type: u8,
+    /// The index of the target.
+    ///
+    /// For most relocation types, this is an index in the linking symbol table.
+    /// For `R_WASM_TYPE_INDEX_LEB`, this is an index in the type section.
+    pub index: u32,
+    /// The addend, for relocation types that have one.
+    pub addend: i64,
+}
+
+/// A symbol in the symbol table of the `linking` section of a Wasm object file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLinkingSymbol<'data> {
+    /// The symbol kind. One of the `WASM_SYMBOL_TYPE_*` constants.
+    pub kind: u8,
+    /// The symbol flags. A combination of the `WASM_SYM_*` constants.
+    pub flags: u32,
+    /// The symbol name.
+    ///
+    /// This is `None` for section symbols, and for undefined symbols that use
+    /// the name of their import.
+    pub name: Option<&'data str>,
+    /// The index of the function, global, tag, table, data segment or section.
+    ///
+    /// This is zero for undefined data symbols.
+    pub index: u32,
+    /// The offset within the data segment, for defined data symbols.
+    pub offset: u32,
+    /// The size of the data, for defined data symbols.
+    pub size: u32,
+}
+
+impl<'data> WasmLinkingSymbol<'data> {
+    fn parse(reader: &mut wp::BinaryReader<'data>) -> Result<Self> {
+        let kind = reader
+            .read_u8()
+            .read_error("Couldn't read Wasm symbol kind")? as u8;
+        let flags = reader
+            .read_var_u32()
+            .read_error("Couldn't read Wasm symbol flags")?;
+        let mut symbol = WasmLinkingSymbol {
+            kind,
+            flags,
+            name: None,
+            index: 0,
+            offset: 0,
+            size: 0,
+        };
+        let undefined = flags & wasm::WASM_SYM_UNDEFINED != 0;
+        match kind {
+            wasm::WASM_SYMBOL_TYPE_FUNCTION
+            | wasm::WASM_SYMBOL_TYPE_GLOBAL
+            | wasm::WASM_SYMBOL_TYPE_TAG
+            | wasm::WASM_SYMBOL_TYPE_TABLE => {
+                symbol.index = reader
+                    .read_var_u32()
+                    .read_error("Couldn't read Wasm symbol index")?;
+                if !undefined || flags & wasm::WASM_SYM_EXPLICIT_NAME != 0 {
+                    symbol.name = Some(
+                        reader
+                            .read_string()
+                            .read_error("Couldn't read Wasm symbol name")?,
+                    );
+                }
+            }
+            wasm::WASM_SYMBOL_TYPE_DATA => {
+                symbol.name = Some(
+                    reader
+                        .read_string()
+                        .read_error("Couldn't read Wasm symbol name")?,
+                );
+                if !undefined {
+                    symbol.index = reader
+                        .read_var_u32()
+                        .read_error("Couldn't read Wasm symbol segment index")?;
+                    symbol.offset = reader
+                        .read_var_u32()
+                        .read_error("Couldn't read Wasm symbol offset")?;
+                    symbol.size = reader
+                        .read_var_u32()
+                        .read_error("Couldn't read Wasm symbol size")?;
+                }
+            }
+            wasm::WASM_SYMBOL_TYPE_SECTION => {
+                symbol.index = reader
+                    .read_var_u32()
+                    .read_error("Couldn't read Wasm symbol section index")?;
+            }
+            _ => return Err(Error("Unsupported Wasm symbol kind")),
+        }
+        Ok(symbol)
+    }
+}
+
+/// The limits of a Wasm memory or table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// The initial size.
+    ///
+    /// This is in units of pages for memories, and elements for tables.
+    pub initial: u32,
+    /// The maximum size, if any.
+    pub maximum: Option<u32>,
+}
+
+impl From<wp::ResizableLimits> for WasmLimits {
+    fn from(limits: wp::ResizableLimits) -> Self {
+        WasmLimits {
+            initial: limits.initial,
+            maximum: limits.maximum,
+        }
+    }
+}
+
+/// A memory defined in a Wasm file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmMemory {
+    /// The size limits of the memory, in units of 64 KiB pages.
+    pub limits: WasmLimits,
+    /// Whether the memory is shared between threads.
+    pub shared: bool,
+}
+
+/// A table defined in a Wasm file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmTable {
+    /// The type of the table elements.
+    pub element_type: WasmValueType,
+    /// The size limits of the table, in elements.
+    pub limits: WasmLimits,
+}
+
+/// A global defined in a Wasm file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmGlobal {
+    /// The type of the global value.
+    pub value_type: WasmValueType,
+    /// Whether the global is mutable.
+    pub mutable: bool,
+    /// The expression for the initial value of the global.
+    pub init: ConstExpr,
+}
+
+/// An iterator over the memories in a Wasm file.
+///
+/// Returned by [`WasmFile::memories`].
+pub struct WasmMemoryIterator<'data> {
+    reader: Option<wp::MemorySectionReader<'data>>,
+    remaining: u32,
+}
+
+impl<'data> WasmMemoryIterator<'data> {
+    /// Return the next memory.
+    pub fn next(&mut self) -> Result<Option<WasmMemory>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) if self.remaining != 0 => reader,
+            _ => return Ok(None),
+        };
+        self.remaining -= 1;
+        let memory = reader
+            .read()
+            .read_error("Couldn't read a memory item")
+            .map(|memory| WasmMemory {
+                limits: memory.limits.into(),
+                shared: memory.shared,
+            });
+        if memory.is_err() {
+            self.remaining = 0;
+        }
+        memory.map(Some)
+    }
+}
+
+impl<'data> fmt::Debug for WasmMemoryIterator<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmMemoryIterator")
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// An iterator over the tables in a Wasm file.
+///
+/// Returned by [`WasmFile::tables`].
+pub struct WasmTableIterator<'data> {
+    reader: Option<wp::TableSectionReader<'data>>,
+    remaining: u32,
+}
+
+impl<'data> WasmTableIterator<'data> {
+    /// Return the next table.
+    pub fn next(&mut self) -> Result<Option<WasmTable>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) if self.remaining != 0 => reader,
+            _ => return Ok(None),
+        };
+        self.remaining -= 1;
+        let table = reader
+            .read()
+            .read_error("Couldn't read a table item")
+            .and_then(|table| {
+                Ok(WasmTable {
+                    element_type: WasmValueType::parse(table.element_type)?,
+                    limits: table.limits.into(),
+                })
+            });
+        if table.is_err() {
+            self.remaining = 0;
+        }
+        table.map(Some)
+    }
+}
+
+impl<'data> fmt::Debug for WasmTableIterator<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmTableIterator")
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// An iterator over the globals in a Wasm file.
+///
+/// Returned by [`WasmFile::globals`].
+pub struct WasmGlobalIterator<'data> {
+    reader: Option<wp::GlobalSectionReader<'data>>,
+    remaining: u32,
+}
+
+impl<'data> WasmGlobalIterator<'data> {
+    /// Return the next global.
+    pub fn next(&mut self) -> Result<Option<WasmGlobal>> {
+        let reader = match self.reader.as_mut() {
+            Some(reader) if self.remaining != 0 => reader,
+            _ => return Ok(None),
+        };
+        self.remaining -= 1;
+        let global = reader
+            .read()
+            .read_error("Couldn't read a global item")
+            .and_then(|global| {
+                Ok(WasmGlobal {
+                    value_type: WasmValueType::parse(global.ty.content_type)?,
+                    mutable: global.ty.mutable,
+                    init: ConstExpr::parse(&global.init_expr)?,
+                })
+            });
+        if global.is_err() {
+            self.remaining = 0;
+        }
+        global.map(Some)
+    }
+}
+
+impl<'data> fmt::Debug for WasmGlobalIterator<'data> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmGlobalIterator")
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
+
+/// A Wasm value type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WasmValueType {
+    /// A 32-bit integer.
+    I32,
+    /// A 64-bit integer.
+    I64,
+    /// A 32-bit float.
+    F32,
+    /// A 64-bit float.
+    F64,
+    /// A 128-bit vector.
+    V128,
+    /// A function reference.
+    FuncRef,
+    /// An external reference.
+    ExternRef,
+}
+
+impl WasmValueType {
+    fn parse(ty: wp::Type) -> Result<Self> {
+        Ok(match ty {
+            wp::Type::I32 => WasmValueType::I32,
+            wp::Type::I64 => WasmValueType::I64,
+            wp::Type::F32 => WasmValueType::F32,
+            wp::Type::F64 => WasmValueType::F64,
+            wp::Type::V128 => WasmValueType::V128,
+            wp::Type::FuncRef => WasmValueType::FuncRef,
+            wp::Type::ExternRef => WasmValueType::ExternRef,
+            _ => return Err(Error("Invalid Wasm value type")),
+        })
+    }
+}
+
+/// A decoded Wasm constant expression.
+///
+/// These are used for the initial values of globals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConstExpr {
+    /// `i32.const`
+    I32Const(i32),
+    /// `i64.const`
+    I64Const(i64),
+    /// `f32.const`, with the bits of the value.
+    F32Const(u32),
+    /// `f64.const`, with the bits of the value.
+    F64Const(u64),
+    /// `v128.const`
+    V128Const([u8; 16]),
+    /// `global.get` of the global with the given index.
+    GlobalGet(u32),
+    /// `ref.null` of the given reference type.
+    RefNull(WasmValueType),
+    /// `ref.func` of the function with the given index.
+    RefFunc(u32),
+}
+
+impl ConstExpr {
+    /// Decode a constant expression consisting of a single instruction and `end`.
+    fn parse(init_expr: &wp::InitExpr) -> Result<Self> {
+        let mut reader = init_expr.get_operators_reader();
+        let expr = match reader
+            .read()
+            .read_error("Couldn't read a Wasm constant expression")?
+        {
+            wp::Operator::I32Const { value } => ConstExpr::I32Const(value),
+            wp::Operator::I64Const { value } => ConstExpr::I64Const(value),
+            wp::Operator::F32Const { value } => ConstExpr::F32Const(value.bits()),
+            wp::Operator::F64Const { value } => ConstExpr::F64Const(value.bits()),
+            wp::Operator::V128Const { value } => ConstExpr::V128Const(*value.bytes()),
+            wp::Operator::GlobalGet { global_index } => ConstExpr::GlobalGet(global_index),
+            wp::Operator::RefNull { ty } => ConstExpr::RefNull(WasmValueType::parse(ty)?),
+            wp::Operator::RefFunc { function_index } => ConstExpr::RefFunc(function_index),
+            _ => return Err(Error("Unsupported Wasm constant expression")),
+        };
+        match reader
+            .read()
+            .read_error("Couldn't read a Wasm constant expression")?
+        {
+            wp::Operator::End => Ok(expr),
+            _ => Err(Error("Unsupported Wasm constant expression")),
+        }
+    }
 }
 
 impl<'data, R> read::private::Sealed for WasmFile<'data, R> {}
@@ -317,6 +864,11 @@ where
     type SymbolTable = WasmSymbolTable<'data, 'file>;
     type DynamicRelocationIterator = NoDynamicRelocationIterator;
 
+    #[inline]
+    fn format(&self) -> BinaryFormat {
+        BinaryFormat::Wasm
+    }
+
     #[inline]
     fn architecture(&self) -> Architecture {
         Architecture::Wasm32
@@ -374,6 +926,11 @@ where
         WasmComdatIterator { file: self }
     }
 
+    #[inline]
+    fn symbol_count(&'file self) -> usize {
+        self.symbols.len()
+    }
+
     #[inline]
     fn symbol_by_index(&'file self, index: SymbolIndex) -> Result<WasmSymbol<'data, 'file>> {
         let symbol = self
@@ -425,10 +982,24 @@ where
         self.has_debug_symbols
     }
 
+    fn is_stripped(&self) -> bool {
+        !self.sections.iter().any(|section| match section.code {
+            wp::SectionCode::Custom {
+                kind: wp::CustomSectionKind::Name,
+                ..
+            } => true,
+            _ => false,
+        })
+    }
+
     fn relative_address_base(&self) -> u64 {
         0
     }
 
+    fn preferred_address(&self) -> u64 {
+        0
+    }
+
     #[inline]
     fn entry(&'file self) -> u64 {
         self.entry
@@ -491,7 +1062,7 @@ impl<'data, 'file, R> ObjectSegment<'data> for WasmSegment<'data, 'file, R> {
     }
 
     fn data_range(&self, _address: u64, _size: u64) -> Result<Option<&'data [u8]>> {
-        unreachable!()
+        Ok(None)
     }
 
     #[inline]
@@ -578,8 +1149,14 @@ impl<'data, 'file, R> ObjectSection<'data> for WasmSection<'data, 'file, R> {
         Ok(reader.read_bytes(reader.bytes_remaining()).unwrap())
     }
 
+    #[inline]
+    fn is_bss(&self) -> bool {
+        false
+    }
+
     fn data_range(&self, _address: u64, _size: u64) -> Result<Option<&'data [u8]>> {
-        unimplemented!()
+        // Sections are not mapped at an address.
+        Ok(None)
     }
 
     #[inline]
@@ -869,7 +1446,7 @@ impl<'data, 'file> ObjectSymbol<'data> for WasmSymbol<'data, 'file> {
     }
 
     #[inline]
-    fn flags(&self) -> SymbolFlags<SectionIndex> {
+    fn flags(&self) -> SymbolFlags<SectionIndex, SymbolIndex> {
         SymbolFlags::None
     }
 }
@@ -889,6 +1466,38 @@ impl<'data, 'file, R> Iterator for WasmRelocationIterator<'data, 'file, R> {
     }
 }
 
+/// Parse the top-level sections of a component.
+///
+/// Returns `Ok(None)` if the preamble is not for a component.
+fn parse_component_sections(data: &[u8]) -> Result<Option<Vec<(u8, &[u8])>>> {
+    // The preamble is the magic, followed by a 16-bit version and a 16-bit layer.
+    let layer = match data.get(..8) {
+        Some(preamble) if preamble[..4] == *b"\0asm" => {
+            u16::from_le_bytes([preamble[6], preamble[7]])
+        }
+        _ => return Ok(None),
+    };
+    if layer != wasm::WASM_COMPONENT_LAYER {
+        return Ok(None);
+    }
+
+    let mut sections = Vec::new();
+    let mut reader = wp::BinaryReader::new(&data[8..]);
+    while !reader.eof() {
+        let id = reader
+            .read_u8()
+            .read_error("Invalid Wasm component section id")?;
+        let size = reader
+            .read_var_u32()
+            .read_error("Invalid Wasm component section size")?;
+        let payload = reader
+            .read_bytes(size as usize)
+            .read_error("Invalid Wasm component section size")?;
+        sections.push((id as u8, payload));
+    }
+    Ok(Some(sections))
+}
+
 fn section_code_to_id(code: wp::SectionCode) -> usize {
     match code {
         wp::SectionCode::Custom { .. } => SECTION_CUSTOM,
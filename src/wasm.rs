@@ -0,0 +1,168 @@
+//! Wasm definitions.
+//!
+//! These definitions are independent of read/write support.
+//!
+//! This module is based on the WebAssembly tool conventions for object files,
+//! as described in `Linking.md`.
+
+// Relocation types, used in the `reloc.*` custom sections.
+
+/// A function index encoded as a 5-byte varuint32.
+pub const R_WASM_FUNCTION_INDEX_LEB: u8 = 0;
+/// A function table index encoded as a 5-byte varint32.
+pub const R_WASM_TABLE_INDEX_SLEB: u8 = 1;
+/// A function table index encoded as a uint32.
+pub const R_WASM_TABLE_INDEX_I32: u8 = 2;
+/// A linear memory address encoded as a 5-byte varuint32.
+pub const R_WASM_MEMORY_ADDR_LEB: u8 = 3;
+/// A linear memory address encoded as a 5-byte varint32.
+pub const R_WASM_MEMORY_ADDR_SLEB: u8 = 4;
+/// A linear memory address encoded as a uint32.
+pub const R_WASM_MEMORY_ADDR_I32: u8 = 5;
+/// A type index encoded as a 5-byte varuint32.
+pub const R_WASM_TYPE_INDEX_LEB: u8 = 6;
+/// A global index encoded as a 5-byte varuint32.
+pub const R_WASM_GLOBAL_INDEX_LEB: u8 = 7;
+/// A byte offset within a code section for a function, encoded as a uint32.
+pub const R_WASM_FUNCTION_OFFSET_I32: u8 = 8;
+/// A byte offset from the start of a section, encoded as a uint32.
+pub const R_WASM_SECTION_OFFSET_I32: u8 = 9;
+/// A tag index encoded as a 5-byte varuint32.
+pub const R_WASM_TAG_INDEX_LEB: u8 = 10;
+/// A linear memory address relative to `__memory_base`, encoded as a 5-byte varint32.
+pub const R_WASM_MEMORY_ADDR_REL_SLEB: u8 = 11;
+/// A function table index relative to `__table_base`, encoded as a 5-byte varint32.
+pub const R_WASM_TABLE_INDEX_REL_SLEB: u8 = 12;
+/// A global index encoded as a uint32.
+pub const R_WASM_GLOBAL_INDEX_I32: u8 = 13;
+/// A 64-bit linear memory address encoded as a 10-byte varuint64.
+pub const R_WASM_MEMORY_ADDR_LEB64: u8 = 14;
+/// A 64-bit linear memory address encoded as a 10-byte varint64.
+pub const R_WASM_MEMORY_ADDR_SLEB64: u8 = 15;
+/// A 64-bit linear memory address encoded as a uint64.
+pub const R_WASM_MEMORY_ADDR_I64: u8 = 16;
+/// A 64-bit linear memory address relative to `__memory_base`, encoded as a 10-byte varint64.
+pub const R_WASM_MEMORY_ADDR_REL_SLEB64: u8 = 17;
+/// A 64-bit function table index encoded as a 10-byte varint64.
+pub const R_WASM_TABLE_INDEX_SLEB64: u8 = 18;
+/// A 64-bit function table index encoded as a uint64.
+pub const R_WASM_TABLE_INDEX_I64: u8 = 19;
+/// A table number encoded as a 5-byte varuint32.
+pub const R_WASM_TABLE_NUMBER_LEB: u8 = 20;
+/// A linear memory address relative to `__tls_base`, encoded as a 5-byte varint32.
+pub const R_WASM_MEMORY_ADDR_TLS_SLEB: u8 = 21;
+/// A byte offset within a code section for a function, encoded as a uint64.
+pub const R_WASM_FUNCTION_OFFSET_I64: u8 = 22;
+/// A linear memory address relative to the relocation location, encoded as a uint32.
+pub const R_WASM_MEMORY_ADDR_LOCREL_I32: u8 = 23;
+/// A 64-bit function table index relative to `__table_base`, encoded as a 10-byte varint64.
+pub const R_WASM_TABLE_INDEX_REL_SLEB64: u8 = 24;
+/// A 64-bit linear memory address relative to `__tls_base`, encoded as a 10-byte varint64.
+pub const R_WASM_MEMORY_ADDR_TLS_SLEB64: u8 = 25;
+/// A function index encoded as a uint32.
+pub const R_WASM_FUNCTION_INDEX_I32: u8 = 26;
+
+/// Return true if relocations of the given type have an addend.
+pub fn r_wasm_has_addend(r_type: u8) -> bool {
+    match r_type {
+        R_WASM_MEMORY_ADDR_LEB
+        | R_WASM_MEMORY_ADDR_SLEB
+        | R_WASM_MEMORY_ADDR_I32
+        | R_WASM_FUNCTION_OFFSET_I32
+        | R_WASM_SECTION_OFFSET_I32
+        | R_WASM_MEMORY_ADDR_REL_SLEB
+        | R_WASM_MEMORY_ADDR_LEB64
+        | R_WASM_MEMORY_ADDR_SLEB64
+        | R_WASM_MEMORY_ADDR_I64
+        | R_WASM_MEMORY_ADDR_REL_SLEB64
+        | R_WASM_MEMORY_ADDR_TLS_SLEB
+        | R_WASM_FUNCTION_OFFSET_I64
+        | R_WASM_MEMORY_ADDR_LOCREL_I32
+        | R_WASM_MEMORY_ADDR_TLS_SLEB64 => true,
+        _ => false,
+    }
+}
+
+// Subsection types, used in the `linking` custom section.
+
+/// Extra metadata about the data segments.
+pub const WASM_SEGMENT_INFO: u8 = 5;
+/// A list of constructor functions.
+pub const WASM_INIT_FUNCS: u8 = 6;
+/// The COMDAT groups of segments, functions and sections.
+pub const WASM_COMDAT_INFO: u8 = 7;
+/// The symbol table.
+pub const WASM_SYMBOL_TABLE: u8 = 8;
+
+/// The version of the `linking` custom section.
+pub const WASM_LINKING_VERSION: u32 = 2;
+
+// Symbol kinds, used in the `WASM_SYMBOL_TABLE` subsection.
+
+/// A function symbol.
+pub const WASM_SYMBOL_TYPE_FUNCTION: u8 = 0;
+/// A data symbol.
+pub const WASM_SYMBOL_TYPE_DATA: u8 = 1;
+/// A global symbol.
+pub const WASM_SYMBOL_TYPE_GLOBAL: u8 = 2;
+/// A section symbol.
+pub const WASM_SYMBOL_TYPE_SECTION: u8 = 3;
+/// A tag symbol.
+pub const WASM_SYMBOL_TYPE_TAG: u8 = 4;
+/// A table symbol.
+pub const WASM_SYMBOL_TYPE_TABLE: u8 = 5;
+
+// Symbol flags, used in the `WASM_SYMBOL_TABLE` subsection.
+
+/// The symbol has weak binding.
+pub const WASM_SYM_BINDING_WEAK: u32 = 0x1;
+/// The symbol has local binding.
+pub const WASM_SYM_BINDING_LOCAL: u32 = 0x2;
+/// The symbol has hidden visibility.
+pub const WASM_SYM_VISIBILITY_HIDDEN: u32 = 0x4;
+/// The symbol is not defined in this object file.
+pub const WASM_SYM_UNDEFINED: u32 = 0x10;
+/// The symbol is intended to be exported from the linked module.
+pub const WASM_SYM_EXPORTED: u32 = 0x20;
+/// The symbol uses an explicit name instead of the import name.
+pub const WASM_SYM_EXPLICIT_NAME: u32 = 0x40;
+/// The symbol is intended to be included in the linked output.
+pub const WASM_SYM_NO_STRIP: u32 = 0x80;
+/// The symbol resides in thread local storage.
+pub const WASM_SYM_TLS: u32 = 0x100;
+/// The symbol represents an absolute address.
+pub const WASM_SYM_ABSOLUTE: u32 = 0x200;
+
+/// The layer in the preamble of a component.
+///
+/// Core modules have a layer of 0.
+pub const WASM_COMPONENT_LAYER: u16 = 1;
+
+// Section ids of the top-level sections of a component.
+
+/// A custom section.
+pub const WASM_COMPONENT_SECTION_CUSTOM: u8 = 0;
+/// A core module section.
+pub const WASM_COMPONENT_SECTION_CORE_MODULE: u8 = 1;
+/// A core instance section.
+pub const WASM_COMPONENT_SECTION_CORE_INSTANCE: u8 = 2;
+/// A core type section.
+pub const WASM_COMPONENT_SECTION_CORE_TYPE: u8 = 3;
+/// A component section.
+pub const WASM_COMPONENT_SECTION_COMPONENT: u8 = 4;
+/// An instance section.
+pub const WASM_COMPONENT_SECTION_INSTANCE: u8 = 5;
+/// An alias section.
+pub const WASM_COMPONENT_SECTION_ALIAS: u8 = 6;
+/// A type section.
+pub const WASM_COMPONENT_SECTION_TYPE: u8 = 7;
+/// A canonical function section.
+pub const WASM_COMPONENT_SECTION_CANON: u8 = 8;
+/// A start section.
+pub const WASM_COMPONENT_SECTION_START: u8 = 9;
+/// An import section.
+pub const WASM_COMPONENT_SECTION_IMPORT: u8 = 10;
+/// An export section.
+pub const WASM_COMPONENT_SECTION_EXPORT: u8 = 11;
+/// A value section.
+pub const WASM_COMPONENT_SECTION_VALUE: u8 = 12;
@@ -0,0 +1,376 @@
+//! Support for writing archive files.
+//!
+//! Supports the GNU and BSD archive formats, including a symbol table
+//! and extended member names.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::archive;
+use crate::pod::bytes_of;
+use crate::write::{Error, Result, WritableBuffer};
+
+/// The archive format to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ArchiveFormat {
+    /// The GNU (or System V) archive format.
+    ///
+    /// The symbol table is the `/` member, and long names are stored in the `//` member.
+    Gnu,
+    /// The BSD archive format.
+    ///
+    /// The symbol table is the `__.SYMDEF` member, and long names are stored
+    /// at the start of the member data.
+    Bsd,
+}
+
+/// A member to be written to an archive.
+#[derive(Debug)]
+struct Member<'a> {
+    name: Vec<u8>,
+    data: Cow<'a, [u8]>,
+    symbols: Vec<Vec<u8>>,
+}
+
+/// A writable archive file.
+#[derive(Debug)]
+pub struct ArchiveWriter<'a> {
+    format: ArchiveFormat,
+    members: Vec<Member<'a>>,
+}
+
+impl<'a> ArchiveWriter<'a> {
+    /// Create an empty archive.
+    pub fn new(format: ArchiveFormat) -> Self {
+        ArchiveWriter {
+            format,
+            members: Vec::new(),
+        }
+    }
+
+    /// Return the archive format.
+    #[inline]
+    pub fn format(&self) -> ArchiveFormat {
+        self.format
+    }
+
+    /// Add a member to the archive.
+    ///
+    /// If the member data is an object file, then its defined global symbols
+    /// are added to the archive symbol table.
+    #[cfg(all(
+        feature = "read_core",
+        any(
+            feature = "coff",
+            feature = "elf",
+            feature = "macho",
+            feature = "pe",
+            feature = "wasm"
+        )
+    ))]
+    pub fn add_member(&mut self, name: Vec<u8>, data: Cow<'a, [u8]>) {
+        let symbols = member_symbols(&data);
+        self.add_member_with_symbols(name, data, symbols);
+    }
+
+    /// Add a member to the archive with the given symbol names.
+    ///
+    /// The symbol names are added to the archive symbol table.
+    pub fn add_member_with_symbols(
+        &mut self,
+        name: Vec<u8>,
+        data: Cow<'a, [u8]>,
+        symbols: Vec<Vec<u8>>,
+    ) {
+        self.members.push(Member {
+            name,
+            data,
+            symbols,
+        });
+    }
+
+    /// Write the archive to a `Vec`.
+    pub fn write(&self) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.emit(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Write the archive to a `WritableBuffer`.
+    pub fn emit(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+        match self.format {
+            ArchiveFormat::Gnu => self.emit_gnu(buffer),
+            ArchiveFormat::Bsd => self.emit_bsd(buffer),
+        }
+    }
+
+    fn symbol_count(&self) -> usize {
+        self.members.iter().map(|m| m.symbols.len()).sum()
+    }
+
+    fn emit_gnu(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+        // Calculate the extended names table and the header names.
+        let mut names = Vec::new();
+        let mut header_names = Vec::with_capacity(self.members.len());
+        for member in &self.members {
+            if member.name.is_empty() || member.name.contains(&b'/') {
+                return Err(Error(format!(
+                    "Invalid GNU archive member name `{}`",
+                    String::from_utf8_lossy(&member.name)
+                )));
+            }
+            if member.name.len() < 16 {
+                let mut header_name = member.name.clone();
+                header_name.push(b'/');
+                header_names.push(header_name);
+            } else {
+                header_names.push(format!("/{}", names.len()).into_bytes());
+                names.extend_from_slice(&member.name);
+                names.extend_from_slice(b"/\n");
+            }
+        }
+
+        // Calculate the symbol table size.
+        let symbol_count = self.symbol_count();
+        let mut symtab_len = 0;
+        if symbol_count != 0 {
+            symtab_len = 4 + 4 * symbol_count;
+            for member in &self.members {
+                for symbol in &member.symbols {
+                    symtab_len += symbol.len() + 1;
+                }
+            }
+        }
+
+        // Calculate the member offsets.
+        let mut offset = archive::MAGIC.len();
+        if symbol_count != 0 {
+            offset += member_len(symtab_len);
+        }
+        if !names.is_empty() {
+            offset += member_len(names.len());
+        }
+        let mut member_offsets = Vec::with_capacity(self.members.len());
+        for member in &self.members {
+            member_offsets.push(offset);
+            offset += member_len(member.data.len());
+        }
+        buffer
+            .reserve(offset)
+            .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
+
+        // Write everything.
+        buffer.write_bytes(&archive::MAGIC);
+        if symbol_count != 0 {
+            let symbol_count = u32_size(symbol_count)?;
+            write_header(buffer, b"/", symtab_len)?;
+            buffer.write_bytes(&symbol_count.to_be_bytes());
+            for (member, member_offset) in self.members.iter().zip(member_offsets.iter()) {
+                let member_offset = u32_size(*member_offset)?;
+                for _ in &member.symbols {
+                    buffer.write_bytes(&member_offset.to_be_bytes());
+                }
+            }
+            for member in &self.members {
+                for symbol in &member.symbols {
+                    buffer.write_bytes(symbol);
+                    buffer.write_bytes(&[0]);
+                }
+            }
+            write_padding(buffer, symtab_len);
+        }
+        if !names.is_empty() {
+            write_header(buffer, b"//", names.len())?;
+            buffer.write_bytes(&names);
+            write_padding(buffer, names.len());
+        }
+        for ((member, header_name), member_offset) in self
+            .members
+            .iter()
+            .zip(header_names.iter())
+            .zip(member_offsets.iter())
+        {
+            debug_assert_eq!(*member_offset, buffer.len());
+            write_header(buffer, header_name, member.data.len())?;
+            buffer.write_bytes(&member.data);
+            write_padding(buffer, member.data.len());
+        }
+        debug_assert_eq!(offset, buffer.len());
+        Ok(())
+    }
+
+    fn emit_bsd(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+        // BSD names that don't fit in the header, or that contain spaces,
+        // are stored at the start of the member data.
+        let extended_names = self
+            .members
+            .iter()
+            .map(|m| m.name.len() > 16 || m.name.contains(&b' '))
+            .collect::<Vec<_>>();
+        for member in &self.members {
+            if member.name.is_empty() {
+                return Err(Error(String::from("Invalid empty BSD archive member name")));
+            }
+        }
+
+        // Calculate the symbol table size.
+        // This is the ranlib array size, the ranlib array, the string table size,
+        // and the string table.
+        let symbol_count = self.symbol_count();
+        let mut strtab_len = 0;
+        for member in &self.members {
+            for symbol in &member.symbols {
+                strtab_len += symbol.len() + 1;
+            }
+        }
+        let symtab_len = 4 + 8 * symbol_count + 4 + strtab_len;
+
+        // Calculate the member offsets.
+        let mut offset = archive::MAGIC.len();
+        if symbol_count != 0 {
+            offset += member_len(symtab_len);
+        }
+        let mut member_offsets = Vec::with_capacity(self.members.len());
+        for (member, extended_name) in self.members.iter().zip(extended_names.iter()) {
+            member_offsets.push(offset);
+            let mut len = member.data.len();
+            if *extended_name {
+                len += member.name.len();
+            }
+            offset += member_len(len);
+        }
+        buffer
+            .reserve(offset)
+            .map_err(|_| Error(String::from("Cannot allocate buffer")))?;
+
+        // Write everything.
+        buffer.write_bytes(&archive::MAGIC);
+        if symbol_count != 0 {
+            write_header(buffer, b"__.SYMDEF", symtab_len)?;
+            buffer.write_bytes(&u32_size(8 * symbol_count)?.to_le_bytes());
+            let mut strx = 0;
+            for (member, member_offset) in self.members.iter().zip(member_offsets.iter()) {
+                let member_offset = u32_size(*member_offset)?;
+                for symbol in &member.symbols {
+                    buffer.write_bytes(&u32_size(strx)?.to_le_bytes());
+                    buffer.write_bytes(&member_offset.to_le_bytes());
+                    strx += symbol.len() + 1;
+                }
+            }
+            buffer.write_bytes(&u32_size(strtab_len)?.to_le_bytes());
+            for member in &self.members {
+                for symbol in &member.symbols {
+                    buffer.write_bytes(symbol);
+                    buffer.write_bytes(&[0]);
+                }
+            }
+            write_padding(buffer, symtab_len);
+        }
+        for (member, extended_name) in self.members.iter().zip(extended_names.iter()) {
+            let len = if *extended_name {
+                let header_name = format!("#1/{}", member.name.len()).into_bytes();
+                let len = member.name.len() + member.data.len();
+                write_header(buffer, &header_name, len)?;
+                buffer.write_bytes(&member.name);
+                len
+            } else {
+                write_header(buffer, &member.name, member.data.len())?;
+                member.data.len()
+            };
+            buffer.write_bytes(&member.data);
+            write_padding(buffer, len);
+        }
+        debug_assert_eq!(offset, buffer.len());
+        Ok(())
+    }
+}
+
+/// Return the defined global symbol names of an object file.
+///
+/// Returns an empty list if the data is not a recognized object file.
+#[cfg(all(
+    feature = "read_core",
+    any(
+        feature = "coff",
+        feature = "elf",
+        feature = "macho",
+        feature = "pe",
+        feature = "wasm"
+    )
+))]
+fn member_symbols(data: &[u8]) -> Vec<Vec<u8>> {
+    use crate::read::{Object, ObjectSymbol};
+    use crate::SymbolKind;
+
+    let mut symbols = Vec::new();
+    let file = match crate::read::File::parse(data) {
+        Ok(file) => file,
+        Err(_) => return symbols,
+    };
+    for symbol in file.symbols() {
+        if !symbol.is_global() || symbol.is_undefined() {
+            continue;
+        }
+        if symbol.kind() == SymbolKind::File || symbol.kind() == SymbolKind::Section {
+            continue;
+        }
+        if let Ok(name) = symbol.name_bytes() {
+            if !name.is_empty() {
+                symbols.push(name.to_vec());
+            }
+        }
+    }
+    symbols
+}
+
+/// The size of a member including its header and padding.
+fn member_len(data_len: usize) -> usize {
+    mem::size_of::<archive::Header>() + data_len + (data_len & 1)
+}
+
+fn u32_size(size: usize) -> Result<u32> {
+    if size > u32::max_value() as usize {
+        return Err(Error(String::from("Archive is too large")));
+    }
+    Ok(size as u32)
+}
+
+fn write_padding(buffer: &mut dyn WritableBuffer, data_len: usize) {
+    if data_len & 1 != 0 {
+        buffer.write_bytes(b"\n");
+    }
+}
+
+fn write_header(buffer: &mut dyn WritableBuffer, name: &[u8], size: usize) -> Result<()> {
+    let mut header = archive::Header {
+        name: [b' '; 16],
+        date: [b' '; 12],
+        uid: [b' '; 6],
+        gid: [b' '; 6],
+        mode: [b' '; 8],
+        size: [b' '; 10],
+        terminator: archive::TERMINATOR,
+    };
+    copy_field(&mut header.name, name)?;
+    copy_field(&mut header.date, b"0")?;
+    copy_field(&mut header.uid, b"0")?;
+    copy_field(&mut header.gid, b"0")?;
+    copy_field(&mut header.mode, b"644")?;
+    copy_field(&mut header.size, format!("{}", size).as_bytes())?;
+    buffer.write_bytes(bytes_of(&header));
+    Ok(())
+}
+
+fn copy_field(field: &mut [u8], value: &[u8]) -> Result<()> {
+    if value.len() > field.len() {
+        return Err(Error(format!(
+            "Archive header field `{}` is too long",
+            String::from_utf8_lossy(value)
+        )));
+    }
+    field[..value.len()].copy_from_slice(value);
+    Ok(())
+}
@@ -176,7 +176,8 @@ impl<'a> Object<'a> {
         self.append_section_data(drectve, &directives, 1);
     }
 
-    pub(crate) fn coff_write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+    /// Returns the file offsets of the section data.
+    pub(crate) fn coff_write(&self, buffer: &mut dyn WritableBuffer) -> Result<Vec<usize>> {
         // Calculate offsets of everything, and build strtab.
         let mut offset = 0;
         let mut strtab = StringTable::default();
@@ -278,6 +279,12 @@ impl<'a> Object<'a> {
                     symbol_offsets[index].aux_count = 1;
                     symtab_count += 1;
                 }
+                SymbolKind::Text | SymbolKind::Data | SymbolKind::Tls => {
+                    if let SymbolFlags::CoffWeakExternal { .. } = symbol.flags {
+                        symbol_offsets[index].aux_count = 1;
+                        symtab_count += 1;
+                    }
+                }
                 _ => {}
             }
             if symbol.name.len() > 8 {
@@ -491,69 +498,13 @@ impl<'a> Object<'a> {
                 }
                 for reloc in &section.relocations {
                     //assert!(reloc.implicit_addend);
-                    let typ = match self.architecture {
-                        Architecture::I386 => match (reloc.kind, reloc.size, reloc.addend) {
-                            (RelocationKind::Absolute, 16, 0) => coff::IMAGE_REL_I386_DIR16,
-                            (RelocationKind::Relative, 16, 0) => coff::IMAGE_REL_I386_REL16,
-                            (RelocationKind::Absolute, 32, 0) => coff::IMAGE_REL_I386_DIR32,
-                            (RelocationKind::ImageOffset, 32, 0) => coff::IMAGE_REL_I386_DIR32NB,
-                            (RelocationKind::SectionIndex, 16, 0) => coff::IMAGE_REL_I386_SECTION,
-                            (RelocationKind::SectionOffset, 32, 0) => coff::IMAGE_REL_I386_SECREL,
-                            (RelocationKind::SectionOffset, 7, 0) => coff::IMAGE_REL_I386_SECREL7,
-                            (RelocationKind::Relative, 32, -4) => coff::IMAGE_REL_I386_REL32,
-                            (RelocationKind::Coff(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::X86_64 => match (reloc.kind, reloc.size, reloc.addend) {
-                            (RelocationKind::Absolute, 64, 0) => coff::IMAGE_REL_AMD64_ADDR64,
-                            (RelocationKind::Absolute, 32, 0) => coff::IMAGE_REL_AMD64_ADDR32,
-                            (RelocationKind::ImageOffset, 32, 0) => coff::IMAGE_REL_AMD64_ADDR32NB,
-                            (RelocationKind::Relative, 32, -4) => coff::IMAGE_REL_AMD64_REL32,
-                            (RelocationKind::Relative, 32, -5) => coff::IMAGE_REL_AMD64_REL32_1,
-                            (RelocationKind::Relative, 32, -6) => coff::IMAGE_REL_AMD64_REL32_2,
-                            (RelocationKind::Relative, 32, -7) => coff::IMAGE_REL_AMD64_REL32_3,
-                            (RelocationKind::Relative, 32, -8) => coff::IMAGE_REL_AMD64_REL32_4,
-                            (RelocationKind::Relative, 32, -9) => coff::IMAGE_REL_AMD64_REL32_5,
-                            (RelocationKind::SectionIndex, 16, 0) => coff::IMAGE_REL_AMD64_SECTION,
-                            (RelocationKind::SectionOffset, 32, 0) => coff::IMAGE_REL_AMD64_SECREL,
-                            (RelocationKind::SectionOffset, 7, 0) => coff::IMAGE_REL_AMD64_SECREL7,
-                            (RelocationKind::Coff(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Arm => match (reloc.kind, reloc.size, reloc.addend) {
-                            (RelocationKind::Absolute, 32, 0) => coff::IMAGE_REL_ARM_ADDR32,
-                            (RelocationKind::ImageOffset, 32, 0) => coff::IMAGE_REL_ARM_ADDR32NB,
-                            (RelocationKind::Relative, 32, -4) => coff::IMAGE_REL_ARM_REL32,
-                            (RelocationKind::SectionIndex, 16, 0) => coff::IMAGE_REL_ARM_SECTION,
-                            (RelocationKind::SectionOffset, 32, 0) => coff::IMAGE_REL_ARM_SECREL,
-                            (RelocationKind::Coff(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Aarch64 => match (reloc.kind, reloc.size, reloc.addend) {
-                            (RelocationKind::Absolute, 32, 0) => coff::IMAGE_REL_ARM64_ADDR32,
-                            (RelocationKind::ImageOffset, 32, 0) => coff::IMAGE_REL_ARM64_ADDR32NB,
-                            (RelocationKind::SectionIndex, 16, 0) => coff::IMAGE_REL_ARM64_SECTION,
-                            (RelocationKind::SectionOffset, 32, 0) => coff::IMAGE_REL_ARM64_SECREL,
-                            (RelocationKind::Absolute, 64, 0) => coff::IMAGE_REL_ARM64_ADDR64,
-                            (RelocationKind::Relative, 32, -4) => coff::IMAGE_REL_ARM64_REL32,
-                            (RelocationKind::Coff(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        _ => {
-                            return Err(Error(format!(
-                                "unimplemented architecture {:?}",
-                                self.architecture
-                            )));
-                        }
-                    };
+                    let typ = coff_relocation_number(
+                        self.architecture,
+                        reloc.kind,
+                        reloc.size,
+                        reloc.addend,
+                    )
+                    .ok_or_else(|| Error(format!("unimplemented relocation {:?}", reloc)))?;
                     let coff_relocation = coff::ImageRelocation {
                         virtual_address: U32Bytes::new(LE, reloc.offset as u32),
                         symbol_table_index: U32Bytes::new(
@@ -594,6 +545,17 @@ impl<'a> Object<'a> {
                 }
                 SymbolKind::Section => coff::IMAGE_SYM_CLASS_STATIC,
                 SymbolKind::Label => coff::IMAGE_SYM_CLASS_LABEL,
+                SymbolKind::Text | SymbolKind::Data | SymbolKind::Tls
+                    if matches!(symbol.flags, SymbolFlags::CoffWeakExternal { .. }) =>
+                {
+                    if symbol.section != SymbolSection::Undefined {
+                        return Err(Error(format!(
+                            "weak external symbol `{}` must be undefined",
+                            symbol.name().unwrap_or("")
+                        )));
+                    }
+                    coff::IMAGE_SYM_CLASS_WEAK_EXTERNAL
+                }
                 SymbolKind::Text | SymbolKind::Data | SymbolKind::Tls => {
                     match symbol.section {
                         SymbolSection::None => {
@@ -687,6 +649,33 @@ impl<'a> Object<'a> {
                     };
                     buffer.write(&aux);
                 }
+                SymbolKind::Text | SymbolKind::Data | SymbolKind::Tls
+                    if number_of_aux_symbols != 0 =>
+                {
+                    debug_assert_eq!(number_of_aux_symbols, 1);
+                    if let SymbolFlags::CoffWeakExternal {
+                        default_symbol,
+                        characteristics,
+                    } = symbol.flags
+                    {
+                        let default_index = match symbol_offsets.get(default_symbol.0) {
+                            Some(offsets) => offsets.index,
+                            None => {
+                                return Err(Error(format!(
+                                    "invalid default symbol for weak external symbol `{}`",
+                                    symbol.name().unwrap_or("")
+                                )));
+                            }
+                        };
+                        let aux = coff::ImageAuxSymbolWeak {
+                            weak_default_sym_index: U32Bytes::new(LE, default_index as u32),
+                            weak_search_type: U32Bytes::new(LE, characteristics),
+                        };
+                        let old_len = buffer.len();
+                        buffer.write(&aux);
+                        buffer.resize(old_len + coff::IMAGE_SIZEOF_SYMBOL);
+                    }
+                }
                 _ => {
                     debug_assert_eq!(number_of_aux_symbols, 0);
                 }
@@ -700,7 +689,10 @@ impl<'a> Object<'a> {
 
         debug_assert_eq!(offset, buffer.len());
 
-        Ok(())
+        Ok(section_offsets
+            .iter()
+            .map(|offsets| offsets.offset)
+            .collect())
     }
 }
 
@@ -710,3 +702,63 @@ fn checksum(data: &[u8]) -> u32 {
     hasher.update(data);
     !hasher.finalize()
 }
+
+/// Return the COFF relocation type for the given relocation kind, size and addend.
+pub(crate) fn coff_relocation_number(
+    architecture: Architecture,
+    kind: RelocationKind,
+    size: u8,
+    addend: i64,
+) -> Option<u16> {
+    let typ = match architecture {
+        Architecture::I386 => match (kind, size, addend) {
+            (RelocationKind::Absolute, 16, 0) => coff::IMAGE_REL_I386_DIR16,
+            (RelocationKind::Relative, 16, 0) => coff::IMAGE_REL_I386_REL16,
+            (RelocationKind::Absolute, 32, 0) => coff::IMAGE_REL_I386_DIR32,
+            (RelocationKind::ImageOffset, 32, 0) => coff::IMAGE_REL_I386_DIR32NB,
+            (RelocationKind::SectionIndex, 16, 0) => coff::IMAGE_REL_I386_SECTION,
+            (RelocationKind::SectionOffset, 32, 0) => coff::IMAGE_REL_I386_SECREL,
+            (RelocationKind::SectionOffset, 7, 0) => coff::IMAGE_REL_I386_SECREL7,
+            (RelocationKind::Relative, 32, -4) => coff::IMAGE_REL_I386_REL32,
+            (RelocationKind::Coff(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::X86_64 => match (kind, size, addend) {
+            (RelocationKind::Absolute, 64, 0) => coff::IMAGE_REL_AMD64_ADDR64,
+            (RelocationKind::Absolute, 32, 0) => coff::IMAGE_REL_AMD64_ADDR32,
+            (RelocationKind::ImageOffset, 32, 0) => coff::IMAGE_REL_AMD64_ADDR32NB,
+            (RelocationKind::Relative, 32, -4) => coff::IMAGE_REL_AMD64_REL32,
+            (RelocationKind::Relative, 32, -5) => coff::IMAGE_REL_AMD64_REL32_1,
+            (RelocationKind::Relative, 32, -6) => coff::IMAGE_REL_AMD64_REL32_2,
+            (RelocationKind::Relative, 32, -7) => coff::IMAGE_REL_AMD64_REL32_3,
+            (RelocationKind::Relative, 32, -8) => coff::IMAGE_REL_AMD64_REL32_4,
+            (RelocationKind::Relative, 32, -9) => coff::IMAGE_REL_AMD64_REL32_5,
+            (RelocationKind::SectionIndex, 16, 0) => coff::IMAGE_REL_AMD64_SECTION,
+            (RelocationKind::SectionOffset, 32, 0) => coff::IMAGE_REL_AMD64_SECREL,
+            (RelocationKind::SectionOffset, 7, 0) => coff::IMAGE_REL_AMD64_SECREL7,
+            (RelocationKind::Coff(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Arm => match (kind, size, addend) {
+            (RelocationKind::Absolute, 32, 0) => coff::IMAGE_REL_ARM_ADDR32,
+            (RelocationKind::ImageOffset, 32, 0) => coff::IMAGE_REL_ARM_ADDR32NB,
+            (RelocationKind::Relative, 32, -4) => coff::IMAGE_REL_ARM_REL32,
+            (RelocationKind::SectionIndex, 16, 0) => coff::IMAGE_REL_ARM_SECTION,
+            (RelocationKind::SectionOffset, 32, 0) => coff::IMAGE_REL_ARM_SECREL,
+            (RelocationKind::Coff(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Aarch64 => match (kind, size, addend) {
+            (RelocationKind::Absolute, 32, 0) => coff::IMAGE_REL_ARM64_ADDR32,
+            (RelocationKind::ImageOffset, 32, 0) => coff::IMAGE_REL_ARM64_ADDR32NB,
+            (RelocationKind::SectionIndex, 16, 0) => coff::IMAGE_REL_ARM64_SECTION,
+            (RelocationKind::SectionOffset, 32, 0) => coff::IMAGE_REL_ARM64_SECREL,
+            (RelocationKind::Absolute, 64, 0) => coff::IMAGE_REL_ARM64_ADDR64,
+            (RelocationKind::Relative, 32, -4) => coff::IMAGE_REL_ARM64_REL32,
+            (RelocationKind::Coff(x), _, _) => x,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(typ)
+}
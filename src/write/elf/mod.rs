@@ -4,6 +4,7 @@
 //! This is also used to provide ELF support for [`write::Object`](crate::write::Object).
 
 mod object;
+pub(crate) use object::elf_relocation_number;
 
 mod writer;
 pub use writer::*;
@@ -148,7 +148,8 @@ impl<'a> Object<'a> {
         }
     }
 
-    pub(crate) fn elf_write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+    /// Returns the file offsets of the section data.
+    pub(crate) fn elf_write(&self, buffer: &mut dyn WritableBuffer) -> Result<Vec<usize>> {
         // Create reloc section header names so we can reference them.
         let is_rela = self.elf_has_relocation_addend()?;
         let reloc_names: Vec<_> = self
@@ -421,251 +422,13 @@ impl<'a> Object<'a> {
                 writer.write_align_relocation();
                 debug_assert_eq!(section_offsets[index].reloc_offset, writer.len());
                 for reloc in &section.relocations {
-                    let r_type = match self.architecture {
-                        Architecture::Aarch64 => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 64) => {
-                                elf::R_AARCH64_ABS64
-                            }
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 32) => {
-                                elf::R_AARCH64_ABS32
-                            }
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 16) => {
-                                elf::R_AARCH64_ABS16
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::Generic, 64) => {
-                                elf::R_AARCH64_PREL64
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::Generic, 32) => {
-                                elf::R_AARCH64_PREL32
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::Generic, 16) => {
-                                elf::R_AARCH64_PREL16
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::AArch64Call, 26)
-                            | (RelocationKind::PltRelative, RelocationEncoding::AArch64Call, 26) => {
-                                elf::R_AARCH64_CALL26
-                            }
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Arm => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, _, 32) => elf::R_ARM_ABS32,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Avr => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, _, 32) => elf::R_AVR_32,
-                            (RelocationKind::Absolute, _, 16) => elf::R_AVR_16,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Bpf => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, _, 64) => elf::R_BPF_64_64,
-                            (RelocationKind::Absolute, _, 32) => elf::R_BPF_64_32,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::I386 => match (reloc.kind, reloc.size) {
-                            (RelocationKind::Absolute, 32) => elf::R_386_32,
-                            (RelocationKind::Relative, 32) => elf::R_386_PC32,
-                            (RelocationKind::Got, 32) => elf::R_386_GOT32,
-                            (RelocationKind::PltRelative, 32) => elf::R_386_PLT32,
-                            (RelocationKind::GotBaseOffset, 32) => elf::R_386_GOTOFF,
-                            (RelocationKind::GotBaseRelative, 32) => elf::R_386_GOTPC,
-                            (RelocationKind::Absolute, 16) => elf::R_386_16,
-                            (RelocationKind::Relative, 16) => elf::R_386_PC16,
-                            (RelocationKind::Absolute, 8) => elf::R_386_8,
-                            (RelocationKind::Relative, 8) => elf::R_386_PC8,
-                            (RelocationKind::Elf(x), _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::X86_64 | Architecture::X86_64_X32 => {
-                            match (reloc.kind, reloc.encoding, reloc.size) {
-                                (RelocationKind::Absolute, RelocationEncoding::Generic, 64) => {
-                                    elf::R_X86_64_64
-                                }
-                                (RelocationKind::Relative, _, 32) => elf::R_X86_64_PC32,
-                                (RelocationKind::Got, _, 32) => elf::R_X86_64_GOT32,
-                                (RelocationKind::PltRelative, _, 32) => elf::R_X86_64_PLT32,
-                                (RelocationKind::GotRelative, _, 32) => elf::R_X86_64_GOTPCREL,
-                                (RelocationKind::Absolute, RelocationEncoding::Generic, 32) => {
-                                    elf::R_X86_64_32
-                                }
-                                (RelocationKind::Absolute, RelocationEncoding::X86Signed, 32) => {
-                                    elf::R_X86_64_32S
-                                }
-                                (RelocationKind::Absolute, _, 16) => elf::R_X86_64_16,
-                                (RelocationKind::Relative, _, 16) => elf::R_X86_64_PC16,
-                                (RelocationKind::Absolute, _, 8) => elf::R_X86_64_8,
-                                (RelocationKind::Relative, _, 8) => elf::R_X86_64_PC8,
-                                (RelocationKind::Elf(x), _, _) => x,
-                                _ => {
-                                    return Err(Error(format!(
-                                        "unimplemented relocation {:?}",
-                                        reloc
-                                    )));
-                                }
-                            }
-                        }
-                        Architecture::Hexagon => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, _, 32) => elf::R_HEX_32,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::LoongArch64 => match (reloc.kind, reloc.encoding, reloc.size)
-                        {
-                            (RelocationKind::Absolute, _, 32) => elf::R_LARCH_32,
-                            (RelocationKind::Absolute, _, 64) => elf::R_LARCH_64,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Mips | Architecture::Mips64 => {
-                            match (reloc.kind, reloc.encoding, reloc.size) {
-                                (RelocationKind::Absolute, _, 16) => elf::R_MIPS_16,
-                                (RelocationKind::Absolute, _, 32) => elf::R_MIPS_32,
-                                (RelocationKind::Absolute, _, 64) => elf::R_MIPS_64,
-                                (RelocationKind::Elf(x), _, _) => x,
-                                _ => {
-                                    return Err(Error(format!(
-                                        "unimplemented relocation {:?}",
-                                        reloc
-                                    )));
-                                }
-                            }
-                        }
-                        Architecture::Msp430 => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, _, 32) => elf::R_MSP430_32,
-                            (RelocationKind::Absolute, _, 16) => elf::R_MSP430_16_BYTE,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::PowerPc => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, _, 32) => elf::R_PPC_ADDR32,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::PowerPc64 => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, _, 32) => elf::R_PPC64_ADDR32,
-                            (RelocationKind::Absolute, _, 64) => elf::R_PPC64_ADDR64,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Riscv32 | Architecture::Riscv64 => {
-                            match (reloc.kind, reloc.encoding, reloc.size) {
-                                (RelocationKind::Absolute, _, 32) => elf::R_RISCV_32,
-                                (RelocationKind::Absolute, _, 64) => elf::R_RISCV_64,
-                                (RelocationKind::Elf(x), _, _) => x,
-                                _ => {
-                                    return Err(Error(format!(
-                                        "unimplemented relocation {:?}",
-                                        reloc
-                                    )));
-                                }
-                            }
-                        }
-                        Architecture::S390x => match (reloc.kind, reloc.encoding, reloc.size) {
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 8) => {
-                                elf::R_390_8
-                            }
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 16) => {
-                                elf::R_390_16
-                            }
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 32) => {
-                                elf::R_390_32
-                            }
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 64) => {
-                                elf::R_390_64
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::Generic, 16) => {
-                                elf::R_390_PC16
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::Generic, 32) => {
-                                elf::R_390_PC32
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::Generic, 64) => {
-                                elf::R_390_PC64
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::S390xDbl, 16) => {
-                                elf::R_390_PC16DBL
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::S390xDbl, 32) => {
-                                elf::R_390_PC32DBL
-                            }
-                            (RelocationKind::PltRelative, RelocationEncoding::S390xDbl, 16) => {
-                                elf::R_390_PLT16DBL
-                            }
-                            (RelocationKind::PltRelative, RelocationEncoding::S390xDbl, 32) => {
-                                elf::R_390_PLT32DBL
-                            }
-                            (RelocationKind::Got, RelocationEncoding::Generic, 16) => {
-                                elf::R_390_GOT16
-                            }
-                            (RelocationKind::Got, RelocationEncoding::Generic, 32) => {
-                                elf::R_390_GOT32
-                            }
-                            (RelocationKind::Got, RelocationEncoding::Generic, 64) => {
-                                elf::R_390_GOT64
-                            }
-                            (RelocationKind::GotRelative, RelocationEncoding::S390xDbl, 32) => {
-                                elf::R_390_GOTENT
-                            }
-                            (RelocationKind::GotBaseOffset, RelocationEncoding::Generic, 16) => {
-                                elf::R_390_GOTOFF16
-                            }
-                            (RelocationKind::GotBaseOffset, RelocationEncoding::Generic, 32) => {
-                                elf::R_390_GOTOFF32
-                            }
-                            (RelocationKind::GotBaseOffset, RelocationEncoding::Generic, 64) => {
-                                elf::R_390_GOTOFF64
-                            }
-                            (RelocationKind::GotBaseRelative, RelocationEncoding::Generic, 64) => {
-                                elf::R_390_GOTPC
-                            }
-                            (RelocationKind::GotBaseRelative, RelocationEncoding::S390xDbl, 32) => {
-                                elf::R_390_GOTPCDBL
-                            }
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Sparc64 => match (reloc.kind, reloc.encoding, reloc.size) {
-                            // TODO: use R_SPARC_32/R_SPARC_64 if aligned.
-                            (RelocationKind::Absolute, _, 32) => elf::R_SPARC_UA32,
-                            (RelocationKind::Absolute, _, 64) => elf::R_SPARC_UA64,
-                            (RelocationKind::Elf(x), _, _) => x,
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        _ => {
-                            if let RelocationKind::Elf(x) = reloc.kind {
-                                x
-                            } else {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        }
-                    };
+                    let r_type = elf_relocation_number(
+                        self.architecture,
+                        reloc.kind,
+                        reloc.encoding,
+                        reloc.size,
+                    )
+                    .ok_or_else(|| Error(format!("unimplemented relocation {:?}", reloc)))?;
                     let r_sym = symbol_offsets[reloc.symbol.0].index.0;
                     writer.write_relocation(
                         is_rela,
@@ -769,6 +532,164 @@ impl<'a> Object<'a> {
 
         debug_assert_eq!(writer.reserved_len(), writer.len());
 
-        Ok(())
+        Ok(section_offsets
+            .iter()
+            .map(|offsets| offsets.offset)
+            .collect())
     }
 }
+
+/// Return the ELF relocation type for the given relocation kind, encoding and size.
+pub(crate) fn elf_relocation_number(
+    architecture: Architecture,
+    kind: RelocationKind,
+    encoding: RelocationEncoding,
+    size: u8,
+) -> Option<u32> {
+    let r_type = match architecture {
+        Architecture::Aarch64 => match (kind, encoding, size) {
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 64) => elf::R_AARCH64_ABS64,
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 32) => elf::R_AARCH64_ABS32,
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 16) => elf::R_AARCH64_ABS16,
+            (RelocationKind::Relative, RelocationEncoding::Generic, 64) => elf::R_AARCH64_PREL64,
+            (RelocationKind::Relative, RelocationEncoding::Generic, 32) => elf::R_AARCH64_PREL32,
+            (RelocationKind::Relative, RelocationEncoding::Generic, 16) => elf::R_AARCH64_PREL16,
+            (RelocationKind::Relative, RelocationEncoding::AArch64Call, 26)
+            | (RelocationKind::PltRelative, RelocationEncoding::AArch64Call, 26) => {
+                elf::R_AARCH64_CALL26
+            }
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Arm => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 32) => elf::R_ARM_ABS32,
+            (RelocationKind::Relative, _, 32) => elf::R_ARM_REL32,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Avr => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 32) => elf::R_AVR_32,
+            (RelocationKind::Absolute, _, 16) => elf::R_AVR_16,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Bpf => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 64) => elf::R_BPF_64_64,
+            (RelocationKind::Absolute, _, 32) => elf::R_BPF_64_32,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::I386 => match (kind, size) {
+            (RelocationKind::Absolute, 32) => elf::R_386_32,
+            (RelocationKind::Relative, 32) => elf::R_386_PC32,
+            (RelocationKind::Got, 32) => elf::R_386_GOT32,
+            (RelocationKind::PltRelative, 32) => elf::R_386_PLT32,
+            (RelocationKind::GotBaseOffset, 32) => elf::R_386_GOTOFF,
+            (RelocationKind::GotBaseRelative, 32) => elf::R_386_GOTPC,
+            (RelocationKind::Absolute, 16) => elf::R_386_16,
+            (RelocationKind::Relative, 16) => elf::R_386_PC16,
+            (RelocationKind::Absolute, 8) => elf::R_386_8,
+            (RelocationKind::Relative, 8) => elf::R_386_PC8,
+            (RelocationKind::Elf(x), _) => x,
+            _ => return None,
+        },
+        Architecture::X86_64 | Architecture::X86_64_X32 => match (kind, encoding, size) {
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 64) => elf::R_X86_64_64,
+            (RelocationKind::Relative, _, 32) => elf::R_X86_64_PC32,
+            (RelocationKind::Got, _, 32) => elf::R_X86_64_GOT32,
+            (RelocationKind::PltRelative, _, 32) => elf::R_X86_64_PLT32,
+            (RelocationKind::GotRelative, _, 32) => elf::R_X86_64_GOTPCREL,
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 32) => elf::R_X86_64_32,
+            (RelocationKind::Absolute, RelocationEncoding::X86Signed, 32) => elf::R_X86_64_32S,
+            (RelocationKind::Absolute, _, 16) => elf::R_X86_64_16,
+            (RelocationKind::Relative, _, 16) => elf::R_X86_64_PC16,
+            (RelocationKind::Absolute, _, 8) => elf::R_X86_64_8,
+            (RelocationKind::Relative, _, 8) => elf::R_X86_64_PC8,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Hexagon => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 32) => elf::R_HEX_32,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::LoongArch64 => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 32) => elf::R_LARCH_32,
+            (RelocationKind::Absolute, _, 64) => elf::R_LARCH_64,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Mips | Architecture::Mips64 => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 16) => elf::R_MIPS_16,
+            (RelocationKind::Absolute, _, 32) => elf::R_MIPS_32,
+            (RelocationKind::Absolute, _, 64) => elf::R_MIPS_64,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Msp430 => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 32) => elf::R_MSP430_32,
+            (RelocationKind::Absolute, _, 16) => elf::R_MSP430_16_BYTE,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::PowerPc => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 32) => elf::R_PPC_ADDR32,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::PowerPc64 => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 32) => elf::R_PPC64_ADDR32,
+            (RelocationKind::Absolute, _, 64) => elf::R_PPC64_ADDR64,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Riscv32 | Architecture::Riscv64 => match (kind, encoding, size) {
+            (RelocationKind::Absolute, _, 32) => elf::R_RISCV_32,
+            (RelocationKind::Absolute, _, 64) => elf::R_RISCV_64,
+            (RelocationKind::Relative, _, 32) => elf::R_RISCV_32_PCREL,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::S390x => match (kind, encoding, size) {
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 8) => elf::R_390_8,
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 16) => elf::R_390_16,
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 32) => elf::R_390_32,
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 64) => elf::R_390_64,
+            (RelocationKind::Relative, RelocationEncoding::Generic, 16) => elf::R_390_PC16,
+            (RelocationKind::Relative, RelocationEncoding::Generic, 32) => elf::R_390_PC32,
+            (RelocationKind::Relative, RelocationEncoding::Generic, 64) => elf::R_390_PC64,
+            (RelocationKind::Relative, RelocationEncoding::S390xDbl, 16) => elf::R_390_PC16DBL,
+            (RelocationKind::Relative, RelocationEncoding::S390xDbl, 32) => elf::R_390_PC32DBL,
+            (RelocationKind::PltRelative, RelocationEncoding::S390xDbl, 16) => elf::R_390_PLT16DBL,
+            (RelocationKind::PltRelative, RelocationEncoding::S390xDbl, 32) => elf::R_390_PLT32DBL,
+            (RelocationKind::Got, RelocationEncoding::Generic, 16) => elf::R_390_GOT16,
+            (RelocationKind::Got, RelocationEncoding::Generic, 32) => elf::R_390_GOT32,
+            (RelocationKind::Got, RelocationEncoding::Generic, 64) => elf::R_390_GOT64,
+            (RelocationKind::GotRelative, RelocationEncoding::S390xDbl, 32) => elf::R_390_GOTENT,
+            (RelocationKind::GotBaseOffset, RelocationEncoding::Generic, 16) => elf::R_390_GOTOFF16,
+            (RelocationKind::GotBaseOffset, RelocationEncoding::Generic, 32) => elf::R_390_GOTOFF32,
+            (RelocationKind::GotBaseOffset, RelocationEncoding::Generic, 64) => elf::R_390_GOTOFF64,
+            (RelocationKind::GotBaseRelative, RelocationEncoding::Generic, 64) => elf::R_390_GOTPC,
+            (RelocationKind::GotBaseRelative, RelocationEncoding::S390xDbl, 32) => {
+                elf::R_390_GOTPCDBL
+            }
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        Architecture::Sparc64 => match (kind, encoding, size) {
+            // TODO: use R_SPARC_32/R_SPARC_64 if aligned.
+            (RelocationKind::Absolute, _, 32) => elf::R_SPARC_UA32,
+            (RelocationKind::Absolute, _, 64) => elf::R_SPARC_UA64,
+            (RelocationKind::Elf(x), _, _) => x,
+            _ => return None,
+        },
+        _ => {
+            if let RelocationKind::Elf(x) = kind {
+                x
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(r_type)
+}
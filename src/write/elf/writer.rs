@@ -5,6 +5,7 @@ use core::mem;
 
 use crate::elf;
 use crate::endian::*;
+use crate::pod::bytes_of;
 use crate::write::string::{StringId, StringTable};
 use crate::write::util;
 use crate::write::{Error, Result, WritableBuffer};
@@ -41,6 +42,9 @@ pub struct Writer<'a> {
     is_mips64el: bool,
     elf_align: usize,
 
+    e_type: Option<ElfType>,
+    e_entry: Option<u64>,
+
     buffer: &'a mut dyn WritableBuffer,
     len: usize,
 
@@ -113,6 +117,10 @@ pub struct Writer<'a> {
     gnu_verneed_count: u16,
     gnu_verneed_remaining: u16,
     gnu_vernaux_remaining: u16,
+
+    note_str_id: Option<StringId>,
+    note_offset: usize,
+    note_data: Vec<u8>,
 }
 
 impl<'a> Writer<'a> {
@@ -126,6 +134,9 @@ impl<'a> Writer<'a> {
             is_mips64el: false,
             elf_align,
 
+            e_type: None,
+            e_entry: None,
+
             buffer,
             len: 0,
 
@@ -198,9 +209,35 @@ impl<'a> Writer<'a> {
             gnu_verneed_count: 0,
             gnu_verneed_remaining: 0,
             gnu_vernaux_remaining: 0,
+
+            note_str_id: None,
+            note_offset: 0,
+            note_data: Vec::new(),
         }
     }
 
+    /// Set the entry point address.
+    ///
+    /// This overrides the `e_entry` field passed to [`Self::write_file_header`].
+    pub fn set_entry(&mut self, address: u64) {
+        self.e_entry = Some(address);
+    }
+
+    /// Set the type of the file.
+    ///
+    /// This overrides the `e_type` field passed to [`Self::write_file_header`].
+    ///
+    /// Relocatable files do not have program headers, so if this is [`ElfType::Rel`]
+    /// then the program header functions do nothing. Shared objects must have a
+    /// `.dynamic` section, so if this is [`ElfType::Dyn`] then
+    /// [`Self::write_file_header`] returns an error if it was not reserved.
+    ///
+    /// This must be called before [`Self::reserve_program_headers`].
+    pub fn set_type(&mut self, e_type: ElfType) {
+        debug_assert_eq!(self.segment_offset, 0);
+        self.e_type = Some(e_type);
+    }
+
     /// Return the current file length that has been reserved.
     pub fn reserved_len(&self) -> usize {
         self.len
@@ -276,6 +313,21 @@ impl<'a> Writer<'a> {
         self.is_mips64el =
             self.is_64 && self.endian.is_little_endian() && header.e_machine == elf::EM_MIPS;
 
+        let e_type = match self.e_type {
+            Some(ElfType::Rel) => elf::ET_REL,
+            Some(ElfType::Exec) => elf::ET_EXEC,
+            Some(ElfType::Dyn) => {
+                if self.dynamic_offset == 0 {
+                    return Err(Error(String::from(
+                        "ELF shared object requires a dynamic section",
+                    )));
+                }
+                elf::ET_DYN
+            }
+            None => header.e_type,
+        };
+        let e_entry = self.e_entry.unwrap_or(header.e_entry);
+
         // Start writing.
         self.buffer
             .reserve(self.len)
@@ -332,10 +384,10 @@ impl<'a> Writer<'a> {
         if self.is_64 {
             let file = elf::FileHeader64 {
                 e_ident,
-                e_type: U16::new(endian, header.e_type),
+                e_type: U16::new(endian, e_type),
                 e_machine: U16::new(endian, header.e_machine),
                 e_version: U32::new(endian, elf::EV_CURRENT.into()),
-                e_entry: U64::new(endian, header.e_entry),
+                e_entry: U64::new(endian, e_entry),
                 e_phoff: U64::new(endian, e_phoff),
                 e_shoff: U64::new(endian, e_shoff),
                 e_flags: U32::new(endian, header.e_flags),
@@ -350,10 +402,10 @@ impl<'a> Writer<'a> {
         } else {
             let file = elf::FileHeader32 {
                 e_ident,
-                e_type: U16::new(endian, header.e_type),
+                e_type: U16::new(endian, e_type),
                 e_machine: U16::new(endian, header.e_machine),
                 e_version: U32::new(endian, elf::EV_CURRENT.into()),
-                e_entry: U32::new(endian, header.e_entry as u32),
+                e_entry: U32::new(endian, e_entry as u32),
                 e_phoff: U32::new(endian, e_phoff as u32),
                 e_shoff: U32::new(endian, e_shoff as u32),
                 e_flags: U32::new(endian, header.e_flags),
@@ -379,9 +431,12 @@ impl<'a> Writer<'a> {
     }
 
     /// Reserve the range for the program headers.
+    ///
+    /// This function does nothing if `num` is zero, or if the file type
+    /// is [`ElfType::Rel`].
     pub fn reserve_program_headers(&mut self, num: u32) {
         debug_assert_eq!(self.segment_offset, 0);
-        if num == 0 {
+        if num == 0 || self.e_type == Some(ElfType::Rel) {
             return;
         }
         self.segment_num = num;
@@ -399,7 +454,12 @@ impl<'a> Writer<'a> {
     }
 
     /// Write a program header.
+    ///
+    /// This function does nothing if the file type is [`ElfType::Rel`].
     pub fn write_program_header(&mut self, header: &ProgramHeader) {
+        if self.e_type == Some(ElfType::Rel) {
+            return;
+        }
         let endian = self.endian;
         if self.is_64 {
             let header = elf::ProgramHeader64 {
@@ -1857,6 +1917,98 @@ impl<'a> Writer<'a> {
             sh_entsize: 4,
         });
     }
+
+    /// Add a note to the note section.
+    ///
+    /// `name` is the owner of the note, excluding the null terminator.
+    /// The name and descriptor are padded to 4 byte alignment.
+    ///
+    /// This must be called before [`Self::reserve_note`].
+    pub fn add_note(&mut self, name: &[u8], n_type: u32, desc: &[u8]) {
+        // The name size includes the null terminator.
+        let namesz = name.len() + 1;
+        let header = elf::NoteHeader32 {
+            n_namesz: U32::new(self.endian, namesz as u32),
+            n_descsz: U32::new(self.endian, desc.len() as u32),
+            n_type: U32::new(self.endian, n_type),
+        };
+        self.note_data.extend_from_slice(bytes_of(&header));
+        self.note_data.extend_from_slice(name);
+        self.note_data.push(0);
+        self.note_data
+            .resize(util::align(self.note_data.len(), 4), 0);
+        self.note_data.extend_from_slice(desc);
+        self.note_data
+            .resize(util::align(self.note_data.len(), 4), 0);
+    }
+
+    /// Add a GNU build ID note to the note section.
+    ///
+    /// This must be called before [`Self::reserve_note`].
+    pub fn add_build_id(&mut self, id: &[u8]) {
+        self.add_note(elf::ELF_NOTE_GNU, elf::NT_GNU_BUILD_ID, id);
+    }
+
+    /// Reserve the section index for the note section.
+    ///
+    /// `name` is usually a `.note.*` name such as `.note.gnu.build-id`.
+    pub fn reserve_note_section_index(&mut self, name: &'a [u8]) -> SectionIndex {
+        debug_assert!(self.note_str_id.is_none());
+        self.note_str_id = Some(self.add_section_name(name));
+        self.reserve_section_index()
+    }
+
+    /// Reserve a file range for the notes that have been added.
+    ///
+    /// Returns the offset of the range.
+    pub fn reserve_note(&mut self) -> usize {
+        self.note_offset = self.reserve(self.note_data.len(), 4);
+        self.note_offset
+    }
+
+    /// Write the notes that have been added.
+    pub fn write_note(&mut self) {
+        if self.note_data.is_empty() {
+            return;
+        }
+        util::write_align(self.buffer, 4);
+        debug_assert_eq!(self.note_offset, self.buffer.len());
+        self.buffer.write_bytes(&self.note_data);
+    }
+
+    /// Write the section header for the note section.
+    ///
+    /// This function does nothing if the section index was not reserved.
+    pub fn write_note_section_header(&mut self, sh_addr: u64) {
+        if self.note_str_id.is_none() {
+            return;
+        }
+        self.write_section_header(&SectionHeader {
+            name: self.note_str_id,
+            sh_type: elf::SHT_NOTE,
+            sh_flags: elf::SHF_ALLOC.into(),
+            sh_addr,
+            sh_offset: self.note_offset as u64,
+            sh_size: self.note_data.len() as u64,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 4,
+            sh_entsize: 0,
+        });
+    }
+}
+
+/// The type of an ELF file.
+///
+/// Used by [`Writer::set_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfType {
+    /// A relocatable file, `ET_REL`.
+    Rel,
+    /// An executable file, `ET_EXEC`.
+    Exec,
+    /// A shared object, `ET_DYN`.
+    Dyn,
 }
 
 /// Native endian version of [`elf::FileHeader64`].
@@ -0,0 +1,345 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::mem;
+
+use crate::endian::{Endian, U32};
+use crate::macho;
+use crate::write::{Error, Result};
+
+/// A pointer that must be adjusted by dyld if the image is not loaded at its
+/// preferred address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rebase {
+    /// The index of the segment containing the pointer.
+    pub segment: u8,
+    /// The offset of the pointer within the segment.
+    pub offset: u64,
+    /// The type of the rebase.
+    ///
+    /// One of the `REBASE_TYPE_*` constants.
+    pub typ: u8,
+}
+
+/// A pointer that must be bound by dyld to a symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bind<'a> {
+    /// The index of the segment containing the pointer.
+    pub segment: u8,
+    /// The offset of the pointer within the segment.
+    pub offset: u64,
+    /// The type of the bind.
+    ///
+    /// One of the `BIND_TYPE_*` constants.
+    pub typ: u8,
+    /// The name of the symbol.
+    pub symbol: &'a [u8],
+    /// The `BIND_SYMBOL_FLAGS_*` flags for the symbol.
+    pub flags: u8,
+    /// The library ordinal of the symbol.
+    ///
+    /// This is a 1-based index into the dylib load commands, or one of the
+    /// `BIND_SPECIAL_DYLIB_*` constants.
+    pub library_ordinal: i32,
+    /// The value that is added to the address of the symbol.
+    pub addend: i64,
+}
+
+/// A builder for the rebase and bind opcode streams of `LC_DYLD_INFO`.
+///
+/// Lazy and weak binds are not supported.
+#[derive(Debug)]
+pub struct DyldInfo<'a> {
+    pointer_size: u64,
+    rebases: Vec<Rebase>,
+    binds: Vec<Bind<'a>>,
+}
+
+impl<'a> DyldInfo<'a> {
+    /// Create a new builder.
+    ///
+    /// `is_64` determines the size of the pointers that are rebased or bound.
+    pub fn new(is_64: bool) -> Self {
+        DyldInfo {
+            pointer_size: if is_64 { 8 } else { 4 },
+            rebases: Vec::new(),
+            binds: Vec::new(),
+        }
+    }
+
+    /// Add a pointer that must be rebased.
+    pub fn add_rebase(&mut self, rebase: Rebase) {
+        self.rebases.push(rebase);
+    }
+
+    /// Add a pointer that must be bound to a symbol.
+    pub fn add_bind(&mut self, bind: Bind<'a>) {
+        self.binds.push(bind);
+    }
+
+    /// Encode the rebase opcodes.
+    ///
+    /// Returns an empty stream if there are no rebases.
+    pub fn rebase_opcodes(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        if self.rebases.is_empty() {
+            return Ok(out);
+        }
+        let mut rebases = self.rebases.clone();
+        rebases.sort_by_key(|rebase| (rebase.typ, rebase.segment, rebase.offset));
+        rebases.dedup();
+
+        let mut typ = None;
+        let mut segment = None;
+        let mut address = 0;
+        let mut i = 0;
+        while i < rebases.len() {
+            let rebase = rebases[i];
+            if typ != Some(rebase.typ) {
+                out.push(macho::REBASE_OPCODE_SET_TYPE_IMM | immediate(rebase.typ, "type")?);
+                typ = Some(rebase.typ);
+            }
+            if segment != Some(rebase.segment) || rebase.offset < address {
+                out.push(
+                    macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB
+                        | immediate(rebase.segment, "segment")?,
+                );
+                write_uleb128(&mut out, rebase.offset);
+                segment = Some(rebase.segment);
+            } else if rebase.offset != address {
+                let delta = rebase.offset - address;
+                if delta % self.pointer_size == 0 && delta / self.pointer_size < 16 {
+                    out.push(
+                        macho::REBASE_OPCODE_ADD_ADDR_IMM_SCALED
+                            | (delta / self.pointer_size) as u8,
+                    );
+                } else {
+                    out.push(macho::REBASE_OPCODE_ADD_ADDR_ULEB);
+                    write_uleb128(&mut out, delta);
+                }
+            }
+
+            // Count the following pointers that are contiguous.
+            let mut count = 1;
+            while let Some(next) = rebases.get(i + count) {
+                if next.typ != rebase.typ
+                    || next.segment != rebase.segment
+                    || next.offset != rebase.offset + count as u64 * self.pointer_size
+                {
+                    break;
+                }
+                count += 1;
+            }
+            if count < 16 {
+                out.push(macho::REBASE_OPCODE_DO_REBASE_IMM_TIMES | count as u8);
+            } else {
+                out.push(macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES);
+                write_uleb128(&mut out, count as u64);
+            }
+            address = rebase.offset + count as u64 * self.pointer_size;
+            i += count;
+        }
+        out.push(macho::REBASE_OPCODE_DONE);
+        Ok(out)
+    }
+
+    /// Encode the bind opcodes.
+    ///
+    /// Returns an empty stream if there are no binds.
+    pub fn bind_opcodes(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        if self.binds.is_empty() {
+            return Ok(out);
+        }
+        let mut binds = self.binds.clone();
+        binds.sort_by(|a, b| {
+            (
+                a.library_ordinal,
+                a.symbol,
+                a.flags,
+                a.typ,
+                a.addend,
+                a.segment,
+                a.offset,
+            )
+                .cmp(&(
+                    b.library_ordinal,
+                    b.symbol,
+                    b.flags,
+                    b.typ,
+                    b.addend,
+                    b.segment,
+                    b.offset,
+                ))
+        });
+        binds.dedup();
+
+        let mut library_ordinal = None;
+        let mut symbol = None;
+        let mut typ = None;
+        let mut addend = 0;
+        let mut segment = None;
+        let mut address = 0;
+        for (i, bind) in binds.iter().enumerate() {
+            if library_ordinal != Some(bind.library_ordinal) {
+                if bind.library_ordinal <= 0 {
+                    if bind.library_ordinal < i32::from(macho::BIND_SPECIAL_DYLIB_WEAK_LOOKUP) {
+                        return Err(Error(format!(
+                            "invalid Mach-O bind library ordinal {}",
+                            bind.library_ordinal
+                        )));
+                    }
+                    out.push(
+                        macho::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM
+                            | (bind.library_ordinal as u8 & macho::BIND_IMMEDIATE_MASK),
+                    );
+                } else if bind.library_ordinal < 16 {
+                    out.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | bind.library_ordinal as u8);
+                } else {
+                    out.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_ULEB);
+                    write_uleb128(&mut out, bind.library_ordinal as u64);
+                }
+                library_ordinal = Some(bind.library_ordinal);
+            }
+            if symbol != Some((bind.symbol, bind.flags)) {
+                out.push(
+                    macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM
+                        | immediate(bind.flags, "symbol flags")?,
+                );
+                out.extend_from_slice(bind.symbol);
+                out.push(0);
+                symbol = Some((bind.symbol, bind.flags));
+            }
+            if typ != Some(bind.typ) {
+                out.push(macho::BIND_OPCODE_SET_TYPE_IMM | immediate(bind.typ, "type")?);
+                typ = Some(bind.typ);
+            }
+            if addend != bind.addend {
+                out.push(macho::BIND_OPCODE_SET_ADDEND_SLEB);
+                write_sleb128(&mut out, bind.addend);
+                addend = bind.addend;
+            }
+            if segment != Some(bind.segment) || bind.offset < address {
+                out.push(
+                    macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB
+                        | immediate(bind.segment, "segment")?,
+                );
+                write_uleb128(&mut out, bind.offset);
+                segment = Some(bind.segment);
+            } else if bind.offset != address {
+                out.push(macho::BIND_OPCODE_ADD_ADDR_ULEB);
+                write_uleb128(&mut out, bind.offset - address);
+            }
+            address = bind.offset + self.pointer_size;
+
+            // If the next bind only changes the address, then combine the address
+            // change with this bind.
+            match binds.get(i + 1) {
+                Some(next)
+                    if next.library_ordinal == bind.library_ordinal
+                        && next.symbol == bind.symbol
+                        && next.flags == bind.flags
+                        && next.typ == bind.typ
+                        && next.addend == bind.addend
+                        && next.segment == bind.segment
+                        && next.offset > address =>
+                {
+                    let delta = next.offset - address;
+                    if delta % self.pointer_size == 0 && delta / self.pointer_size < 16 {
+                        out.push(
+                            macho::BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED
+                                | (delta / self.pointer_size) as u8,
+                        );
+                    } else {
+                        out.push(macho::BIND_OPCODE_DO_BIND_ADD_ADDR_ULEB);
+                        write_uleb128(&mut out, delta);
+                    }
+                    address = next.offset;
+                }
+                _ => out.push(macho::BIND_OPCODE_DO_BIND),
+            }
+        }
+        out.push(macho::BIND_OPCODE_DONE);
+        Ok(out)
+    }
+
+    /// Append the opcode streams to the `__LINKEDIT` data, and return the
+    /// `LC_DYLD_INFO_ONLY` command that describes them.
+    ///
+    /// `linkedit_offset` is the file offset of the start of `linkedit`.
+    /// Each stream is padded to a multiple of the pointer size.
+    pub fn write<E: Endian>(
+        &self,
+        endian: E,
+        linkedit: &mut Vec<u8>,
+        linkedit_offset: u32,
+    ) -> Result<macho::DyldInfoCommand<E>> {
+        let pointer_size = self.pointer_size as usize;
+        let mut append = |data: Vec<u8>| -> Result<(u32, u32)> {
+            if data.is_empty() {
+                return Ok((0, 0));
+            }
+            let offset = linkedit_offset as usize + linkedit.len();
+            linkedit.extend_from_slice(&data);
+            while linkedit.len() % pointer_size != 0 {
+                linkedit.push(macho::REBASE_OPCODE_DONE);
+            }
+            let size = linkedit_offset as usize + linkedit.len() - offset;
+            if u32::try_from(offset + size).is_err() {
+                return Err(Error(String::from("Mach-O dyld info is too large")));
+            }
+            Ok((offset as u32, size as u32))
+        };
+        let (rebase_off, rebase_size) = append(self.rebase_opcodes()?)?;
+        let (bind_off, bind_size) = append(self.bind_opcodes()?)?;
+        Ok(macho::DyldInfoCommand {
+            cmd: U32::new(endian, macho::LC_DYLD_INFO_ONLY),
+            cmdsize: U32::new(endian, mem::size_of::<macho::DyldInfoCommand<E>>() as u32),
+            rebase_off: U32::new(endian, rebase_off),
+            rebase_size: U32::new(endian, rebase_size),
+            bind_off: U32::new(endian, bind_off),
+            bind_size: U32::new(endian, bind_size),
+            weak_bind_off: U32::new(endian, 0),
+            weak_bind_size: U32::new(endian, 0),
+            lazy_bind_off: U32::new(endian, 0),
+            lazy_bind_size: U32::new(endian, 0),
+            export_off: U32::new(endian, 0),
+            export_size: U32::new(endian, 0),
+        })
+    }
+}
+
+/// Check that a value fits in the immediate field of an opcode.
+fn immediate(value: u8, name: &str) -> Result<u8> {
+    if value > macho::REBASE_IMMEDIATE_MASK {
+        return Err(Error(format!(
+            "invalid Mach-O dyld info {} {}",
+            name, value
+        )));
+    }
+    Ok(value)
+}
+
+fn write_uleb128(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = value as u8 & 0x7f;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn write_sleb128(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = value as u8 & 0x7f;
+        value >>= 7;
+        if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
@@ -0,0 +1,10 @@
+//! Support for writing Mach-O files.
+//!
+//! Provides [`DyldInfo`] for encoding the rebase and bind information of Mach-O images.
+//! This is also used to provide Mach-O support for [`write::Object`](crate::write::Object).
+
+mod object;
+pub(crate) use object::macho_relocation_number;
+
+mod dyld_info;
+pub use dyld_info::*;
@@ -184,7 +184,8 @@ impl<'a> Object<'a> {
         constant
     }
 
-    pub(crate) fn macho_write(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+    /// Returns the file offsets of the section data.
+    pub(crate) fn macho_write(&self, buffer: &mut dyn WritableBuffer) -> Result<Vec<usize>> {
         let address_size = self.architecture.address_size().unwrap();
         let endian = self.endian;
         let macho32 = MachO32 { endian };
@@ -548,63 +549,13 @@ impl<'a> Object<'a> {
                         64 => 3,
                         _ => return Err(Error(format!("unimplemented reloc size {:?}", reloc))),
                     };
-                    let (r_pcrel, r_type) = match self.architecture {
-                        Architecture::I386 => match reloc.kind {
-                            RelocationKind::Absolute => (false, macho::GENERIC_RELOC_VANILLA),
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::X86_64 => match (reloc.kind, reloc.encoding, reloc.addend) {
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 0) => {
-                                (false, macho::X86_64_RELOC_UNSIGNED)
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::Generic, -4) => {
-                                (true, macho::X86_64_RELOC_SIGNED)
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::X86RipRelative, -4) => {
-                                (true, macho::X86_64_RELOC_SIGNED)
-                            }
-                            (RelocationKind::Relative, RelocationEncoding::X86Branch, -4) => {
-                                (true, macho::X86_64_RELOC_BRANCH)
-                            }
-                            (RelocationKind::PltRelative, RelocationEncoding::X86Branch, -4) => {
-                                (true, macho::X86_64_RELOC_BRANCH)
-                            }
-                            (RelocationKind::GotRelative, RelocationEncoding::Generic, -4) => {
-                                (true, macho::X86_64_RELOC_GOT)
-                            }
-                            (
-                                RelocationKind::GotRelative,
-                                RelocationEncoding::X86RipRelativeMovq,
-                                -4,
-                            ) => (true, macho::X86_64_RELOC_GOT_LOAD),
-                            (RelocationKind::MachO { value, relative }, _, _) => (relative, value),
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        Architecture::Aarch64 => match (reloc.kind, reloc.encoding, reloc.addend) {
-                            (RelocationKind::Absolute, RelocationEncoding::Generic, 0) => {
-                                (false, macho::ARM64_RELOC_UNSIGNED)
-                            }
-                            (
-                                RelocationKind::MachO { value, relative },
-                                RelocationEncoding::Generic,
-                                0,
-                            ) => (relative, value),
-                            _ => {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        },
-                        _ => {
-                            if let RelocationKind::MachO { value, relative } = reloc.kind {
-                                (relative, value)
-                            } else {
-                                return Err(Error(format!("unimplemented relocation {:?}", reloc)));
-                            }
-                        }
-                    };
+                    let (r_pcrel, r_type) = macho_relocation_number(
+                        self.architecture,
+                        reloc.kind,
+                        reloc.encoding,
+                        reloc.addend,
+                    )
+                    .ok_or_else(|| Error(format!("unimplemented relocation {:?}", reloc)))?;
                     let reloc_info = macho::RelocationInfo {
                         r_address: reloc.offset as u32,
                         r_symbolnum,
@@ -620,7 +571,10 @@ impl<'a> Object<'a> {
 
         debug_assert_eq!(offset, buffer.len());
 
-        Ok(())
+        Ok(section_offsets
+            .iter()
+            .map(|offsets| offsets.offset)
+            .collect())
     }
 }
 
@@ -856,3 +810,61 @@ impl<E: Endian> MachO for MachO64<E> {
         buffer.write(&nlist);
     }
 }
+
+/// Return the Mach-O relocation type and PC-relative flag for the given
+/// relocation kind, encoding and addend.
+pub(crate) fn macho_relocation_number(
+    architecture: Architecture,
+    kind: RelocationKind,
+    encoding: RelocationEncoding,
+    addend: i64,
+) -> Option<(bool, u8)> {
+    let relocation = match architecture {
+        Architecture::I386 => match kind {
+            RelocationKind::Absolute => (false, macho::GENERIC_RELOC_VANILLA),
+            _ => return None,
+        },
+        Architecture::X86_64 => match (kind, encoding, addend) {
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 0) => {
+                (false, macho::X86_64_RELOC_UNSIGNED)
+            }
+            (RelocationKind::Relative, RelocationEncoding::Generic, -4) => {
+                (true, macho::X86_64_RELOC_SIGNED)
+            }
+            (RelocationKind::Relative, RelocationEncoding::X86RipRelative, -4) => {
+                (true, macho::X86_64_RELOC_SIGNED)
+            }
+            (RelocationKind::Relative, RelocationEncoding::X86Branch, -4) => {
+                (true, macho::X86_64_RELOC_BRANCH)
+            }
+            (RelocationKind::PltRelative, RelocationEncoding::X86Branch, -4) => {
+                (true, macho::X86_64_RELOC_BRANCH)
+            }
+            (RelocationKind::GotRelative, RelocationEncoding::Generic, -4) => {
+                (true, macho::X86_64_RELOC_GOT)
+            }
+            (RelocationKind::GotRelative, RelocationEncoding::X86RipRelativeMovq, -4) => {
+                (true, macho::X86_64_RELOC_GOT_LOAD)
+            }
+            (RelocationKind::MachO { value, relative }, _, _) => (relative, value),
+            _ => return None,
+        },
+        Architecture::Aarch64 => match (kind, encoding, addend) {
+            (RelocationKind::Absolute, RelocationEncoding::Generic, 0) => {
+                (false, macho::ARM64_RELOC_UNSIGNED)
+            }
+            (RelocationKind::MachO { value, relative }, RelocationEncoding::Generic, 0) => {
+                (relative, value)
+            }
+            _ => return None,
+        },
+        _ => {
+            if let RelocationKind::MachO { value, relative } = kind {
+                (relative, value)
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(relocation)
+}
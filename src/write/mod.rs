@@ -10,11 +10,15 @@ use hashbrown::HashMap;
 use std::{boxed::Box, collections::HashMap, error, io};
 
 use crate::endian::{Endianness, U32, U64};
+use crate::pod::bytes_of;
 use crate::{
     Architecture, BinaryFormat, ComdatKind, FileFlags, RelocationEncoding, RelocationKind,
     SectionFlags, SectionKind, SymbolFlags, SymbolKind, SymbolScope,
 };
 
+#[cfg(feature = "archive")]
+pub mod archive;
+
 #[cfg(feature = "coff")]
 mod coff;
 #[cfg(feature = "coff")]
@@ -24,7 +28,7 @@ pub use coff::CoffExportStyle;
 pub mod elf;
 
 #[cfg(feature = "macho")]
-mod macho;
+pub mod macho;
 
 #[cfg(feature = "pe")]
 pub mod pe;
@@ -207,6 +211,31 @@ impl<'a> Object<'a> {
         id
     }
 
+    /// Set the producer string that identifies the tool that generated the file.
+    ///
+    /// For ELF and COFF, this appends the null terminated string to the `.comment`
+    /// section, creating the section if needed.
+    /// Mach-O has no equivalent section, so this does nothing for Mach-O.
+    pub fn set_producer(&mut self, producer: &str) {
+        let kind = match self.format {
+            BinaryFormat::Elf => SectionKind::OtherString,
+            // MSVC emits this with `IMAGE_SCN_LNK_INFO | IMAGE_SCN_LNK_REMOVE`.
+            BinaryFormat::Coff => SectionKind::Linker,
+            _ => return,
+        };
+        let section = match self
+            .sections
+            .iter()
+            .position(|section| section.segment.is_empty() && section.name == b".comment")
+        {
+            Some(index) => SectionId(index),
+            None => self.add_section(Vec::new(), b".comment".to_vec(), kind),
+        };
+        let mut data = producer.as_bytes().to_vec();
+        data.push(0);
+        self.append_section_data(section, &data, 1);
+    }
+
     fn section_info(
         &self,
         section: StandardSection,
@@ -567,6 +596,28 @@ impl<'a> Object<'a> {
 
     /// Write the object to a `WritableBuffer`.
     pub fn emit(&self, buffer: &mut dyn WritableBuffer) -> Result<()> {
+        self.emit_sections(buffer).map(|_| ())
+    }
+
+    /// Write the object to a `Vec`, and return a helper for patching the section data.
+    pub fn write_patcher(&self) -> Result<ObjectPatcher> {
+        let mut data = Vec::new();
+        let section_offsets = self.emit_sections(&mut data)?;
+        let sections = self
+            .sections
+            .iter()
+            .zip(section_offsets)
+            .map(|(section, offset)| (offset, section.data.len()))
+            .collect();
+        Ok(ObjectPatcher {
+            data,
+            endian: self.endian,
+            sections,
+        })
+    }
+
+    /// Write the object to a `WritableBuffer`, and return the file offsets of the section data.
+    fn emit_sections(&self, buffer: &mut dyn WritableBuffer) -> Result<Vec<usize>> {
         match self.format {
             #[cfg(feature = "coff")]
             BinaryFormat::Coff => self.coff_write(buffer),
@@ -579,6 +630,69 @@ impl<'a> Object<'a> {
     }
 }
 
+/// The data of a written object file, with support for patching values in its sections.
+///
+/// This can be used to fix up values after the layout of the file is known.
+///
+/// Returned by [`Object::write_patcher`].
+#[derive(Debug)]
+pub struct ObjectPatcher {
+    data: Vec<u8>,
+    endian: Endianness,
+    // The file offset and size of the data of each section.
+    sections: Vec<(usize, usize)>,
+}
+
+impl ObjectPatcher {
+    /// Write a 32-bit value at the given offset within the data of a section.
+    ///
+    /// The value is written using the endianness of the object.
+    /// Returns an error if the value is not within the section data.
+    pub fn patch_u32_at(&mut self, section: SectionId, offset: u64, value: u32) -> Result<()> {
+        let value = U32::new(self.endian, value);
+        self.patch_at(section, offset, bytes_of(&value))
+    }
+
+    /// Write a 64-bit value at the given offset within the data of a section.
+    ///
+    /// The value is written using the endianness of the object.
+    /// Returns an error if the value is not within the section data.
+    pub fn patch_u64_at(&mut self, section: SectionId, offset: u64, value: u64) -> Result<()> {
+        let value = U64::new(self.endian, value);
+        self.patch_at(section, offset, bytes_of(&value))
+    }
+
+    fn patch_at(&mut self, section: SectionId, offset: u64, bytes: &[u8]) -> Result<()> {
+        let (section_offset, section_size) = *self
+            .sections
+            .get(section.0)
+            .ok_or_else(|| Error(format!("invalid section id {}", section.0)))?;
+        if offset > section_size as u64 || section_size - (offset as usize) < bytes.len() {
+            return Err(Error(format!(
+                "invalid patch offset {}+{} (max {})",
+                offset,
+                bytes.len(),
+                section_size
+            )));
+        }
+        let start = section_offset + offset as usize;
+        self.data[start..][..bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Return the data of the object file.
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Return the data of the object file, consuming the patcher.
+    #[inline]
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
 /// A standard segment kind.
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -807,7 +921,7 @@ pub struct Symbol {
     /// The section containing the symbol.
     pub section: SymbolSection,
     /// Symbol flags that are specific to each file format.
-    pub flags: SymbolFlags<SectionId>,
+    pub flags: SymbolFlags<SectionId, SymbolId>,
 }
 
 impl Symbol {
@@ -859,6 +973,58 @@ pub struct Relocation {
     pub addend: i64,
 }
 
+/// Return the format and architecture specific relocation type for a relocation.
+///
+/// This is the type that would be written for a relocation with the given kind,
+/// encoding and size, assuming the usual addend for the relocation kind
+/// (`-4` for PC-relative relocations on formats that require it, otherwise `0`).
+///
+/// Format specific relocation kinds are returned unchanged.
+/// Returns an error if there is no mapping for the relocation.
+pub fn relocation_number(
+    format: BinaryFormat,
+    architecture: Architecture,
+    kind: RelocationKind,
+    encoding: RelocationEncoding,
+    size: u8,
+) -> Result<u32> {
+    let r_type = match format {
+        #[cfg(feature = "coff")]
+        BinaryFormat::Coff => {
+            let kind = match kind {
+                RelocationKind::PltRelative => RelocationKind::Relative,
+                kind => kind,
+            };
+            let addend = if kind == RelocationKind::Relative {
+                -4
+            } else {
+                0
+            };
+            coff::coff_relocation_number(architecture, kind, size, addend).map(u32::from)
+        }
+        #[cfg(feature = "elf")]
+        BinaryFormat::Elf => elf::elf_relocation_number(architecture, kind, encoding, size),
+        #[cfg(feature = "macho")]
+        BinaryFormat::MachO if [8, 16, 32, 64].contains(&size) => {
+            let addend = match kind {
+                RelocationKind::Relative
+                | RelocationKind::GotRelative
+                | RelocationKind::PltRelative => -4,
+                _ => 0,
+            };
+            macho::macho_relocation_number(architecture, kind, encoding, addend)
+                .map(|(_, r_type)| u32::from(r_type))
+        }
+        _ => None,
+    };
+    r_type.ok_or_else(|| {
+        Error(format!(
+            "unimplemented {:?} relocation {:?} {:?} {} for {:?}",
+            format, kind, encoding, size, architecture
+        ))
+    })
+}
+
 /// An identifier used to reference a COMDAT section group.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ComdatId(usize);
@@ -1,10 +1,14 @@
 //! Helper for writing PE files.
 use alloc::string::String;
 use alloc::vec::Vec;
-use core::mem;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::ops::Range;
+use core::{iter, mem};
 
 use crate::endian::{LittleEndian as LE, *};
 use crate::pe;
+use crate::pod::bytes_of;
 use crate::write::util;
 use crate::write::{Error, Result, WritableBuffer};
 
@@ -43,6 +47,15 @@ pub struct Writer<'a> {
     reloc_blocks: Vec<RelocBlock>,
     relocs: Vec<U16<LE>>,
     reloc_offset: u32,
+
+    exports: Vec<Export>,
+    export_dll_name: Vec<u8>,
+    export_address: u32,
+    export_offset: u32,
+
+    resources: Vec<Resource>,
+    resource_data: Vec<u8>,
+    resource_offset: u32,
 }
 
 impl<'a> Writer<'a> {
@@ -80,6 +93,15 @@ impl<'a> Writer<'a> {
             reloc_blocks: Vec::new(),
             relocs: Vec::new(),
             reloc_offset: 0,
+
+            exports: Vec::new(),
+            export_dll_name: Vec::new(),
+            export_address: 0,
+            export_offset: 0,
+
+            resources: Vec::new(),
+            resource_data: Vec::new(),
+            resource_offset: 0,
         }
     }
 
@@ -758,6 +780,316 @@ impl<'a> Writer<'a> {
         self.write_align(self.file_alignment);
     }
 
+    /// Add an export.
+    ///
+    /// `rva` is the address of the exported function or data.
+    ///
+    /// Exports are assigned ordinals in the order that they are added, starting at 1.
+    pub fn add_export(&mut self, name: &str, rva: u32) {
+        self.exports.push(Export {
+            name: name.as_bytes().to_vec(),
+            rva,
+        });
+    }
+
+    /// Return true if an export has been added.
+    pub fn has_exports(&self) -> bool {
+        !self.exports.is_empty()
+    }
+
+    /// Reserve an `.edata` section.
+    ///
+    /// This contains the export directory and tables for the exports that were added
+    /// with `add_export`. `dll_name` is the name of the DLL that is stored in the
+    /// export directory.
+    ///
+    /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_EXPORT` data directory.
+    pub fn reserve_export_section(&mut self, dll_name: &[u8]) -> SectionRange {
+        self.export_dll_name = dll_name.to_vec();
+        let count = self.exports.len() as u32;
+        let mut size = mem::size_of::<pe::ImageExportDirectory>() as u32
+            // Address table, name pointer table, and ordinal table.
+            + count * (4 + 4 + 2)
+            + dll_name.len() as u32
+            + 1;
+        for export in &self.exports {
+            size += export.name.len() as u32 + 1;
+        }
+        let range = self.reserve_edata_section(size);
+        self.export_address = range.virtual_address;
+        self.export_offset = range.file_offset;
+        range
+    }
+
+    /// Write an `.edata` section.
+    ///
+    /// This contains the export directory and tables for the exports that were added
+    /// with `add_export`.
+    ///
+    /// Returns an error if there are too many exports for the ordinal table.
+    pub fn write_export_section(&mut self) -> Result<()> {
+        if self.export_offset == 0 {
+            return Ok(());
+        }
+
+        // The name pointer table must be sorted so that the loader can use a binary search.
+        // The ordinal table is parallel to it, and contains indices into the address table.
+        let mut sorted: Vec<usize> = (0..self.exports.len()).collect();
+        sorted.sort_by(|&a, &b| self.exports[a].name.cmp(&self.exports[b].name));
+        let mut ordinals = Vec::with_capacity(sorted.len());
+        for &index in &sorted {
+            let ordinal = u16::try_from(index)
+                .map_err(|_| Error(format!("Too many PE exports: {}", self.exports.len())))?;
+            ordinals.push(ordinal);
+        }
+
+        self.pad_until(self.export_offset);
+
+        let count = self.exports.len() as u32;
+        let functions_address =
+            self.export_address + mem::size_of::<pe::ImageExportDirectory>() as u32;
+        let names_address = functions_address + count * 4;
+        let ordinals_address = names_address + count * 4;
+        let dll_name_address = ordinals_address + count * 2;
+        self.buffer.write(&pe::ImageExportDirectory {
+            characteristics: U32::new(LE, 0),
+            time_date_stamp: U32::new(LE, 0),
+            major_version: U16::new(LE, 0),
+            minor_version: U16::new(LE, 0),
+            name: U32::new(LE, dll_name_address),
+            base: U32::new(LE, EXPORT_ORDINAL_BASE),
+            number_of_functions: U32::new(LE, count),
+            number_of_names: U32::new(LE, count),
+            address_of_functions: U32::new(LE, functions_address),
+            address_of_names: U32::new(LE, names_address),
+            address_of_name_ordinals: U32::new(LE, ordinals_address),
+        });
+
+        // The address table is in ordinal order.
+        for export in &self.exports {
+            self.buffer.write(&U32::new(LE, export.rva));
+        }
+
+        let mut name_addresses = vec![0; self.exports.len()];
+        let mut name_address = dll_name_address + self.export_dll_name.len() as u32 + 1;
+        for (export, address) in self.exports.iter().zip(name_addresses.iter_mut()) {
+            *address = name_address;
+            name_address += export.name.len() as u32 + 1;
+        }
+        for &index in &sorted {
+            self.buffer.write(&U32::new(LE, name_addresses[index]));
+        }
+        for ordinal in ordinals {
+            self.buffer.write(&U16::new(LE, ordinal));
+        }
+
+        self.buffer.write_bytes(&self.export_dll_name);
+        self.buffer.write_bytes(&[0]);
+        for export in &self.exports {
+            self.buffer.write_bytes(&export.name);
+            self.buffer.write_bytes(&[0]);
+        }
+
+        self.write_align(self.file_alignment);
+        Ok(())
+    }
+
+    /// Add a resource.
+    ///
+    /// `type_id`, `name_id` and `lang_id` are the IDs of the entries in the three levels
+    /// of the resource directory. The type is usually one of the `RT_*` constants,
+    /// and the language is usually a numeric language ID.
+    ///
+    /// Names are compared without regard to case, as they are by Windows. If the
+    /// same combination of IDs is added more than once, then the data of the last
+    /// one is used.
+    pub fn add_resource(
+        &mut self,
+        type_id: ResourceId,
+        name_id: ResourceId,
+        lang_id: ResourceId,
+        data: &[u8],
+    ) {
+        self.resources.push(Resource {
+            type_id: ResourceKey::new(type_id),
+            name_id: ResourceKey::new(name_id),
+            lang_id: ResourceKey::new(lang_id),
+            data: data.to_vec(),
+        });
+    }
+
+    /// Return true if a resource has been added.
+    pub fn has_resources(&self) -> bool {
+        !self.resources.is_empty()
+    }
+
+    /// Reserve a `.rsrc` section.
+    ///
+    /// This contains the resource directory and data for the resources that were added
+    /// with `add_resource`.
+    ///
+    /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_RESOURCE` data directory.
+    pub fn reserve_resource_section(&mut self) -> SectionRange {
+        // Directory entries with names must be before entries with IDs, and each
+        // must be sorted. The sort is stable, so duplicates stay in the order they
+        // were added.
+        self.resources.sort_by(|a, b| {
+            (&a.type_id, &a.name_id, &a.lang_id).cmp(&(&b.type_id, &b.name_id, &b.lang_id))
+        });
+        // Keep the first entry of each duplicate, but with the data of the last.
+        self.resources.dedup_by(|later, earlier| {
+            if (&later.type_id, &later.name_id, &later.lang_id)
+                == (&earlier.type_id, &earlier.name_id, &earlier.lang_id)
+            {
+                mem::swap(&mut later.data, &mut earlier.data);
+                true
+            } else {
+                false
+            }
+        });
+        // The size does not depend on the address of the section.
+        let size = self.resource_section_data(0).len() as u32;
+        let range = self.reserve_rsrc_section(size);
+        self.resource_data = self.resource_section_data(range.virtual_address);
+        self.resource_offset = range.file_offset;
+        range
+    }
+
+    /// Write a `.rsrc` section.
+    ///
+    /// This contains the resource directory and data for the resources that were added
+    /// with `add_resource`.
+    pub fn write_resource_section(&mut self) {
+        if self.resource_offset == 0 {
+            return;
+        }
+        self.pad_until(self.resource_offset);
+        self.buffer.write_bytes(&self.resource_data);
+        self.write_align(self.file_alignment);
+    }
+
+    /// Build the contents of the `.rsrc` section for the sorted resources.
+    ///
+    /// The directory tables are in breadth first order, and are followed by the
+    /// data entries, the names, and the data.
+    fn resource_section_data(&self, virtual_address: u32) -> Vec<u8> {
+        let resources = &self.resources;
+        fn key(level: usize, resource: &Resource) -> &ResourceKey {
+            match level {
+                0 => &resource.type_id,
+                1 => &resource.name_id,
+                _ => &resource.lang_id,
+            }
+        }
+
+        // Find the tables for each level. Each table is a list of entries, and each
+        // entry is the range of resources that it contains. The entries of one level
+        // are the tables of the next level.
+        let mut levels: Vec<Vec<Vec<Range<usize>>>> = Vec::new();
+        // The root table contains all of the resources.
+        let mut ranges: Vec<Range<usize>> = iter::once(0..resources.len()).collect();
+        for level in 0..3 {
+            let tables: Vec<Vec<Range<usize>>> = ranges
+                .iter()
+                .map(|range| {
+                    let mut entries = Vec::new();
+                    let mut start = range.start;
+                    for i in range.clone() {
+                        if key(level, &resources[i]) != key(level, &resources[start]) {
+                            entries.push(start..i);
+                            start = i;
+                        }
+                    }
+                    if start < range.end {
+                        entries.push(start..range.end);
+                    }
+                    entries
+                })
+                .collect();
+            ranges = tables.iter().flatten().cloned().collect();
+            levels.push(tables);
+        }
+
+        let mut offset = 0;
+        let mut table_offsets = Vec::new();
+        for tables in &levels {
+            let mut offsets = Vec::new();
+            for table in tables {
+                offsets.push(offset);
+                offset += (mem::size_of::<pe::ImageResourceDirectory>()
+                    + table.len() * mem::size_of::<pe::ImageResourceDirectoryEntry>())
+                    as u32;
+            }
+            table_offsets.push(offsets);
+        }
+        let data_entry_size = mem::size_of::<pe::ImageResourceDataEntry>() as u32;
+        let data_entries_offset = offset;
+        let strings_offset = data_entries_offset + resources.len() as u32 * data_entry_size;
+
+        let mut section = Vec::new();
+        let mut strings = Vec::new();
+        for (level, tables) in levels.iter().enumerate() {
+            let mut child = 0;
+            for table in tables {
+                let named = table
+                    .iter()
+                    .filter(|range| key(level, &resources[range.start]).is_name())
+                    .count();
+                section.extend_from_slice(bytes_of(&pe::ImageResourceDirectory {
+                    characteristics: U32::new(LE, 0),
+                    time_date_stamp: U32::new(LE, 0),
+                    major_version: U16::new(LE, 0),
+                    minor_version: U16::new(LE, 0),
+                    number_of_named_entries: U16::new(LE, named as u16),
+                    number_of_id_entries: U16::new(LE, (table.len() - named) as u16),
+                }));
+                for range in table {
+                    let name_or_id = match *key(level, &resources[range.start]) {
+                        ResourceKey::Name(ref name) => {
+                            let offset = strings_offset + strings.len() as u32;
+                            strings.extend_from_slice(&(name.len() as u16).to_le_bytes());
+                            for c in name {
+                                strings.extend_from_slice(&c.to_le_bytes());
+                            }
+                            pe::IMAGE_RESOURCE_NAME_IS_STRING | offset
+                        }
+                        ResourceKey::Id(id) => id.into(),
+                    };
+                    // The high bit is set for the offset of a table in the next level.
+                    // The last level contains the offsets of the data entries.
+                    let offset = if level + 1 < levels.len() {
+                        pe::IMAGE_RESOURCE_DATA_IS_DIRECTORY | table_offsets[level + 1][child]
+                    } else {
+                        data_entries_offset + range.start as u32 * data_entry_size
+                    };
+                    child += 1;
+                    section.extend_from_slice(bytes_of(&pe::ImageResourceDirectoryEntry {
+                        name_or_id: U32::new(LE, name_or_id),
+                        offset_to_data_or_directory: U32::new(LE, offset),
+                    }));
+                }
+            }
+        }
+        debug_assert_eq!(section.len() as u32, data_entries_offset);
+
+        let mut data_offset = util::align_u32(strings_offset + strings.len() as u32, 8);
+        for resource in resources {
+            section.extend_from_slice(bytes_of(&pe::ImageResourceDataEntry {
+                offset_to_data: U32::new(LE, virtual_address + data_offset),
+                size: U32::new(LE, resource.data.len() as u32),
+                code_page: U32::new(LE, 0),
+                reserved: U32::new(LE, 0),
+            }));
+            data_offset = util::align_u32(data_offset + resource.data.len() as u32, 8);
+        }
+        section.extend_from_slice(&strings);
+        for resource in resources {
+            section.resize(util::align(section.len(), 8), 0);
+            section.extend_from_slice(&resource.data);
+        }
+        section
+    }
+
     /// Reserve the certificate table.
     ///
     /// This also sets the `pe::IMAGE_DIRECTORY_ENTRY_SECURITY` data directory.
@@ -835,6 +1167,101 @@ pub struct SectionRange {
     pub file_size: u32,
 }
 
+/// The ordinal of the first export written by `Writer::write_export_section`.
+const EXPORT_ORDINAL_BASE: u32 = 1;
+
+struct Export {
+    name: Vec<u8>,
+    rva: u32,
+}
+
+/// A resource name or ID for [`Writer::add_resource`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceId<'a> {
+    /// A resource name.
+    Name(&'a str),
+    /// A resource ID.
+    Id(u16),
+}
+
+/// A resource name or ID, in the order of the entries in a resource directory table.
+///
+/// Names are before IDs, and are compared without regard to case.
+#[derive(Debug, Clone)]
+enum ResourceKey {
+    /// A name as UTF-16 code units.
+    Name(Vec<u16>),
+    Id(u16),
+}
+
+impl ResourceKey {
+    fn new(id: ResourceId<'_>) -> Self {
+        match id {
+            ResourceId::Name(name) => ResourceKey::Name(name.encode_utf16().collect()),
+            ResourceId::Id(id) => ResourceKey::Id(id),
+        }
+    }
+
+    fn is_name(&self) -> bool {
+        match self {
+            ResourceKey::Name(_) => true,
+            ResourceKey::Id(_) => false,
+        }
+    }
+}
+
+impl PartialEq for ResourceKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ResourceKey {}
+
+impl PartialOrd for ResourceKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ResourceKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (ResourceKey::Name(a), ResourceKey::Name(b)) => {
+                let a = a.iter().map(|&unit| upcase(unit));
+                let b = b.iter().map(|&unit| upcase(unit));
+                a.cmp(b)
+            }
+            (ResourceKey::Name(_), ResourceKey::Id(_)) => Ordering::Less,
+            (ResourceKey::Id(_), ResourceKey::Name(_)) => Ordering::Greater,
+            (ResourceKey::Id(a), ResourceKey::Id(b)) => a.cmp(b),
+        }
+    }
+}
+
+/// Convert a UTF-16 code unit to upper case.
+///
+/// Code units are left unchanged if they are surrogates, or if their upper case
+/// is not a single code unit.
+fn upcase(unit: u16) -> u16 {
+    if let Some(c) = core::char::from_u32(u32::from(unit)) {
+        let mut upper = c.to_uppercase();
+        if let (Some(upper), None) = (upper.next(), upper.next()) {
+            if let Ok(upper) = u16::try_from(u32::from(upper)) {
+                return upper;
+            }
+        }
+    }
+    unit
+}
+
+struct Resource {
+    type_id: ResourceKey,
+    name_id: ResourceKey,
+    lang_id: ResourceKey,
+    data: Vec<u8>,
+}
+
 struct RelocBlock {
     virtual_address: u32,
     count: u32,
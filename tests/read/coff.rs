@@ -1,23 +1,237 @@
 use object::{pe, read, Object, ObjectSection};
-use std::fs;
-use std::path::PathBuf;
+
+/// Build a COFF file with a single section that has the given relocation count
+/// field and relocation virtual addresses.
+#[cfg(feature = "coff")]
+fn coff_relocations_file(number: u16, characteristics: u32, addresses: &[u32]) -> Vec<u8> {
+    let mut data = Vec::new();
+    // File header.
+    data.extend_from_slice(&pe::IMAGE_FILE_MACHINE_AMD64.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&[0; 16]);
+    // Section header.
+    data.extend_from_slice(b".text\0\0\0");
+    data.extend_from_slice(&[0; 16]);
+    data.extend_from_slice(&60u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&number.to_le_bytes());
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data.extend_from_slice(&characteristics.to_le_bytes());
+    // Relocations.
+    for &address in addresses {
+        data.extend_from_slice(&address.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&pe::IMAGE_REL_AMD64_ADDR64.to_le_bytes());
+    }
+    data
+}
 
 #[cfg(feature = "coff")]
 #[test]
 fn coff_extended_relocations() {
-    let path_to_obj: PathBuf = ["testfiles", "coff", "relocs_overflow.o"].iter().collect();
-    let contents = fs::read(&path_to_obj).expect("Could not read relocs_overflow.o");
-    let file =
-        read::coff::CoffFile::parse(&contents[..]).expect("Could not parse relocs_overflow.o");
-    let code_section = file
-        .section_by_name(".text")
-        .expect("Could not find .text section in relocs_overflow.o");
-    match code_section.flags() {
+    let characteristics = pe::IMAGE_SCN_CNT_CODE | pe::IMAGE_SCN_LNK_NRELOC_OVFL;
+    // The first relocation contains the count, which includes itself.
+    let mut addresses = vec![0x10001];
+    addresses.extend(0..0x10000);
+    let data = coff_relocations_file(0xffff, characteristics, &addresses);
+
+    let file = read::coff::CoffFile::parse(&*data).unwrap();
+    let section = file.section_by_name(".text").unwrap();
+    match section.flags() {
         object::SectionFlags::Coff { characteristics } => {
             assert!(characteristics & pe::IMAGE_SCN_LNK_NRELOC_OVFL != 0)
         }
         _ => panic!("Invalid section flags flavour."),
     };
-    let relocations = code_section.relocations().collect::<Vec<_>>();
-    assert_eq!(relocations.len(), 65536);
+    let relocations = section.relocations().collect::<Vec<_>>();
+    assert_eq!(relocations.len(), 0x10000);
+    // The count is not included in the relocations.
+    assert_eq!(relocations[0].0, 0);
+    assert_eq!(relocations[0xffff].0, 0xffff);
+
+    // The flag is ignored if the count field is not at its maximum.
+    let data = coff_relocations_file(2, characteristics, &[4, 8]);
+    let file = read::coff::CoffFile::parse(&*data).unwrap();
+    let section = file.section_by_name(".text").unwrap();
+    let offsets = section
+        .relocations()
+        .map(|(offset, _)| offset)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, [4, 8]);
+
+    // The extended count must include the first relocation.
+    let data = coff_relocations_file(0xffff, characteristics, &[0]);
+    let mut offset = 0;
+    let header = pe::ImageFileHeader::parse(&*data, &mut offset).unwrap();
+    let sections = header.sections(&*data, offset).unwrap();
+    let section = sections.section(1).unwrap();
+    assert!(section.coff_relocations(&*data).is_err());
+}
+
+#[cfg(feature = "coff")]
+#[test]
+fn coff_line_numbers() {
+    use object::read::coff::{CoffLineNumber, CoffLineNumberTarget};
+    use object::SymbolIndex;
+
+    let mut data = Vec::new();
+    // File header.
+    data.extend_from_slice(&pe::IMAGE_FILE_MACHINE_I386.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&[0; 16]);
+    // Section header.
+    data.extend_from_slice(b".text\0\0\0");
+    data.extend_from_slice(&[0; 16]);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&60u32.to_le_bytes());
+    data.extend_from_slice(&0u16.to_le_bytes());
+    data.extend_from_slice(&3u16.to_le_bytes());
+    data.extend_from_slice(&pe::IMAGE_SCN_CNT_CODE.to_le_bytes());
+    // Line numbers.
+    for &(value, line) in &[(5u32, 0u16), (0x10, 1), (0x18, 3)] {
+        data.extend_from_slice(&value.to_le_bytes());
+        data.extend_from_slice(&line.to_le_bytes());
+    }
+
+    let file = read::coff::CoffFile::parse(&*data).unwrap();
+    let section = file.section_by_name(".text").unwrap();
+    let line_numbers = section.line_numbers().unwrap().collect::<Vec<_>>();
+    assert_eq!(
+        line_numbers,
+        [
+            CoffLineNumber {
+                symbol_or_address: CoffLineNumberTarget::Symbol(SymbolIndex(5)),
+                line: 0,
+            },
+            CoffLineNumber {
+                symbol_or_address: CoffLineNumberTarget::Address(0x10),
+                line: 1,
+            },
+            CoffLineNumber {
+                symbol_or_address: CoffLineNumberTarget::Address(0x18),
+                line: 3,
+            },
+        ]
+    );
+}
+
+#[cfg(feature = "coff")]
+#[test]
+fn coff_symbol_section() {
+    use object::{ObjectSymbol, SectionIndex, SymbolSection};
+
+    let symbols = [
+        (
+            1,
+            pe::IMAGE_SYM_CLASS_STATIC,
+            0,
+            SymbolSection::Section(SectionIndex(1)),
+        ),
+        (
+            pe::IMAGE_SYM_UNDEFINED,
+            pe::IMAGE_SYM_CLASS_EXTERNAL,
+            0,
+            SymbolSection::Undefined,
+        ),
+        // An undefined external symbol with a value is a common symbol.
+        (
+            pe::IMAGE_SYM_UNDEFINED,
+            pe::IMAGE_SYM_CLASS_EXTERNAL,
+            16,
+            SymbolSection::Common,
+        ),
+        (
+            pe::IMAGE_SYM_UNDEFINED,
+            pe::IMAGE_SYM_CLASS_WEAK_EXTERNAL,
+            0,
+            SymbolSection::Undefined,
+        ),
+        (
+            pe::IMAGE_SYM_UNDEFINED,
+            pe::IMAGE_SYM_CLASS_STATIC,
+            0,
+            SymbolSection::Unknown,
+        ),
+        (
+            pe::IMAGE_SYM_ABSOLUTE,
+            pe::IMAGE_SYM_CLASS_STATIC,
+            5,
+            SymbolSection::Absolute,
+        ),
+        (
+            pe::IMAGE_SYM_DEBUG,
+            pe::IMAGE_SYM_CLASS_FILE,
+            0,
+            SymbolSection::None,
+        ),
+        (
+            pe::IMAGE_SYM_DEBUG,
+            pe::IMAGE_SYM_CLASS_STATIC,
+            0,
+            SymbolSection::Unknown,
+        ),
+    ];
+
+    let mut data = Vec::new();
+    // File header.
+    data.extend_from_slice(&pe::IMAGE_FILE_MACHINE_AMD64.to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&60u32.to_le_bytes());
+    data.extend_from_slice(&(symbols.len() as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 4]);
+    // Section header.
+    data.extend_from_slice(b".text\0\0\0");
+    data.extend_from_slice(&[0; 28]);
+    data.extend_from_slice(&pe::IMAGE_SCN_CNT_CODE.to_le_bytes());
+    // Symbols.
+    for (i, &(section_number, storage_class, value, _)) in symbols.iter().enumerate() {
+        data.extend_from_slice(&[b's', b'0' + i as u8, 0, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(&(value as u32).to_le_bytes());
+        data.extend_from_slice(&section_number.to_le_bytes());
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&[storage_class, 0]);
+    }
+    // String table.
+    data.extend_from_slice(&4u32.to_le_bytes());
+
+    let file = read::coff::CoffFile::parse(&*data).unwrap();
+    let sections = file
+        .symbols()
+        .map(|symbol| symbol.section())
+        .collect::<Vec<_>>();
+    let expected = symbols
+        .iter()
+        .map(|&(_, _, _, section)| section)
+        .collect::<Vec<_>>();
+    assert_eq!(sections, expected);
+}
+
+#[cfg(feature = "coff")]
+#[test]
+fn coff_sub_architecture() {
+    use object::{Architecture, BinaryFormat, SubArchitecture};
+
+    for &(machine, arch, sub_arch) in &[
+        (pe::IMAGE_FILE_MACHINE_AMD64, Architecture::X86_64, None),
+        (
+            pe::IMAGE_FILE_MACHINE_ARMNT,
+            Architecture::Arm,
+            Some(SubArchitecture::ArmThumb),
+        ),
+        (pe::IMAGE_FILE_MACHINE_ARM64, Architecture::Aarch64, None),
+        (
+            pe::IMAGE_FILE_MACHINE_ARM64EC,
+            Architecture::Aarch64,
+            Some(SubArchitecture::Arm64EC),
+        ),
+    ] {
+        let mut data = Vec::new();
+        data.extend_from_slice(&machine.to_le_bytes());
+        data.extend_from_slice(&[0; 18]);
+        let file = read::coff::CoffFile::parse(&*data).unwrap();
+        assert_eq!(file.format(), BinaryFormat::Coff);
+        assert_eq!(file.architecture(), arch);
+        assert_eq!(file.sub_architecture(), sub_arch);
+    }
 }
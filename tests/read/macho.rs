@@ -0,0 +1,877 @@
+#![cfg(feature = "macho")]
+
+use object::macho;
+use object::read::macho::{DataInCodeEntry, DataInCodeKind, MachOFile64, ObjcMethod};
+use object::read::ParseOptions;
+use object::{Endianness, Object};
+
+/// Build a little endian x86-64 Mach-O executable containing the given load commands.
+fn macho_file(ncmds: u32, commands: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&macho::MH_MAGIC_64.to_le_bytes());
+    data.extend_from_slice(&macho::CPU_TYPE_X86_64.to_le_bytes());
+    data.extend_from_slice(&3u32.to_le_bytes());
+    data.extend_from_slice(&macho::MH_EXECUTE.to_le_bytes());
+    data.extend_from_slice(&ncmds.to_le_bytes());
+    data.extend_from_slice(&(commands.len() as u32).to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(commands);
+    data
+}
+
+#[test]
+fn macho_sub_commands() {
+    fn sub_command(data: &mut Vec<u8>, cmd: u32, name: &[u8]) {
+        let cmdsize = (12 + name.len() + 1 + 7) & !7;
+        data.extend_from_slice(&cmd.to_le_bytes());
+        data.extend_from_slice(&(cmdsize as u32).to_le_bytes());
+        data.extend_from_slice(&12u32.to_le_bytes());
+        data.extend_from_slice(name);
+        data.resize(data.len() + cmdsize - 12 - name.len(), 0);
+    }
+
+    let mut commands = Vec::new();
+    sub_command(&mut commands, macho::LC_SUB_FRAMEWORK, b"Umbrella");
+    sub_command(&mut commands, macho::LC_SUB_CLIENT, b"ClientA");
+    sub_command(&mut commands, macho::LC_SUB_CLIENT, b"ClientB");
+    sub_command(&mut commands, macho::LC_SUB_LIBRARY, b"libfoo");
+    let data = macho_file(4, &commands);
+
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.sub_framework().unwrap(), Some(&b"Umbrella"[..]));
+    assert_eq!(
+        file.sub_clients().unwrap(),
+        vec![&b"ClientA"[..], &b"ClientB"[..]]
+    );
+    assert!(file.sub_umbrellas().unwrap().is_empty());
+    assert_eq!(file.sub_libraries().unwrap(), vec![&b"libfoo"[..]]);
+}
+
+#[test]
+fn macho_entry() {
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert!(!file.has_entry());
+
+    // LC_MAIN contains a file offset, which must be converted to an address.
+    let mut commands = Vec::new();
+    commands.extend_from_slice(&macho::LC_SEGMENT_64.to_le_bytes());
+    commands.extend_from_slice(&72u32.to_le_bytes());
+    commands.extend_from_slice(b"__TEXT\0\0\0\0\0\0\0\0\0\0");
+    commands.extend_from_slice(&0x1_0000_0000u64.to_le_bytes());
+    commands.extend_from_slice(&0x1000u64.to_le_bytes());
+    commands.extend_from_slice(&0u64.to_le_bytes());
+    commands.extend_from_slice(&0x1000u64.to_le_bytes());
+    commands.extend_from_slice(&[0; 16]);
+    commands.extend_from_slice(&macho::LC_MAIN.to_le_bytes());
+    commands.extend_from_slice(&24u32.to_le_bytes());
+    commands.extend_from_slice(&0x400u64.to_le_bytes());
+    commands.extend_from_slice(&0u64.to_le_bytes());
+    let mut data = macho_file(2, &commands);
+    data.resize(0x1000, 0);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert!(file.has_entry());
+    assert_eq!(file.entry(), 0x1_0000_0400);
+    assert_eq!(file.relative_address_base(), 0x1_0000_0000);
+    assert_eq!(file.preferred_address(), 0x1_0000_0000);
+
+    // LC_UNIXTHREAD contains the initial value of rip in x86_THREAD_STATE64.
+    let mut commands = Vec::new();
+    commands.extend_from_slice(&macho::LC_UNIXTHREAD.to_le_bytes());
+    commands.extend_from_slice(&(16u32 + 21 * 8).to_le_bytes());
+    commands.extend_from_slice(&4u32.to_le_bytes());
+    commands.extend_from_slice(&42u32.to_le_bytes());
+    for i in 0..21u64 {
+        let value: u64 = if i == 16 { 0x1000_1234 } else { 0 };
+        commands.extend_from_slice(&value.to_le_bytes());
+    }
+    let data = macho_file(1, &commands);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.entry(), 0x1000_1234);
+}
+
+#[test]
+fn macho_exports_trie() {
+    fn linkedit_command(data: &mut Vec<u8>, cmd: u32, dataoff: u32, datasize: u32) {
+        data.extend_from_slice(&cmd.to_le_bytes());
+        data.extend_from_slice(&16u32.to_le_bytes());
+        data.extend_from_slice(&dataoff.to_le_bytes());
+        data.extend_from_slice(&datasize.to_le_bytes());
+    }
+
+    fn dyld_info_command(data: &mut Vec<u8>, export_off: u32, export_size: u32) {
+        data.extend_from_slice(&macho::LC_DYLD_INFO_ONLY.to_le_bytes());
+        data.extend_from_slice(&48u32.to_le_bytes());
+        data.extend_from_slice(&[0; 32]);
+        data.extend_from_slice(&export_off.to_le_bytes());
+        data.extend_from_slice(&export_size.to_le_bytes());
+    }
+
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.exports_trie_data().unwrap(), None);
+    assert_eq!(file.chained_fixups_data().unwrap(), None);
+
+    // Older binaries store the export trie in the dyld info range.
+    let mut commands = Vec::new();
+    dyld_info_command(&mut commands, 0x100, 4);
+    let mut data = macho_file(1, &commands);
+    data.resize(0x100, 0);
+    data.extend_from_slice(b"info");
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.exports_trie_data().unwrap(), Some(&b"info"[..]));
+
+    // Newer binaries use separate linkedit data commands.
+    let mut commands = Vec::new();
+    dyld_info_command(&mut commands, 0x100, 4);
+    linkedit_command(&mut commands, macho::LC_DYLD_EXPORTS_TRIE, 0x104, 4);
+    linkedit_command(&mut commands, macho::LC_DYLD_CHAINED_FIXUPS, 0x108, 6);
+    let mut data = macho_file(3, &commands);
+    data.resize(0x100, 0);
+    data.extend_from_slice(b"infotriefixups");
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.exports_trie_data().unwrap(), Some(&b"trie"[..]));
+    assert_eq!(file.chained_fixups_data().unwrap(), Some(&b"fixups"[..]));
+}
+
+#[test]
+fn macho_sub_architecture() {
+    use object::SubArchitecture;
+
+    let mut data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.sub_architecture(), None);
+
+    data[8..12].copy_from_slice(&macho::CPU_SUBTYPE_X86_64_H.to_le_bytes());
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.sub_architecture(), Some(SubArchitecture::X86_64H));
+
+    data[4..8].copy_from_slice(&macho::CPU_TYPE_ARM64.to_le_bytes());
+    data[8..12].copy_from_slice(&(macho::CPU_SUBTYPE_ARM64E | 0x8000_0000).to_le_bytes());
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.architecture(), object::Architecture::Aarch64);
+    assert_eq!(file.sub_architecture(), Some(SubArchitecture::Arm64E));
+}
+
+#[test]
+fn macho_objc_classes() {
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..][..bytes.len()].copy_from_slice(bytes);
+    }
+    fn put64(data: &mut [u8], offset: usize, value: u64) {
+        put(data, offset, &value.to_le_bytes());
+    }
+    fn put32(data: &mut [u8], offset: usize, value: u32) {
+        put(data, offset, &value.to_le_bytes());
+    }
+
+    // A single segment mapping the whole file at address 0x1000.
+    let mut commands = Vec::new();
+    commands.extend_from_slice(&macho::LC_SEGMENT_64.to_le_bytes());
+    commands.extend_from_slice(&152u32.to_le_bytes());
+    commands.extend_from_slice(b"__DATA\0\0\0\0\0\0\0\0\0\0");
+    commands.extend_from_slice(&0x1000u64.to_le_bytes());
+    commands.extend_from_slice(&0x400u64.to_le_bytes());
+    commands.extend_from_slice(&0u64.to_le_bytes());
+    commands.extend_from_slice(&0x400u64.to_le_bytes());
+    commands.extend_from_slice(&[0; 8]);
+    commands.extend_from_slice(&1u32.to_le_bytes());
+    commands.extend_from_slice(&[0; 4]);
+    commands.extend_from_slice(b"__objc_classlist");
+    commands.extend_from_slice(b"__DATA\0\0\0\0\0\0\0\0\0\0");
+    commands.extend_from_slice(&0x1100u64.to_le_bytes());
+    commands.extend_from_slice(&16u64.to_le_bytes());
+    commands.extend_from_slice(&0x100u32.to_le_bytes());
+    commands.extend_from_slice(&[0; 28]);
+    let mut data = macho_file(1, &commands);
+    data.resize(0x400, 0);
+
+    put64(&mut data, 0x100, 0x1110);
+    put64(&mut data, 0x108, 0x1240);
+
+    // A class using a method list with pointers.
+    // The low bits of the data pointer are flags.
+    put64(&mut data, 0x130, 0x1140 | 1);
+    put64(&mut data, 0x158, 0x1200);
+    put64(&mut data, 0x160, 0x1180);
+    put32(&mut data, 0x180, 24);
+    put32(&mut data, 0x184, 2);
+    put64(&mut data, 0x188, 0x1210);
+    put64(&mut data, 0x190, 0x1220);
+    put64(&mut data, 0x198, 0x1400);
+    put64(&mut data, 0x1a0, 0x1218);
+    put64(&mut data, 0x1a8, 0x1220);
+    put64(&mut data, 0x1b0, 0x1410);
+    put(&mut data, 0x200, b"Foo\0");
+    put(&mut data, 0x210, b"init\0");
+    put(&mut data, 0x218, b"run\0");
+    put(&mut data, 0x220, b"v16@0:8\0");
+
+    // A class using a method list with relative offsets.
+    put64(&mut data, 0x260, 0x1270);
+    put64(&mut data, 0x288, 0x1300);
+    put64(&mut data, 0x290, 0x12a0);
+    put32(&mut data, 0x2a0, 0x8000_000c);
+    put32(&mut data, 0x2a4, 1);
+    put32(&mut data, 0x2a8, 0x12c0 - 0x12a8);
+    put32(&mut data, 0x2b0, 0x1500 - 0x12b0);
+    put64(&mut data, 0x2c0, 0x1218);
+    put(&mut data, 0x300, b"Bar\0");
+
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    let mut classes = file.objc_classes().unwrap();
+    let class = classes.next().unwrap().unwrap();
+    assert_eq!(class.address, 0x1110);
+    assert_eq!(class.name, b"Foo");
+    assert_eq!(
+        class.methods,
+        [
+            ObjcMethod {
+                name: b"init",
+                implementation: 0x1400,
+            },
+            ObjcMethod {
+                name: b"run",
+                implementation: 0x1410,
+            },
+        ]
+    );
+    let class = classes.next().unwrap().unwrap();
+    assert_eq!(class.address, 0x1240);
+    assert_eq!(class.name, b"Bar");
+    assert_eq!(
+        class.methods,
+        [ObjcMethod {
+            name: b"run",
+            implementation: 0x1500,
+        }]
+    );
+    assert!(classes.next().unwrap().is_none());
+
+    // Files without Objective-C metadata have no classes.
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert!(file.objc_classes().unwrap().next().unwrap().is_none());
+}
+
+fn dylib_command(data: &mut Vec<u8>, cmd: u32, name: &[u8]) {
+    let cmdsize = (24 + name.len() + 1 + 7) & !7;
+    data.extend_from_slice(&cmd.to_le_bytes());
+    data.extend_from_slice(&(cmdsize as u32).to_le_bytes());
+    data.extend_from_slice(&24u32.to_le_bytes());
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(name);
+    data.resize(data.len() + cmdsize - 24 - name.len(), 0);
+}
+
+#[test]
+fn macho_library_for_ordinal() {
+    let mut commands = Vec::new();
+    dylib_command(
+        &mut commands,
+        macho::LC_LOAD_DYLIB,
+        b"/usr/lib/libSystem.B.dylib",
+    );
+    // Commands that are not dylib load commands do not affect the ordinals.
+    dylib_command(&mut commands, macho::LC_ID_DYLIB, b"/usr/lib/libself.dylib");
+    dylib_command(
+        &mut commands,
+        macho::LC_LOAD_WEAK_DYLIB,
+        b"/usr/lib/libweak.dylib",
+    );
+    let data = macho_file(3, &commands);
+
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(
+        file.library_for_ordinal(1).unwrap(),
+        Some(&b"/usr/lib/libSystem.B.dylib"[..])
+    );
+    assert_eq!(
+        file.library_for_ordinal(2).unwrap(),
+        Some(&b"/usr/lib/libweak.dylib"[..])
+    );
+    assert!(file.library_for_ordinal(3).is_err());
+    assert_eq!(
+        file.library_for_ordinal(macho::SELF_LIBRARY_ORDINAL.into())
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        file.library_for_ordinal(macho::DYNAMIC_LOOKUP_ORDINAL.into())
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        file.library_for_ordinal(macho::EXECUTABLE_ORDINAL.into())
+            .unwrap(),
+        None
+    );
+}
+
+#[test]
+fn macho_needed_libraries() {
+    let mut commands = Vec::new();
+    dylib_command(&mut commands, macho::LC_ID_DYLIB, b"/usr/lib/libself.dylib");
+    dylib_command(
+        &mut commands,
+        macho::LC_LOAD_DYLIB,
+        b"/usr/lib/libSystem.B.dylib",
+    );
+    dylib_command(
+        &mut commands,
+        macho::LC_LOAD_WEAK_DYLIB,
+        b"/usr/lib/libweak.dylib",
+    );
+    dylib_command(
+        &mut commands,
+        macho::LC_REEXPORT_DYLIB,
+        b"/usr/lib/libSystem.B.dylib",
+    );
+    let data = macho_file(4, &commands);
+
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(
+        file.needed_libraries().unwrap(),
+        [
+            &b"/usr/lib/libSystem.B.dylib"[..],
+            &b"/usr/lib/libweak.dylib"[..]
+        ]
+    );
+}
+
+#[test]
+fn macho_source_version() {
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.source_version().unwrap(), None);
+    assert_eq!(file.dylib_code_sign_drs_data().unwrap(), None);
+
+    // Version 1234.5.67.8.1023
+    let version: u64 = (1234 << 40) | (5 << 30) | (67 << 20) | (8 << 10) | 1023;
+    let mut commands = Vec::new();
+    commands.extend_from_slice(&macho::LC_SOURCE_VERSION.to_le_bytes());
+    commands.extend_from_slice(&16u32.to_le_bytes());
+    commands.extend_from_slice(&version.to_le_bytes());
+    commands.extend_from_slice(&macho::LC_DYLIB_CODE_SIGN_DRS.to_le_bytes());
+    commands.extend_from_slice(&16u32.to_le_bytes());
+    commands.extend_from_slice(&0x100u32.to_le_bytes());
+    commands.extend_from_slice(&4u32.to_le_bytes());
+    let mut data = macho_file(2, &commands);
+    data.resize(0x100, 0);
+    data.extend_from_slice(b"drs!");
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.source_version().unwrap(), Some((1234, 5, 67, 8, 1023)));
+    assert_eq!(file.dylib_code_sign_drs_data().unwrap(), Some(&b"drs!"[..]));
+}
+
+#[test]
+fn macho_data_in_code() {
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert!(file.data_in_code().unwrap().is_none());
+
+    let mut commands = Vec::new();
+    commands.extend_from_slice(&macho::LC_DATA_IN_CODE.to_le_bytes());
+    commands.extend_from_slice(&16u32.to_le_bytes());
+    commands.extend_from_slice(&0x100u32.to_le_bytes());
+    commands.extend_from_slice(&16u32.to_le_bytes());
+    let mut data = macho_file(1, &commands);
+    data.resize(0x100, 0);
+    data.extend_from_slice(&0x1000u32.to_le_bytes());
+    data.extend_from_slice(&8u16.to_le_bytes());
+    data.extend_from_slice(&(macho::DICE_KIND_JUMP_TABLE32 as u16).to_le_bytes());
+    data.extend_from_slice(&0x1010u32.to_le_bytes());
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.extend_from_slice(&9u16.to_le_bytes());
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    let entries = file.data_in_code().unwrap().unwrap().collect::<Vec<_>>();
+    assert_eq!(
+        entries,
+        [
+            DataInCodeEntry {
+                offset: 0x1000,
+                length: 8,
+                kind: macho::DICE_KIND_JUMP_TABLE32 as u16,
+            },
+            DataInCodeEntry {
+                offset: 0x1010,
+                length: 2,
+                kind: 9,
+            },
+        ]
+    );
+    assert_eq!(entries[0].kind_enum(), DataInCodeKind::JumpTable32);
+    assert_eq!(entries[1].kind_enum(), DataInCodeKind::Unknown(9));
+}
+
+#[test]
+fn macho_notes() {
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.notes().unwrap().count(), 0);
+
+    let mut commands = Vec::new();
+    for &(owner, offset, size) in &[
+        (&b"addrable bits"[..], 0x100u64, 4u64),
+        (&b"0123456789abcdef"[..], 0x104, 8),
+        (&b"bad"[..], 0x10c, 8),
+    ] {
+        let mut data_owner = [0; 16];
+        data_owner[..owner.len()].copy_from_slice(owner);
+        commands.extend_from_slice(&macho::LC_NOTE.to_le_bytes());
+        commands.extend_from_slice(&40u32.to_le_bytes());
+        commands.extend_from_slice(&data_owner);
+        commands.extend_from_slice(&offset.to_le_bytes());
+        commands.extend_from_slice(&size.to_le_bytes());
+    }
+    let mut data = macho_file(3, &commands);
+    data.resize(0x100, 0);
+    data.extend_from_slice(&39u32.to_le_bytes());
+    data.extend_from_slice(b"note");
+    data.extend_from_slice(b"data");
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    let notes = file.notes().unwrap().collect::<Vec<_>>();
+    assert_eq!(notes.len(), 3);
+    assert_eq!(notes[0].owner(), b"addrable bits");
+    assert_eq!(notes[0].offset, 0x100);
+    assert_eq!(notes[0].size, 4);
+    assert_eq!(notes[0].data(&file), Ok(&39u32.to_le_bytes()[..]));
+    assert_eq!(notes[1].owner(), b"0123456789abcdef");
+    assert_eq!(notes[1].data(&file), Ok(&b"notedata"[..]));
+    assert_eq!(notes[2].owner(), b"bad");
+    assert!(notes[2].data(&file).is_err());
+}
+
+#[test]
+fn macho_function_starts() {
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(file.function_starts().unwrap(), None);
+
+    let mut commands = Vec::new();
+    commands.extend_from_slice(&macho::LC_FUNCTION_STARTS.to_le_bytes());
+    commands.extend_from_slice(&16u32.to_le_bytes());
+    commands.extend_from_slice(&0x100u32.to_le_bytes());
+    commands.extend_from_slice(&8u32.to_le_bytes());
+    let mut data = macho_file(1, &commands);
+    data.resize(0x100, 0);
+    // ULEB128 deltas, terminated by a zero delta and padding.
+    data.extend_from_slice(&[0x80, 0x20, 0x10, 0x20, 0, 0, 0, 0]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(
+        file.function_starts().unwrap(),
+        Some(vec![0x1000, 0x1010, 0x1030])
+    );
+    // There are no sections, so the sizes can't be inferred.
+    let functions = file.functions();
+    assert_eq!(
+        functions
+            .iter()
+            .map(|function| (function.address, function.size))
+            .collect::<Vec<_>>(),
+        [(0x1000, None), (0x1010, None), (0x1030, None)]
+    );
+}
+
+#[test]
+fn macho_pointer_fixups() {
+    fn segment(commands: &mut Vec<u8>, name: &[u8; 16], vmaddr: u64, fileoff: u64) {
+        commands.extend_from_slice(&macho::LC_SEGMENT_64.to_le_bytes());
+        commands.extend_from_slice(&72u32.to_le_bytes());
+        commands.extend_from_slice(name);
+        commands.extend_from_slice(&vmaddr.to_le_bytes());
+        commands.extend_from_slice(&0x1000u64.to_le_bytes());
+        commands.extend_from_slice(&fileoff.to_le_bytes());
+        commands.extend_from_slice(&0x1000u64.to_le_bytes());
+        commands.extend_from_slice(&[0; 16]);
+    }
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..][..bytes.len()].copy_from_slice(bytes);
+    }
+    fn fixups(data: &[u8]) -> Vec<(u64, u8)> {
+        let file = MachOFile64::<Endianness>::parse(data).unwrap();
+        file.pointer_fixups()
+            .unwrap()
+            .map(|fixup| (fixup.address(), fixup.size()))
+            .collect()
+    }
+    fn rebase_data(commands: &[u8], opcodes: &[u8]) -> Vec<u8> {
+        let mut commands = commands.to_vec();
+        commands.extend_from_slice(&macho::LC_DYLD_INFO_ONLY.to_le_bytes());
+        commands.extend_from_slice(&48u32.to_le_bytes());
+        commands.extend_from_slice(&0x800u32.to_le_bytes());
+        commands.extend_from_slice(&(opcodes.len() as u32).to_le_bytes());
+        commands.extend_from_slice(&[0; 32]);
+        let mut data = macho_file(3, &commands);
+        data.resize(0x2000, 0);
+        put(&mut data, 0x800, opcodes);
+        data
+    }
+
+    let mut commands = Vec::new();
+    segment(
+        &mut commands,
+        b"__TEXT\0\0\0\0\0\0\0\0\0\0",
+        0x1_0000_0000,
+        0,
+    );
+    segment(
+        &mut commands,
+        b"__DATA\0\0\0\0\0\0\0\0\0\0",
+        0x1_0000_1000,
+        0x1000,
+    );
+    let mut data = macho_file(2, &commands);
+    data.resize(0x2000, 0);
+    assert!(fixups(&data).is_empty());
+
+    // Rebase opcodes in LC_DYLD_INFO_ONLY.
+    let opcodes = [
+        macho::REBASE_OPCODE_SET_TYPE_IMM | macho::REBASE_TYPE_POINTER,
+        macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 1,
+        0x10,
+        macho::REBASE_OPCODE_DO_REBASE_IMM_TIMES | 2,
+        macho::REBASE_OPCODE_ADD_ADDR_ULEB,
+        0x80,
+        0x01,
+        macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB,
+        2,
+        8,
+        macho::REBASE_OPCODE_DONE,
+    ];
+    let data = rebase_data(&commands, &opcodes);
+    assert_eq!(
+        fixups(&data),
+        [
+            (0x1_0000_1010, 8),
+            (0x1_0000_1018, 8),
+            (0x1_0000_10a0, 8),
+            (0x1_0000_10b0, 8)
+        ]
+    );
+
+    // Rebases that run past the end of the segment are rejected without
+    // producing the fixups.
+    let prefix = [
+        macho::REBASE_OPCODE_SET_TYPE_IMM | macho::REBASE_TYPE_POINTER,
+        macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 1,
+        0x10,
+    ];
+    let count = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    // A skip that wraps the advance to 0.
+    let skip = [0xf8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    let mut skipping = prefix.to_vec();
+    skipping.push(macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES_SKIPPING_ULEB);
+    skipping.extend_from_slice(&count);
+    skipping.extend_from_slice(&skip);
+    let mut times = prefix.to_vec();
+    times.push(macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES);
+    times.extend_from_slice(&count);
+    // 0x1fe pointers fit after offset 0x10, but not 0x1ff.
+    let mut too_many = prefix.to_vec();
+    too_many.extend_from_slice(&[macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES, 0xff, 0x03]);
+    for opcodes in &[skipping, times, too_many] {
+        let data = rebase_data(&commands, opcodes);
+        let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+        assert!(file.pointer_fixups().is_err());
+    }
+    let mut all = prefix.to_vec();
+    all.extend_from_slice(&[macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES, 0xfe, 0x03]);
+    let data = rebase_data(&commands, &all);
+    assert_eq!(fixups(&data).len(), 0x1fe);
+    let options = ParseOptions {
+        max_pointer_fixups: 0x100,
+        ..ParseOptions::default()
+    };
+    let file = MachOFile64::<Endianness>::parse_with_options(&*data, &options).unwrap();
+    assert!(file.pointer_fixups().is_err());
+
+    // Repeating the rebases of the whole segment produces more fixups than
+    // there are pointers in the file.
+    let mut repeated = vec![macho::REBASE_OPCODE_SET_TYPE_IMM | macho::REBASE_TYPE_POINTER];
+    for _ in 0..5 {
+        repeated.extend_from_slice(&[
+            macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 1,
+            0x10,
+            macho::REBASE_OPCODE_DO_REBASE_ULEB_TIMES,
+            0xfe,
+            0x03,
+        ]);
+    }
+    let data = rebase_data(&commands, &repeated);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert_eq!(
+        file.pointer_fixups().unwrap_err().to_string(),
+        "Too many Mach-O pointer fixups"
+    );
+
+    // A chain using DYLD_CHAINED_PTR_64_OFFSET in LC_DYLD_CHAINED_FIXUPS.
+    let mut chained_commands = commands;
+    chained_commands.extend_from_slice(&macho::LC_DYLD_CHAINED_FIXUPS.to_le_bytes());
+    chained_commands.extend_from_slice(&16u32.to_le_bytes());
+    chained_commands.extend_from_slice(&0x800u32.to_le_bytes());
+    chained_commands.extend_from_slice(&0x50u32.to_le_bytes());
+    let mut data = macho_file(3, &chained_commands);
+    data.resize(0x2000, 0);
+    // The header, with the starts at offset 0x20.
+    put(&mut data, 0x804, &0x20u32.to_le_bytes());
+    // The starts in image, with no starts for the first segment.
+    put(&mut data, 0x820, &2u32.to_le_bytes());
+    put(&mut data, 0x828, &0x10u32.to_le_bytes());
+    // The starts in segment, with one page whose chain starts at offset 0x10.
+    put(&mut data, 0x830, &24u32.to_le_bytes());
+    put(&mut data, 0x834, &0x1000u16.to_le_bytes());
+    put(
+        &mut data,
+        0x836,
+        &macho::DYLD_CHAINED_PTR_64_OFFSET.to_le_bytes(),
+    );
+    put(&mut data, 0x838, &0x1000u64.to_le_bytes());
+    put(&mut data, 0x844, &1u16.to_le_bytes());
+    put(&mut data, 0x846, &0x10u16.to_le_bytes());
+    // A rebase, then a bind, then a rebase. The strides are 4 bytes.
+    put(&mut data, 0x1010, &(0x100u64 | (2 << 51)).to_le_bytes());
+    put(&mut data, 0x1018, &((1u64 << 63) | (2 << 51)).to_le_bytes());
+    put(&mut data, 0x1020, &0x200u64.to_le_bytes());
+    assert_eq!(fixups(&data), [(0x1_0000_1010, 8), (0x1_0000_1020, 8)]);
+    let options = ParseOptions {
+        max_pointer_fixups: 1,
+        ..ParseOptions::default()
+    };
+    let file = MachOFile64::<Endianness>::parse_with_options(&*data, &options).unwrap();
+    assert!(file.pointer_fixups().is_err());
+}
+
+#[test]
+fn macho_compact_unwind() {
+    fn put32(data: &mut [u8], offset: usize, value: u32) {
+        data[offset..][..4].copy_from_slice(&value.to_le_bytes());
+    }
+    fn put16(data: &mut [u8], offset: usize, value: u16) {
+        data[offset..][..2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert!(file.compact_unwind().unwrap().is_none());
+    assert!(file
+        .compact_unwind_entries()
+        .unwrap()
+        .next()
+        .unwrap()
+        .is_none());
+
+    // A `__TEXT` segment mapping the whole file, containing `__unwind_info` at 0x800.
+    let mut commands = Vec::new();
+    commands.extend_from_slice(&macho::LC_SEGMENT_64.to_le_bytes());
+    commands.extend_from_slice(&152u32.to_le_bytes());
+    commands.extend_from_slice(b"__TEXT\0\0\0\0\0\0\0\0\0\0");
+    commands.extend_from_slice(&0x1_0000_0000u64.to_le_bytes());
+    commands.extend_from_slice(&0x1000u64.to_le_bytes());
+    commands.extend_from_slice(&0u64.to_le_bytes());
+    commands.extend_from_slice(&0x1000u64.to_le_bytes());
+    commands.extend_from_slice(&[0; 8]);
+    commands.extend_from_slice(&1u32.to_le_bytes());
+    commands.extend_from_slice(&[0; 4]);
+    commands.extend_from_slice(b"__unwind_info\0\0\0");
+    commands.extend_from_slice(b"__TEXT\0\0\0\0\0\0\0\0\0\0");
+    commands.extend_from_slice(&0x1_0000_0800u64.to_le_bytes());
+    commands.extend_from_slice(&0x100u64.to_le_bytes());
+    commands.extend_from_slice(&0x800u32.to_le_bytes());
+    commands.extend_from_slice(&[0; 28]);
+    let mut data = macho_file(1, &commands);
+    data.resize(0x1000, 0);
+
+    let unwind = &mut data[0x800..0x900];
+    // The header, with two common encodings and three index entries.
+    for (i, value) in [macho::UNWIND_SECTION_VERSION, 28, 2, 36, 0, 36, 3]
+        .iter()
+        .enumerate()
+    {
+        put32(unwind, i * 4, *value);
+    }
+    put32(unwind, 28, 0x0100_0000);
+    put32(unwind, 32, 0x0200_0000);
+    // The first level index, including the sentinel.
+    put32(unwind, 36, 0x1000);
+    put32(unwind, 40, 0x50);
+    put32(unwind, 48, 0x2000);
+    put32(unwind, 52, 0x70);
+    put32(unwind, 60, 0x3000);
+    // A regular page.
+    put32(unwind, 0x50, macho::UNWIND_SECOND_LEVEL_REGULAR);
+    put16(unwind, 0x54, 8);
+    put16(unwind, 0x56, 2);
+    put32(unwind, 0x58, 0x1000);
+    put32(unwind, 0x5c, 0x0300_0000);
+    put32(unwind, 0x60, 0x1100);
+    put32(unwind, 0x64, 0x0400_0000);
+    // A compressed page, with one page encoding following the common encodings.
+    put32(unwind, 0x70, macho::UNWIND_SECOND_LEVEL_COMPRESSED);
+    put16(unwind, 0x74, 12);
+    put16(unwind, 0x76, 3);
+    put16(unwind, 0x78, 24);
+    put16(unwind, 0x7a, 1);
+    put32(unwind, 0x7c, 0x0000_0000);
+    put32(unwind, 0x80, 0x0100_0020);
+    put32(unwind, 0x84, 0x0200_0040);
+    put32(unwind, 0x88, 0x0500_0000);
+
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    let compact_unwind = file.compact_unwind().unwrap().unwrap();
+    assert_eq!(compact_unwind.common_encodings().len(), 2);
+    assert_eq!(compact_unwind.index().len(), 3);
+    let mut entries = file.compact_unwind_entries().unwrap();
+    let mut result = Vec::new();
+    while let Some(entry) = entries.next().unwrap() {
+        result.push((entry.address, entry.encoding));
+    }
+    assert_eq!(
+        result,
+        [
+            (0x1_0000_1000, 0x0300_0000),
+            (0x1_0000_1100, 0x0400_0000),
+            (0x1_0000_2000, 0x0100_0000),
+            (0x1_0000_2020, 0x0200_0000),
+            (0x1_0000_2040, 0x0500_0000),
+        ]
+    );
+
+    // An encoding index past the page encodings is an error.
+    put32(&mut data, 0x884, 0x0300_0040);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    let mut entries = file.compact_unwind_entries().unwrap();
+    for _ in 0..4 {
+        entries.next().unwrap();
+    }
+    assert!(entries.next().is_err());
+}
+
+#[test]
+fn macho_dysymtab_symbols() {
+    use object::ObjectSymbol;
+
+    let mut commands = Vec::new();
+    let symoff = 32 + 24 + 80;
+    let stroff = symoff + 3 * 16;
+    let strings = b"\0_l\0_e\0_u\0";
+    for value in &[
+        macho::LC_SYMTAB,
+        24,
+        symoff,
+        3,
+        stroff,
+        strings.len() as u32,
+    ] {
+        commands.extend_from_slice(&value.to_le_bytes());
+    }
+    commands.extend_from_slice(&macho::LC_DYSYMTAB.to_le_bytes());
+    commands.extend_from_slice(&80u32.to_le_bytes());
+    for value in &[0u32, 1, 1, 1, 2, 1] {
+        commands.extend_from_slice(&value.to_le_bytes());
+    }
+    commands.resize(24 + 80, 0);
+    let mut data = macho_file(2, &commands);
+    for &(strx, n_type) in &[
+        (1u32, macho::N_ABS),
+        (4, macho::N_ABS | macho::N_EXT),
+        (7, macho::N_UNDF | macho::N_EXT),
+    ] {
+        data.extend_from_slice(&strx.to_le_bytes());
+        data.extend_from_slice(&[n_type, 0, 0, 0]);
+        data.extend_from_slice(&0u64.to_le_bytes());
+    }
+    data.extend_from_slice(strings);
+
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    let symtab = file.symtab_command().unwrap().unwrap();
+    assert_eq!(symtab.symoff.get(Endianness::Little), symoff);
+    assert_eq!(symtab.nsyms.get(Endianness::Little), 3);
+    assert_eq!(symtab.stroff.get(Endianness::Little), stroff);
+    assert_eq!(symtab.strsize.get(Endianness::Little), strings.len() as u32);
+    let dysymtab = file.dysymtab_command().unwrap().unwrap();
+    assert_eq!(dysymtab.iundefsym.get(Endianness::Little), 2);
+
+    let local = file.local_symbols().unwrap();
+    assert_eq!(local.map(|s| s.name().unwrap()).collect::<Vec<_>>(), ["_l"]);
+    let external = file.external_symbols().unwrap();
+    assert_eq!(
+        external.map(|s| s.name().unwrap()).collect::<Vec<_>>(),
+        ["_e"]
+    );
+    let undefined = file.undefined_symbols().unwrap();
+    assert_eq!(
+        undefined.map(|s| s.name().unwrap()).collect::<Vec<_>>(),
+        ["_u"]
+    );
+
+    // The ranges must be within the symbol table.
+    let mut bad = data.clone();
+    bad[32 + 24 + 28..][..4].copy_from_slice(&2u32.to_le_bytes());
+    let file = MachOFile64::<Endianness>::parse(&*bad).unwrap();
+    assert!(file.undefined_symbols().is_err());
+
+    let data = macho_file(0, &[]);
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    assert!(file.symtab_command().unwrap().is_none());
+    assert!(file.dysymtab_command().unwrap().is_none());
+    assert_eq!(file.undefined_symbols().unwrap().count(), 0);
+}
+
+#[test]
+fn macho_common_symbols() {
+    let symoff = 32 + 24;
+    let symbols: &[(u8, u16, u64)] = &[
+        // Explicit alignment of 2^3.
+        (macho::N_UNDF | macho::N_EXT, 3 << 8, 100),
+        // Natural alignment, rounded up to a power of 2.
+        (macho::N_UNDF | macho::N_EXT, 0, 12),
+        (macho::N_UNDF | macho::N_EXT, 0, 8),
+        // Natural alignment is limited to 2^15.
+        (macho::N_UNDF | macho::N_EXT, 0, 1 << 20),
+        // Not common symbols.
+        (macho::N_UNDF | macho::N_EXT, 0, 0),
+        (macho::N_UNDF, 0, 4),
+        (macho::N_ABS | macho::N_EXT, 0, 4),
+    ];
+    let stroff = symoff + symbols.len() as u32 * 16;
+    let strings = b"\0_c\0";
+    let mut commands = Vec::new();
+    for value in &[
+        macho::LC_SYMTAB,
+        24,
+        symoff,
+        symbols.len() as u32,
+        stroff,
+        strings.len() as u32,
+    ] {
+        commands.extend_from_slice(&value.to_le_bytes());
+    }
+    let mut data = macho_file(1, &commands);
+    for &(n_type, n_desc, n_value) in symbols {
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[n_type, 0]);
+        data.extend_from_slice(&n_desc.to_le_bytes());
+        data.extend_from_slice(&n_value.to_le_bytes());
+    }
+    data.extend_from_slice(strings);
+
+    let file = MachOFile64::<Endianness>::parse(&*data).unwrap();
+    let common = file
+        .symbols()
+        .map(|symbol| (symbol.common_size(), symbol.common_alignment()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        common,
+        [
+            (Some(100), Some(8)),
+            (Some(12), Some(16)),
+            (Some(8), Some(8)),
+            (Some(1 << 20), Some(1 << 15)),
+            (None, None),
+            (None, None),
+            (None, None),
+        ]
+    );
+}
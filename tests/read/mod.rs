@@ -1,3 +1,8 @@
 #![cfg(feature = "read")]
 
 mod coff;
+mod macho;
+mod pe;
+mod read_cache;
+mod symbol_map;
+mod wasm;
@@ -0,0 +1,209 @@
+use object::read::pe::{Arm64PackedUnwindData, Arm64UnwindInfo};
+
+#[cfg(feature = "pe")]
+#[test]
+fn pe_arm64_unwind_info() {
+    assert_eq!(
+        Arm64UnwindInfo::parse(0x0001_2340),
+        Arm64UnwindInfo::Xdata(0x0001_2340)
+    );
+
+    // FrameSize = 2, CR = 3, H = 0, RegI = 2, RegF = 0, FunctionLength = 8, Flag = 1
+    let unwind_data = (2 << 23) | (3 << 21) | (2 << 16) | (8 << 2) | 1;
+    assert_eq!(
+        Arm64UnwindInfo::parse(unwind_data),
+        Arm64UnwindInfo::Packed(Arm64PackedUnwindData {
+            flag: 1,
+            function_length: 32,
+            reg_f: 0,
+            reg_i: 2,
+            h: false,
+            cr: 3,
+            frame_size: 32,
+        })
+    );
+}
+
+#[cfg(feature = "pe")]
+#[test]
+fn pe_delay_load_descriptor_limits() {
+    use object::read::pe::DelayLoadImportTable;
+
+    // Three non-null descriptors whose names point to "a.dll" at offset 0x80,
+    // followed by a null descriptor.
+    let mut data = vec![0; 0x88];
+    for i in 0..3 {
+        data[i * 32 + 4..i * 32 + 8].copy_from_slice(&0x1080u32.to_le_bytes());
+    }
+    data[0x80..0x86].copy_from_slice(b"a.dll\0");
+
+    let table = DelayLoadImportTable::new(&data, 0x1000, 0x1000);
+    let mut descriptors = table.descriptors().unwrap();
+    let mut count = 0;
+    while let Some(desc) = descriptors.next().unwrap() {
+        assert_eq!(
+            table.name(desc.dll_name_rva.get(object::LittleEndian)),
+            Ok(&b"a.dll"[..])
+        );
+        count += 1;
+    }
+    assert_eq!(count, 3);
+
+    let mut descriptors = table.descriptors().unwrap();
+    descriptors.set_max_descriptors(2);
+    assert!(descriptors.next().is_ok());
+    assert!(descriptors.next().is_ok());
+    assert!(descriptors.next().is_err());
+
+    // A name outside of the section is an error.
+    data[4..8].copy_from_slice(&0x2000u32.to_le_bytes());
+    let table = DelayLoadImportTable::new(&data, 0x1000, 0x1000);
+    assert!(table.descriptors().unwrap().next().is_err());
+}
+
+#[cfg(feature = "pe")]
+#[test]
+fn pe_resource_find() {
+    use object::pe;
+    use object::read::pe::{ResourceDirectory, ResourceId};
+
+    fn table(data: &mut Vec<u8>, id: u32, offset: u32) {
+        let start = data.len();
+        data.resize(start + 16, 0);
+        data[start + 14..start + 16].copy_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&id.to_le_bytes());
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+
+    // type -> name -> language -> data entry
+    let mut data = Vec::new();
+    table(&mut data, pe::RT_MANIFEST.into(), 0x8000_0018);
+    table(&mut data, 1, 0x8000_0030);
+    table(&mut data, 0x409, 0x48);
+    data.extend_from_slice(&0x2000u32.to_le_bytes());
+    data.extend_from_slice(&0x10u32.to_le_bytes());
+    data.resize(0x58, 0);
+
+    let directory = ResourceDirectory::new(&data);
+    let entry = directory
+        .find(
+            ResourceId::Id(pe::RT_MANIFEST),
+            ResourceId::Id(1),
+            ResourceId::Id(0x409),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.offset_to_data.get(object::LittleEndian), 0x2000);
+    assert!(directory
+        .find(
+            ResourceId::Id(pe::RT_MANIFEST),
+            ResourceId::Id(1),
+            ResourceId::Id(0x407),
+        )
+        .unwrap()
+        .is_none());
+    assert!(directory
+        .find_first_language(ResourceId::Id(pe::RT_MANIFEST), ResourceId::Id(1))
+        .unwrap()
+        .is_some());
+    assert!(directory
+        .find_first_language(ResourceId::Id(pe::RT_ICON), ResourceId::Id(1))
+        .unwrap()
+        .is_none());
+
+    // A tree without the language level.
+    data[0x18 + 20..0x18 + 24].copy_from_slice(&0x48u32.to_le_bytes());
+    let directory = ResourceDirectory::new(&data);
+    let entry = directory
+        .find(
+            ResourceId::Id(pe::RT_MANIFEST),
+            ResourceId::Id(1),
+            ResourceId::Id(0x409),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.size.get(object::LittleEndian), 0x10);
+
+    // A table that refers back to an ancestor.
+    data[0x30 + 20..0x30 + 24].copy_from_slice(&0x8000_0000u32.to_le_bytes());
+    data[0x18 + 20..0x18 + 24].copy_from_slice(&0x8000_0030u32.to_le_bytes());
+    let directory = ResourceDirectory::new(&data);
+    assert!(directory
+        .find_first_language(ResourceId::Id(pe::RT_MANIFEST), ResourceId::Id(1))
+        .is_err());
+
+    // A table that refers to itself.
+    data[20..24].copy_from_slice(&0x8000_0000u32.to_le_bytes());
+    let directory = ResourceDirectory::new(&data);
+    assert!(directory
+        .find_first_language(ResourceId::Id(pe::RT_MANIFEST), ResourceId::Id(1))
+        .is_err());
+
+    // A tree with more than three levels.
+    let mut data = Vec::new();
+    table(&mut data, pe::RT_MANIFEST.into(), 0x8000_0018);
+    table(&mut data, 1, 0x8000_0030);
+    table(&mut data, 0x409, 0x8000_0048);
+    table(&mut data, 0, 0x60);
+    data.extend_from_slice(&0x2000u32.to_le_bytes());
+    data.extend_from_slice(&0x10u32.to_le_bytes());
+    data.resize(0x70, 0);
+    let directory = ResourceDirectory::new(&data);
+    assert!(directory
+        .find(
+            ResourceId::Id(pe::RT_MANIFEST),
+            ResourceId::Id(1),
+            ResourceId::Id(0x409),
+        )
+        .is_err());
+}
+
+#[cfg(feature = "pe")]
+#[test]
+fn pe_data_directories_count() {
+    use object::pe;
+    use object::read::pe::DataDirectories;
+
+    let data = [0u8; 8 * pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES];
+
+    let directories = DataDirectories::parse(&data, 2).unwrap();
+    assert_eq!(directories.as_slice().len(), 2);
+
+    // Counts larger than the architectural maximum are clamped.
+    let directories = DataDirectories::parse(&data, 0x20).unwrap();
+    assert_eq!(
+        directories.as_slice().len(),
+        pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES
+    );
+
+    assert!(DataDirectories::parse(&data[..8], 2).is_err());
+}
+
+#[cfg(feature = "pe")]
+#[test]
+fn pe_find_headers() {
+    use object::pe;
+    use object::read::pe::find_pe_headers;
+
+    fn header(data: &mut Vec<u8>, magic: u16) {
+        let start = data.len();
+        data.resize(start + 0x40 + 4 + 20 + 2, 0);
+        data[start..][..2].copy_from_slice(b"MZ");
+        data[start + 0x3c..][..4].copy_from_slice(&0x40u32.to_le_bytes());
+        data[start + 0x40..][..4].copy_from_slice(b"PE\0\0");
+        data[start + 0x58..][..2].copy_from_slice(&magic.to_le_bytes());
+    }
+
+    let mut data = b"junkMZjunk".to_vec();
+    header(&mut data, pe::IMAGE_NT_OPTIONAL_HDR64_MAGIC);
+    data.push(0);
+    header(&mut data, 0x1234);
+    header(&mut data, pe::IMAGE_NT_OPTIONAL_HDR32_MAGIC);
+    // Truncated before the optional header magic.
+    let start = data.len();
+    header(&mut data, pe::IMAGE_NT_OPTIONAL_HDR32_MAGIC);
+    data.truncate(start + 0x50);
+
+    let offsets = find_pe_headers(&data).collect::<Vec<_>>();
+    assert_eq!(offsets, [10, 10 + 0x5a + 1 + 0x5a]);
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+
+use std::cell::Cell;
+use std::io::{self, Cursor, Read, Seek, SeekFrom};
+use std::rc::Rc;
+
+use object::read::{ReadCache, ReadRef};
+
+/// A reader that counts the number of reads.
+struct CountingReader {
+    inner: Cursor<Vec<u8>>,
+    reads: Rc<Cell<usize>>,
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.reads.set(self.reads.get() + 1);
+        self.inner.read(buf)
+    }
+}
+
+impl Seek for CountingReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn read_cache_contained_reads() {
+    let data = (0..=255).collect::<Vec<u8>>();
+    let reads = Rc::new(Cell::new(0));
+    let cache = ReadCache::new(CountingReader {
+        inner: Cursor::new(data),
+        reads: reads.clone(),
+    });
+
+    assert_eq!(
+        cache.read_bytes_at(16, 32).unwrap(),
+        &(16..48).collect::<Vec<u8>>()[..]
+    );
+    let count = reads.get();
+    assert!(count > 0);
+
+    // Reads within the cached block don't read from the stream.
+    assert_eq!(cache.read_bytes_at(20, 4).unwrap(), &[20, 21, 22, 23]);
+    assert_eq!(cache.read_bytes_at(16, 32).unwrap()[0], 16);
+    assert_eq!(cache.read_bytes_at(47, 1).unwrap(), &[47]);
+    assert_eq!(reads.get(), count);
+
+    // Reads extending past the cached block do.
+    assert_eq!(cache.read_bytes_at(40, 16).unwrap()[15], 55);
+    assert!(reads.get() > count);
+    assert!(cache.read_bytes_at(250, 16).is_err());
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "demangle")]
+
+use object::{SymbolMap, SymbolMapName};
+
+#[test]
+fn symbol_map_demangle() {
+    let names = [
+        (0x1000, "_ZN4core3ptr13drop_in_place17h0123456789abcdefE"),
+        (0x2000, "_ZN66_$LT$alloc..vec..Vec$LT$T$GT$$u20$as$u20$core..ops..drop..Drop$GT$4drop17hfedcba9876543210E"),
+        (0x3000, "__ZN3std2rt10lang_start17h0000000000000000E.llvm.42"),
+        (0x4000, "main"),
+        (0x5000, "_ZN3foo$XX$E"),
+        (0x6000, "_ZN3foo3barEv"),
+        (0x7000, "_RNvCs1234_7mycrate3foo"),
+    ];
+    let symbols = names
+        .iter()
+        .map(|&(address, name)| SymbolMapName::new(address, name))
+        .collect();
+    let map = SymbolMap::new(symbols);
+
+    let name = map.get(0x1010).unwrap();
+    assert_eq!(name.address(), 0x1000);
+    assert_eq!(name.name(), names[0].1);
+    assert_eq!(
+        name.demangled().unwrap().to_string(),
+        "core::ptr::drop_in_place"
+    );
+    assert_eq!(
+        map.get(0x2000).unwrap().demangled().unwrap().to_string(),
+        "<alloc::vec::Vec<T> as core::ops::drop::Drop>::drop"
+    );
+    assert_eq!(
+        map.get(0x3000).unwrap().demangled().unwrap().to_string(),
+        "std::rt::lang_start.llvm.42"
+    );
+    assert!(map.get(0x4000).unwrap().demangled().is_none());
+    assert!(map.get(0x5000).unwrap().demangled().is_none());
+    // Itanium C++ and Rust v0 names are not supported.
+    assert!(map.get(0x6000).unwrap().demangled().is_none());
+    assert!(map.get(0x7000).unwrap().demangled().is_none());
+
+    // Without demangling, names are displayed unchanged.
+    let name = *map.get(0x1010).unwrap();
+    assert_eq!(name.to_string(), name.raw());
+
+    let map = map.with_demangling();
+    let demangled = map.get(0x1010).unwrap();
+    assert_eq!(demangled.raw(), names[0].1);
+    assert_eq!(demangled.to_string(), "core::ptr::drop_in_place");
+    assert_eq!(*demangled, name);
+    assert_eq!(
+        map.get(0x3000).unwrap().to_string(),
+        "std::rt::lang_start.llvm.42"
+    );
+    assert_eq!(map.get(0x4000).unwrap().to_string(), "main");
+    assert_eq!(map.get(0x5000).unwrap().to_string(), "_ZN3foo$XX$E");
+    assert_eq!(map.get(0x6000).unwrap().to_string(), "_ZN3foo3barEv");
+    assert_eq!(
+        map.get(0x7000).unwrap().to_string(),
+        "_RNvCs1234_7mycrate3foo"
+    );
+}
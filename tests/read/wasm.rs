@@ -0,0 +1,211 @@
+#![cfg(feature = "wasm")]
+
+use object::read::wasm::{
+    ConstExpr, WasmFile, WasmLimits, WasmLinkingSymbol, WasmRelocation, WasmValueType,
+};
+use object::{wasm, Object, ObjectSection, ObjectSymbol, SectionIndex, SectionKind};
+
+#[test]
+fn wasm_memories_tables_globals() {
+    #[rustfmt::skip]
+    let data = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        // Table section: funcref, min 1
+        0x04, 0x04, 0x01, 0x70, 0x00, 0x01,
+        // Memory section: min 1, max 2
+        0x05, 0x04, 0x01, 0x01, 0x01, 0x02,
+        // Global section: mut i32 = i32.const 42, i64 = global.get 0
+        0x06, 0x0b, 0x02, 0x7f, 0x01, 0x41, 0x2a, 0x0b, 0x7e, 0x00, 0x23, 0x00, 0x0b,
+    ];
+    let file = WasmFile::parse(&data[..]).unwrap();
+
+    let mut memories = file.memories().unwrap();
+    let memory = memories.next().unwrap().unwrap();
+    assert_eq!(
+        memory.limits,
+        WasmLimits {
+            initial: 1,
+            maximum: Some(2)
+        }
+    );
+    assert!(!memory.shared);
+    assert!(memories.next().unwrap().is_none());
+
+    let mut tables = file.tables().unwrap();
+    let table = tables.next().unwrap().unwrap();
+    assert_eq!(table.element_type, WasmValueType::FuncRef);
+    assert_eq!(
+        table.limits,
+        WasmLimits {
+            initial: 1,
+            maximum: None
+        }
+    );
+    assert!(tables.next().unwrap().is_none());
+
+    let mut globals = file.globals().unwrap();
+    let global = globals.next().unwrap().unwrap();
+    assert_eq!(global.value_type, WasmValueType::I32);
+    assert!(global.mutable);
+    assert_eq!(global.init, ConstExpr::I32Const(42));
+    let global = globals.next().unwrap().unwrap();
+    assert_eq!(global.value_type, WasmValueType::I64);
+    assert!(!global.mutable);
+    assert_eq!(global.init, ConstExpr::GlobalGet(0));
+    assert!(globals.next().unwrap().is_none());
+
+    #[rustfmt::skip]
+    let data = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        // Memory section: a count of 2, but only 1 memory
+        0x05, 0x03, 0x02, 0x00, 0x01,
+    ];
+    let file = WasmFile::parse(&data[..]).unwrap();
+    let mut memories = file.memories().unwrap();
+    assert!(memories.next().unwrap().is_some());
+    assert!(memories.next().is_err());
+    assert!(memories.next().unwrap().is_none());
+    assert!(file.tables().unwrap().next().unwrap().is_none());
+}
+
+#[test]
+fn wasm_relocations() {
+    #[rustfmt::skip]
+    let data = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        // Type section: () -> ()
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+        // Function section
+        0x03, 0x02, 0x01, 0x00,
+        // Code section: call 0
+        0x0a, 0x06, 0x01, 0x04, 0x00, 0x10, 0x00, 0x0b,
+        // Linking section: defined function "f", undefined data "d"
+        0x00, 0x15, 0x07, b'l', b'i', b'n', b'k', b'i', b'n', b'g', 0x02,
+        0x08, 0x0a, 0x02,
+        0x00, 0x00, 0x00, 0x01, b'f',
+        0x01, 0x10, 0x01, b'd',
+        // Relocations for the code section
+        0x00, 0x14, 0x0a, b'r', b'e', b'l', b'o', b'c', b'.', b'C', b'O', b'D', b'E',
+        0x02, 0x02,
+        0x00, 0x04, 0x00,
+        0x04, 0x01, 0x01, 0x08,
+    ];
+    let file = WasmFile::parse(&data[..]).unwrap();
+
+    let relocations = file.relocations().unwrap();
+    assert_eq!(
+        relocations,
+        [
+            WasmRelocation {
+                section: SectionIndex(2),
+                offset: 4,
+                r_type: wasm::R_WASM_FUNCTION_INDEX_LEB,
+                index: 0,
+                addend: 0,
+            },
+            WasmRelocation {
+                section: SectionIndex(2),
+                offset: 1,
+                r_type: wasm::R_WASM_MEMORY_ADDR_SLEB,
+                index: 1,
+                addend: 8,
+            },
+        ]
+    );
+    // The section index is the position of the target section in the file.
+    let code = file.sections().nth(2).unwrap();
+    assert_eq!(code.kind(), SectionKind::Text);
+
+    let symbols = file.linking_symbols().unwrap();
+    assert_eq!(
+        symbols,
+        [
+            WasmLinkingSymbol {
+                kind: wasm::WASM_SYMBOL_TYPE_FUNCTION,
+                flags: 0,
+                name: Some("f"),
+                index: 0,
+                offset: 0,
+                size: 0,
+            },
+            WasmLinkingSymbol {
+                kind: wasm::WASM_SYMBOL_TYPE_DATA,
+                flags: wasm::WASM_SYM_UNDEFINED,
+                name: Some("d"),
+                index: 0,
+                offset: 0,
+                size: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn wasm_component_sections() {
+    #[rustfmt::skip]
+    let data = [
+        0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00,
+        // Core module section containing an empty module.
+        0x01, 0x08, 0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        // Custom section named "a".
+        0x00, 0x03, 0x01, b'a', 0xff,
+        // Canon section with no entries.
+        0x08, 0x01, 0x00,
+    ];
+    let file = WasmFile::parse(&data[..]).unwrap();
+    assert!(file.is_component());
+    assert_eq!(
+        file.component_sections().collect::<Vec<_>>(),
+        [
+            (wasm::WASM_COMPONENT_SECTION_CORE_MODULE, &data[10..18]),
+            (wasm::WASM_COMPONENT_SECTION_CUSTOM, &data[20..23]),
+            (wasm::WASM_COMPONENT_SECTION_CANON, &data[25..26]),
+        ]
+    );
+    let module = WasmFile::parse(&data[10..18]).unwrap();
+    assert!(!module.is_component());
+    assert_eq!(module.component_sections().count(), 0);
+
+    // The last section is truncated.
+    assert!(WasmFile::parse(&data[..25]).is_err());
+}
+
+#[test]
+fn wasm_read_at_address() {
+    #[rustfmt::skip]
+    let data = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        // Custom section named "a".
+        0x00, 0x06, 0x01, b'a', b'b', b'c', 0x00, 0xff,
+    ];
+    let file = WasmFile::parse(&data[..]).unwrap();
+    assert_eq!(file.read_pod_at_address::<u8>(0), Ok(None));
+    assert_eq!(file.read_pod_at_address::<u32>(0), Ok(None));
+}
+
+#[test]
+fn wasm_entry_with_imports() {
+    #[rustfmt::skip]
+    let data = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00,
+        // Type section: () -> ()
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00,
+        // Import section: function "env" "f"
+        0x02, 0x09, 0x01, 0x03, b'e', b'n', b'v', 0x01, b'f', 0x00, 0x00,
+        // Function section: two functions
+        0x03, 0x03, 0x02, 0x00, 0x00,
+        // Start section: function 2, which is the second local function
+        0x08, 0x01, 0x02,
+        // Code section: two empty functions
+        0x0a, 0x07, 0x02, 0x02, 0x00, 0x0b, 0x02, 0x00, 0x0b,
+    ];
+    let file = WasmFile::parse(&data[..]).unwrap();
+    let second = file
+        .symbols()
+        .filter(|symbol| symbol.section_index() == Some(SectionIndex(10)))
+        .map(|symbol| symbol.address())
+        .nth(1)
+        .unwrap();
+    assert_ne!(second, 0);
+    assert_eq!(file.entry(), second);
+}
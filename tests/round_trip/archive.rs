@@ -0,0 +1,209 @@
+use object::read::archive::{ArchiveFile, ArchiveKind};
+use object::write::archive::{ArchiveFormat, ArchiveWriter};
+use object::{write, Architecture, BinaryFormat, Endianness, SymbolFlags, SymbolKind, SymbolScope};
+
+fn object_with_symbol(name: &[u8]) -> Vec<u8> {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    let offset = object.append_section_data(text, &[0xc3], 1);
+    object.add_symbol(write::Symbol {
+        name: name.to_vec(),
+        value: offset,
+        size: 1,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Dynamic,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    object.write().unwrap()
+}
+
+fn read_u32(data: &[u8], offset: usize, big_endian: bool) -> usize {
+    let bytes = [
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ];
+    if big_endian {
+        u32::from_be_bytes(bytes) as usize
+    } else {
+        u32::from_le_bytes(bytes) as usize
+    }
+}
+
+#[test]
+fn archive_gnu() {
+    let obj1 = object_with_symbol(b"func1");
+    let obj2 = object_with_symbol(b"func2");
+    let mut writer = ArchiveWriter::new(ArchiveFormat::Gnu);
+    writer.add_member(b"a.o".to_vec(), obj1[..].into());
+    writer.add_member(b"a_very_long_member_name.o".to_vec(), obj2[..].into());
+    writer.add_member_with_symbols(b"odd.txt".to_vec(), b"odd"[..].into(), Vec::new());
+    let data = writer.write().unwrap();
+
+    let archive = ArchiveFile::parse(&*data).unwrap();
+    assert_eq!(archive.kind(), ArchiveKind::Gnu);
+    let members = archive.members().map(|m| m.unwrap()).collect::<Vec<_>>();
+    assert_eq!(members.len(), 3);
+    assert_eq!(members[0].name(), b"a.o");
+    assert_eq!(members[0].data(&*data).unwrap(), &obj1[..]);
+    assert_eq!(members[1].name(), b"a_very_long_member_name.o");
+    assert_eq!(members[1].data(&*data).unwrap(), &obj2[..]);
+    assert_eq!(members[2].name(), b"odd.txt");
+    assert_eq!(members[2].data(&*data).unwrap(), b"odd");
+
+    // The symbol table is the first member, and its offsets point to member headers.
+    let symtab = &data[68..];
+    assert_eq!(read_u32(symtab, 0, true), 2);
+    assert_eq!(
+        read_u32(symtab, 4, true) + 60,
+        members[0].file_range().0 as usize
+    );
+    assert_eq!(
+        read_u32(symtab, 8, true) + 60,
+        members[1].file_range().0 as usize
+    );
+    assert_eq!(&symtab[12..24], b"func1\0func2\0");
+}
+
+#[test]
+fn archive_bsd() {
+    let obj1 = object_with_symbol(b"func1");
+    let mut writer = ArchiveWriter::new(ArchiveFormat::Bsd);
+    writer.add_member(b"a_very_long_member_name.o".to_vec(), obj1[..].into());
+    writer.add_member_with_symbols(b"b.o".to_vec(), b"b"[..].into(), vec![b"b".to_vec()]);
+    let data = writer.write().unwrap();
+
+    let archive = ArchiveFile::parse(&*data).unwrap();
+    assert_eq!(archive.kind(), ArchiveKind::Bsd);
+    let members = archive.members().map(|m| m.unwrap()).collect::<Vec<_>>();
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].name(), b"a_very_long_member_name.o");
+    assert_eq!(members[0].data(&*data).unwrap(), &obj1[..]);
+    assert_eq!(members[1].name(), b"b.o");
+    assert_eq!(members[1].data(&*data).unwrap(), b"b");
+
+    let symtab = &data[68..];
+    assert_eq!(read_u32(symtab, 0, false), 16);
+    assert_eq!(read_u32(symtab, 4, false), 0);
+    let member_offset = read_u32(symtab, 8, false);
+    assert_eq!(&data[member_offset..member_offset + 5], b"#1/25");
+    assert_eq!(read_u32(symtab, 12, false), 6);
+    assert_eq!(
+        read_u32(symtab, 16, false),
+        members[1].file_range().0 as usize - 60
+    );
+    assert_eq!(read_u32(symtab, 20, false), 8);
+    assert_eq!(&symtab[24..32], b"func1\0b\0");
+}
+
+#[test]
+fn archive_parse_object() {
+    use object::pe;
+    use object::read::archive::ArchiveObject;
+    use object::read::coff::{ImportName, ImportType};
+    use object::{Object, ObjectSymbol};
+
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    let offset = object.append_section_data(text, &[0xc3], 1);
+    object.add_symbol(write::Symbol {
+        name: b"func1".to_vec(),
+        value: offset,
+        size: 1,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Dynamic,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    let obj = object.write().unwrap();
+
+    // A short import member for `__imp_?func2@@YAXXZ` from `foo.dll`.
+    let strings = b"?func2@@YAXXZ\0foo.dll\0";
+    let mut import = Vec::new();
+    import.extend_from_slice(&0u16.to_le_bytes());
+    import.extend_from_slice(&pe::IMPORT_OBJECT_HDR_SIG2.to_le_bytes());
+    import.extend_from_slice(&0u16.to_le_bytes());
+    import.extend_from_slice(&pe::IMAGE_FILE_MACHINE_AMD64.to_le_bytes());
+    import.extend_from_slice(&0u32.to_le_bytes());
+    import.extend_from_slice(&(strings.len() as u32).to_le_bytes());
+    import.extend_from_slice(&7u16.to_le_bytes());
+    let name_type = pe::IMPORT_OBJECT_CODE | pe::IMPORT_OBJECT_NAME_UNDECORATE << 2;
+    import.extend_from_slice(&name_type.to_le_bytes());
+    import.extend_from_slice(strings);
+
+    let mut writer = ArchiveWriter::new(ArchiveFormat::Gnu);
+    writer.add_member(b"a.obj".to_vec(), obj[..].into());
+    writer.add_member_with_symbols(b"foo.dll".to_vec(), import[..].into(), Vec::new());
+    let data = writer.write().unwrap();
+
+    let archive = ArchiveFile::parse(&*data).unwrap();
+    let mut members = archive.members();
+    let member = members.next().unwrap().unwrap();
+    match member.parse_object(&*data).unwrap() {
+        ArchiveObject::File(file) => {
+            assert_eq!(file.format(), BinaryFormat::Coff);
+            assert!(file.symbols().any(|symbol| symbol.name() == Ok("func1")));
+        }
+        _ => panic!("expected object file"),
+    }
+    let member = members.next().unwrap().unwrap();
+    match member.parse_object(&*data).unwrap() {
+        ArchiveObject::Import(import) => {
+            assert_eq!(import.architecture(), Architecture::X86_64);
+            assert_eq!(import.symbol(), b"?func2@@YAXXZ");
+            assert_eq!(import.dll(), b"foo.dll");
+            assert_eq!(import.import(), ImportName::Name(b"func2"));
+            assert_eq!(import.import_type(), ImportType::Code);
+        }
+        _ => panic!("expected import file"),
+    }
+    assert!(members.next().is_none());
+}
+
+#[test]
+fn container() {
+    use object::macho;
+    use object::read::Container;
+
+    let obj = object_with_symbol(b"func");
+    assert_eq!(Container::parse(&*obj).unwrap(), Container::Single);
+
+    let mut writer = ArchiveWriter::new(ArchiveFormat::Gnu);
+    writer.add_member(b"a.o".to_vec(), obj[..].into());
+    let data = writer.write().unwrap();
+    assert_eq!(
+        Container::parse(&*data).unwrap(),
+        Container::Archive {
+            kind: ArchiveKind::Gnu
+        }
+    );
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&macho::FAT_MAGIC.to_be_bytes());
+    data.extend_from_slice(&2u32.to_be_bytes());
+    for (cputype, offset) in &[
+        (macho::CPU_TYPE_X86_64, 0x1000),
+        (macho::CPU_TYPE_ARM64, 0x2000),
+    ] {
+        data.extend_from_slice(&cputype.to_be_bytes());
+        data.extend_from_slice(&0u32.to_be_bytes());
+        data.extend_from_slice(&(*offset as u32).to_be_bytes());
+        data.extend_from_slice(&0x1000u32.to_be_bytes());
+        data.extend_from_slice(&12u32.to_be_bytes());
+    }
+    assert_eq!(
+        Container::parse(&*data).unwrap(),
+        Container::FatMachO {
+            arches: vec![Architecture::X86_64, Architecture::Aarch64]
+        }
+    );
+
+    // The fat header is truncated.
+    assert!(Container::parse(&data[..30]).is_err());
+}
@@ -1,10 +1,43 @@
 #![cfg(all(feature = "read", feature = "write"))]
 
+use std::convert::TryInto;
+
 use object::read::{Object, ObjectSection, ObjectSymbol};
-use object::{read, write};
 use object::{
-    Architecture, BinaryFormat, Endianness, SectionKind, SymbolFlags, SymbolKind, SymbolScope,
+    pe, Architecture, BinaryFormat, Endianness, SectionKind, SymbolFlags, SymbolKind, SymbolScope,
 };
+use object::{read, write};
+
+#[test]
+fn coff_x86_64_uninitialized_with_raw_data() {
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[1; 0x10], 4);
+    let bss = object.section_id(write::StandardSection::UninitializedData);
+    object.append_section_bss(bss, 0x10, 4);
+    let mut bytes = object.write().unwrap();
+
+    // Flag `.data` as uninitialized. It still has a pointer to raw data.
+    let header = bytes
+        .windows(8)
+        .position(|name| name == b".data\0\0\0")
+        .unwrap();
+    let characteristics = header + 36;
+    let flags = u32::from_le_bytes(bytes[characteristics..][..4].try_into().unwrap());
+    let flags =
+        (flags & !pe::IMAGE_SCN_CNT_INITIALIZED_DATA) | pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA;
+    bytes[characteristics..][..4].copy_from_slice(&flags.to_le_bytes());
+
+    let object = read::File::parse(&*bytes).unwrap();
+    let data = object.section_by_name(".data").unwrap();
+    assert_eq!(data.kind(), SectionKind::UninitializedData);
+    assert!(!data.is_bss());
+    assert_eq!(data.data(), Ok(&[1; 0x10][..]));
+    let bss = object.section_by_name(".bss").unwrap();
+    assert!(bss.is_bss());
+    assert_eq!(bss.data(), Ok(&[][..]));
+}
 
 #[test]
 fn coff_x86_64_bss() {
@@ -54,6 +87,8 @@ fn coff_x86_64_bss() {
     assert_eq!(bss.kind(), SectionKind::UninitializedData);
     assert_eq!(bss.size(), 58);
     assert_eq!(bss.data(), Ok(&[][..]));
+    assert!(bss.is_bss());
+    assert_eq!(bss.uninitialized_size(), bss.size());
 
     let section = sections.next();
     assert!(section.is_none(), "unexpected section {:?}", section);
@@ -139,6 +174,8 @@ fn elf_x86_64_bss() {
     assert_eq!(bss.kind(), SectionKind::UninitializedData);
     assert_eq!(bss.size(), 58);
     assert_eq!(bss.data(), Ok(&[][..]));
+    assert!(bss.is_bss());
+    assert_eq!(bss.uninitialized_size(), bss.size());
 
     let mut symbols = object.symbols();
 
@@ -224,6 +261,8 @@ fn macho_x86_64_bss() {
     assert_eq!(bss.kind(), SectionKind::UninitializedData);
     assert_eq!(bss.size(), 58);
     assert_eq!(bss.data(), Ok(&[][..]));
+    assert!(bss.is_bss());
+    assert_eq!(bss.uninitialized_size(), bss.size());
 
     let section = sections.next();
     assert!(section.is_none(), "unexpected section {:?}", section);
@@ -54,3 +54,154 @@ fn reloc_overflow() {
     }
     assert_eq!(i, 0x10000);
 }
+
+#[test]
+fn section_by_long_name() {
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let section = object.add_section(
+        Vec::new(),
+        b".debug_long_name".to_vec(),
+        object::SectionKind::Debug,
+    );
+    object.append_section_data(section, &[1, 2, 3], 1);
+    let bytes = object.write().unwrap();
+
+    let object = read::File::parse(&*bytes).unwrap();
+    let section = object.section_by_name(".debug_long_name").unwrap();
+    assert_eq!(section.data(), Ok(&[1, 2, 3][..]));
+    // The raw header name is an offset into the string table.
+    assert!(object.section_by_name(".debug_l").is_none());
+}
+
+#[test]
+fn coff_loadable_segments() {
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 1);
+    let bytes = object.write().unwrap();
+
+    let object = read::File::parse(&*bytes).unwrap();
+    assert!(object.segments().next().is_some());
+    assert!(object.loadable_segments().next().is_none());
+}
+
+#[test]
+fn coff_weak_external() {
+    use object::read::ObjectSymbol;
+    use object::{pe, SymbolSection};
+
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 1);
+    let default_symbol = object.add_symbol(write::Symbol {
+        name: b"f_default".to_vec(),
+        value: 0,
+        size: 1,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    object.add_symbol(write::Symbol {
+        name: b"f".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: true,
+        section: write::SymbolSection::Undefined,
+        flags: SymbolFlags::CoffWeakExternal {
+            default_symbol,
+            characteristics: pe::IMAGE_WEAK_EXTERN_SEARCH_ALIAS.into(),
+        },
+    });
+    let bytes = object.write().unwrap();
+
+    let object = read::File::parse(&*bytes).unwrap();
+    let default_symbol = object
+        .symbols()
+        .find(|symbol| symbol.name() == Ok("f_default"))
+        .unwrap();
+    let symbol = object
+        .symbols()
+        .find(|symbol| symbol.name() == Ok("f"))
+        .unwrap();
+    assert_eq!(symbol.section(), SymbolSection::Undefined);
+    assert!(symbol.is_weak());
+    assert_eq!(
+        symbol.flags(),
+        SymbolFlags::CoffWeakExternal {
+            default_symbol: default_symbol.index(),
+            characteristics: pe::IMAGE_WEAK_EXTERN_SEARCH_ALIAS.into(),
+        }
+    );
+
+    let header = pe::ImageFileHeader::parse(&*bytes, &mut 0).unwrap();
+    let symbols = header.symbols(&*bytes).unwrap();
+    let raw = symbols.symbol(symbol.index().0).unwrap();
+    assert_eq!(raw.storage_class, pe::IMAGE_SYM_CLASS_WEAK_EXTERNAL);
+    assert_eq!(raw.number_of_aux_symbols, 1);
+    let aux = symbols.aux_weak_external(symbol.index().0).unwrap();
+    assert_eq!(
+        aux.weak_default_sym_index.get(object::LittleEndian),
+        default_symbol.index().0 as u32
+    );
+
+    // Weak externals must be undefined.
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 1);
+    let default_symbol = object.add_symbol(write::Symbol {
+        name: b"f_default".to_vec(),
+        value: 0,
+        size: 1,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    object.add_symbol(write::Symbol {
+        name: b"f".to_vec(),
+        value: 0,
+        size: 1,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: true,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::CoffWeakExternal {
+            default_symbol,
+            characteristics: pe::IMAGE_WEAK_EXTERN_SEARCH_ALIAS.into(),
+        },
+    });
+    assert!(object.write().is_err());
+
+    // The default symbol must exist.
+    let mut other =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    let mut missing_symbol = None;
+    for _ in 0..10 {
+        missing_symbol = Some(other.add_file_symbol(b"other.c".to_vec()));
+    }
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    object.add_symbol(write::Symbol {
+        name: b"f".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: true,
+        section: write::SymbolSection::Undefined,
+        flags: SymbolFlags::CoffWeakExternal {
+            default_symbol: missing_symbol.unwrap(),
+            characteristics: pe::IMAGE_WEAK_EXTERN_SEARCH_ALIAS.into(),
+        },
+    });
+    assert!(object.write().is_err());
+}
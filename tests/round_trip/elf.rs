@@ -1,8 +1,9 @@
-use object::read::elf::{FileHeader, SectionHeader};
-use object::read::{Object, ObjectSymbol};
+use object::read::elf::{FileHeader, SectionHeader, Sym};
+use object::read::{Object, ObjectSection, ObjectSymbol};
 use object::{
-    elf, read, write, Architecture, BinaryFormat, Endianness, LittleEndian, SectionIndex,
-    SectionKind, SymbolFlags, SymbolKind, SymbolScope, SymbolSection, U32,
+    elf, read, write, Architecture, BinaryFormat, Endianness, LittleEndian, RelocationEncoding,
+    RelocationKind, SectionIndex, SectionKind, SymbolFlags, SymbolKind, SymbolScope, SymbolSection,
+    U32,
 };
 use std::io::Write;
 
@@ -40,6 +41,27 @@ fn symtab_shndx() {
             SymbolSection::Section(SectionIndex(symbol.index().0))
         );
     }
+
+    // The section count and string table index don't fit in the header.
+    let elf = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let endian = elf.endian();
+    let header = elf.raw_header();
+    assert_eq!(header.e_shnum(endian), 0);
+    assert_eq!(header.e_shstrndx(endian), elf::SHN_XINDEX);
+    let sections = header.sections(endian, &*bytes).unwrap();
+    assert!(sections.len() > 0x10000);
+    assert_eq!(
+        header.shstrndx(endian, &*bytes).unwrap() as usize,
+        elf.section_by_name(".shstrtab").unwrap().index().0
+    );
+    let symbols = sections.symbols(endian, &*bytes, elf::SHT_SYMTAB).unwrap();
+    let last = symbols.len() - 1;
+    let symbol = symbols.symbol(last).unwrap();
+    assert_eq!(symbol.st_shndx(endian), elf::SHN_XINDEX);
+    assert_eq!(
+        symbols.symbol_section(endian, symbol, last).unwrap(),
+        Some(SectionIndex(last))
+    );
 }
 
 #[cfg(feature = "compression")]
@@ -121,6 +143,18 @@ fn compression_gnu() {
     let section = object.section_by_name(".zdebug_info").unwrap();
     let uncompressed = section.uncompressed_data().unwrap();
     assert_eq!(data, &*uncompressed);
+
+    // The section can also be found using its logical name.
+    let section = object.section_by_name(".debug_info").unwrap();
+    assert_eq!(section.name().unwrap(), ".zdebug_info");
+    let uncompressed = section.uncompressed_data().unwrap();
+    assert_eq!(data, &*uncompressed);
+
+    let elf = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let section = elf.section_by_name(".zdebug_info").unwrap();
+    assert_eq!(section.logical_name().unwrap(), ".debug_info");
+    let section = elf.section_by_name(".shstrtab").unwrap();
+    assert_eq!(section.logical_name().unwrap(), ".shstrtab");
 }
 
 #[test]
@@ -216,3 +250,2203 @@ fn note() {
     assert_eq!(note.n_type(endian), 2);
     assert!(notes.next().unwrap().is_none());
 }
+
+#[test]
+fn got_relocations() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+
+    writer.reserve_file_header();
+    writer.reserve_program_headers(1);
+
+    writer.reserve_null_section_index();
+    let dynsym_index = writer.reserve_dynsym_section_index();
+    writer.reserve_dynstr_section_index();
+    let rela_plt_name = writer.add_section_name(b".rela.plt");
+    writer.reserve_section_index();
+    let rela_dyn_name = writer.add_section_name(b".rela.dyn");
+    writer.reserve_section_index();
+    writer.reserve_dynamic_section_index();
+    writer.reserve_shstrtab_section_index();
+
+    let foo = writer.add_dynamic_string(b"foo");
+    let bar = writer.add_dynamic_string(b"bar");
+    writer.reserve_null_dynamic_symbol_index();
+    writer.reserve_dynamic_symbol_index();
+    writer.reserve_dynamic_symbol_index();
+    writer.reserve_dynsym();
+    writer.reserve_dynstr();
+    let rela_plt_offset = writer.reserve_relocations(1, true);
+    let rela_dyn_offset = writer.reserve_relocations(2, true);
+    writer.reserve_dynamic(6);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+    let len = writer.reserved_len() as u64;
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_program_headers();
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_LOAD,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: 0,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: len,
+        p_memsz: len,
+        p_align: 0x1000,
+    });
+
+    writer.write_null_dynamic_symbol();
+    for name in &[foo, bar] {
+        writer.write_dynamic_symbol(&object::write::elf::Sym {
+            name: Some(*name),
+            section: None,
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: 0,
+            st_shndx: 0,
+            st_value: 0,
+            st_size: 0,
+        });
+    }
+    writer.write_dynstr();
+
+    writer.write_align_relocation();
+    writer.write_relocation(
+        true,
+        &object::write::elf::Rel {
+            r_offset: 0x3018,
+            r_sym: 1,
+            r_type: elf::R_X86_64_JUMP_SLOT,
+            r_addend: 0,
+        },
+    );
+    writer.write_align_relocation();
+    writer.write_relocation(
+        true,
+        &object::write::elf::Rel {
+            r_offset: 0x3020,
+            r_sym: 2,
+            r_type: elf::R_X86_64_GLOB_DAT,
+            r_addend: 0,
+        },
+    );
+    writer.write_relocation(
+        true,
+        &object::write::elf::Rel {
+            r_offset: 0x3028,
+            r_sym: 0,
+            r_type: elf::R_X86_64_RELATIVE,
+            r_addend: 0x1000,
+        },
+    );
+
+    writer.write_align_dynamic();
+    writer.write_dynamic(elf::DT_JMPREL, rela_plt_offset as u64);
+    writer.write_dynamic(elf::DT_PLTRELSZ, 24);
+    writer.write_dynamic(elf::DT_PLTREL, elf::DT_RELA.into());
+    writer.write_dynamic(elf::DT_RELA, rela_dyn_offset as u64);
+    writer.write_dynamic(elf::DT_RELASZ, 48);
+    writer.write_dynamic(elf::DT_NULL, 0);
+    writer.write_shstrtab();
+
+    writer.write_null_section_header();
+    writer.write_dynsym_section_header(0, 1);
+    writer.write_dynstr_section_header(0);
+    writer.write_relocation_section_header(
+        rela_plt_name,
+        object::write::elf::SectionIndex(0),
+        dynsym_index,
+        rela_plt_offset,
+        1,
+        true,
+    );
+    writer.write_relocation_section_header(
+        rela_dyn_name,
+        object::write::elf::SectionIndex(0),
+        dynsym_index,
+        rela_dyn_offset,
+        2,
+        true,
+    );
+    writer.write_dynamic_section_header(0);
+    writer.write_shstrtab_section_header();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(file.plt_relocations().unwrap(), vec![(0x3018, &b"foo"[..])]);
+    assert_eq!(file.got_relocations().unwrap(), vec![(0x3020, &b"bar"[..])]);
+}
+
+#[test]
+fn interpreter() {
+    let interp = b"/lib64/ld-linux-x86-64.so.2\0";
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_program_headers(1);
+    let interp_offset = writer.reserve(interp.len(), 1);
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_EXEC,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_program_headers();
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_INTERP,
+        p_flags: elf::PF_R,
+        p_offset: interp_offset as u64,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: interp.len() as u64,
+        p_memsz: interp.len() as u64,
+        p_align: 1,
+    });
+    writer.write(interp);
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.interpreter().unwrap(),
+        Some(&b"/lib64/ld-linux-x86-64.so.2"[..])
+    );
+
+    let object = write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.interpreter().unwrap(), None);
+}
+
+#[test]
+fn elf_type() {
+    let build = |e_type| {
+        let code = [0xc3; 0x10];
+        let mut buffer = Vec::new();
+        let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+        writer.set_type(e_type);
+        writer.set_entry(0x40_0000 + 0x78);
+        writer.reserve_file_header();
+        writer.reserve_program_headers(1);
+        let code_offset = writer.reserve(code.len(), 1);
+        let len = writer.reserved_len() as u64;
+        let result = writer.write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_NONE,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        });
+        if result.is_ok() {
+            writer.write_align_program_headers();
+            writer.write_program_header(&object::write::elf::ProgramHeader {
+                p_type: elf::PT_LOAD,
+                p_flags: elf::PF_R | elf::PF_X,
+                p_offset: 0,
+                p_vaddr: 0x40_0000,
+                p_paddr: 0x40_0000,
+                p_filesz: len,
+                p_memsz: len,
+                p_align: 0x1000,
+            });
+            writer.pad_until(code_offset);
+            writer.write(&code);
+        }
+        result.map(|()| buffer)
+    };
+
+    let buffer = build(object::write::elf::ElfType::Exec).unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.raw_header().e_type.get(Endianness::Little),
+        elf::ET_EXEC
+    );
+    assert_eq!(file.entry(), 0x40_0078);
+    assert_eq!(file.raw_segments().len(), 1);
+    assert_eq!(file.raw_header().e_phoff.get(Endianness::Little), 0x40);
+
+    let buffer = build(object::write::elf::ElfType::Rel).unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.raw_header().e_type.get(Endianness::Little),
+        elf::ET_REL
+    );
+    assert_eq!(file.raw_segments().len(), 0);
+    assert_eq!(buffer.len(), 0x40 + 0x10);
+
+    // A shared object needs a dynamic section.
+    assert!(build(object::write::elf::ElfType::Dyn).is_err());
+}
+
+#[test]
+fn stabs() {
+    // The strings of each compilation unit start with an empty string.
+    let unit1 = b"\0a.c\0main:F1\0t:T1=s4x:1,\\\x000,32;;\0";
+    let unit2 = b"\0b.c\0";
+    let mut strings = unit1.to_vec();
+    strings.extend_from_slice(unit2);
+    let mut stab = Vec::new();
+    for &(n_strx, n_type, n_desc, n_value) in &[
+        (1u32, 0u8, 4u16, unit1.len() as u32),
+        (5, 0x24, 1, 0x10),
+        (13, 0x80, 0, 0),
+        (26, 0x80, 0, 0),
+        (1, 0, 1, unit2.len() as u32),
+    ] {
+        stab.extend_from_slice(&n_strx.to_le_bytes());
+        stab.extend_from_slice(&[n_type, 0]);
+        stab.extend_from_slice(&n_desc.to_le_bytes());
+        stab.extend_from_slice(&n_value.to_le_bytes());
+    }
+
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let section = object.add_section(Vec::new(), b".stab".to_vec(), SectionKind::Debug);
+    object.append_section_data(section, &stab, 4);
+    let section = object.add_section(Vec::new(), b".stabstr".to_vec(), SectionKind::Debug);
+    object.append_section_data(section, &strings, 1);
+    let bytes = object.write().unwrap();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let mut stabs = file.stabs().unwrap().unwrap();
+    let mut entries = Vec::new();
+    while let Some(stab) = stabs.next().unwrap() {
+        entries.push((stab.name.into_owned(), stab.n_type, stab.n_value));
+    }
+    assert_eq!(
+        entries,
+        [
+            (b"a.c".to_vec(), 0, unit1.len() as u32),
+            (b"main:F1".to_vec(), 0x24, 0x10),
+            (b"t:T1=s4x:1,0,32;;".to_vec(), 0x80, 0),
+            (b"b.c".to_vec(), 0, unit2.len() as u32),
+        ]
+    );
+
+    let object = write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert!(file.stabs().unwrap().is_none());
+}
+
+#[test]
+fn comment() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let section = object.add_section(Vec::new(), b".comment".to_vec(), SectionKind::OtherString);
+    object.append_section_data(
+        section,
+        b"\0GCC: (GNU) 13.2.0\0clang version 17.0.6\0rustc",
+        1,
+    );
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let comments = file.comment().unwrap().unwrap().collect::<Vec<_>>();
+    assert_eq!(
+        comments,
+        [
+            &b"GCC: (GNU) 13.2.0"[..],
+            &b"clang version 17.0.6"[..],
+            &b"rustc"[..],
+        ]
+    );
+
+    let object = write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert!(file.comment().unwrap().is_none());
+}
+
+#[test]
+fn relocated_section_data() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[0; 16], 8);
+    let data_symbol = object.add_symbol(write::Symbol {
+        name: b"data1".to_vec(),
+        value: 4,
+        size: 4,
+        kind: SymbolKind::Data,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Section(data),
+        flags: SymbolFlags::None,
+    });
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xcc; 16], 4);
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 0,
+                size: 64,
+                kind: RelocationKind::Absolute,
+                encoding: RelocationEncoding::Generic,
+                symbol: data_symbol,
+                addend: 8,
+            },
+        )
+        .unwrap();
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 8,
+                size: 32,
+                kind: RelocationKind::Relative,
+                encoding: RelocationEncoding::Generic,
+                symbol: data_symbol,
+                addend: -4,
+            },
+        )
+        .unwrap();
+    let bytes = object.write().unwrap();
+
+    let file = read::File::parse(&*bytes).unwrap();
+    let bias = 0x10_0000;
+    let text = file.section_by_name(".text").unwrap();
+    let relocated = file.relocated_section_data(&text, bias).unwrap();
+    let mut expected = [0xcc; 16];
+    expected[..8].copy_from_slice(&(bias + 4 + 8).to_le_bytes());
+    expected[8..12].copy_from_slice(&(4i32 - 4 - 8).to_le_bytes());
+    assert_eq!(&*relocated, &expected[..]);
+
+    // Sections without relocations are borrowed.
+    let data = file.section_by_name(".data").unwrap();
+    let relocated = file.relocated_section_data(&data, bias).unwrap();
+    assert!(matches!(relocated, std::borrow::Cow::Borrowed(_)));
+}
+
+#[test]
+fn relocate_instruction_field() {
+    let mut object = write::Object::new(BinaryFormat::Elf, Architecture::Arm, Endianness::Little);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[0; 16], 8);
+    let data_symbol = object.section_symbol(data);
+    let text = object.section_id(write::StandardSection::Text);
+    // movw r0, #0x123
+    object.append_section_data(text, &[0x23, 0x01, 0x00, 0xe3], 4);
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 0,
+                size: 16,
+                kind: RelocationKind::Elf(elf::R_ARM_MOVW_ABS_NC),
+                encoding: RelocationEncoding::Generic,
+                symbol: data_symbol,
+                addend: 0,
+            },
+        )
+        .unwrap();
+    let bytes = object.write().unwrap();
+
+    // The place is an instruction, so it must not be read or written as a data word.
+    let file = read::File::parse(&*bytes).unwrap();
+    let text = file.section_by_name(".text").unwrap();
+    let relocations = file.relocations_with_addends(&text).unwrap();
+    assert_eq!(relocations.len(), 1);
+    let relocation = &relocations[0].1;
+    assert_eq!(relocation.size(), 16);
+    assert!(relocation.has_implicit_addend());
+    assert_eq!(relocation.addend(), 0);
+    assert_eq!(
+        file.relocated_section_data(&text, 0)
+            .unwrap_err()
+            .to_string(),
+        "Unsupported relocation place"
+    );
+    let resolved = file.resolve_reference(&text, 0, 0).unwrap().unwrap();
+    assert_eq!(resolved.addend, 0);
+    assert_eq!(resolved.target_address, None);
+}
+
+#[test]
+fn disassembly_view() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xe8, 0, 0, 0, 0, 0xc3], 4);
+    let callee = object.add_symbol(write::Symbol {
+        name: b"callee".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Dynamic,
+        weak: false,
+        section: write::SymbolSection::Undefined,
+        flags: SymbolFlags::None,
+    });
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 1,
+                size: 32,
+                kind: RelocationKind::Relative,
+                encoding: RelocationEncoding::Generic,
+                symbol: callee,
+                addend: -4,
+            },
+        )
+        .unwrap();
+    // A second relocation for the same place must not hide the first.
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 1,
+                size: 32,
+                kind: RelocationKind::Absolute,
+                encoding: RelocationEncoding::Generic,
+                symbol: callee,
+                addend: 0,
+            },
+        )
+        .unwrap();
+    let bytes = object.write().unwrap();
+
+    let file = read::File::parse(&*bytes).unwrap();
+    let text = file.section_by_name(".text").unwrap();
+    let view = file.disassembly_view(&text).unwrap();
+    assert_eq!(view.data(), [0xe8, 0, 0, 0, 0, 0xc3]);
+    assert!(view.relocation_at(0).is_none());
+    assert!(view.relocation_at(2).is_none());
+    let (kind, target, addend) = view.relocation_at(1).unwrap();
+    assert_eq!(kind, RelocationKind::Relative);
+    assert_eq!(addend, -4);
+    let symbol = match target {
+        read::RelocationTarget::Symbol(index) => file.symbol_by_index(index).unwrap(),
+        _ => panic!("unexpected relocation target {:?}", target),
+    };
+    assert_eq!(symbol.name(), Ok("callee"));
+    let relocations = view.relocations_at(1);
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0].0, RelocationKind::Relative);
+    assert_eq!(relocations[1].0, RelocationKind::Absolute);
+    assert_eq!(relocations[1].2, 0);
+    assert!(view.relocations_at(0).is_empty());
+    assert!(view.relocations_at(5).is_empty());
+}
+
+#[test]
+fn init_array() {
+    fn addresses(
+        mut iter: read::elf::FunctionArrayIterator<elf::FileHeader64<Endianness>>,
+    ) -> Vec<u64> {
+        let mut addresses = Vec::new();
+        while let Some(address) = iter.next().unwrap() {
+            addresses.push(address);
+        }
+        addresses
+    }
+
+    let file_header = object::write::elf::FileHeader {
+        os_abi: 0,
+        abi_version: 0,
+        e_type: elf::ET_DYN,
+        e_machine: elf::EM_X86_64,
+        e_entry: 0,
+        e_flags: 0,
+    };
+    let mut array = Vec::new();
+    array.extend_from_slice(&0x1100u64.to_le_bytes());
+    array.extend_from_slice(&0x1200u64.to_le_bytes());
+
+    // Located using the section headers.
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let name = writer.add_section_name(b".init_array");
+    writer.reserve_section_index();
+    writer.reserve_shstrtab_section_index();
+    let array_offset = writer.reserve(array.len(), 8);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer.write_file_header(&file_header).unwrap();
+    writer.write_align(8);
+    writer.write(&array);
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(name),
+        sh_type: elf::SHT_INIT_ARRAY,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_WRITE).into(),
+        sh_addr: 0x2000,
+        sh_offset: array_offset as u64,
+        sh_size: array.len() as u64,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 8,
+        sh_entsize: 8,
+    });
+    writer.write_shstrtab_section_header();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(addresses(file.init_array().unwrap()), [0x1100, 0x1200]);
+    assert!(addresses(file.fini_array().unwrap()).is_empty());
+    assert_eq!(file.init_function().unwrap(), None);
+
+    // Located using the dynamic entries.
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_program_headers(2);
+    let array_offset = writer.reserve(array.len(), 8);
+    let dynamic_offset = writer.reserved_len();
+    writer.reserve_dynamic(4);
+    let len = writer.reserved_len() as u64;
+
+    writer.write_file_header(&file_header).unwrap();
+    writer.write_align_program_headers();
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_LOAD,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: 0,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: len,
+        p_memsz: len,
+        p_align: 0x1000,
+    });
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_DYNAMIC,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: dynamic_offset as u64,
+        p_vaddr: dynamic_offset as u64,
+        p_paddr: 0,
+        p_filesz: len - dynamic_offset as u64,
+        p_memsz: len - dynamic_offset as u64,
+        p_align: 8,
+    });
+    writer.write_align(8);
+    writer.write(&array);
+    writer.write_align_dynamic();
+    writer.write_dynamic(elf::DT_INIT, 0x1000);
+    writer.write_dynamic(elf::DT_FINI_ARRAY, array_offset as u64);
+    writer.write_dynamic(elf::DT_FINI_ARRAYSZ, array.len() as u64);
+    writer.write_dynamic(elf::DT_NULL, 0);
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert!(addresses(file.init_array().unwrap()).is_empty());
+    assert_eq!(addresses(file.fini_array().unwrap()), [0x1100, 0x1200]);
+    assert_eq!(file.init_function().unwrap(), Some(0x1000));
+    assert_eq!(file.fini_function().unwrap(), None);
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn symhash() {
+    let symbols: &[(&[u8], u8, u8, bool)] = &[
+        (b"puts", elf::STB_GLOBAL, elf::STT_FUNC, false),
+        (b"Printf", elf::STB_GLOBAL, elf::STT_FUNC, false),
+        (b"__cxa_finalize", elf::STB_WEAK, elf::STT_FUNC, false),
+        (b"my_export", elf::STB_GLOBAL, elf::STT_FUNC, true),
+        (b"printf", elf::STB_WEAK, elf::STT_FUNC, true),
+        (b"local_fn", elf::STB_LOCAL, elf::STT_FUNC, true),
+        (b"data_obj", elf::STB_GLOBAL, elf::STT_OBJECT, true),
+    ];
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let text_name = writer.add_section_name(b".text");
+    let text = writer.reserve_section_index();
+    let names = symbols
+        .iter()
+        .map(|(name, ..)| writer.add_dynamic_string(name))
+        .collect::<Vec<_>>();
+    for _ in symbols {
+        writer.reserve_dynamic_symbol_index();
+    }
+    writer.reserve_dynsym_section_index();
+    writer.reserve_dynstr_section_index();
+    writer.reserve_shstrtab_section_index();
+    writer.reserve_dynsym();
+    writer.reserve_dynstr();
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_null_dynamic_symbol();
+    for (&(_, bind, typ, defined), name) in symbols.iter().zip(names) {
+        writer.write_dynamic_symbol(&object::write::elf::Sym {
+            name: Some(name),
+            section: if defined { Some(text) } else { None },
+            st_info: (bind << 4) | typ,
+            st_other: 0,
+            st_shndx: 0,
+            st_value: 0,
+            st_size: 0,
+        });
+    }
+    writer.write_dynstr();
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(text_name),
+        sh_type: elf::SHT_PROGBITS,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+        sh_addr: 0,
+        sh_offset: 0,
+        sh_size: 0,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 1,
+        sh_entsize: 0,
+    });
+    writer.write_dynsym_section_header(0, 1);
+    writer.write_dynstr_section_header(0);
+    writer.write_shstrtab_section_header();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    // md5("data_obj,my_export,printf")
+    assert_eq!(
+        file.symhash().unwrap().as_deref(),
+        Some("29545682639e7ea9475b5342835d13c6")
+    );
+}
+
+#[test]
+fn sub_architecture() {
+    use object::{FileFlags, SubArchitecture};
+
+    for &(arch, endian, e_flags, expected) in &[
+        (Architecture::X86_64, Endianness::Little, 0, None),
+        (Architecture::Arm, Endianness::Little, 0, None),
+        (
+            Architecture::Arm,
+            Endianness::Little,
+            elf::EF_ARM_EABI_VER5,
+            Some(SubArchitecture::ArmEabi(5)),
+        ),
+        (
+            Architecture::Mips,
+            Endianness::Big,
+            elf::EF_MIPS_ABI_O32,
+            Some(SubArchitecture::MipsO32),
+        ),
+        (
+            Architecture::Mips,
+            Endianness::Big,
+            elf::EF_MIPS_ABI2,
+            Some(SubArchitecture::MipsN32),
+        ),
+        (
+            Architecture::Mips64,
+            Endianness::Little,
+            0,
+            Some(SubArchitecture::MipsN64),
+        ),
+        (
+            Architecture::Riscv64,
+            Endianness::Little,
+            elf::EF_RISCV_FLOAT_ABI_DOUBLE,
+            Some(SubArchitecture::RiscvDoubleFloat),
+        ),
+        (
+            Architecture::Riscv32,
+            Endianness::Little,
+            0,
+            Some(SubArchitecture::RiscvSoftFloat),
+        ),
+    ] {
+        let mut object = write::Object::new(BinaryFormat::Elf, arch, endian);
+        object.flags = FileFlags::Elf {
+            os_abi: 0,
+            abi_version: 0,
+            e_flags,
+        };
+        let bytes = object.write().unwrap();
+        let object = read::File::parse(&*bytes).unwrap();
+        assert_eq!(object.format(), BinaryFormat::Elf);
+        assert_eq!(object.architecture(), arch);
+        assert_eq!(
+            object.sub_architecture(),
+            expected,
+            "{:?} {:x}",
+            arch,
+            e_flags
+        );
+    }
+}
+
+#[test]
+fn os_abi() {
+    use object::read::elf::{MipsAbi, OsAbi};
+    use object::FileFlags;
+
+    let mut object = write::Object::new(BinaryFormat::Elf, Architecture::Arm, Endianness::Little);
+    object.flags = FileFlags::Elf {
+        os_abi: elf::ELFOSABI_LINUX,
+        abi_version: 1,
+        e_flags: elf::EF_ARM_EABI_VER5 | elf::EF_ARM_ABI_FLOAT_HARD,
+    };
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile32::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.os_abi(), OsAbi::Linux);
+    assert_eq!(file.abi_version(), 1);
+    assert_eq!(
+        file.e_flags(),
+        elf::EF_ARM_EABI_VER5 | elf::EF_ARM_ABI_FLOAT_HARD
+    );
+    assert_eq!(file.arm_eabi_version(), Some(5));
+    assert_eq!(file.mips_abi(), None);
+
+    let mut object = write::Object::new(BinaryFormat::Elf, Architecture::Mips, Endianness::Big);
+    object.flags = FileFlags::Elf {
+        os_abi: 0x42,
+        abi_version: 0,
+        e_flags: elf::EF_MIPS_ABI_EABI32,
+    };
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile32::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.os_abi(), OsAbi::Unknown(0x42));
+    assert_eq!(file.arm_eabi_version(), None);
+    assert_eq!(file.mips_abi(), Some(MipsAbi::Eabi32));
+
+    let object = write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.os_abi(), OsAbi::SysV);
+    assert_eq!(file.mips_abi(), None);
+}
+
+#[test]
+fn writer_note() {
+    let build_id = [0x12, 0x34, 0x56, 0x78, 0x9a];
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.add_note(b"Linux", 0x100, &[1, 2, 3]);
+    writer.add_build_id(&build_id);
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    writer.reserve_note_section_index(b".note.gnu.build-id");
+    writer.reserve_shstrtab_section_index();
+    writer.reserve_note();
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_EXEC,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_note();
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_note_section_header(0);
+    writer.write_shstrtab_section_header();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(file.build_id().unwrap(), Some(&build_id[..]));
+
+    let endian = file.endian();
+    let section = file.raw_header().section_headers(endian, &*buffer).unwrap()[1];
+    assert_eq!(section.sh_type(endian), elf::SHT_NOTE);
+    assert_eq!(section.sh_size(endian), (12 + 8 + 4) + (12 + 4 + 8));
+    let mut notes = section.notes(endian, &*buffer).unwrap().unwrap();
+    let note = notes.next().unwrap().unwrap();
+    assert_eq!(note.name(), b"Linux");
+    assert_eq!(note.n_namesz(endian), 6);
+    assert_eq!(note.n_type(endian), 0x100);
+    assert_eq!(note.desc(), [1, 2, 3]);
+    let note = notes.next().unwrap().unwrap();
+    assert_eq!(note.name(), elf::ELF_NOTE_GNU);
+    assert_eq!(note.n_type(endian), elf::NT_GNU_BUILD_ID);
+    assert_eq!(note.desc(), build_id);
+    assert!(notes.next().unwrap().is_none());
+}
+
+#[test]
+fn build_id_segment() {
+    let build_id = [0x12, 0x34, 0x56, 0x78, 0x9a];
+
+    // The note is only described by a program header, but there are section headers.
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.add_build_id(&build_id);
+    writer.reserve_file_header();
+    writer.reserve_program_headers(1);
+    writer.reserve_null_section_index();
+    writer.reserve_shstrtab_section_index();
+    let note_offset = writer.reserve_note() as u64;
+    let note_size = writer.reserved_len() as u64 - note_offset;
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_EXEC,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_program_headers();
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_NOTE,
+        p_flags: elf::PF_R,
+        p_offset: note_offset,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: note_size,
+        p_memsz: note_size,
+        p_align: 4,
+    });
+    writer.write_note();
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_shstrtab_section_header();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert!(file.section_by_name(".note.gnu.build-id").is_none());
+    assert_eq!(file.build_id().unwrap(), Some(&build_id[..]));
+}
+
+#[test]
+fn parse_with_kind() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 1);
+    let bytes = object.write().unwrap();
+
+    let kind = read::FileKind::parse(&*bytes).unwrap();
+    assert_eq!(kind, read::FileKind::Elf64);
+    let file = read::File::parse_with_kind(&*bytes, kind).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Elf);
+    assert!(file.is_64());
+
+    for kind in &[
+        read::FileKind::Elf32,
+        read::FileKind::MachO64,
+        read::FileKind::Pe64,
+        read::FileKind::Archive,
+    ] {
+        assert!(read::File::parse_with_kind(&*bytes, *kind).is_err());
+        let error = read::File::parse_staged(&*bytes, *kind).unwrap_err();
+        assert_eq!(error.stage, read::ParseStage::Header, "{:?}", kind);
+    }
+
+    // Point the section headers past the end of the file.
+    let mut invalid = bytes.clone();
+    let len = invalid.len() as u64;
+    invalid[0x28..0x30].copy_from_slice(&len.to_le_bytes());
+    let error = read::File::parse_staged(&*invalid, kind).unwrap_err();
+    assert_eq!(error.stage, read::ParseStage::Sections);
+    assert_eq!(
+        error.to_string(),
+        format!("{} (while parsing sections)", error.detail)
+    );
+    assert_eq!(
+        read::File::parse_with_kind(&*invalid, kind).unwrap_err(),
+        error.detail
+    );
+
+    // The section name string table is part of the section table.
+    let mut invalid = bytes.clone();
+    invalid[0x3e..0x40].copy_from_slice(&0x7fffu16.to_le_bytes());
+    let error = read::File::parse_staged(&*invalid, kind).unwrap_err();
+    assert_eq!(error.stage, read::ParseStage::Sections);
+
+    // Point the symbol table past the end of the file.
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let symtab = file.section_by_name(".symtab").unwrap().index().0;
+    let shoff = file.raw_header().e_shoff(file.endian()) as usize;
+    let offset = shoff + symtab * 0x40 + 0x18;
+    let mut invalid = bytes.clone();
+    invalid[offset..offset + 8].copy_from_slice(&len.to_le_bytes());
+    let error = read::File::parse_staged(&*invalid, kind).unwrap_err();
+    assert_eq!(error.stage, read::ParseStage::Symbols);
+}
+
+#[test]
+fn loadable_segments() {
+    use object::read::{LoadSegment, SegmentPermissions};
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_program_headers(3);
+    let len = writer.reserved_len() as u64;
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_EXEC,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_program_headers();
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_LOAD,
+        p_flags: elf::PF_R | elf::PF_X,
+        p_offset: 0,
+        p_vaddr: 0x40_0000,
+        p_paddr: 0,
+        p_filesz: len,
+        p_memsz: len,
+        p_align: 0x1000,
+    });
+    // Segments that are not `PT_LOAD` are not loadable.
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_GNU_STACK,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: 0,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: 0,
+        p_memsz: 0,
+        p_align: 0x10,
+    });
+    // A segment that is entirely zero filled.
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_LOAD,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: len,
+        p_vaddr: 0x60_0000,
+        p_paddr: 0,
+        p_filesz: 0,
+        p_memsz: 0x2000,
+        p_align: 0x1000,
+    });
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    let segments = file.loadable_segments().collect::<Vec<_>>();
+    assert_eq!(
+        segments,
+        [
+            LoadSegment {
+                address: 0x40_0000,
+                size: len,
+                file_offset: 0,
+                file_size: len,
+                permissions: SegmentPermissions {
+                    read: true,
+                    write: false,
+                    execute: true,
+                },
+            },
+            LoadSegment {
+                address: 0x60_0000,
+                size: 0x2000,
+                file_offset: len,
+                file_size: 0,
+                permissions: SegmentPermissions {
+                    read: true,
+                    write: true,
+                    execute: false,
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn dynamic_symbol_count() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_program_headers(2);
+    let hash_offset = writer.reserved_len();
+    writer.reserve_gnu_hash(1, 2, 3);
+    writer.reserve_dynamic(2);
+    let len = writer.reserved_len() as u64;
+    let dynamic_offset = len as usize - 2 * std::mem::size_of::<elf::Dyn64<Endianness>>();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_program_headers();
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_LOAD,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: 0,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: len,
+        p_memsz: len,
+        p_align: 0x1000,
+    });
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_DYNAMIC,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: dynamic_offset as u64,
+        p_vaddr: dynamic_offset as u64,
+        p_paddr: 0,
+        p_filesz: len - dynamic_offset as u64,
+        p_memsz: len - dynamic_offset as u64,
+        p_align: 8,
+    });
+    // The symbols must be sorted by bucket.
+    let hashes = [0x10, 0x20, 0x11];
+    writer.write_gnu_hash(1, 0, 1, 2, 3, |i| hashes[i as usize]);
+    writer.write_align_dynamic();
+    writer.write_dynamic(elf::DT_GNU_HASH, hash_offset as u64);
+    writer.write_dynamic(elf::DT_NULL, 0);
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    // The null symbol and the 3 hashed symbols.
+    assert_eq!(file.dynamic_symbol_count().unwrap(), Some(4));
+}
+
+#[test]
+fn raw_dynamic_symbol_table() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_program_headers(2);
+    let names = [
+        writer.add_dynamic_string(b"foo"),
+        writer.add_dynamic_string(b"bar"),
+    ];
+    for _ in &names {
+        writer.reserve_dynamic_symbol_index();
+    }
+    let hash_offset = writer.reserved_len();
+    writer.reserve_hash(1, 3);
+    let dynsym_offset = writer.reserved_len();
+    writer.reserve_dynsym();
+    let dynstr_offset = writer.reserved_len();
+    writer.reserve_dynstr();
+    let dynstr_size = writer.reserved_len() - dynstr_offset;
+    writer.reserve_dynamic(5);
+    let len = writer.reserved_len() as u64;
+    let dynamic_offset = len - 5 * std::mem::size_of::<elf::Dyn64<Endianness>>() as u64;
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_program_headers();
+    // Load at a different address than the file offset.
+    let base = 0x10_0000;
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_LOAD,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: 0,
+        p_vaddr: base,
+        p_paddr: 0,
+        p_filesz: len,
+        p_memsz: len,
+        p_align: 0x1000,
+    });
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_DYNAMIC,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: dynamic_offset,
+        p_vaddr: base + dynamic_offset,
+        p_paddr: 0,
+        p_filesz: len - dynamic_offset,
+        p_memsz: len - dynamic_offset,
+        p_align: 8,
+    });
+    writer.write_hash(1, 3, Some);
+    writer.write_null_dynamic_symbol();
+    for &name in &names {
+        writer.write_dynamic_symbol(&object::write::elf::Sym {
+            name: Some(name),
+            section: None,
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: 0,
+            st_shndx: 0,
+            st_value: 0,
+            st_size: 0,
+        });
+    }
+    writer.write_dynstr();
+    writer.write_align_dynamic();
+    writer.write_dynamic(elf::DT_HASH, base + hash_offset as u64);
+    writer.write_dynamic(elf::DT_SYMTAB, base + dynsym_offset as u64);
+    writer.write_dynamic(elf::DT_STRTAB, base + dynstr_offset as u64);
+    writer.write_dynamic(elf::DT_STRSZ, dynstr_size as u64);
+    writer.write_dynamic(elf::DT_NULL, 0);
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert!(file.dynamic_symbol_table().is_none());
+    let symbols = file.raw_dynamic_symbol_table().unwrap().unwrap();
+    assert_eq!(symbols.len(), 3);
+    let names = symbols
+        .iter()
+        .skip(1)
+        .map(|symbol| symbols.symbol_name(Endianness::Little, symbol).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [&b"foo"[..], &b"bar"[..]]);
+}
+
+#[test]
+fn relro() {
+    fn build(relro: Option<(u64, u64)>, pltgot: Option<u64>, jmprel: bool) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+        writer.reserve_file_header();
+        writer.reserve_program_headers(3);
+        writer.reserve_dynamic(3);
+        let len = writer.reserved_len() as u64;
+        let dynamic_offset = len - 3 * std::mem::size_of::<elf::Dyn64<Endianness>>() as u64;
+
+        writer
+            .write_file_header(&object::write::elf::FileHeader {
+                os_abi: 0,
+                abi_version: 0,
+                e_type: elf::ET_DYN,
+                e_machine: elf::EM_X86_64,
+                e_entry: 0,
+                e_flags: 0,
+            })
+            .unwrap();
+        writer.write_align_program_headers();
+        writer.write_program_header(&object::write::elf::ProgramHeader {
+            p_type: elf::PT_LOAD,
+            p_flags: elf::PF_R | elf::PF_W,
+            p_offset: 0,
+            p_vaddr: 0,
+            p_paddr: 0,
+            p_filesz: len,
+            p_memsz: len,
+            p_align: 0x1000,
+        });
+        writer.write_program_header(&object::write::elf::ProgramHeader {
+            p_type: elf::PT_DYNAMIC,
+            p_flags: elf::PF_R | elf::PF_W,
+            p_offset: dynamic_offset,
+            p_vaddr: dynamic_offset,
+            p_paddr: 0,
+            p_filesz: len - dynamic_offset,
+            p_memsz: len - dynamic_offset,
+            p_align: 8,
+        });
+        let (p_type, start, end) = match relro {
+            Some((start, end)) => (elf::PT_GNU_RELRO, start, end),
+            None => (elf::PT_NULL, 0, 0),
+        };
+        writer.write_program_header(&object::write::elf::ProgramHeader {
+            p_type,
+            p_flags: elf::PF_R,
+            p_offset: start,
+            p_vaddr: start,
+            p_paddr: 0,
+            p_filesz: end - start,
+            p_memsz: end - start,
+            p_align: 1,
+        });
+        writer.write_align_dynamic();
+        if let Some(pltgot) = pltgot {
+            writer.write_dynamic(elf::DT_PLTGOT, pltgot);
+        }
+        if jmprel {
+            writer.write_dynamic(elf::DT_JMPREL, 0x1000);
+        }
+        while writer.len() < len as usize {
+            writer.write_dynamic(elf::DT_NULL, 0);
+        }
+        buffer
+    }
+
+    let buffer = build(Some((0x2000, 0x3000)), Some(0x2800), true);
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(file.relro_range().unwrap(), Some(0x2000..0x3000));
+    assert!(file.relro_is_full());
+
+    // The PLT GOT is after the relro region.
+    let buffer = build(Some((0x2000, 0x2800)), Some(0x2800), true);
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(file.relro_range().unwrap(), Some(0x2000..0x2800));
+    assert!(!file.relro_is_full());
+
+    // No PLT GOT, so full relro depends on whether there are lazy PLT relocations.
+    let buffer = build(Some((0x2000, 0x3000)), None, false);
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert!(file.relro_is_full());
+    let buffer = build(Some((0x2000, 0x3000)), None, true);
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert!(!file.relro_is_full());
+
+    let buffer = build(None, Some(0x2800), false);
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert_eq!(file.relro_range().unwrap(), None);
+    assert!(!file.relro_is_full());
+}
+
+#[test]
+fn dynamic_flags() {
+    use object::read::elf::{Df1Flags, DfFlags};
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_program_headers(2);
+    writer.reserve_dynamic(3);
+    let len = writer.reserved_len() as u64;
+    let dynamic_offset = len - 3 * std::mem::size_of::<elf::Dyn64<Endianness>>() as u64;
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_program_headers();
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_LOAD,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: 0,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: len,
+        p_memsz: len,
+        p_align: 0x1000,
+    });
+    writer.write_program_header(&object::write::elf::ProgramHeader {
+        p_type: elf::PT_DYNAMIC,
+        p_flags: elf::PF_R | elf::PF_W,
+        p_offset: dynamic_offset,
+        p_vaddr: dynamic_offset,
+        p_paddr: 0,
+        p_filesz: len - dynamic_offset,
+        p_memsz: len - dynamic_offset,
+        p_align: 8,
+    });
+    writer.write_align_dynamic();
+    writer.write_dynamic(elf::DT_FLAGS, elf::DF_BIND_NOW.into());
+    writer.write_dynamic(elf::DT_FLAGS_1, (elf::DF_1_NOW | elf::DF_1_PIE).into());
+    writer.write_dynamic(elf::DT_NULL, 0);
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    let (flags, flags_1) = file.dynamic_flags().unwrap();
+    assert_eq!(flags, DfFlags::BIND_NOW);
+    assert!(!flags.contains(DfFlags::TEXTREL));
+    assert!(flags_1.contains(Df1Flags::NOW | Df1Flags::PIE));
+    assert!(!flags_1.contains(Df1Flags::NODELETE));
+    assert_eq!(flags_1.bits(), elf::DF_1_NOW | elf::DF_1_PIE);
+}
+
+#[test]
+fn symbol_by_name() {
+    // The first symbol is undefined, so it is not included in the GNU hash table.
+    let symbols: &[(&[u8], bool)] = &[(b"puts", false), (b"foo", true), (b"bar", true)];
+    let symbol_base = 2;
+    let hash_count = symbols.len() as u32 + 1 - symbol_base;
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let text_name = writer.add_section_name(b".text");
+    let text = writer.reserve_section_index();
+    let names = symbols
+        .iter()
+        .map(|(name, _)| writer.add_dynamic_string(name))
+        .collect::<Vec<_>>();
+    for _ in symbols {
+        writer.reserve_dynamic_symbol_index();
+    }
+    writer.reserve_dynsym_section_index();
+    writer.reserve_dynstr_section_index();
+    writer.reserve_gnu_hash_section_index();
+    writer.reserve_shstrtab_section_index();
+    writer.reserve_dynsym();
+    writer.reserve_dynstr();
+    writer.reserve_gnu_hash(1, 1, hash_count);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_null_dynamic_symbol();
+    for (&(_, defined), name) in symbols.iter().zip(names) {
+        writer.write_dynamic_symbol(&object::write::elf::Sym {
+            name: Some(name),
+            section: if defined { Some(text) } else { None },
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: 0,
+            st_shndx: 0,
+            st_value: 0,
+            st_size: 0,
+        });
+    }
+    writer.write_dynstr();
+    writer.write_gnu_hash(symbol_base, 6, 1, 1, hash_count, |i| {
+        elf::gnu_hash(symbols[(symbol_base - 1 + i) as usize].0)
+    });
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(text_name),
+        sh_type: elf::SHT_PROGBITS,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+        sh_addr: 0,
+        sh_offset: 0,
+        sh_size: 0,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 1,
+        sh_entsize: 0,
+    });
+    writer.write_dynsym_section_header(0, 1);
+    writer.write_dynstr_section_header(0);
+    writer.write_gnu_hash_section_header(0);
+    writer.write_shstrtab_section_header();
+
+    let file = read::File::parse(&*buffer).unwrap();
+    let symbol = file.symbol_by_name_bytes(b"bar").unwrap().unwrap();
+    assert_eq!(symbol.index(), object::SymbolIndex(3));
+    assert!(symbol.is_definition());
+    let symbol = file.symbol_by_name_bytes(b"puts").unwrap().unwrap();
+    assert_eq!(symbol.index(), object::SymbolIndex(1));
+    assert!(symbol.is_undefined());
+    assert!(file.contains_symbol(b"foo").unwrap());
+    assert!(!file.contains_symbol(b"baz").unwrap());
+}
+
+#[test]
+fn symbol_binding() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xcc; 16], 4);
+    let symbols: &[(&[u8], u8, u8, u8)] = &[
+        (b"func", elf::STB_GLOBAL, elf::STT_FUNC, elf::STV_DEFAULT),
+        (b"ifunc", elf::STB_WEAK, elf::STT_GNU_IFUNC, elf::STV_HIDDEN),
+        (b"tls", elf::STB_LOCAL, elf::STT_TLS, elf::STV_PROTECTED),
+    ];
+    for &(name, bind, typ, visibility) in symbols {
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value: 0,
+            size: 8,
+            kind: SymbolKind::Unknown,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::Elf {
+                st_info: (bind << 4) | typ,
+                st_other: visibility,
+            },
+        });
+    }
+    let bytes = object.write().unwrap();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    for &(name, bind, typ, visibility) in symbols {
+        let symbol = file
+            .symbols()
+            .find(|symbol| symbol.name_bytes() == Ok(name))
+            .unwrap();
+        assert_eq!(symbol.binding(), bind);
+        assert_eq!(symbol.elf_type(), typ);
+        assert_eq!(symbol.visibility(), visibility);
+        assert_eq!(symbol.size(), 8);
+    }
+    let ifunc = file.symbol_by_name_bytes(b"ifunc").unwrap().unwrap();
+    assert_eq!(ifunc.kind(), SymbolKind::Text);
+    assert!(ifunc.is_definition());
+
+    // Defined IFUNC symbols are included in the symbol map.
+    let map = file.symbol_map();
+    let names = map
+        .symbols()
+        .iter()
+        .map(|symbol| symbol.name())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&"ifunc"), "{:?}", names);
+}
+
+#[test]
+fn ifunc_relocations() {
+    fn ifunc_file(is_64: bool, e_machine: u16, r_type: u32) -> Vec<u8> {
+        let is_rela = is_64;
+        let word_size = if is_64 { 8 } else { 4 };
+
+        let mut buffer = Vec::new();
+        let mut writer = object::write::elf::Writer::new(Endianness::Little, is_64, &mut buffer);
+        writer.reserve_file_header();
+        writer.reserve_program_headers(1);
+        let got_offset = writer.reserve(2 * word_size, word_size);
+        writer.reserve_null_section_index();
+        let name = writer.add_section_name(if is_rela {
+            &b".rela.iplt"[..]
+        } else {
+            &b".rel.iplt"[..]
+        });
+        writer.reserve_section_index();
+        writer.reserve_shstrtab_section_index();
+        let rel_offset = writer.reserve_relocations(2, is_rela);
+        let len = writer.reserved_len() as u64;
+        writer.reserve_shstrtab();
+        writer.reserve_section_headers();
+
+        writer
+            .write_file_header(&object::write::elf::FileHeader {
+                os_abi: 0,
+                abi_version: 0,
+                e_type: elf::ET_EXEC,
+                e_machine,
+                e_entry: 0,
+                e_flags: 0,
+            })
+            .unwrap();
+        writer.write_align_program_headers();
+        writer.write_program_header(&object::write::elf::ProgramHeader {
+            p_type: elf::PT_LOAD,
+            p_flags: elf::PF_R | elf::PF_W,
+            p_offset: 0,
+            p_vaddr: 0,
+            p_paddr: 0,
+            p_filesz: len,
+            p_memsz: len,
+            p_align: 0x1000,
+        });
+        // The implicit addends for `SHT_REL`.
+        writer.write_align(word_size);
+        if is_64 {
+            writer.write(&0x1100u64.to_le_bytes());
+            writer.write(&0x1200u64.to_le_bytes());
+        } else {
+            writer.write(&0x1100u32.to_le_bytes());
+            writer.write(&0x1200u32.to_le_bytes());
+        }
+        writer.write_align_relocation();
+        writer.write_relocation(
+            is_rela,
+            &object::write::elf::Rel {
+                r_offset: got_offset as u64,
+                r_sym: 0,
+                r_type,
+                r_addend: if is_rela { 0x1100 } else { 0 },
+            },
+        );
+        // Other relocation types are ignored.
+        writer.write_relocation(
+            is_rela,
+            &object::write::elf::Rel {
+                r_offset: (got_offset + word_size) as u64,
+                r_sym: 0,
+                r_type: r_type + 1,
+                r_addend: 0,
+            },
+        );
+        writer.write_shstrtab();
+        writer.write_null_section_header();
+        writer.write_section_header(&object::write::elf::SectionHeader {
+            name: Some(name),
+            sh_type: if is_rela { elf::SHT_RELA } else { elf::SHT_REL },
+            sh_flags: elf::SHF_ALLOC.into(),
+            sh_addr: rel_offset as u64,
+            sh_offset: rel_offset as u64,
+            sh_size: (len - rel_offset as u64),
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: word_size as u64,
+            sh_entsize: if is_rela { 3 } else { 2 } * word_size as u64,
+        });
+        writer.write_shstrtab_section_header();
+        buffer
+    }
+
+    let buffer = ifunc_file(true, elf::EM_X86_64, elf::R_X86_64_IRELATIVE);
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    let got = file.ifunc_relocations().unwrap();
+    assert_eq!(got.len(), 1);
+    assert_eq!(got[0].1, 0x1100);
+
+    let buffer = ifunc_file(false, elf::EM_386, elf::R_386_IRELATIVE);
+    let file = read::elf::ElfFile32::<Endianness>::parse(&*buffer).unwrap();
+    let got = file.ifunc_relocations().unwrap();
+    assert_eq!(got.len(), 1);
+    assert_eq!(got[0].1, 0x1100);
+}
+
+#[test]
+fn package_note() {
+    let json = br#"{"type":"rpm","name":"hello","version":"1.0"}"#;
+    let mut desc = json.to_vec();
+    desc.push(0);
+
+    let mut note = Vec::new();
+    note.extend_from_slice(&4u32.to_le_bytes());
+    note.extend_from_slice(&(desc.len() as u32).to_le_bytes());
+    note.extend_from_slice(&elf::NT_FDO_PACKAGING_METADATA.to_le_bytes());
+    note.extend_from_slice(b"FDO\0");
+    note.extend_from_slice(&desc);
+    while note.len() % 4 != 0 {
+        note.push(0);
+    }
+
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 1);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.package_note().unwrap(), None);
+
+    let section = object.add_section(Vec::new(), b".note.package".to_vec(), SectionKind::Note);
+    object.append_section_data(section, &note, 4);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.package_note().unwrap(), Some(&json[..]));
+}
+
+#[test]
+fn go_build_id() {
+    let build_id = b"abcdefghij/klmnopqrst/uvwxyz0123/456789ABCD";
+
+    // The Go linker pads the note name to 4 bytes.
+    let mut note = Vec::new();
+    note.extend_from_slice(&4u32.to_le_bytes());
+    note.extend_from_slice(&(build_id.len() as u32).to_le_bytes());
+    note.extend_from_slice(&elf::NT_GO_BUILD_ID.to_le_bytes());
+    note.extend_from_slice(b"Go\0\0");
+    note.extend_from_slice(build_id);
+    while note.len() % 4 != 0 {
+        note.push(0);
+    }
+
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 1);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.go_build_id().unwrap(), None);
+
+    let section = object.add_section(Vec::new(), b".note.go.buildid".to_vec(), SectionKind::Note);
+    object.append_section_data(section, &note, 4);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.go_build_id().unwrap(), Some(&build_id[..]));
+    assert_eq!(file.build_id().unwrap(), None);
+}
+
+#[test]
+fn section_type() {
+    use object::read::elf::SectionType;
+
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0; 8], 4);
+    let bss = object.section_id(write::StandardSection::UninitializedData);
+    object.append_section_bss(bss, 8, 8);
+    let symbol = object.section_symbol(bss);
+    object
+        .add_relocation(
+            text,
+            write::Relocation {
+                offset: 0,
+                size: 64,
+                kind: RelocationKind::Absolute,
+                encoding: RelocationEncoding::Generic,
+                symbol,
+                addend: 0,
+            },
+        )
+        .unwrap();
+    let bytes = object.write().unwrap();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*bytes).unwrap();
+    let section_type = |name: &str| file.section_by_name(name).unwrap().sh_type_enum();
+    assert_eq!(section_type(".text"), SectionType::Progbits);
+    assert_eq!(section_type(".bss"), SectionType::Nobits);
+    assert_eq!(section_type(".symtab"), SectionType::Symtab);
+    assert_eq!(section_type(".strtab"), SectionType::Strtab);
+    assert_eq!(section_type(".rela.text"), SectionType::Rela);
+    let names = file
+        .relocation_sections()
+        .map(|section| section.name().unwrap().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [".rela.text"]);
+
+    assert_eq!(SectionType::from_sh_type(elf::SHT_RELR), SectionType::Relr);
+    assert!(SectionType::Relr.is_relocation());
+    assert_eq!(
+        SectionType::from_sh_type(elf::SHT_GNU_VERSYM),
+        SectionType::GnuVersym
+    );
+    assert_eq!(
+        SectionType::from_sh_type(0x6000_0001),
+        SectionType::OsSpecific(0x6000_0001)
+    );
+    assert_eq!(
+        SectionType::from_sh_type(elf::SHT_X86_64_UNWIND),
+        SectionType::ProcessorSpecific(elf::SHT_X86_64_UNWIND)
+    );
+    assert_eq!(SectionType::from_sh_type(100), SectionType::Other(100));
+}
+
+#[test]
+fn dynamic_imports_exports() {
+    // Name, defined, binding, visibility.
+    let symbols: &[(&[u8], bool, u8, u8)] = &[
+        (b"puts", false, elf::STB_GLOBAL, elf::STV_DEFAULT),
+        (b"foo", true, elf::STB_GLOBAL, elf::STV_DEFAULT),
+        (b"bar", true, elf::STB_WEAK, elf::STV_PROTECTED),
+        (b"hidden", true, elf::STB_GLOBAL, elf::STV_HIDDEN),
+        (b"local", true, elf::STB_LOCAL, elf::STV_DEFAULT),
+    ];
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let text_name = writer.add_section_name(b".text");
+    let text = writer.reserve_section_index();
+    let names = symbols
+        .iter()
+        .map(|(name, ..)| writer.add_dynamic_string(name))
+        .collect::<Vec<_>>();
+    for _ in symbols {
+        writer.reserve_dynamic_symbol_index();
+    }
+    writer.reserve_dynsym_section_index();
+    writer.reserve_dynstr_section_index();
+    writer.reserve_shstrtab_section_index();
+    writer.reserve_dynsym();
+    writer.reserve_dynstr();
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_null_dynamic_symbol();
+    for (&(_, defined, binding, visibility), name) in symbols.iter().zip(names) {
+        writer.write_dynamic_symbol(&object::write::elf::Sym {
+            name: Some(name),
+            section: if defined { Some(text) } else { None },
+            st_info: (binding << 4) | elf::STT_FUNC,
+            st_other: visibility,
+            st_shndx: 0,
+            st_value: 0,
+            st_size: 0,
+        });
+    }
+    writer.write_dynstr();
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(text_name),
+        sh_type: elf::SHT_PROGBITS,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+        sh_addr: 0,
+        sh_offset: 0,
+        sh_size: 0,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 1,
+        sh_entsize: 0,
+    });
+    writer.write_dynsym_section_header(0, 1);
+    writer.write_dynstr_section_header(0);
+    writer.write_shstrtab_section_header();
+
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    let imports = file
+        .dynamic_imports()
+        .map(|symbol| symbol.name_bytes().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(imports, [&b"puts"[..]]);
+    let exports = file
+        .dynamic_exports()
+        .map(|symbol| symbol.name_bytes().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(exports, [&b"foo"[..], &b"bar"[..]]);
+}
+
+#[test]
+fn pointer_fixups() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let rela_name = writer.add_section_name(b".rela.dyn");
+    writer.reserve_section_index();
+    let relr_name = writer.add_section_name(b".relr.dyn");
+    writer.reserve_section_index();
+    writer.reserve_shstrtab_section_index();
+    let rela_offset = writer.reserve_relocations(3, true);
+    let relr_offset = writer.reserve(16, 8);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align_relocation();
+    for &(r_offset, r_type) in &[
+        (0x2000, elf::R_X86_64_RELATIVE),
+        (0x2008, elf::R_X86_64_64),
+        // Symbol binds are ignored.
+        (0x2010, elf::R_X86_64_GLOB_DAT),
+    ] {
+        writer.write_relocation(
+            true,
+            &object::write::elf::Rel {
+                r_offset,
+                r_sym: 0,
+                r_type,
+                r_addend: 0,
+            },
+        );
+    }
+    // An address entry, followed by a bitmap for the 1st and 3rd following words.
+    writer.write_align(8);
+    writer.write(&0x3000u64.to_le_bytes());
+    writer.write(&0xbu64.to_le_bytes());
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(rela_name),
+        sh_type: elf::SHT_RELA,
+        sh_flags: elf::SHF_ALLOC.into(),
+        sh_addr: 0,
+        sh_offset: rela_offset as u64,
+        sh_size: 3 * 24,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 8,
+        sh_entsize: 24,
+    });
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(relr_name),
+        sh_type: elf::SHT_RELR,
+        sh_flags: elf::SHF_ALLOC.into(),
+        sh_addr: 0,
+        sh_offset: relr_offset as u64,
+        sh_size: 16,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 8,
+        sh_entsize: 8,
+    });
+    writer.write_shstrtab_section_header();
+
+    let file = read::File::parse(&*buffer).unwrap();
+    let fixups = file
+        .pointer_fixups()
+        .unwrap()
+        .map(|fixup| (fixup.address(), fixup.size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fixups,
+        [
+            (0x2000, 8),
+            (0x2008, 8),
+            (0x3000, 8),
+            (0x3008, 8),
+            (0x3018, 8)
+        ]
+    );
+}
+
+#[test]
+fn preferred_address() {
+    fn build(e_type: u16, vaddrs: &[u64]) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+        writer.reserve_file_header();
+        writer.reserve_program_headers(vaddrs.len() as u32);
+        writer
+            .write_file_header(&object::write::elf::FileHeader {
+                os_abi: 0,
+                abi_version: 0,
+                e_type,
+                e_machine: elf::EM_X86_64,
+                e_entry: 0,
+                e_flags: 0,
+            })
+            .unwrap();
+        writer.write_align_program_headers();
+        for &p_vaddr in vaddrs {
+            writer.write_program_header(&object::write::elf::ProgramHeader {
+                p_type: elf::PT_LOAD,
+                p_flags: elf::PF_R,
+                p_offset: 0,
+                p_vaddr,
+                p_paddr: 0,
+                p_filesz: 0,
+                p_memsz: 0x1000,
+                p_align: 0x1000,
+            });
+        }
+        buffer
+    }
+
+    let buffer = build(elf::ET_EXEC, &[0x60_0000, 0x40_0000]);
+    let file = read::File::parse(&*buffer).unwrap();
+    assert_eq!(file.relative_address_base(), 0);
+    assert_eq!(file.preferred_address(), 0x40_0000);
+
+    let buffer = build(elf::ET_DYN, &[0x2000, 0x1000]);
+    let file = read::File::parse(&*buffer).unwrap();
+    assert_eq!(file.relative_address_base(), 0x1000);
+    assert_eq!(file.preferred_address(), 0x1000);
+
+    // A position independent executable that is linked at a non-zero address.
+    let buffer = build(elf::ET_DYN, &[0x40_0000, 0x60_0000]);
+    let file = read::File::parse(&*buffer).unwrap();
+    assert_eq!(file.relative_address_base(), 0x40_0000);
+    assert_eq!(file.preferred_address(), 0x40_0000);
+
+    let buffer = build(elf::ET_REL, &[]);
+    let file = read::File::parse(&*buffer).unwrap();
+    assert_eq!(file.relative_address_base(), 0);
+    assert_eq!(file.preferred_address(), 0);
+}
+
+#[test]
+fn functions() {
+    // A CIE with pc-relative sdata4 pointers, followed by two FDEs.
+    fn fde(eh_frame: &mut Vec<u8>, target: u64, range: u32) {
+        let id_offset = eh_frame.len() as u32 + 4;
+        let pc_begin = target as i64 - (0x2000 + i64::from(id_offset) + 4);
+        eh_frame.extend_from_slice(&16u32.to_le_bytes());
+        eh_frame.extend_from_slice(&id_offset.to_le_bytes());
+        eh_frame.extend_from_slice(&(pc_begin as i32).to_le_bytes());
+        eh_frame.extend_from_slice(&range.to_le_bytes());
+        eh_frame.extend_from_slice(&[0, 0, 0, 0]);
+    }
+    let mut eh_frame = Vec::new();
+    eh_frame.extend_from_slice(&16u32.to_le_bytes());
+    eh_frame.extend_from_slice(&0u32.to_le_bytes());
+    eh_frame.extend_from_slice(&[1, b'z', b'R', 0, 1, 0x78, 16, 1, 0x1b, 0, 0, 0]);
+    fde(&mut eh_frame, 0x1000, 0x10);
+    fde(&mut eh_frame, 0x1020, 0x20);
+    eh_frame.extend_from_slice(&0u32.to_le_bytes());
+
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    let text_name = writer.add_section_name(b".text");
+    let text = writer.reserve_section_index();
+    let eh_frame_name = writer.add_section_name(b".eh_frame");
+    writer.reserve_section_index();
+    writer.reserve_null_symbol_index();
+    let symbols = [(&b"first"[..], 0x1000), (&b"second"[..], 0x1010)];
+    let names = symbols
+        .iter()
+        .map(|&(name, _)| {
+            writer.reserve_symbol_index(Some(text));
+            writer.add_string(name)
+        })
+        .collect::<Vec<_>>();
+    writer.reserve_symtab_section_index();
+    writer.reserve_strtab_section_index();
+    writer.reserve_shstrtab_section_index();
+    let text_offset = writer.reserve(0x40, 16);
+    let eh_frame_offset = writer.reserve(eh_frame.len(), 8);
+    writer.reserve_symtab();
+    writer.reserve_strtab();
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_align(16);
+    writer.write(&[0xcc; 0x40]);
+    writer.write_align(8);
+    writer.write(&eh_frame);
+    writer.write_null_symbol();
+    for (&(_, st_value), name) in symbols.iter().zip(names) {
+        writer.write_symbol(&object::write::elf::Sym {
+            name: Some(name),
+            section: Some(text),
+            st_info: (elf::STB_GLOBAL << 4) | elf::STT_FUNC,
+            st_other: 0,
+            st_shndx: 0,
+            st_value,
+            st_size: 0,
+        });
+    }
+    writer.write_strtab();
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(text_name),
+        sh_type: elf::SHT_PROGBITS,
+        sh_flags: (elf::SHF_ALLOC | elf::SHF_EXECINSTR).into(),
+        sh_addr: 0x1000,
+        sh_offset: text_offset as u64,
+        sh_size: 0x40,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 16,
+        sh_entsize: 0,
+    });
+    writer.write_section_header(&object::write::elf::SectionHeader {
+        name: Some(eh_frame_name),
+        sh_type: elf::SHT_PROGBITS,
+        sh_flags: elf::SHF_ALLOC.into(),
+        sh_addr: 0x2000,
+        sh_offset: eh_frame_offset as u64,
+        sh_size: eh_frame.len() as u64,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 8,
+        sh_entsize: 0,
+    });
+    writer.write_symtab_section_header(1);
+    writer.write_strtab_section_header();
+    writer.write_shstrtab_section_header();
+
+    let file = read::File::parse(&*buffer).unwrap();
+    let function = |name: Option<&str>, address, size| read::Function {
+        name: name.map(String::from),
+        address,
+        size: Some(size),
+    };
+    assert_eq!(
+        file.functions(),
+        [
+            // The size is from the FDE.
+            function(Some("first"), 0x1000, 0x10),
+            // The size is inferred from the next function.
+            function(Some("second"), 0x1010, 0x10),
+            // There is no symbol for the FDE.
+            function(None, 0x1020, 0x20),
+        ]
+    );
+}
+
+#[test]
+fn mips_reginfo() {
+    fn write_mips(
+        is_64: bool,
+        endian: Endianness,
+        sh_type: u32,
+        name: &[u8],
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = object::write::elf::Writer::new(endian, is_64, &mut buffer);
+        writer.reserve_file_header();
+        writer.reserve_null_section_index();
+        let name = writer.add_section_name(name);
+        writer.reserve_section_index();
+        writer.reserve_shstrtab_section_index();
+        let offset = writer.reserve(data.len(), 8);
+        writer.reserve_shstrtab();
+        writer.reserve_section_headers();
+
+        writer
+            .write_file_header(&object::write::elf::FileHeader {
+                os_abi: 0,
+                abi_version: 0,
+                e_type: elf::ET_EXEC,
+                e_machine: elf::EM_MIPS,
+                e_entry: 0,
+                e_flags: 0,
+            })
+            .unwrap();
+        writer.write_align(8);
+        writer.write(data);
+        writer.write_shstrtab();
+        writer.write_null_section_header();
+        writer.write_section_header(&object::write::elf::SectionHeader {
+            name: Some(name),
+            sh_type,
+            sh_flags: elf::SHF_ALLOC.into(),
+            sh_addr: 0,
+            sh_offset: offset as u64,
+            sh_size: data.len() as u64,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 8,
+            sh_entsize: 0,
+        });
+        writer.write_shstrtab_section_header();
+        buffer
+    }
+
+    // A 32-bit `.reginfo` section.
+    let mut data = Vec::new();
+    for value in &[0xf000_00f6u32, 1, 2, 3, 4, 0x8001_7ff0] {
+        data.extend_from_slice(&value.to_be_bytes());
+    }
+    let buffer = write_mips(
+        false,
+        Endianness::Big,
+        elf::SHT_MIPS_REGINFO,
+        b".reginfo",
+        &data,
+    );
+    let file = read::elf::ElfFile32::<Endianness>::parse(&*buffer).unwrap();
+    let reginfo = file.mips_reginfo().unwrap().unwrap();
+    assert_eq!(reginfo.gprmask, 0xf000_00f6);
+    assert_eq!(reginfo.cprmask, [1, 2, 3, 4]);
+    assert_eq!(reginfo.gp_value, 0x8001_7ff0);
+
+    // A 64-bit `.MIPS.options` section with an `ODK_REGINFO` entry after another entry.
+    let mut data = vec![elf::ODK_PAD as u8, 8, 0, 0, 0, 0, 0, 0];
+    data.extend_from_slice(&[elf::ODK_REGINFO as u8, 48, 0, 0, 0, 0, 0, 0]);
+    for value in &[0x1234u32, 0, 5, 6, 7, 8] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&0x1_2000_8000u64.to_le_bytes());
+    let buffer = write_mips(
+        true,
+        Endianness::Little,
+        elf::SHT_MIPS_OPTIONS,
+        b".MIPS.options",
+        &data,
+    );
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    let reginfo = file.mips_reginfo().unwrap().unwrap();
+    assert_eq!(reginfo.gprmask, 0x1234);
+    assert_eq!(reginfo.cprmask, [5, 6, 7, 8]);
+    assert_eq!(reginfo.gp_value, 0x1_2000_8000);
+
+    // A descriptor with a zero size is invalid.
+    let data = [elf::ODK_PAD as u8, 0, 0, 0, 0, 0, 0, 0];
+    let buffer = write_mips(
+        true,
+        Endianness::Little,
+        elf::SHT_MIPS_OPTIONS,
+        b".MIPS.options",
+        &data,
+    );
+    let file = read::elf::ElfFile64::<Endianness>::parse(&*buffer).unwrap();
+    assert!(file.mips_reginfo().is_err());
+
+    let object = write::Object::new(BinaryFormat::Elf, Architecture::Mips, Endianness::Big);
+    let bytes = object.write().unwrap();
+    let file = read::elf::ElfFile32::<Endianness>::parse(&*bytes).unwrap();
+    assert_eq!(file.mips_reginfo().unwrap(), None);
+}
+
+#[test]
+fn needed_libraries() {
+    let mut buffer = Vec::new();
+    let mut writer = object::write::elf::Writer::new(Endianness::Little, true, &mut buffer);
+    writer.reserve_file_header();
+    writer.reserve_null_section_index();
+    writer.reserve_dynstr_section_index();
+    writer.reserve_dynamic_section_index();
+    writer.reserve_shstrtab_section_index();
+    let libc = writer.add_dynamic_string(b"libc.so.6");
+    let libm = writer.add_dynamic_string(b"libm.so.6");
+    let soname = writer.add_dynamic_string(b"libfoo.so.1");
+    writer.reserve_dynstr();
+    writer.reserve_dynamic(5);
+    writer.reserve_shstrtab();
+    writer.reserve_section_headers();
+
+    writer
+        .write_file_header(&object::write::elf::FileHeader {
+            os_abi: 0,
+            abi_version: 0,
+            e_type: elf::ET_DYN,
+            e_machine: elf::EM_X86_64,
+            e_entry: 0,
+            e_flags: 0,
+        })
+        .unwrap();
+    writer.write_dynstr();
+    writer.write_align_dynamic();
+    writer.write_dynamic_string(elf::DT_NEEDED, libc);
+    writer.write_dynamic_string(elf::DT_SONAME, soname);
+    writer.write_dynamic_string(elf::DT_NEEDED, libm);
+    writer.write_dynamic_string(elf::DT_NEEDED, libc);
+    writer.write_dynamic(elf::DT_NULL, 0);
+    writer.write_shstrtab();
+    writer.write_null_section_header();
+    writer.write_dynstr_section_header(0);
+    writer.write_dynamic_section_header(0);
+    writer.write_shstrtab_section_header();
+
+    let file = read::File::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.needed_libraries().unwrap(),
+        [&b"libc.so.6"[..], &b"libm.so.6"[..]]
+    );
+
+    let object = write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let bytes = object.write().unwrap();
+    let file = read::File::parse(&*bytes).unwrap();
+    assert!(file.needed_libraries().unwrap().is_empty());
+}
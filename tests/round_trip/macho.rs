@@ -1,5 +1,5 @@
 use object::read::macho::MachHeader;
-use object::{macho, write, Architecture, BinaryFormat, Endianness};
+use object::{macho, read, write, Architecture, BinaryFormat, Endianness, SectionKind};
 
 #[test]
 // Test that segment size is valid when the first section needs alignment.
@@ -22,3 +22,166 @@ fn issue_286_segment_file_size() {
     assert_eq!(segment.vmsize.get(endian), 30);
     assert_eq!(segment.filesize.get(endian), 30);
 }
+
+#[test]
+fn nlist_fields() {
+    use object::read::{Object, ObjectSymbol};
+    use object::{SymbolFlags, SymbolKind, SymbolScope};
+
+    let mut object = write::Object::new(
+        BinaryFormat::MachO,
+        Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 1);
+    object.add_symbol(write::Symbol {
+        name: b"local".to_vec(),
+        value: 0,
+        size: 1,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Compilation,
+        weak: false,
+        section: write::SymbolSection::Section(text),
+        flags: SymbolFlags::None,
+    });
+    object.add_symbol(write::Symbol {
+        name: b"undef".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Dynamic,
+        weak: false,
+        section: write::SymbolSection::Undefined,
+        flags: SymbolFlags::None,
+    });
+
+    let bytes = object.write().unwrap();
+    let file = object::read::macho::MachOFile64::<Endianness>::parse(&*bytes).unwrap();
+    let local = file.symbols().find(|s| s.name() == Ok("_local")).unwrap();
+    assert!(!local.is_stab());
+    assert!(!local.is_external());
+    assert_eq!(local.symbol_type(), macho::N_SECT);
+    assert_eq!(local.n_type(), macho::N_SECT);
+    assert_eq!(local.n_sect(), 1);
+
+    let undef = file.symbols().find(|s| s.name() == Ok("_undef")).unwrap();
+    assert!(undef.is_external());
+    assert_eq!(undef.symbol_type(), macho::N_UNDF);
+    assert_eq!(undef.n_desc(), 0);
+    assert_eq!(undef.library_ordinal(), macho::SELF_LIBRARY_ORDINAL);
+}
+
+#[test]
+fn section_by_segment_name() {
+    use object::read::ObjectSection;
+
+    let mut object = write::Object::new(
+        BinaryFormat::MachO,
+        Architecture::X86_64,
+        Endianness::Little,
+    );
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3], 1);
+    let data = object.add_section(b"__DATA".to_vec(), b"__text".to_vec(), SectionKind::Data);
+    object.append_section_data(data, &[1, 2], 1);
+
+    let bytes = &*object.write().unwrap();
+    let file = read::macho::MachOFile64::<Endianness>::parse(bytes).unwrap();
+
+    let section = file.section_by_segment_name(b"__TEXT", b"__text").unwrap();
+    assert_eq!(section.data(), Ok(&[0xc3][..]));
+    let section = file.section_by_segment_name(b"__DATA", b"__text").unwrap();
+    assert_eq!(section.data(), Ok(&[1, 2][..]));
+    assert!(file.section_by_segment_name(b"__DATA", b"__data").is_none());
+    assert!(file.section_by_segment_name(b"__TEXT", b"text").is_none());
+}
+
+#[test]
+fn dyld_info() {
+    use object::write::macho::{Bind, DyldInfo, Rebase};
+
+    let mut dyld_info = DyldInfo::new(true);
+    for &offset in &[0x40, 0x10, 0x18] {
+        dyld_info.add_rebase(Rebase {
+            segment: 2,
+            offset,
+            typ: macho::REBASE_TYPE_POINTER,
+        });
+    }
+    let bind = |symbol: &'static [u8], library_ordinal, offset, addend| Bind {
+        segment: 2,
+        offset,
+        typ: macho::BIND_TYPE_POINTER,
+        symbol,
+        flags: 0,
+        library_ordinal,
+        addend,
+    };
+    dyld_info.add_bind(bind(b"_malloc", 1, 0x0, 0));
+    dyld_info.add_bind(bind(b"_malloc", 1, 0x20, 0));
+    dyld_info.add_bind(bind(b"_free", 1, 0x8, 0));
+    dyld_info.add_bind(bind(
+        b"_x",
+        macho::BIND_SPECIAL_DYLIB_FLAT_LOOKUP.into(),
+        0x30,
+        8,
+    ));
+
+    let rebase = [
+        macho::REBASE_OPCODE_SET_TYPE_IMM | macho::REBASE_TYPE_POINTER,
+        macho::REBASE_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 2,
+        0x10,
+        macho::REBASE_OPCODE_DO_REBASE_IMM_TIMES | 2,
+        macho::REBASE_OPCODE_ADD_ADDR_IMM_SCALED | 4,
+        macho::REBASE_OPCODE_DO_REBASE_IMM_TIMES | 1,
+        macho::REBASE_OPCODE_DONE,
+    ];
+    assert_eq!(dyld_info.rebase_opcodes().unwrap(), rebase);
+
+    let mut bind = Vec::new();
+    bind.push(macho::BIND_OPCODE_SET_DYLIB_SPECIAL_IMM | 0xe);
+    bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    bind.extend_from_slice(b"_x\0");
+    bind.push(macho::BIND_OPCODE_SET_TYPE_IMM | macho::BIND_TYPE_POINTER);
+    bind.extend_from_slice(&[macho::BIND_OPCODE_SET_ADDEND_SLEB, 8]);
+    bind.extend_from_slice(&[macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 2, 0x30]);
+    bind.push(macho::BIND_OPCODE_DO_BIND);
+    bind.push(macho::BIND_OPCODE_SET_DYLIB_ORDINAL_IMM | 1);
+    bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    bind.extend_from_slice(b"_free\0");
+    bind.extend_from_slice(&[macho::BIND_OPCODE_SET_ADDEND_SLEB, 0]);
+    bind.extend_from_slice(&[macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 2, 0x8]);
+    bind.push(macho::BIND_OPCODE_DO_BIND);
+    bind.push(macho::BIND_OPCODE_SET_SYMBOL_TRAILING_FLAGS_IMM);
+    bind.extend_from_slice(b"_malloc\0");
+    bind.extend_from_slice(&[macho::BIND_OPCODE_SET_SEGMENT_AND_OFFSET_ULEB | 2, 0]);
+    // The second `_malloc` bind is 3 pointers after the first one.
+    bind.push(macho::BIND_OPCODE_DO_BIND_ADD_ADDR_IMM_SCALED | 3);
+    bind.push(macho::BIND_OPCODE_DO_BIND);
+    bind.push(macho::BIND_OPCODE_DONE);
+    assert_eq!(dyld_info.bind_opcodes().unwrap(), bind);
+
+    let mut linkedit = vec![0xaa; 4];
+    let command = dyld_info
+        .write(Endianness::Little, &mut linkedit, 0x1000)
+        .unwrap();
+    let endian = Endianness::Little;
+    assert_eq!(command.cmd.get(endian), macho::LC_DYLD_INFO_ONLY);
+    assert_eq!(command.rebase_off.get(endian), 0x1004);
+    assert_eq!(command.rebase_size.get(endian), 12);
+    assert_eq!(command.bind_off.get(endian), 0x1010);
+    assert_eq!(linkedit.len() % 8, 0);
+    assert_eq!(&linkedit[4..4 + rebase.len()], &rebase[..]);
+    assert_eq!(&linkedit[0x10..0x10 + bind.len()], &bind[..]);
+    assert_eq!(command.lazy_bind_size.get(endian), 0);
+
+    // The segment index must fit in the opcode immediate.
+    let mut dyld_info = DyldInfo::new(true);
+    dyld_info.add_rebase(Rebase {
+        segment: 16,
+        offset: 0,
+        typ: macho::REBASE_TYPE_POINTER,
+    });
+    assert!(dyld_info.rebase_opcodes().is_err());
+}
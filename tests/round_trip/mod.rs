@@ -7,13 +7,16 @@ use object::{
     SymbolFlags, SymbolKind, SymbolScope, SymbolSection,
 };
 
+mod archive;
 mod bss;
 mod coff;
 mod comdat;
 mod common;
 mod elf;
 mod macho;
+mod pe;
 mod section_flags;
+mod strings;
 mod tls;
 
 #[test]
@@ -446,3 +449,952 @@ fn macho_x86_64() {
     assert_eq!(symbol.name(), "_func1");
     assert_eq!(map.get(func1_offset - 1), None);
 }
+
+#[test]
+fn relocation_number() {
+    let number = |format, architecture, kind, encoding, size| {
+        write::relocation_number(format, architecture, kind, encoding, size)
+    };
+    let generic = RelocationEncoding::Generic;
+
+    assert_eq!(
+        number(
+            BinaryFormat::Elf,
+            Architecture::X86_64,
+            RelocationKind::Relative,
+            generic,
+            32
+        )
+        .unwrap(),
+        object::elf::R_X86_64_PC32
+    );
+    assert_eq!(
+        number(
+            BinaryFormat::Elf,
+            Architecture::Aarch64,
+            RelocationKind::PltRelative,
+            RelocationEncoding::AArch64Call,
+            26
+        )
+        .unwrap(),
+        object::elf::R_AARCH64_CALL26
+    );
+    assert_eq!(
+        number(
+            BinaryFormat::Elf,
+            Architecture::Arm,
+            RelocationKind::Relative,
+            generic,
+            32
+        )
+        .unwrap(),
+        object::elf::R_ARM_REL32
+    );
+    assert_eq!(
+        number(
+            BinaryFormat::Elf,
+            Architecture::Riscv64,
+            RelocationKind::Absolute,
+            generic,
+            64
+        )
+        .unwrap(),
+        object::elf::R_RISCV_64
+    );
+    assert_eq!(
+        number(
+            BinaryFormat::Coff,
+            Architecture::X86_64,
+            RelocationKind::Relative,
+            generic,
+            32
+        )
+        .unwrap(),
+        u32::from(object::pe::IMAGE_REL_AMD64_REL32)
+    );
+    assert_eq!(
+        number(
+            BinaryFormat::MachO,
+            Architecture::X86_64,
+            RelocationKind::PltRelative,
+            RelocationEncoding::X86Branch,
+            32
+        )
+        .unwrap(),
+        u32::from(object::macho::X86_64_RELOC_BRANCH)
+    );
+    assert_eq!(
+        number(
+            BinaryFormat::Elf,
+            Architecture::Mips,
+            RelocationKind::Elf(object::elf::R_MIPS_26),
+            generic,
+            26
+        )
+        .unwrap(),
+        object::elf::R_MIPS_26
+    );
+
+    assert!(number(
+        BinaryFormat::Elf,
+        Architecture::Riscv64,
+        RelocationKind::GotRelative,
+        generic,
+        32
+    )
+    .is_err());
+    assert!(number(
+        BinaryFormat::Coff,
+        Architecture::Riscv64,
+        RelocationKind::Absolute,
+        generic,
+        32
+    )
+    .is_err());
+}
+
+#[test]
+fn stripped_and_debug_symbols() {
+    for format in [BinaryFormat::Coff, BinaryFormat::Elf, BinaryFormat::MachO].iter() {
+        let mut object = write::Object::new(*format, Architecture::X86_64, Endianness::Little);
+        let data = object.section_id(write::StandardSection::Data);
+        let offset = object.append_section_data(data, &[1; 8], 8);
+        object.add_symbol(write::Symbol {
+            name: b"data1".to_vec(),
+            value: offset,
+            size: 8,
+            kind: SymbolKind::Data,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(data),
+            flags: SymbolFlags::None,
+        });
+
+        let bytes = object.write().unwrap();
+        let file = read::File::parse(&*bytes).unwrap();
+        assert!(file.is_stripped(), "{:?}", format);
+        assert!(!file.has_debug_symbols(), "{:?}", format);
+
+        let text = object.section_id(write::StandardSection::Text);
+        let offset = object.append_section_data(text, &[0xc3], 1);
+        object.add_symbol(write::Symbol {
+            name: b"func1".to_vec(),
+            value: offset,
+            size: 1,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Compilation,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+
+        let bytes = object.write().unwrap();
+        let file = read::File::parse(&*bytes).unwrap();
+        assert!(!file.is_stripped(), "{:?}", format);
+        // Local symbols are not debug information.
+        assert!(!file.has_debug_symbols(), "{:?}", format);
+    }
+
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let debug = object.add_section(
+        object.segment_name(write::StandardSegment::Debug).to_vec(),
+        b".debug_line".to_vec(),
+        SectionKind::Debug,
+    );
+    object.append_section_data(debug, &[0; 8], 1);
+    let bytes = object.write().unwrap();
+    let file = read::File::parse(&*bytes).unwrap();
+    assert!(file.is_stripped());
+    assert!(file.has_debug_symbols());
+}
+
+#[test]
+fn patcher() {
+    for &(format, endian) in &[
+        (BinaryFormat::Coff, Endianness::Little),
+        (BinaryFormat::Elf, Endianness::Little),
+        (BinaryFormat::Elf, Endianness::Big),
+        (BinaryFormat::MachO, Endianness::Little),
+    ] {
+        let mut object = write::Object::new(format, Architecture::X86_64, endian);
+        let data = object.section_id(write::StandardSection::Data);
+        object.append_section_data(data, &[0; 16], 8);
+        let bss = object.section_id(write::StandardSection::UninitializedData);
+        object.append_section_bss(bss, 16, 8);
+
+        let mut patcher = object.write_patcher().unwrap();
+        patcher.patch_u32_at(data, 0, 0x1234_5678).unwrap();
+        patcher
+            .patch_u64_at(data, 8, 0x1122_3344_5566_7788)
+            .unwrap();
+        assert!(patcher.patch_u32_at(data, 13, 0).is_err());
+        assert!(patcher.patch_u64_at(data, u64::MAX, 0).is_err());
+        assert!(patcher.patch_u32_at(bss, 0, 0).is_err());
+
+        let bytes = patcher.into_data();
+        let file = read::File::parse(&*bytes).unwrap();
+        let section = file
+            .sections()
+            .find(|section| section.kind() == SectionKind::Data)
+            .unwrap();
+        let mut expected = Vec::new();
+        if endian == Endianness::Little {
+            expected.extend_from_slice(&0x1234_5678u32.to_le_bytes());
+            expected.extend_from_slice(&[0; 4]);
+            expected.extend_from_slice(&0x1122_3344_5566_7788u64.to_le_bytes());
+        } else {
+            expected.extend_from_slice(&0x1234_5678u32.to_be_bytes());
+            expected.extend_from_slice(&[0; 4]);
+            expected.extend_from_slice(&0x1122_3344_5566_7788u64.to_be_bytes());
+        }
+        assert_eq!(section.data().unwrap(), &expected[..], "{:?}", format);
+    }
+}
+
+#[test]
+fn symbol_diff() {
+    fn build(symbols: &[(&str, u64, SymbolScope)]) -> Vec<u8> {
+        let mut object =
+            write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0xc3; 0x40], 1);
+        for &(name, value, scope) in symbols {
+            object.add_symbol(write::Symbol {
+                name: name.as_bytes().to_vec(),
+                value,
+                size: 1,
+                kind: SymbolKind::Text,
+                scope,
+                weak: false,
+                section: write::SymbolSection::Section(text),
+                flags: SymbolFlags::None,
+            });
+        }
+        object.write().unwrap()
+    }
+
+    let old = build(&[
+        ("same", 0, SymbolScope::Dynamic),
+        ("moved", 0x10, SymbolScope::Dynamic),
+        ("gone", 0x20, SymbolScope::Linkage),
+        ("local", 0x30, SymbolScope::Compilation),
+    ]);
+    let new = build(&[
+        ("same", 0, SymbolScope::Dynamic),
+        ("moved", 0x18, SymbolScope::Dynamic),
+        ("new", 0x20, SymbolScope::Dynamic),
+        ("local", 0x38, SymbolScope::Compilation),
+    ]);
+    let old = read::File::parse(&*old).unwrap();
+    let new = read::File::parse(&*new).unwrap();
+
+    let diff = read::symbol_diff(&old, &new, false);
+    assert_eq!(diff.added, [(&b"new"[..], 0x20)]);
+    assert_eq!(diff.removed, [(&b"gone"[..], 0x20)]);
+    assert_eq!(diff.changed, [(&b"moved"[..], 0x10, 0x18)]);
+
+    let diff = read::symbol_diff(&old, &new, true);
+    assert_eq!(
+        diff.changed,
+        [(&b"local"[..], 0x30, 0x38), (&b"moved"[..], 0x10, 0x18)]
+    );
+
+    assert!(read::symbol_diff(&old, &old, true).is_empty());
+}
+
+#[test]
+fn resolve_reference() {
+    for &format in &[BinaryFormat::Elf, BinaryFormat::Coff] {
+        let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+        let data = object.section_id(write::StandardSection::Data);
+        object.append_section_data(data, &[0; 16], 8);
+        let data_symbol = object.add_symbol(write::Symbol {
+            name: b"data1".to_vec(),
+            value: 4,
+            size: 4,
+            kind: SymbolKind::Data,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(data),
+            flags: SymbolFlags::None,
+        });
+        let callee = object.add_symbol(write::Symbol {
+            name: b"callee".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0; 16], 4);
+        for &(offset, size, kind, symbol, addend) in &[
+            (0, 64, RelocationKind::Absolute, data_symbol, 8),
+            (8, 32, RelocationKind::Relative, data_symbol, -4),
+            (12, 32, RelocationKind::Relative, callee, -4),
+        ] {
+            object
+                .add_relocation(
+                    text,
+                    write::Relocation {
+                        offset,
+                        size,
+                        kind,
+                        encoding: RelocationEncoding::Generic,
+                        symbol,
+                        addend,
+                    },
+                )
+                .unwrap();
+        }
+        let bytes = object.write().unwrap();
+
+        let file = read::File::parse(&*bytes).unwrap();
+        let bias = 0x10_0000;
+        let text = file.section_by_name(".text").unwrap();
+        let data = file.section_by_name(".data").unwrap();
+        let data_symbol = file
+            .symbols()
+            .find(|symbol| symbol.name() == Ok("data1"))
+            .unwrap();
+        let data_address = bias + data_symbol.address();
+
+        let resolved = file.resolve_reference(&text, 0, bias).unwrap().unwrap();
+        assert_eq!(resolved.symbol, Some(data_symbol.index()), "{:?}", format);
+        assert_eq!(resolved.section, Some(data.index()));
+        assert_eq!(resolved.addend, 8);
+        assert_eq!(resolved.target_address, Some(data_address + 8));
+
+        let resolved = file.resolve_reference(&text, 8, bias).unwrap().unwrap();
+        assert_eq!(resolved.addend, -4, "{:?}", format);
+        assert_eq!(resolved.target_address, Some(data_address - 4));
+
+        // Undefined symbols have no target address.
+        let resolved = file.resolve_reference(&text, 12, bias).unwrap().unwrap();
+        assert_eq!(resolved.section, None, "{:?}", format);
+        assert_eq!(resolved.target_address, None);
+        let symbol = file.symbol_by_index(resolved.symbol.unwrap()).unwrap();
+        assert_eq!(symbol.name(), Ok("callee"));
+
+        assert_eq!(file.resolve_reference(&text, 4, bias).unwrap(), None);
+
+        // COFF has implicit addends, so the section data must be read. Errors
+        // for invalid data are returned unchanged.
+        if format == BinaryFormat::Coff {
+            let mut bytes = bytes.clone();
+            let header = bytes
+                .windows(8)
+                .position(|name| name == b".text\0\0\0")
+                .unwrap();
+            bytes[header + 20..][..4].copy_from_slice(&0xffff_0000u32.to_le_bytes());
+            let file = read::File::parse(&*bytes).unwrap();
+            let text = file.section_by_name(".text").unwrap();
+            let error = text.data().unwrap_err();
+            assert_eq!(file.resolve_reference(&text, 0, bias), Err(error));
+        }
+    }
+}
+
+#[test]
+fn relocated_section_data_errors() {
+    for &(format, architecture, size, kind, encoding, error) in &[
+        (
+            BinaryFormat::Elf,
+            Architecture::Aarch64,
+            26,
+            RelocationKind::Relative,
+            RelocationEncoding::AArch64Call,
+            "Unsupported AArch64Call relocation encoding",
+        ),
+        (
+            BinaryFormat::Elf,
+            Architecture::X86_64,
+            0,
+            RelocationKind::Elf(object::elf::R_X86_64_NONE),
+            RelocationEncoding::Generic,
+            "Unsupported relocation size",
+        ),
+        (
+            BinaryFormat::Coff,
+            Architecture::X86_64,
+            16,
+            RelocationKind::SectionIndex,
+            RelocationEncoding::Generic,
+            "Unsupported relocation place",
+        ),
+        (
+            BinaryFormat::Elf,
+            Architecture::X86_64,
+            64,
+            RelocationKind::Elf(object::elf::R_X86_64_GLOB_DAT),
+            RelocationEncoding::Generic,
+            "Unsupported ELF relocation type",
+        ),
+        (
+            BinaryFormat::Coff,
+            Architecture::X86_64,
+            32,
+            RelocationKind::Coff(object::pe::IMAGE_REL_AMD64_SREL32),
+            RelocationEncoding::Generic,
+            "Unsupported COFF relocation type",
+        ),
+        (
+            BinaryFormat::Coff,
+            Architecture::X86_64,
+            32,
+            RelocationKind::ImageOffset,
+            RelocationEncoding::Generic,
+            "Unsupported image offset relocation",
+        ),
+        (
+            BinaryFormat::Elf,
+            Architecture::X86_64,
+            32,
+            RelocationKind::GotRelative,
+            RelocationEncoding::Generic,
+            "Unsupported relocation place",
+        ),
+    ] {
+        let mut object = write::Object::new(format, architecture, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0; 16], 4);
+        let symbol = object.section_symbol(text);
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset: 0,
+                    size,
+                    kind,
+                    encoding,
+                    symbol,
+                    addend: 0,
+                },
+            )
+            .unwrap();
+        let bytes = object.write().unwrap();
+
+        let file = read::File::parse(&*bytes).unwrap();
+        let text = file.section_by_name(".text").unwrap();
+        assert_eq!(
+            file.relocated_section_data(&text, 0)
+                .unwrap_err()
+                .to_string(),
+            error,
+            "{:?} {:?}",
+            architecture,
+            kind
+        );
+    }
+}
+
+#[test]
+fn relocations_with_addends() {
+    // Format specific kinds use the size that is derived from their type.
+    for &(format, architecture, specific_kind) in &[
+        (
+            BinaryFormat::Elf,
+            Architecture::I386,
+            RelocationKind::Elf(object::elf::R_386_RELATIVE),
+        ),
+        (
+            BinaryFormat::Elf,
+            Architecture::X86_64,
+            RelocationKind::Elf(object::elf::R_X86_64_RELATIVE),
+        ),
+        (
+            BinaryFormat::Coff,
+            Architecture::X86_64,
+            RelocationKind::Coff(object::pe::IMAGE_REL_AMD64_SREL32),
+        ),
+        (
+            BinaryFormat::MachO,
+            Architecture::X86_64,
+            RelocationKind::Absolute,
+        ),
+    ] {
+        let mut object = write::Object::new(format, architecture, Endianness::Little);
+        let data = object.section_id(write::StandardSection::Data);
+        object.append_section_data(data, &[0; 16], 8);
+        let data_symbol = object.add_symbol(write::Symbol {
+            name: b"data1".to_vec(),
+            value: 4,
+            size: 4,
+            kind: SymbolKind::Data,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(data),
+            flags: SymbolFlags::None,
+        });
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0; 16], 4);
+        for &(offset, kind, addend) in &[
+            (0, RelocationKind::Absolute, 8),
+            (8, RelocationKind::Relative, -4),
+            (12, specific_kind, 16),
+        ] {
+            object
+                .add_relocation(
+                    text,
+                    write::Relocation {
+                        offset,
+                        size: 32,
+                        kind,
+                        encoding: RelocationEncoding::Generic,
+                        symbol: data_symbol,
+                        addend,
+                    },
+                )
+                .unwrap();
+        }
+        let bytes = object.write().unwrap();
+
+        let file = read::File::parse(&*bytes).unwrap();
+        let text = file.section_by_name(".text").unwrap();
+        let mut relocations = file.relocations_with_addends(&text).unwrap();
+        relocations.sort_by_key(|(offset, _)| *offset);
+        let addends = relocations
+            .iter()
+            .map(|(offset, relocation)| {
+                assert!(!relocation.has_implicit_addend(), "{:?}", format);
+                (*offset, relocation.addend())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            addends,
+            [(0, 8), (8, -4), (12, 16)],
+            "{:?} {:?}",
+            format,
+            architecture
+        );
+    }
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn section_hashes() {
+    use object::read::HashAlgo;
+
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, b"abc", 1);
+    let bss = object.section_id(write::StandardSection::UninitializedData);
+    object.append_section_bss(bss, 16, 8);
+    let bytes = object.write().unwrap();
+    let file = read::File::parse(&*bytes).unwrap();
+
+    let text = file.section_by_name(".text").unwrap();
+    assert_eq!(
+        text.hash(HashAlgo::Md5).unwrap(),
+        HashAlgo::Md5.digest(b"abc")
+    );
+    let sha256 = text.hash(HashAlgo::Sha256).unwrap();
+    assert_eq!(sha256.len(), HashAlgo::Sha256.digest_size());
+    assert_eq!(sha256[..4], [0xba, 0x78, 0x16, 0xbf]);
+
+    let bss = file.section_by_name(".bss").unwrap();
+    assert_eq!(
+        bss.hash(HashAlgo::Sha1).unwrap(),
+        HashAlgo::Sha1.digest(&[])
+    );
+
+    let hashes = file.section_hashes(HashAlgo::Sha1).unwrap();
+    assert_eq!(hashes.len(), file.sections().count());
+    assert!(hashes
+        .iter()
+        .any(|(name, hash)| name == ".text" && *hash == HashAlgo::Sha1.digest(b"abc")));
+}
+
+#[test]
+fn symbol_count() {
+    for (format, architecture) in [
+        (BinaryFormat::Coff, Architecture::X86_64),
+        (BinaryFormat::Elf, Architecture::X86_64),
+        (BinaryFormat::MachO, Architecture::X86_64),
+    ]
+    .iter()
+    {
+        let mut object = write::Object::new(*format, *architecture, Endianness::Little);
+        object.add_file_symbol(b"a_long_file_name_with_aux_records.c".to_vec());
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0xc3], 1);
+        object.section_symbol(text);
+        object.add_symbol(write::Symbol {
+            name: b"f".to_vec(),
+            value: 0,
+            size: 1,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+        let bytes = object.write().unwrap();
+
+        let object = read::File::parse(&*bytes).unwrap();
+        let count = object.symbol_count();
+        assert!(object.symbols().next().is_some());
+        for symbol in object.symbols() {
+            assert!(symbol.index().0 < count, "{:?}", format);
+            let by_index = object.symbol_by_index(symbol.index()).unwrap();
+            assert_eq!(by_index.name(), symbol.name());
+        }
+        assert!(object.symbol_by_index(object::SymbolIndex(count)).is_err());
+    }
+
+    // COFF indices include the auxiliary records.
+    let mut object =
+        write::Object::new(BinaryFormat::Coff, Architecture::X86_64, Endianness::Little);
+    object.add_file_symbol(b"a_long_file_name_with_aux_records.c".to_vec());
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    assert_eq!(object.symbols().count(), 1);
+    assert_eq!(object.symbol_count(), 3);
+}
+
+#[test]
+fn parse_options() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0; 16], 1);
+    let symbol = object.add_symbol(write::Symbol {
+        name: b"f".to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: write::SymbolSection::Undefined,
+        flags: SymbolFlags::None,
+    });
+    for offset in &[0, 8] {
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset: *offset,
+                    size: 64,
+                    kind: RelocationKind::Absolute,
+                    encoding: RelocationEncoding::Generic,
+                    symbol,
+                    addend: 0,
+                },
+            )
+            .unwrap();
+    }
+    let bytes = object.write().unwrap();
+
+    let object = read::File::parse(&*bytes).unwrap();
+    let options = read::ParseOptions {
+        max_section_count: object.sections().count(),
+        max_symbol_count: object.symbol_count(),
+        max_relocations: 2,
+        ..Default::default()
+    };
+    read::File::parse_with_options(&*bytes, &options).unwrap();
+    read::File::parse_with_options(&*bytes, &read::ParseOptions::strict()).unwrap();
+    for options in &[
+        read::ParseOptions {
+            max_section_count: options.max_section_count - 1,
+            ..options
+        },
+        read::ParseOptions {
+            max_symbol_count: options.max_symbol_count - 1,
+            ..options
+        },
+        read::ParseOptions {
+            max_relocations: 1,
+            ..options
+        },
+    ] {
+        assert!(read::File::parse_with_options(&*bytes, options).is_err());
+    }
+}
+
+#[test]
+fn producer() {
+    for format in [BinaryFormat::Coff, BinaryFormat::Elf, BinaryFormat::MachO].iter() {
+        let mut object = write::Object::new(*format, Architecture::X86_64, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0xc3], 1);
+        object.set_producer("tool 1.0");
+        object.set_producer("tool 2.0");
+        let bytes = object.write().unwrap();
+
+        let object = read::File::parse(&*bytes).unwrap();
+        let comment = object.section_by_name(".comment");
+        if *format == BinaryFormat::MachO {
+            assert!(comment.is_none());
+            continue;
+        }
+        let comment = comment.unwrap();
+        assert_eq!(comment.data().unwrap(), b"tool 1.0\0tool 2.0\0");
+        if *format == BinaryFormat::Elf {
+            assert_eq!(
+                comment.flags(),
+                object::SectionFlags::Elf {
+                    sh_flags: u64::from(object::elf::SHF_MERGE | object::elf::SHF_STRINGS),
+                }
+            );
+        } else {
+            assert_eq!(
+                comment.flags(),
+                object::SectionFlags::Coff {
+                    characteristics: object::pe::IMAGE_SCN_LNK_INFO
+                        | object::pe::IMAGE_SCN_LNK_REMOVE
+                        | object::pe::IMAGE_SCN_ALIGN_1BYTES,
+                }
+            );
+        }
+    }
+}
+
+#[test]
+fn parse_at() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0x90, 0xc3], 1);
+    let bytes = object.write().unwrap();
+
+    let mut blob = vec![0xff; 0x123];
+    blob.extend_from_slice(&bytes);
+
+    let file = read::File::parse_at(&blob, 0x123).unwrap();
+    assert_eq!(file.format(), BinaryFormat::Elf);
+    assert_eq!(file.base_offset(), 0x123);
+    let section = file.section_by_name(".text").unwrap();
+    assert_eq!(section.data().unwrap(), [0x90, 0xc3]);
+    let (offset, size) = section.file_range().unwrap();
+    let offset = (file.base_offset() + offset) as usize;
+    assert_eq!(&blob[offset..][..size as usize], [0x90, 0xc3]);
+
+    let file = read::File::parse(&*bytes).unwrap();
+    assert_eq!(file.base_offset(), 0);
+
+    assert!(read::File::parse_at(&blob, 0).is_err());
+    assert!(read::File::parse_at(&blob, blob.len() as u64 + 1).is_err());
+}
+
+#[test]
+fn trailing_data() {
+    for format in [BinaryFormat::Elf, BinaryFormat::MachO].iter() {
+        let mut object = write::Object::new(*format, Architecture::X86_64, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0xc3], 1);
+        object.add_symbol(write::Symbol {
+            name: b"f".to_vec(),
+            value: 0,
+            size: 1,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+        let mut bytes = object.write().unwrap();
+
+        let file = read::File::parse(&*bytes).unwrap();
+        assert_eq!(file.trailing_data().unwrap(), None, "{:?}", format);
+
+        bytes.extend_from_slice(b"payload");
+        let file = read::File::parse(&*bytes).unwrap();
+        assert_eq!(
+            file.trailing_data().unwrap(),
+            Some(&b"payload"[..]),
+            "{:?}",
+            format
+        );
+    }
+}
+
+#[test]
+fn describe_address() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, &[0xc3; 16], 16);
+    let data = object.section_id(write::StandardSection::Data);
+    object.append_section_data(data, &[0; 32], 8);
+    for &(name, section, value, kind) in &[
+        (&b"f"[..], text, 0, SymbolKind::Text),
+        (&b"g"[..], text, 8, SymbolKind::Text),
+        (&b"v"[..], data, 4, SymbolKind::Data),
+    ] {
+        object.add_symbol(write::Symbol {
+            name: name.to_vec(),
+            value,
+            size: 0,
+            kind,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(section),
+            flags: SymbolFlags::None,
+        });
+    }
+    let bytes = object.write().unwrap();
+    let file = read::File::parse(&*bytes).unwrap();
+
+    let description = file.describe_address(10);
+    assert_eq!(description.section, Some((".text".to_string(), 10)));
+    assert_eq!(description.symbol, Some(("g", 2)));
+
+    // Only `.data` contains this address. The symbols are those of `symbol_map`,
+    // which does not consider sections.
+    let description = file.describe_address(20);
+    assert_eq!(description.section, Some((".data".to_string(), 20)));
+    assert_eq!(description.symbol, Some(("g", 12)));
+
+    let description = file.describe_address(0x100);
+    assert_eq!(description.section, None);
+    assert_eq!(description.symbol, Some(("g", 0xf8)));
+
+    let map = file.address_map();
+    assert_eq!(map.describe(10), file.describe_address(10));
+    assert_eq!(map.describe(0x100), file.describe_address(0x100));
+}
+
+#[test]
+fn all_relocations() {
+    for &format in &[BinaryFormat::Elf, BinaryFormat::Coff, BinaryFormat::MachO] {
+        let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+        let data = object.section_id(write::StandardSection::Data);
+        object.append_section_data(data, &[0; 16], 8);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0; 16], 4);
+        let symbol = object.add_symbol(write::Symbol {
+            name: b"data1".to_vec(),
+            value: 4,
+            size: 4,
+            kind: SymbolKind::Data,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Section(data),
+            flags: SymbolFlags::None,
+        });
+        for &(section, offset, kind) in &[
+            (text, 0, RelocationKind::Absolute),
+            (text, 8, RelocationKind::Relative),
+            (data, 8, RelocationKind::Absolute),
+        ] {
+            object
+                .add_relocation(
+                    section,
+                    write::Relocation {
+                        offset,
+                        size: 32,
+                        kind,
+                        encoding: RelocationEncoding::Generic,
+                        symbol,
+                        addend: 0,
+                    },
+                )
+                .unwrap();
+        }
+        let bytes = object.write().unwrap();
+
+        let file = read::File::parse(&*bytes).unwrap();
+        let text = file.section_by_name(".text").unwrap().index();
+        let data = file.section_by_name(".data").unwrap().index();
+        let mut relocations = file
+            .all_relocations()
+            .unwrap()
+            .iter()
+            .map(|(section, offset, relocation)| (section.0, *offset, relocation.kind()))
+            .collect::<Vec<_>>();
+        relocations.sort_unstable_by_key(|&(section, offset, _)| (section, offset));
+        let mut expected = vec![
+            (text.0, 0, RelocationKind::Absolute),
+            (text.0, 8, RelocationKind::Relative),
+            (data.0, 8, RelocationKind::Absolute),
+        ];
+        expected.sort_unstable_by_key(|&(section, offset, _)| (section, offset));
+        assert_eq!(relocations, expected, "{:?}", format);
+    }
+}
+
+#[test]
+fn format_relocation_sizes() {
+    for &(format, architecture, kind, size) in &[
+        (
+            BinaryFormat::Elf,
+            Architecture::X86_64,
+            RelocationKind::Elf(object::elf::R_X86_64_GOTPCRELX),
+            32,
+        ),
+        (
+            BinaryFormat::Elf,
+            Architecture::X86_64,
+            RelocationKind::Elf(object::elf::R_X86_64_TPOFF64),
+            64,
+        ),
+        (
+            BinaryFormat::Elf,
+            Architecture::Aarch64,
+            RelocationKind::Elf(object::elf::R_AARCH64_ADR_PREL_PG_HI21),
+            21,
+        ),
+        (
+            BinaryFormat::Elf,
+            Architecture::Aarch64,
+            RelocationKind::Elf(object::elf::R_AARCH64_LDST64_ABS_LO12_NC),
+            12,
+        ),
+        (
+            BinaryFormat::Coff,
+            Architecture::X86_64,
+            RelocationKind::Coff(object::pe::IMAGE_REL_AMD64_TOKEN),
+            32,
+        ),
+        (
+            BinaryFormat::MachO,
+            Architecture::X86_64,
+            RelocationKind::MachO {
+                value: object::macho::X86_64_RELOC_TLV,
+                relative: true,
+            },
+            32,
+        ),
+    ] {
+        let mut object = write::Object::new(format, architecture, Endianness::Little);
+        let text = object.section_id(write::StandardSection::Text);
+        object.append_section_data(text, &[0; 16], 4);
+        let symbol = object.add_symbol(write::Symbol {
+            name: b"func1".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        object
+            .add_relocation(
+                text,
+                write::Relocation {
+                    offset: 4,
+                    size,
+                    kind,
+                    encoding: RelocationEncoding::Generic,
+                    symbol,
+                    addend: 0,
+                },
+            )
+            .unwrap();
+        let bytes = object.write().unwrap();
+
+        let file = read::File::parse(&*bytes).unwrap();
+        let text = file.section_by_name(".text").unwrap();
+        let relocations = text
+            .relocations()
+            .map(|(offset, relocation)| (offset, relocation.kind(), relocation.size()))
+            .collect::<Vec<_>>();
+        assert_eq!(relocations, [(4, kind, size)], "{:?}", kind);
+    }
+}
@@ -0,0 +1,2119 @@
+use std::mem;
+
+use object::read::pe::{
+    self as pe_read, ChpeCodeRange, ChpeCodeType, DllCharacteristics, EntryPointInfo,
+    ExDllCharacteristics, ExportTarget, FileCharacteristics, ImageNtHeaders, ImageOptionalHeader,
+    ImageTlsDirectory, Machine, Packer, PeFile32, PeFile64, SectionAnomaly, SectionAnomalyKind,
+    Subsystem, VcFeature,
+};
+use object::read::{Export, Object, ObjectSection};
+use object::{pe, write, LittleEndian, RelocationKind, SectionIndex};
+
+fn nt_headers() -> write::pe::NtHeaders {
+    write::pe::NtHeaders {
+        machine: pe::IMAGE_FILE_MACHINE_AMD64,
+        time_date_stamp: 0,
+        characteristics: pe::IMAGE_FILE_EXECUTABLE_IMAGE | pe::IMAGE_FILE_DLL,
+        major_linker_version: 0,
+        minor_linker_version: 0,
+        address_of_entry_point: 0,
+        image_base: 0x1_8000_0000,
+        major_operating_system_version: 0,
+        minor_operating_system_version: 0,
+        major_image_version: 0,
+        minor_image_version: 0,
+        major_subsystem_version: 0,
+        minor_subsystem_version: 0,
+        subsystem: pe::IMAGE_SUBSYSTEM_WINDOWS_CUI,
+        dll_characteristics: 0,
+        size_of_stack_reserve: 0,
+        size_of_stack_commit: 0,
+        size_of_heap_reserve: 0,
+        size_of_heap_commit: 0,
+    }
+}
+
+#[test]
+fn exports() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(2);
+    let text = writer.reserve_text_section(0x10);
+    writer.add_export("zeta", text.virtual_address);
+    writer.add_export("alpha", text.virtual_address + 4);
+    writer.add_export("mid", text.virtual_address + 8);
+    assert!(writer.has_exports());
+    writer.reserve_export_section(b"test.dll");
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xc3; 0x10]);
+    writer.write_export_section().unwrap();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let table = file.export_table().unwrap().unwrap();
+    let dll_name = table.dll_name().unwrap();
+    assert_eq!(dll_name, b"test.dll");
+    assert_eq!(table.ordinal_base(), 1);
+    assert_eq!(table.ordinal_range(), 1..4);
+    let directory = table.directory();
+    assert_eq!(directory.number_of_functions.get(LittleEndian), 3);
+    assert_eq!(directory.number_of_names.get(LittleEndian), 3);
+    let names = table
+        .name_iter()
+        .map(|(name, index)| (table.name_from_pointer(name).unwrap(), index))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [(&b"alpha"[..], 1), (&b"mid"[..], 2), (&b"zeta"[..], 0)]
+    );
+
+    let export = |name: &[u8]| {
+        file.exports()
+            .unwrap()
+            .into_iter()
+            .find(|export: &Export| export.name() == name)
+            .map(|export| export.address())
+    };
+    let base = 0x1_8000_0000 + u64::from(text.virtual_address);
+    assert_eq!(export(b"zeta"), Some(base));
+    assert_eq!(export(b"alpha"), Some(base + 4));
+    assert_eq!(export(b"mid"), Some(base + 8));
+    assert_eq!(
+        table.address_by_ordinal(3).unwrap(),
+        text.virtual_address + 8
+    );
+
+    let exports = table.exports().unwrap();
+    assert_eq!(exports[2].ordinal, 3);
+    assert_eq!(&*exports[2].name_or_ordinal(dll_name), b"mid");
+    let unnamed = pe_read::Export {
+        ordinal: 7,
+        name: None,
+        target: ExportTarget::Address(0),
+    };
+    assert_eq!(&*unnamed.name_or_ordinal(dll_name), b"test.#7");
+    assert_eq!(&*unnamed.name_or_ordinal(b"test"), b"test.#7");
+}
+
+#[test]
+fn too_many_exports() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(2);
+    let text = writer.reserve_text_section(0x10);
+    // Ordinal table entries are 16 bits, so only 0x10000 exports can be named.
+    for i in 0..0x10001 {
+        writer.add_export(&format!("f{}", i), text.virtual_address);
+    }
+    writer.reserve_export_section(b"test.dll");
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xc3; 0x10]);
+    assert!(writer.write_export_section().is_err());
+}
+
+#[test]
+fn uninitialized_section_with_raw_data() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(2);
+    let data = writer.reserve_section(
+        *b".data\0\0\0",
+        pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA | pe::IMAGE_SCN_MEM_READ | pe::IMAGE_SCN_MEM_WRITE,
+        0x10,
+        0x10,
+    );
+    writer.reserve_bss_section(0x10);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(data.file_offset, &[1; 0x10]);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let data = file.section_by_name(".data").unwrap();
+    assert!(!data.is_bss());
+    assert_eq!(data.uninitialized_size(), 0);
+    assert_eq!(data.data().unwrap(), &[1; 0x10][..]);
+    let bss = file.section_by_name(".bss").unwrap();
+    assert!(bss.is_bss());
+    assert_eq!(bss.uninitialized_size(), 0x10);
+}
+
+#[test]
+fn section_anomalies() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(4);
+    let text = writer.reserve_text_section(0x10);
+    writer.reserve_section(
+        *b"UPX0\0\0\0\0",
+        pe::IMAGE_SCN_CNT_UNINITIALIZED_DATA | pe::IMAGE_SCN_MEM_EXECUTE | pe::IMAGE_SCN_MEM_READ,
+        0x10000,
+        0,
+    );
+    let bss = writer.reserve_bss_section(0x10000);
+    let rdata = writer.reserve_rdata_section(0x10);
+    assert_eq!(bss.file_size, 0);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xc3; 0x10]);
+    writer.write_section(rdata.file_offset, &[0; 0x10]);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let anomalies = file.section_anomalies();
+    assert_eq!(
+        anomalies,
+        [
+            SectionAnomaly {
+                index: SectionIndex(2),
+                kind: SectionAnomalyKind::LargeVirtualSize,
+            },
+            SectionAnomaly {
+                index: SectionIndex(2),
+                kind: SectionAnomalyKind::NonStandardName,
+            },
+        ]
+    );
+    let standard = file
+        .section_table()
+        .iter()
+        .map(|section| file.section_name_is_standard(section))
+        .collect::<Vec<_>>();
+    assert_eq!(standard, [true, false, true, true]);
+    assert_eq!(file.nonstandard_section_count(), 1);
+
+    // Names with non-printable bytes are never standard.
+    let header = buffer
+        .windows(8)
+        .position(|name| name == b".bss\0\0\0\0")
+        .unwrap();
+    buffer[header..][..8].copy_from_slice(b".b\x01ss\0\0\0");
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.nonstandard_section_count(), 2);
+    buffer[header..][..8].copy_from_slice(b".bss\0\0\0\0");
+
+    // Move the `.rdata` raw data so that it overlaps `.text` and extends past the end.
+    let header = buffer
+        .windows(8)
+        .position(|name| name == b".rdata\0\0")
+        .unwrap();
+    buffer[header + 16..][..4].copy_from_slice(&0x10000u32.to_le_bytes());
+    buffer[header + 20..][..4].copy_from_slice(&text.file_offset.to_le_bytes());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let anomalies = file.section_anomalies();
+    assert!(anomalies.contains(&SectionAnomaly {
+        index: SectionIndex(4),
+        kind: SectionAnomalyKind::RawDataPastEnd,
+    }));
+    assert!(anomalies.contains(&SectionAnomaly {
+        index: SectionIndex(4),
+        kind: SectionAnomalyKind::OverlappingRawData(SectionIndex(1)),
+    }));
+
+    // Also overlap `UPX0` with `.text`. Each section is only reported once.
+    let header = buffer
+        .windows(8)
+        .position(|name| name == b"UPX0\0\0\0\0")
+        .unwrap();
+    buffer[header + 16..][..4].copy_from_slice(&0x200u32.to_le_bytes());
+    buffer[header + 20..][..4].copy_from_slice(&text.file_offset.to_le_bytes());
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let overlaps = file
+        .section_anomalies()
+        .into_iter()
+        .filter(|anomaly| matches!(anomaly.kind, SectionAnomalyKind::OverlappingRawData(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        overlaps,
+        [
+            SectionAnomaly {
+                index: SectionIndex(2),
+                kind: SectionAnomalyKind::OverlappingRawData(SectionIndex(1)),
+            },
+            SectionAnomaly {
+                index: SectionIndex(4),
+                kind: SectionAnomalyKind::OverlappingRawData(SectionIndex(1)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn pogo_info() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(0x100);
+    writer.set_data_directory(pe::IMAGE_DIRECTORY_ENTRY_DEBUG, rdata.virtual_address, 28);
+
+    let mut pogo = Vec::new();
+    pogo.extend_from_slice(&pe::IMAGE_POGO_SIGNATURE_LTCG.to_le_bytes());
+    for (rva, size, name) in &[
+        (0x1000u32, 0x20u32, &b".text$mn"[..]),
+        (0x1020, 0x8, b".text$x"),
+        (0x2000, 0x4, b".rdata"),
+    ] {
+        pogo.extend_from_slice(&rva.to_le_bytes());
+        pogo.extend_from_slice(&size.to_le_bytes());
+        pogo.extend_from_slice(name);
+        pogo.push(0);
+        while pogo.len() % 4 != 0 {
+            pogo.push(0);
+        }
+    }
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&[0; 12]);
+    data.extend_from_slice(&pe::IMAGE_DEBUG_TYPE_POGO.to_le_bytes());
+    data.extend_from_slice(&(pogo.len() as u32).to_le_bytes());
+    data.extend_from_slice(&(rdata.virtual_address + 28).to_le_bytes());
+    data.extend_from_slice(&(rdata.file_offset + 28).to_le_bytes());
+    data.extend_from_slice(&pogo);
+    data.resize(0x100, 0);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, &data);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let info = file.pogo_info().unwrap().unwrap();
+    assert_eq!(info.signature(), pe::IMAGE_POGO_SIGNATURE_LTCG);
+    let mut entries = info.entries();
+    let mut names = Vec::new();
+    while let Some(entry) = entries.next().unwrap() {
+        names.push((entry.rva, entry.size, entry.name));
+    }
+    assert_eq!(
+        names,
+        [
+            (0x1000, 0x20, &b".text$mn"[..]),
+            (0x1020, 0x8, &b".text$x"[..]),
+            (0x2000, 0x4, &b".rdata"[..]),
+        ]
+    );
+    assert!(file.reproducible_hash().unwrap().is_none());
+}
+
+#[test]
+fn reproducible_hash() {
+    let build = |hash: &[u8], size: u32| {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(1);
+        let rdata = writer.reserve_rdata_section(0x100);
+        writer.set_data_directory(pe::IMAGE_DIRECTORY_ENTRY_DEBUG, rdata.virtual_address, 28);
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&pe::IMAGE_DEBUG_TYPE_REPRO.to_le_bytes());
+        data.extend_from_slice(&size.to_le_bytes());
+        data.extend_from_slice(&(rdata.virtual_address + 28).to_le_bytes());
+        data.extend_from_slice(&(rdata.file_offset + 28).to_le_bytes());
+        data.extend_from_slice(hash);
+        data.resize(0x100, 0);
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(nt_headers());
+        writer.write_section_headers();
+        writer.write_section(rdata.file_offset, &data);
+        buffer
+    };
+
+    // The entry data is the hash length followed by the hash.
+    let mut data = 32u32.to_le_bytes().to_vec();
+    data.extend((0..32).map(|i| i as u8));
+    let buffer = build(&data, data.len() as u32);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.reproducible_hash().unwrap(), Some(&data[4..]));
+    assert!(file.is_reproducible_build().unwrap());
+
+    // The linker may not record the hash.
+    let buffer = build(&[], 0);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.reproducible_hash().unwrap(), Some(&[][..]));
+    assert!(file.is_reproducible_build().unwrap());
+
+    // The hash length must be within the entry data.
+    let buffer = build(&data, 20);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert!(file.reproducible_hash().is_err());
+    assert!(file.is_reproducible_build().is_err());
+}
+
+#[test]
+fn vc_feature() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(0x100);
+    writer.set_data_directory(
+        pe::IMAGE_DIRECTORY_ENTRY_DEBUG,
+        rdata.virtual_address,
+        2 * 28,
+    );
+
+    let mut data = Vec::new();
+    let mut debug_directory = |typ: u32, offset: u32, size: u32| {
+        data.extend_from_slice(&[0; 12]);
+        data.extend_from_slice(&typ.to_le_bytes());
+        data.extend_from_slice(&size.to_le_bytes());
+        data.extend_from_slice(&(rdata.virtual_address + offset).to_le_bytes());
+        data.extend_from_slice(&(rdata.file_offset + offset).to_le_bytes());
+    };
+    debug_directory(pe::IMAGE_DEBUG_TYPE_VC_FEATURE, 56, 20);
+    debug_directory(pe::IMAGE_DEBUG_TYPE_EX_DLLCHARACTERISTICS, 76, 4);
+    for value in &[0u32, 10, 9, 2, 37] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(
+        &(pe::IMAGE_DLLCHARACTERISTICS_EX_CET_COMPAT
+            | pe::IMAGE_DLLCHARACTERISTICS_EX_FORWARD_CFI_COMPAT)
+            .to_le_bytes(),
+    );
+    data.resize(0x100, 0);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, &data);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.vc_feature().unwrap(),
+        Some(VcFeature {
+            pre_vc11: 0,
+            c_cpp: 10,
+            gs: 9,
+            sdl: 2,
+            guard_n: 37,
+        })
+    );
+    let flags = file.ex_dll_characteristics().unwrap().unwrap();
+    assert!(flags.cet_compat());
+    assert!(flags.contains(ExDllCharacteristics::FORWARD_CFI_COMPAT));
+    assert!(!flags.contains(ExDllCharacteristics::CET_COMPAT_STRICT_MODE));
+    assert!(file.pogo_info().unwrap().is_none());
+}
+
+#[test]
+fn iat() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(0x100);
+    // The size includes a trailing partial slot.
+    writer.set_data_directory(
+        pe::IMAGE_DIRECTORY_ENTRY_IAT,
+        rdata.virtual_address + 0x10,
+        3 * 8 + 4,
+    );
+
+    let mut data = vec![0; 0x10];
+    for value in &[0x1_8000_2000u64, 0, pe::IMAGE_ORDINAL_FLAG64 | 7] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.resize(0x100, 0xff);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, &data);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let iat = file.iat().unwrap().unwrap();
+    assert_eq!(iat, &data[0x10..0x10 + 28]);
+    let base = rdata.virtual_address + 0x10;
+    let mut entries = file.iat_entries().unwrap();
+    assert_eq!(entries.next().unwrap(), Some((base, 0x1_8000_2000)));
+    assert_eq!(entries.next().unwrap(), Some((base + 8, 0)));
+    assert_eq!(
+        entries.next().unwrap(),
+        Some((base + 16, pe::IMAGE_ORDINAL_FLAG64 | 7))
+    );
+    assert_eq!(entries.next().unwrap(), None);
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn imphash() {
+    // Each library has a list of imports, which are either a name or an ordinal.
+    type Library<'a> = (&'a [u8], &'a [Result<&'a [u8], u16>]);
+    let build = |libraries: &[Library]| {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(1);
+        let idata = writer.reserve_idata_section(0x200);
+        let rva = idata.virtual_address;
+
+        // The descriptors are followed by the thunks, and then the names.
+        let thunks_offset = (libraries.len() + 1) * 20;
+        let names_offset = thunks_offset
+            + libraries
+                .iter()
+                .map(|(_, imports)| (imports.len() + 1) * 8)
+                .sum::<usize>();
+        let mut descriptors = Vec::new();
+        let mut thunks = Vec::new();
+        let mut names = Vec::new();
+        for (dll, imports) in libraries {
+            let thunk = rva + (thunks_offset + thunks.len()) as u32;
+            descriptors.extend_from_slice(&thunk.to_le_bytes());
+            descriptors.extend_from_slice(&[0; 8]);
+            descriptors
+                .extend_from_slice(&(rva + (names_offset + names.len()) as u32).to_le_bytes());
+            descriptors.extend_from_slice(&thunk.to_le_bytes());
+            names.extend_from_slice(dll);
+            names.push(0);
+            for import in imports.iter() {
+                let thunk = match import {
+                    Ok(name) => {
+                        let thunk = u64::from(rva + (names_offset + names.len()) as u32);
+                        names.extend_from_slice(&[0, 0]);
+                        names.extend_from_slice(name);
+                        names.push(0);
+                        thunk
+                    }
+                    Err(ordinal) => pe::IMAGE_ORDINAL_FLAG64 | u64::from(*ordinal),
+                };
+                thunks.extend_from_slice(&thunk.to_le_bytes());
+            }
+            thunks.extend_from_slice(&[0; 8]);
+        }
+        let mut data = descriptors;
+        data.resize(thunks_offset, 0);
+        data.extend_from_slice(&thunks);
+        data.extend_from_slice(&names);
+        data.resize(0x200, 0);
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(nt_headers());
+        writer.write_section_headers();
+        writer.write_section(idata.file_offset, &data);
+        buffer
+    };
+
+    let buffer = build(&[
+        (
+            b"KERNEL32.dll",
+            &[Ok(b"GetProcAddress"), Ok(b"ExitProcess")],
+        ),
+        (b"WS2_32.dll", &[Err(115), Err(98), Err(999)]),
+        (b"OLEAUT32.dll", &[Err(411), Err(428), Err(9999)]),
+        (b"wsock32.dll", &[Err(60)]),
+    ]);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    // This is the imphash computed by `pefile`:
+    // md5("kernel32.getprocaddress,kernel32.exitprocess,ws2_32.wsastartup,ws2_32.getaddrinfo,
+    // ws2_32.ord999,oleaut32.safearraycreatevector,oleaut32.varui8fromi8,oleaut32.ord9999,
+    // wsock32.wsaioctl")
+    assert_eq!(
+        file.imphash().unwrap().as_deref(),
+        Some("f53ee2c6efa5c1dcb15c8955413beb20")
+    );
+
+    // A descriptor without imports gives the digest of the empty string.
+    let buffer = build(&[(b"KERNEL32.dll", &[])]);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.imphash().unwrap().as_deref(),
+        Some("d41d8cd98f00b204e9800998ecf8427e")
+    );
+
+    // No descriptors gives no hash.
+    let buffer = build(&[]);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.imphash().unwrap(), None);
+}
+
+#[test]
+fn import_consistency() {
+    let build = |iat: Option<(u32, u32)>| {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(1);
+        let idata = writer.reserve_idata_section(0x100);
+        let rva = idata.virtual_address;
+        if let Some((offset, size)) = iat {
+            writer.set_data_directory(pe::IMAGE_DIRECTORY_ENTRY_IAT, rva + offset, size);
+        }
+
+        let mut data = Vec::new();
+        for &(thunks, name) in &[(64, 112), (88, 112)] {
+            data.extend_from_slice(&0u32.to_le_bytes());
+            data.extend_from_slice(&[0; 8]);
+            data.extend_from_slice(&(rva + name).to_le_bytes());
+            data.extend_from_slice(&(rva + thunks).to_le_bytes());
+        }
+        data.resize(64, 0);
+        for thunk in &[
+            pe::IMAGE_ORDINAL_FLAG64 | 1,
+            pe::IMAGE_ORDINAL_FLAG64 | 2,
+            0,
+            pe::IMAGE_ORDINAL_FLAG64 | 3,
+            0,
+        ] {
+            data.extend_from_slice(&thunk.to_le_bytes());
+        }
+        data.resize(112, 0);
+        data.extend_from_slice(b"KERNEL32.dll\0");
+        data.resize(0x100, 0);
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(nt_headers());
+        writer.write_section_headers();
+        writer.write_section(idata.file_offset, &data);
+        (buffer, rva)
+    };
+
+    let ranges = |ranges: &[std::ops::Range<u32>]| {
+        ranges
+            .iter()
+            .map(|range| (range.start, range.end))
+            .collect::<Vec<_>>()
+    };
+
+    let (buffer, rva) = build(Some((64, 40)));
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let consistency = file.import_consistency().unwrap();
+    assert!(consistency.is_consistent());
+    assert_eq!(consistency.iat, Some(rva + 64..rva + 104));
+    assert_eq!(ranges(&consistency.thunks), [(rva + 64, rva + 104)]);
+
+    // The IAT directory is larger than the thunks, and doesn't cover the start.
+    let (buffer, rva) = build(Some((72, 48)));
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let consistency = file.import_consistency().unwrap();
+    assert!(!consistency.is_consistent());
+    assert_eq!(ranges(&consistency.unused), [(rva + 104, rva + 120)]);
+    assert_eq!(ranges(&consistency.outside), [(rva + 64, rva + 72)]);
+
+    let (buffer, rva) = build(None);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let consistency = file.import_consistency().unwrap();
+    assert!(!consistency.is_consistent());
+    assert_eq!(consistency.iat, None);
+    assert_eq!(ranges(&consistency.outside), [(rva + 64, rva + 104)]);
+}
+
+#[test]
+fn read_pod_at_address() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(0x200);
+
+    let mut data = vec![0; 0x200];
+    data[0x10..0x18].copy_from_slice(&[0x78, 0x56, 0x34, 0x12, 0x20, 0, 0, 0]);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, &data);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let address = 0x1_8000_0000 + u64::from(rdata.virtual_address);
+    let dir = file
+        .read_pod_at_address::<pe::ImageDataDirectory>(address + 0x10)
+        .unwrap()
+        .unwrap();
+    assert_eq!(dir.virtual_address.get(LittleEndian), 0x1234_5678);
+    assert_eq!(dir.size.get(LittleEndian), 0x20);
+
+    // The value must be entirely within the section data.
+    assert!(file
+        .read_pod_at_address::<pe::ImageDataDirectory>(address + 0x1f8)
+        .unwrap()
+        .is_some());
+    assert!(file
+        .read_pod_at_address::<pe::ImageDataDirectory>(address + 0x1fc)
+        .unwrap()
+        .is_none());
+    assert!(file
+        .read_pod_at_address::<pe::ImageDataDirectory>(0x1000)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn read_cstr_at_address() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(0x200);
+
+    let mut data = vec![0; 0x200];
+    data[0x10..0x16].copy_from_slice(b"hello\0");
+    data[0x1fc..].copy_from_slice(b"tail");
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, &data);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let address = 0x1_8000_0000 + u64::from(rdata.virtual_address);
+    assert_eq!(
+        file.read_cstr_at_address(address + 0x10, 0x100).unwrap(),
+        Some(&b"hello"[..])
+    );
+    assert_eq!(
+        file.read_cstr_at_address(address + 0x10, 3).unwrap(),
+        Some(&b"hel"[..])
+    );
+    // The string is bounded by the end of the section data.
+    assert_eq!(
+        file.read_cstr_at_address(address + 0x1fc, 0x100).unwrap(),
+        Some(&b"tail"[..])
+    );
+    assert_eq!(
+        file.read_cstr_at_address(address + 0x200, 0x100).unwrap(),
+        None
+    );
+    assert_eq!(file.read_cstr_at_address(0x1000, 0x100).unwrap(), None);
+}
+
+#[test]
+fn entry_point_analysis() {
+    fn build(entry: u32) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(2);
+        let text = writer.reserve_text_section(0x10);
+        let rdata = writer.reserve_rdata_section(0x10);
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(write::pe::NtHeaders {
+            address_of_entry_point: entry,
+            ..nt_headers()
+        });
+        writer.write_section_headers();
+        writer.write_section(text.file_offset, &[0xc3; 0x10]);
+        writer.write_section(rdata.file_offset, &[0; 0x10]);
+        buffer
+    }
+
+    let buffer = build(0x1000);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.entry_point_analysis(),
+        EntryPointInfo {
+            address: 0x1000,
+            section: Some(SectionIndex(1)),
+            entry_section_not_executable: false,
+            entry_at_section_start: true,
+            entry_at_section_end: false,
+            entry_in_last_section: false,
+        }
+    );
+
+    // The section size is rounded up to the file alignment.
+    let buffer = build(0x2000 + 0x1f8);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.entry_point_analysis(),
+        EntryPointInfo {
+            address: 0x21f8,
+            section: Some(SectionIndex(2)),
+            entry_section_not_executable: true,
+            entry_at_section_start: false,
+            entry_at_section_end: true,
+            entry_in_last_section: true,
+        }
+    );
+
+    let buffer = build(0x8000);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let info = file.entry_point_analysis();
+    assert_eq!(info.section, None);
+    assert!(!info.entry_in_last_section);
+}
+
+#[test]
+fn detect_packer() {
+    const CODE: u32 = pe::IMAGE_SCN_CNT_CODE | pe::IMAGE_SCN_MEM_EXECUTE | pe::IMAGE_SCN_MEM_READ;
+
+    fn build(names: &[[u8; 8]], text: &[u8], entry: u32) -> Vec<u8> {
+        let sections = names.iter().map(|&name| (name, CODE)).collect::<Vec<_>>();
+        build_with_characteristics(&sections, text, entry)
+    }
+
+    fn build_with_characteristics(sections: &[([u8; 8], u32)], text: &[u8], entry: u32) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(sections.len() as u16);
+        let ranges = sections
+            .iter()
+            .map(|&(name, characteristics)| {
+                writer.reserve_section(name, characteristics, text.len() as u32, text.len() as u32)
+            })
+            .collect::<Vec<_>>();
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(write::pe::NtHeaders {
+            address_of_entry_point: ranges[0].virtual_address + entry,
+            ..nt_headers()
+        });
+        writer.write_section_headers();
+        for range in &ranges {
+            writer.write_section(range.file_offset, text);
+        }
+        buffer
+    }
+
+    // Pseudo-random data has an entropy close to 8 bits per byte.
+    let mut state = 1u32;
+    let random = (0..0x1000)
+        .map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (state >> 16) as u8
+        })
+        .collect::<Vec<_>>();
+    let code = [0xc3; 0x1000];
+
+    let buffer = build(&[*b"UPX0\0\0\0\0", *b"UPX1\0\0\0\0"], &code, 0);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.detect_packer(), Some(Packer::Upx));
+
+    let buffer = build(&[*b".text\0\0\0", *b".nsp1\0\0\0"], &code, 0);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.detect_packer(), Some(Packer::Nspack));
+
+    let buffer = build(&[*b".text\0\0\0"], &code, 0);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.detect_packer(), None);
+
+    let buffer = build(&[*b".text\0\0\0"], &random, 0);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.detect_packer(), Some(Packer::Generic));
+
+    // An entry point outside of the first section is not enough by itself.
+    let mut buffer = build(&[*b".text\0\0\0", *b".stub\0\0\0"], &code, 0x1000);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.detect_packer(), None);
+
+    // The entry section has no raw data.
+    let header = buffer
+        .windows(8)
+        .position(|name| name == b".stub\0\0\0")
+        .unwrap();
+    buffer[header + 16..][..4].copy_from_slice(&0u32.to_le_bytes());
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.detect_packer(), Some(Packer::Generic));
+
+    // The entry section is writable.
+    let buffer = build_with_characteristics(
+        &[
+            (*b".text\0\0\0", CODE),
+            (*b".stub\0\0\0", CODE | pe::IMAGE_SCN_MEM_WRITE),
+        ],
+        &code,
+        0x1000,
+    );
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.detect_packer(), Some(Packer::Generic));
+}
+
+#[test]
+fn loadable_segments() {
+    use object::read::{LoadSegment, SegmentPermissions};
+
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(3);
+    let text = writer.reserve_text_section(0x10);
+    let data = writer.reserve_data_section(0x2000, 0x100);
+    let bss = writer.reserve_bss_section(0x100);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xc3; 0x10]);
+    writer.write_section(data.file_offset, &[1; 0x100]);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let image_base = file.relative_address_base();
+    let segments = file.loadable_segments().collect::<Vec<_>>();
+    assert_eq!(
+        segments,
+        [
+            LoadSegment {
+                address: image_base + u64::from(text.virtual_address),
+                size: 0x10,
+                file_offset: text.file_offset.into(),
+                file_size: 0x10,
+                permissions: SegmentPermissions {
+                    read: true,
+                    write: false,
+                    execute: true,
+                },
+            },
+            LoadSegment {
+                address: image_base + u64::from(data.virtual_address),
+                size: 0x2000,
+                file_offset: data.file_offset.into(),
+                // The raw data size is rounded up to the file alignment.
+                file_size: 0x200,
+                permissions: SegmentPermissions {
+                    read: true,
+                    write: true,
+                    execute: false,
+                },
+            },
+            LoadSegment {
+                address: image_base + u64::from(bss.virtual_address),
+                size: 0x100,
+                file_offset: 0,
+                file_size: 0,
+                permissions: SegmentPermissions {
+                    read: true,
+                    write: true,
+                    execute: false,
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn header_consistency() {
+    let build = |is_64: bool, machine: u16| {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(is_64, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(0);
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(write::pe::NtHeaders {
+            machine,
+            ..nt_headers()
+        });
+        writer.write_section_headers();
+        buffer
+    };
+
+    let data = build(true, pe::IMAGE_FILE_MACHINE_AMD64);
+    let file = PeFile64::parse_strict(&*data).unwrap();
+    assert!(file.header_consistency().is_ok());
+    assert!(file.warnings().is_empty());
+
+    let data = build(false, pe::IMAGE_FILE_MACHINE_I386);
+    assert!(PeFile32::parse_strict(&*data).is_ok());
+
+    // A 64-bit optional header with a 32-bit machine.
+    let data = build(true, pe::IMAGE_FILE_MACHINE_I386);
+    let file = PeFile64::parse(&*data).unwrap();
+    assert!(file.header_consistency().is_err());
+    assert_eq!(file.warnings(), [file.header_consistency().unwrap_err()]);
+    assert!(PeFile64::parse_strict(&*data).is_err());
+
+    // A 32-bit optional header with a 64-bit machine.
+    let data = build(false, pe::IMAGE_FILE_MACHINE_ARM64);
+    assert!(PeFile32::parse(&*data).is_ok());
+    assert!(PeFile32::parse_strict(&*data).is_err());
+
+    // Unknown machines are not checked.
+    let data = build(true, pe::IMAGE_FILE_MACHINE_UNKNOWN);
+    assert!(PeFile64::parse_strict(&*data).is_ok());
+}
+
+#[test]
+fn arm64ec_architecture() {
+    use object::{Architecture, SubArchitecture};
+
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(0);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(write::pe::NtHeaders {
+        machine: pe::IMAGE_FILE_MACHINE_ARM64EC,
+        ..nt_headers()
+    });
+    writer.write_section_headers();
+
+    // ARM64EC is reported as AArch64, and only the sub-architecture distinguishes it.
+    let file = object::File::parse(&*buffer).unwrap();
+    assert_eq!(file.architecture(), Architecture::Aarch64);
+    assert_eq!(file.sub_architecture(), Some(SubArchitecture::Arm64EC));
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.architecture(), Architecture::Aarch64);
+    assert_eq!(file.sub_architecture(), Some(SubArchitecture::Arm64EC));
+}
+
+#[test]
+fn embedded_pe_offsets() {
+    let mut inner = Vec::new();
+    let mut writer = write::pe::Writer::new(false, 0x1000, 0x200, &mut inner);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(0);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(write::pe::NtHeaders {
+        machine: pe::IMAGE_FILE_MACHINE_I386,
+        ..nt_headers()
+    });
+    writer.write_section_headers();
+
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let data_size = inner.len() as u32 + 0x10;
+    let data = writer.reserve_data_section(data_size, data_size);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    let mut section_data = vec![0; 0x10];
+    section_data.extend_from_slice(&inner);
+    writer.write_section(data.file_offset, &section_data);
+    let overlay = buffer.len() as u64;
+    buffer.extend_from_slice(b"overlay");
+    buffer.extend_from_slice(&inner);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let offsets = file.embedded_pe_offsets();
+    assert_eq!(offsets, [u64::from(data.file_offset) + 0x10, overlay + 7]);
+    for offset in offsets {
+        let embedded = PeFile32::parse(&buffer[offset as usize..]).unwrap();
+        assert_eq!(embedded.architecture(), object::Architecture::I386);
+    }
+}
+
+#[test]
+fn tls_directory() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(0x100);
+    let tls_size = mem::size_of::<pe::ImageTlsDirectory64>() as u32;
+    writer.set_data_directory(
+        pe::IMAGE_DIRECTORY_ENTRY_TLS,
+        rdata.virtual_address,
+        tls_size,
+    );
+    // The global pointer entry has no size.
+    writer.set_data_directory(
+        pe::IMAGE_DIRECTORY_ENTRY_GLOBALPTR,
+        rdata.virtual_address + 0x80,
+        0,
+    );
+
+    let image_base = 0x1_8000_0000u64;
+    let va = image_base + u64::from(rdata.virtual_address);
+    let mut data = Vec::new();
+    for value in &[va + 0x40, va + 0x48, va + 0x50, va + 0x58] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(&0x10u32.to_le_bytes());
+    data.extend_from_slice(&pe::IMAGE_SCN_ALIGN_8BYTES.to_le_bytes());
+    data.resize(0x100, 0);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, &data);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.global_pointer(), Some(rdata.virtual_address + 0x80));
+    let tls = file.tls_directory().unwrap().unwrap();
+    assert_eq!(tls.start_address_of_raw_data(), va + 0x40);
+    assert_eq!(tls.end_address_of_raw_data(), va + 0x48);
+    assert_eq!(tls.address_of_call_backs(), va + 0x58);
+    assert_eq!(tls.size_of_zero_fill(), 0x10);
+    assert_eq!(tls.characteristics(), pe::IMAGE_SCN_ALIGN_8BYTES);
+    assert_eq!(
+        file.tls_index_address().unwrap(),
+        Some(rdata.virtual_address + 0x50)
+    );
+    assert_eq!(file.tls_raw_data().unwrap(), Some(&data[0x40..0x48]));
+
+    // An empty template.
+    let offset = rdata.file_offset as usize + 8;
+    buffer[offset..offset + 8].copy_from_slice(&(va + 0x40).to_le_bytes());
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.tls_raw_data().unwrap(), Some(&[][..]));
+
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(0);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.global_pointer(), None);
+    assert!(file.tls_directory().unwrap().is_none());
+    assert_eq!(file.tls_index_address().unwrap(), None);
+    assert_eq!(file.tls_raw_data().unwrap(), None);
+}
+
+#[test]
+fn file_characteristics() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(0);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(write::pe::NtHeaders {
+        characteristics: pe::IMAGE_FILE_EXECUTABLE_IMAGE
+            | pe::IMAGE_FILE_DLL
+            | pe::IMAGE_FILE_LARGE_ADDRESS_AWARE,
+        ..nt_headers()
+    });
+    writer.write_section_headers();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let flags = file.file_characteristics();
+    assert!(flags.is_dll());
+    assert!(flags.is_executable());
+    assert!(!flags.relocs_stripped());
+    assert!(flags.contains(FileCharacteristics::LARGE_ADDRESS_AWARE | FileCharacteristics::DLL));
+    assert!(!flags.contains(FileCharacteristics::SYSTEM));
+    assert_eq!(
+        (flags & FileCharacteristics::LARGE_ADDRESS_AWARE).bits(),
+        pe::IMAGE_FILE_LARGE_ADDRESS_AWARE
+    );
+}
+
+#[test]
+fn raw_bytes() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(3);
+    let text = writer.reserve_text_section(0x10);
+    let idata = writer.reserve_idata_section(0x100);
+    let rva = idata.virtual_address;
+    writer.add_export("func", text.virtual_address);
+    writer.reserve_export_section(b"test.dll");
+
+    // One descriptor, followed by the null descriptor and some other data.
+    let mut data = Vec::new();
+    data.extend_from_slice(&(rva + 64).to_le_bytes());
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&(rva + 80).to_le_bytes());
+    data.extend_from_slice(&(rva + 64).to_le_bytes());
+    data.resize(40, 0);
+    data.resize(64, 0xff);
+    data.extend_from_slice(&pe::IMAGE_ORDINAL_FLAG64.to_le_bytes());
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(b"KERNEL32.dll\0");
+    data.resize(0x100, 0);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xc3; 0x10]);
+    writer.write_section(idata.file_offset, &data);
+    writer.write_export_section().unwrap();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let import_table = file.import_table().unwrap().unwrap();
+    assert_eq!(import_table.raw_bytes().unwrap(), &data[..40]);
+
+    let export_table = file.export_table().unwrap().unwrap();
+    let export_dir = file
+        .data_directory(pe::IMAGE_DIRECTORY_ENTRY_EXPORT)
+        .unwrap();
+    let (offset, size) = export_dir.file_range(&file.section_table()).unwrap();
+    assert_eq!(
+        export_table.raw_bytes(),
+        &buffer[offset as usize..][..size as usize]
+    );
+
+    let optional_header = file.nt_headers().optional_header();
+    let offset = file.dos_header().nt_headers_offset() as usize + 4 + 20;
+    let size = mem::size_of::<pe::ImageOptionalHeader64>();
+    assert_eq!(optional_header.raw_bytes(), &buffer[offset..][..size]);
+    assert_eq!(
+        file.data_directories().raw_bytes(),
+        &buffer[offset + size..][..16 * 8]
+    );
+
+    let entries = file.data_directory_entries();
+    assert_eq!(entries.len(), pe::IMAGE_NUMBEROF_DIRECTORY_ENTRIES);
+    assert_eq!(
+        entries[pe::IMAGE_DIRECTORY_ENTRY_EXPORT].address_range(),
+        export_dir.address_range()
+    );
+}
+
+#[test]
+fn pointer_fixups() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.add_reloc(0x2000, pe::IMAGE_REL_BASED_DIR64);
+    writer.add_reloc(0x2010, pe::IMAGE_REL_BASED_HIGHLOW);
+    // Other types are ignored.
+    writer.add_reloc(0x2020, pe::IMAGE_REL_BASED_HIGHADJ);
+    writer.add_reloc(0x3008, pe::IMAGE_REL_BASED_DIR64);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    writer.reserve_reloc_section();
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_reloc_section();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let fixups = file
+        .pointer_fixups()
+        .unwrap()
+        .map(|fixup| (fixup.address(), fixup.size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fixups,
+        [(0x1_8000_2000, 8), (0x1_8000_2010, 4), (0x1_8000_3008, 8)]
+    );
+
+    let mut blocks = file
+        .data_directories()
+        .relocation_blocks(&*buffer, &file.section_table())
+        .unwrap()
+        .unwrap();
+    let mut sizes = Vec::new();
+    while let Some(block) = blocks.next().unwrap() {
+        sizes.extend(block.map(|relocation| relocation.size()));
+    }
+    assert_eq!(sizes, [64, 32, 16, 64]);
+}
+
+#[test]
+fn all_relocations() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.add_reloc(0x1000, pe::IMAGE_REL_BASED_DIR64);
+    writer.add_reloc(0x1008, pe::IMAGE_REL_BASED_HIGHLOW);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(2);
+    let data = writer.reserve_data_section(0x10, 0x10);
+    writer.reserve_reloc_section();
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(data.file_offset, &[0; 0x10]);
+    writer.write_reloc_section();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let relocations = file
+        .all_relocations()
+        .unwrap()
+        .iter()
+        .map(|(section, offset, relocation)| {
+            assert_eq!(relocation.kind(), RelocationKind::Absolute);
+            assert!(relocation.has_implicit_addend());
+            (section.0, *offset, relocation.size())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(relocations, [(1, 0, 64), (1, 8, 32)]);
+}
+
+#[test]
+fn chpe_code_ranges() {
+    fn build(load_config_size: u32) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(1);
+        let rdata = writer.reserve_rdata_section(0x400);
+        writer.set_data_directory(
+            pe::IMAGE_DIRECTORY_ENTRY_LOAD_CONFIG,
+            rdata.virtual_address,
+            mem::size_of::<pe::ImageLoadConfigDirectory64>() as u32,
+        );
+
+        let va = 0x1_8000_0000u64 + u64::from(rdata.virtual_address);
+        let mut data = vec![0; 0x400];
+        data[..4].copy_from_slice(&load_config_size.to_le_bytes());
+        // The CHPE metadata pointer.
+        data[200..208].copy_from_slice(&(va + 0x200).to_le_bytes());
+        // The ARM64EC metadata: version, code map, and code map count.
+        data[0x200..0x204].copy_from_slice(&1u32.to_le_bytes());
+        data[0x204..0x208].copy_from_slice(&(rdata.virtual_address + 0x300).to_le_bytes());
+        data[0x208..0x20c].copy_from_slice(&3u32.to_le_bytes());
+        // The code map entries, with the code type in the low bits of the start.
+        let entries: [(u32, u32); 3] = [(0x1000 | 1, 0x100), (0x1100 | 2, 0x80), (0x1180, 0x10)];
+        for (i, &(start, length)) in entries.iter().enumerate() {
+            let offset = 0x300 + i * 8;
+            data[offset..][..4].copy_from_slice(&start.to_le_bytes());
+            data[offset + 4..][..4].copy_from_slice(&length.to_le_bytes());
+        }
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(write::pe::NtHeaders {
+            machine: pe::IMAGE_FILE_MACHINE_ARM64EC,
+            ..nt_headers()
+        });
+        writer.write_section_headers();
+        writer.write_section(rdata.file_offset, &data);
+        buffer
+    }
+
+    let buffer = build(mem::size_of::<pe::ImageLoadConfigDirectory64>() as u32);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.chpe_metadata_pointer().unwrap(), Some(0x1_8000_1200));
+    assert_eq!(
+        file.chpe_code_ranges().unwrap(),
+        [
+            ChpeCodeRange {
+                start: 0x1000,
+                length: 0x100,
+                code_type: ChpeCodeType::Arm64Ec,
+            },
+            ChpeCodeRange {
+                start: 0x1100,
+                length: 0x80,
+                code_type: ChpeCodeType::Amd64,
+            },
+            ChpeCodeRange {
+                start: 0x1180,
+                length: 0x10,
+                code_type: ChpeCodeType::Arm64,
+            },
+        ]
+    );
+
+    // Older load config directories do not contain the CHPE metadata pointer.
+    let buffer = build(0x70);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.chpe_metadata_pointer().unwrap(), None);
+    assert!(file.chpe_code_ranges().unwrap().is_empty());
+}
+
+#[test]
+fn delay_load_imports() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(false, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(0x200);
+    let rva = rdata.virtual_address;
+    writer.set_data_directory(pe::IMAGE_DIRECTORY_ENTRY_DELAY_IMPORT, rva, 0x60);
+
+    let image_base = 0x40_0000;
+    let mut data = vec![0; 0x200];
+    let mut put = |offset: usize, value: u32| {
+        data[offset..][..4].copy_from_slice(&value.to_le_bytes());
+    };
+    // A descriptor using RVAs.
+    put(0x00, pe::IMAGE_DELAYLOAD_RVA_BASED);
+    put(0x04, rva + 0x100);
+    put(0x10, rva + 0x140);
+    // A version 1 descriptor using virtual addresses.
+    put(0x20, 0);
+    put(0x24, image_base + rva + 0x110);
+    put(0x30, image_base + rva + 0x160);
+    // The third descriptor is null.
+
+    // The import name tables.
+    put(0x140, rva + 0x180);
+    put(0x144, pe::IMAGE_ORDINAL_FLAG32 | 7);
+    put(0x160, image_base + rva + 0x190);
+    put(0x180, 3);
+    data[0x100..][..8].copy_from_slice(b"rva.dll\0");
+    data[0x110..][..7].copy_from_slice(b"va.dll\0");
+    data[0x182..][..4].copy_from_slice(b"foo\0");
+    data[0x192..][..4].copy_from_slice(b"bar\0");
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(write::pe::NtHeaders {
+        machine: pe::IMAGE_FILE_MACHINE_I386,
+        image_base: image_base.into(),
+        ..nt_headers()
+    });
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, &data);
+
+    let file = PeFile32::parse(&*buffer).unwrap();
+    let delay_imports = file.delay_load_imports().unwrap();
+    assert_eq!(delay_imports.len(), 2);
+    assert_eq!(delay_imports[0].dll, b"rva.dll");
+    let imports = &delay_imports[0].imports;
+    assert_eq!(imports.len(), 2);
+    assert!(matches!(imports[0], pe_read::Import::Name(3, b"foo")));
+    assert!(matches!(imports[1], pe_read::Import::Ordinal(7)));
+    assert_eq!(delay_imports[1].dll, b"va.dll");
+    let imports = &delay_imports[1].imports;
+    assert_eq!(imports.len(), 1);
+    assert!(matches!(imports[0], pe_read::Import::Name(0, b"bar")));
+    assert_eq!(
+        file.needed_libraries().unwrap(),
+        [&b"rva.dll"[..], &b"va.dll"[..]]
+    );
+    assert_eq!(file.trailing_data().unwrap(), None);
+
+    buffer.extend_from_slice(b"overlay");
+    let file = PeFile32::parse(&*buffer).unwrap();
+    assert_eq!(file.trailing_data().unwrap(), Some(&b"overlay"[..]));
+
+    // An invalid symbol table is ignored.
+    let nt_offset = usize::from(buffer[0x3c]) | usize::from(buffer[0x3d]) << 8;
+    buffer[nt_offset + 12..][..4].copy_from_slice(&0x7fff_fff0u32.to_le_bytes());
+    let file = PeFile32::parse(&*buffer).unwrap();
+    assert_eq!(file.trailing_data().unwrap(), Some(&b"overlay"[..]));
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn authenticode_digest() {
+    use object::read::HashAlgo;
+
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(4);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, b"data");
+    assert_eq!(buffer.len() as u32, rdata.file_offset + 0x200);
+
+    // Append a certificate table, and point the data directory at it.
+    let certificate_offset = buffer.len();
+    buffer.extend_from_slice(&[0xaa; 16]);
+    let nt_headers_offset = 0x40;
+    let check_sum_offset = nt_headers_offset + 24 + 64;
+    let security_offset = nt_headers_offset + mem::size_of::<pe::ImageNtHeaders64>() + 4 * 8;
+    buffer[security_offset..][..4].copy_from_slice(&(certificate_offset as u32).to_le_bytes());
+    buffer[security_offset + 4..][..4].copy_from_slice(&16u32.to_le_bytes());
+
+    let mut hashed = Vec::new();
+    hashed.extend_from_slice(&buffer[..check_sum_offset]);
+    hashed.extend_from_slice(&buffer[check_sum_offset + 4..security_offset]);
+    hashed.extend_from_slice(&buffer[security_offset + 8..certificate_offset]);
+    let expected = HashAlgo::Sha256.digest(&hashed);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(
+        file.authenticode_digest(HashAlgo::Sha256).unwrap(),
+        expected
+    );
+    assert_eq!(
+        file.authenticode_digest(HashAlgo::Sha1).unwrap(),
+        HashAlgo::Sha1.digest(&hashed)
+    );
+
+    // Changing the excluded fields does not change the digest.
+    let mut modified = buffer.clone();
+    modified[check_sum_offset] = 0x55;
+    modified[certificate_offset] = 0x55;
+    let file = PeFile64::parse(&*modified).unwrap();
+    assert_eq!(
+        file.authenticode_digest(HashAlgo::Sha256).unwrap(),
+        expected
+    );
+
+    // Changing the section data does.
+    let mut modified = buffer.clone();
+    modified[rdata.file_offset as usize] = b'D';
+    let file = PeFile64::parse(&*modified).unwrap();
+    assert_ne!(
+        file.authenticode_digest(HashAlgo::Sha256).unwrap(),
+        expected
+    );
+
+    // Data after the certificate table is included.
+    let mut appended = buffer.clone();
+    appended.extend_from_slice(b"overlay");
+    hashed.extend_from_slice(b"overlay");
+    let file = PeFile64::parse(&*appended).unwrap();
+    assert_eq!(
+        file.authenticode_digest(HashAlgo::Sha256).unwrap(),
+        HashAlgo::Sha256.digest(&hashed)
+    );
+
+    // The certificate table must be within the file, and after the headers.
+    for &(offset, size) in &[
+        (certificate_offset as u32, 17u32),
+        (certificate_offset as u32 + 1, 16),
+        (0xffff_fff0, 0x20),
+        (check_sum_offset as u32, 4),
+    ] {
+        let mut modified = buffer.clone();
+        modified[security_offset..][..4].copy_from_slice(&offset.to_le_bytes());
+        modified[security_offset + 4..][..4].copy_from_slice(&size.to_le_bytes());
+        let file = PeFile64::parse(&*modified).unwrap();
+        assert!(file.authenticode_digest(HashAlgo::Sha256).is_err());
+    }
+}
+
+#[test]
+fn functions() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(2);
+    let text = writer.reserve_text_section(0x40);
+    let pdata = writer.reserve_pdata_section(24);
+
+    let mut data = Vec::new();
+    for &(begin, end) in &[(0x20, 0x38), (0, 0x10)] {
+        data.extend_from_slice(&(text.virtual_address + begin).to_le_bytes());
+        data.extend_from_slice(&(text.virtual_address + end).to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+    }
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(text.file_offset, &[0xcc; 0x40]);
+    writer.write_section(pdata.file_offset, &data);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let address = 0x1_8000_0000 + u64::from(text.virtual_address);
+    let functions = file.functions();
+    assert_eq!(
+        functions
+            .iter()
+            .map(|function| (function.address, function.size))
+            .collect::<Vec<_>>(),
+        [(address, Some(0x10)), (address + 0x20, Some(0x18))]
+    );
+}
+
+#[test]
+fn parse_options() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(2);
+    let idata = writer.reserve_idata_section(0x100);
+    let rdata = writer.reserve_rdata_section(0x100);
+    writer.set_data_directory(
+        pe::IMAGE_DIRECTORY_ENTRY_RESOURCE,
+        rdata.virtual_address,
+        0x40,
+    );
+
+    // Two import descriptors, followed by the null descriptor.
+    let mut import_data = Vec::new();
+    for _ in 0..2 {
+        import_data.extend_from_slice(&[0; 12]);
+        import_data.extend_from_slice(&(idata.virtual_address + 0x80).to_le_bytes());
+        import_data.extend_from_slice(&[0; 4]);
+    }
+    import_data.resize(0x80, 0);
+    import_data.extend_from_slice(b"KERNEL32.dll\0");
+    import_data.resize(0x100, 0);
+
+    // A root table with a subtable that references itself.
+    let mut resource_data = Vec::new();
+    for _ in 0..2 {
+        resource_data.extend_from_slice(&[0; 14]);
+        resource_data.extend_from_slice(&1u16.to_le_bytes());
+        resource_data.extend_from_slice(&1u32.to_le_bytes());
+        resource_data
+            .extend_from_slice(&(pe::IMAGE_RESOURCE_DATA_IS_DIRECTORY | 0x18).to_le_bytes());
+    }
+    resource_data.resize(0x100, 0);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(idata.file_offset, &import_data);
+    writer.write_section(rdata.file_offset, &resource_data);
+
+    // The defaults are unlimited, and the resource directory is not walked.
+    let options = object::read::ParseOptions::default();
+    let file = object::File::parse_with_options(&*buffer, &options).unwrap();
+    assert_eq!(file.needed_libraries().unwrap(), [&b"KERNEL32.dll"[..]]);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let resources = file
+        .data_directories()
+        .resource_directory(&*buffer, &file.section_table())
+        .unwrap()
+        .unwrap();
+    assert!(resources.depth().is_err());
+    assert!(resources.check_depth(8).is_err());
+
+    // The descriptor limit is applied when the imports are read.
+    let options = object::read::ParseOptions {
+        max_import_descriptors: 1,
+        ..Default::default()
+    };
+    let file = PeFile64::parse_with_options(&*buffer, &options).unwrap();
+    assert!(file.needed_libraries().is_err());
+    let mut descriptors = file.import_table().unwrap().unwrap().descriptors().unwrap();
+    assert!(descriptors.next().unwrap().is_some());
+    assert!(descriptors.next().is_err());
+    let options = object::read::ParseOptions {
+        max_import_descriptors: 2,
+        ..Default::default()
+    };
+    let file = PeFile64::parse_with_options(&*buffer, &options).unwrap();
+    assert_eq!(file.needed_libraries().unwrap(), [&b"KERNEL32.dll"[..]]);
+
+    let options = object::read::ParseOptions {
+        max_resource_depth: 8,
+        ..Default::default()
+    };
+    assert!(object::File::parse_with_options(&*buffer, &options).is_err());
+    let options = object::read::ParseOptions::strict();
+    assert!(object::File::parse_with_options(&*buffer, &options).is_err());
+    let options = object::read::ParseOptions {
+        max_section_count: 1,
+        ..Default::default()
+    };
+    assert!(object::File::parse_with_options(&*buffer, &options).is_err());
+}
+
+#[test]
+fn version_info() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    let rdata = writer.reserve_rdata_section(0x100);
+    writer.set_data_directory(
+        pe::IMAGE_DIRECTORY_ENTRY_RESOURCE,
+        rdata.virtual_address,
+        0x100,
+    );
+
+    // The type, name and language tables, each with a single entry.
+    let mut data = Vec::new();
+    for &(id, offset) in &[
+        (
+            pe::RT_VERSION.into(),
+            pe::IMAGE_RESOURCE_DATA_IS_DIRECTORY | 0x18,
+        ),
+        (1, pe::IMAGE_RESOURCE_DATA_IS_DIRECTORY | 0x30),
+        (0x409u32, 0x48u32),
+    ] {
+        data.extend_from_slice(&[0; 14]);
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&id.to_le_bytes());
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    // The data entry.
+    data.extend_from_slice(&(rdata.virtual_address + 0x60).to_le_bytes());
+    data.extend_from_slice(&92u32.to_le_bytes());
+    data.resize(0x60, 0);
+    // The `VS_VERSIONINFO` header, key and padding.
+    data.extend_from_slice(&92u16.to_le_bytes());
+    data.extend_from_slice(&52u16.to_le_bytes());
+    data.extend_from_slice(&0u16.to_le_bytes());
+    for c in "VS_VERSION_INFO\0".encode_utf16() {
+        data.extend_from_slice(&c.to_le_bytes());
+    }
+    data.extend_from_slice(&[0; 2]);
+    // The `VS_FIXEDFILEINFO`.
+    for value in &[
+        pe::VS_FFI_SIGNATURE,
+        0x0001_0000,
+        0x000a_0000,
+        0x4a61_0001,
+        0x000a_0002,
+        0x0003_0004,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.resize(0x100, 0);
+
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_section(rdata.file_offset, &data);
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let version_info = file.version_info().unwrap().unwrap();
+    assert_eq!(version_info.file_version(), (10, 0, 19041, 1));
+    assert_eq!(version_info.product_version(), (10, 2, 3, 4));
+    assert!(version_info.file_version() > (10, 0, 19040, 0xffff));
+    assert_eq!(
+        version_info
+            .fixed_file_info()
+            .struc_version
+            .get(LittleEndian),
+        0x0001_0000
+    );
+
+    // An invalid signature.
+    let offset = rdata.file_offset as usize + 0x60 + 40;
+    buffer[offset] = 0;
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert!(file.version_info().is_err());
+
+    // No version resource.
+    let offset = rdata.file_offset as usize + 16;
+    buffer[offset..offset + 4].copy_from_slice(&u32::from(pe::RT_MANIFEST).to_le_bytes());
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert!(file.version_info().unwrap().is_none());
+}
+
+#[test]
+fn manifest() {
+    let build = |manifest: &[u8]| {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(1);
+        let rdata = writer.reserve_rdata_section(0x200);
+        writer.set_data_directory(
+            pe::IMAGE_DIRECTORY_ENTRY_RESOURCE,
+            rdata.virtual_address,
+            0x200,
+        );
+
+        // The type, name and language tables, each with a single entry.
+        let mut data = Vec::new();
+        for &(id, offset) in &[
+            (
+                pe::RT_MANIFEST.into(),
+                pe::IMAGE_RESOURCE_DATA_IS_DIRECTORY | 0x18,
+            ),
+            (1, pe::IMAGE_RESOURCE_DATA_IS_DIRECTORY | 0x30),
+            (0x409u32, 0x48u32),
+        ] {
+            data.extend_from_slice(&[0; 14]);
+            data.extend_from_slice(&1u16.to_le_bytes());
+            data.extend_from_slice(&id.to_le_bytes());
+            data.extend_from_slice(&offset.to_le_bytes());
+        }
+        // The data entry.
+        data.extend_from_slice(&(rdata.virtual_address + 0x60).to_le_bytes());
+        data.extend_from_slice(&(manifest.len() as u32).to_le_bytes());
+        data.resize(0x60, 0);
+        data.extend_from_slice(manifest);
+        data.resize(0x200, 0);
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(nt_headers());
+        writer.write_section_headers();
+        writer.write_section(rdata.file_offset, &data);
+        buffer
+    };
+
+    let manifest = br#"<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+  <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
+    <security>
+      <requestedPrivileges>
+        <requestedExecutionLevel uiAccess = 'false'
+          level="requireAdministrator"/>
+      </requestedPrivileges>
+    </security>
+  </trustInfo>
+</assembly>"#;
+    let buffer = build(manifest);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.manifest().unwrap(), Some(&manifest[..]));
+    assert_eq!(
+        file.requested_execution_level().unwrap(),
+        Some(&b"requireAdministrator"[..])
+    );
+
+    let manifest = br#"<assembly><requestedExecutionLevel uiAccess="false"/></assembly>"#;
+    let buffer = build(manifest);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.manifest().unwrap(), Some(&manifest[..]));
+    assert_eq!(file.requested_execution_level().unwrap(), None);
+
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.manifest().unwrap(), None);
+    assert_eq!(file.requested_execution_level().unwrap(), None);
+}
+
+#[test]
+fn resource_writer() {
+    let manifest = br#"<assembly><requestedExecutionLevel level="asInvoker"/></assembly>"#;
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    assert!(!writer.has_resources());
+    writer.add_resource(
+        write::pe::ResourceId::Id(pe::RT_MANIFEST),
+        write::pe::ResourceId::Id(1),
+        write::pe::ResourceId::Id(0x409),
+        manifest,
+    );
+    writer.add_resource(
+        write::pe::ResourceId::Name("MYRES"),
+        write::pe::ResourceId::Name("ITEM"),
+        write::pe::ResourceId::Id(0),
+        b"named",
+    );
+    writer.add_resource(
+        write::pe::ResourceId::Name("MYRES"),
+        write::pe::ResourceId::Id(7),
+        write::pe::ResourceId::Id(0),
+        b"numbered",
+    );
+    assert!(writer.has_resources());
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    writer.reserve_resource_section();
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_resource_section();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.manifest().unwrap(), Some(&manifest[..]));
+    assert_eq!(
+        file.requested_execution_level().unwrap(),
+        Some(&b"asInvoker"[..])
+    );
+
+    let sections = file.section_table();
+    let resources = file
+        .data_directories()
+        .resource_directory(&*buffer, &sections)
+        .unwrap()
+        .unwrap();
+    assert_eq!(resources.depth(), Ok(3));
+    let options = object::read::ParseOptions::strict();
+    assert!(PeFile64::parse_with_options(&*buffer, &options).is_ok());
+
+    // Named entries must come before ID entries.
+    let root = resources.root().unwrap();
+    assert_eq!(root.header.number_of_named_entries.get(LittleEndian), 1);
+    assert_eq!(root.header.number_of_id_entries.get(LittleEndian), 1);
+    assert_eq!(
+        root.entries[0]
+            .name_or_id()
+            .name()
+            .unwrap()
+            .to_string_lossy(resources)
+            .unwrap(),
+        "MYRES"
+    );
+
+    let myres: Vec<u16> = "MYRES".encode_utf16().collect();
+    let item: Vec<u16> = "ITEM".encode_utf16().collect();
+    for &(name, data) in &[
+        (pe_read::ResourceId::Name(&item), &b"named"[..]),
+        (pe_read::ResourceId::Id(7), &b"numbered"[..]),
+    ] {
+        let entry = resources
+            .find(
+                pe_read::ResourceId::Name(&myres),
+                name,
+                pe_read::ResourceId::Id(0),
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.data(&*buffer, &sections), Ok(data));
+    }
+    assert!(resources
+        .find(
+            pe_read::ResourceId::Name(&myres),
+            pe_read::ResourceId::Id(8),
+            pe_read::ResourceId::Id(0),
+        )
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn resource_writer_order() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    for &(name, data) in &[
+        ("beta", &b"beta"[..]),
+        ("Gamma", b"gamma"),
+        ("ALPHA", b"alpha"),
+        ("_under", b"under"),
+        // A duplicate, differing only in case. The last data is used.
+        ("BETA", b"beta2"),
+    ] {
+        writer.add_resource(
+            write::pe::ResourceId::Id(pe::RT_RCDATA),
+            write::pe::ResourceId::Name(name),
+            write::pe::ResourceId::Id(0),
+            data,
+        );
+    }
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(1);
+    writer.reserve_resource_section();
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+    writer.write_resource_section();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let sections = file.section_table();
+    let resources = file
+        .data_directories()
+        .resource_directory(&*buffer, &sections)
+        .unwrap()
+        .unwrap();
+    let root = resources.root().unwrap();
+    let table = root.entries[0].data(resources).unwrap().table().unwrap();
+    // Names are sorted without regard to case, so '_' is after the letters.
+    let names = table
+        .entries
+        .iter()
+        .map(|entry| {
+            entry
+                .name_or_id()
+                .name()
+                .unwrap()
+                .to_string_lossy(resources)
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["ALPHA", "beta", "Gamma", "_under"]);
+
+    let beta: Vec<u16> = "beta".encode_utf16().collect();
+    let entry = resources
+        .find(
+            pe_read::ResourceId::Id(pe::RT_RCDATA),
+            pe_read::ResourceId::Name(&beta),
+            pe_read::ResourceId::Id(0),
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(entry.data(&*buffer, &sections), Ok(&b"beta2"[..]));
+}
+
+#[test]
+fn clr_header() {
+    let build = |strong_name_size: u32| {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(1);
+        let rdata = writer.reserve_rdata_section(0x100);
+        let clr_size = mem::size_of::<pe::ImageCor20Header>() as u32;
+        writer.set_data_directory(
+            pe::IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR,
+            rdata.virtual_address,
+            clr_size,
+        );
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&clr_size.to_le_bytes());
+        data.extend_from_slice(&2u16.to_le_bytes());
+        data.extend_from_slice(&5u16.to_le_bytes());
+        // Metadata.
+        data.extend_from_slice(&[0; 8]);
+        data.extend_from_slice(&pe::COMIMAGE_FLAGS_ILONLY.to_le_bytes());
+        data.extend_from_slice(&0x0600_0001u32.to_le_bytes());
+        // Resources.
+        data.extend_from_slice(&[0; 8]);
+        // Strong name signature.
+        data.extend_from_slice(&(rdata.virtual_address + 0x80).to_le_bytes());
+        data.extend_from_slice(&strong_name_size.to_le_bytes());
+        data.resize(0x80, 0);
+        data.extend((0..0x10).map(|i| i as u8));
+        data.resize(0x100, 0);
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(nt_headers());
+        writer.write_section_headers();
+        writer.write_section(rdata.file_offset, &data);
+        buffer
+    };
+
+    let buffer = build(0x10);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let clr = file.clr_header().unwrap().unwrap();
+    assert_eq!(clr.header().major_runtime_version.get(LittleEndian), 2);
+    assert_eq!(clr.flags(), pe::COMIMAGE_FLAGS_ILONLY);
+    let entry_point = clr.entry_point();
+    assert_eq!(entry_point.table(), 6);
+    assert_eq!(entry_point.row(), 1);
+    let signature = clr
+        .strong_name_signature(&*buffer, &file.section_table())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        signature,
+        &(0..0x10).map(|i| i as u8).collect::<Vec<_>>()[..]
+    );
+
+    // A zero size means there is no strong name signature.
+    let buffer = build(0);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    let clr = file.clr_header().unwrap().unwrap();
+    assert_eq!(
+        clr.strong_name_signature(&*buffer, &file.section_table())
+            .unwrap(),
+        None
+    );
+
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(0);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert!(file.clr_header().unwrap().is_none());
+}
+
+#[test]
+fn typed_headers() {
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(0);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(write::pe::NtHeaders {
+        dll_characteristics: pe::IMAGE_DLLCHARACTERISTICS_DYNAMIC_BASE
+            | pe::IMAGE_DLLCHARACTERISTICS_NX_COMPAT,
+        ..nt_headers()
+    });
+    writer.write_section_headers();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.machine(), Machine::Amd64);
+    assert_eq!(file.machine().raw(), pe::IMAGE_FILE_MACHINE_AMD64);
+    assert_eq!(file.subsystem(), Subsystem::WindowsCui);
+    assert_eq!(file.subsystem().raw(), pe::IMAGE_SUBSYSTEM_WINDOWS_CUI);
+    let flags = file.dll_characteristics();
+    assert!(flags.contains(DllCharacteristics::DYNAMIC_BASE | DllCharacteristics::NX_COMPAT));
+    assert!(!flags.contains(DllCharacteristics::HIGH_ENTROPY_VA));
+    assert_eq!(
+        (flags & DllCharacteristics::NX_COMPAT).bits(),
+        pe::IMAGE_DLLCHARACTERISTICS_NX_COMPAT
+    );
+
+    // Unknown values are preserved.
+    assert_eq!(Machine::from(0x1234), Machine::Unknown(0x1234));
+    assert_eq!(Machine::from(0x1234).raw(), 0x1234);
+    assert_eq!(Subsystem::from(0x99), Subsystem::Unknown(0x99));
+    assert_eq!(Subsystem::from(0x99).raw(), 0x99);
+}
+
+#[test]
+fn debug_directories() {
+    let build = |types: &[u32]| {
+        let mut buffer = Vec::new();
+        let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+        writer.reserve_dos_header();
+        writer.reserve_nt_headers(16);
+        writer.reserve_section_headers(1);
+        let rdata = writer.reserve_rdata_section(0x100);
+        let size = mem::size_of::<pe::ImageDebugDirectory>() * types.len();
+        writer.set_data_directory(
+            pe::IMAGE_DIRECTORY_ENTRY_DEBUG,
+            rdata.virtual_address,
+            size as u32,
+        );
+
+        let mut data = Vec::new();
+        for typ in types {
+            data.extend_from_slice(&[0; 12]);
+            data.extend_from_slice(&typ.to_le_bytes());
+            data.extend_from_slice(&[0; 12]);
+        }
+        data.resize(0x100, 0);
+
+        writer.write_empty_dos_header().unwrap();
+        writer.write_nt_headers(write::pe::NtHeaders {
+            time_date_stamp: 0x6543_2100,
+            ..nt_headers()
+        });
+        writer.write_section_headers();
+        writer.write_section(rdata.file_offset, &data);
+        buffer
+    };
+    let types = |file: &PeFile64| {
+        file.debug_directories()
+            .unwrap()
+            .iter()
+            .map(|d| d.typ.get(LittleEndian))
+            .collect::<Vec<_>>()
+    };
+
+    let buffer = build(&[pe::IMAGE_DEBUG_TYPE_CODEVIEW, pe::IMAGE_DEBUG_TYPE_REPRO]);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.timestamp(), 0x6543_2100);
+    assert_eq!(
+        types(&file),
+        [pe::IMAGE_DEBUG_TYPE_CODEVIEW, pe::IMAGE_DEBUG_TYPE_REPRO]
+    );
+    assert!(file.is_reproducible_build().unwrap());
+
+    let buffer = build(&[pe::IMAGE_DEBUG_TYPE_CODEVIEW]);
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(types(&file), [pe::IMAGE_DEBUG_TYPE_CODEVIEW]);
+    assert!(!file.is_reproducible_build().unwrap());
+
+    let mut buffer = Vec::new();
+    let mut writer = write::pe::Writer::new(true, 0x1000, 0x200, &mut buffer);
+    writer.reserve_dos_header();
+    writer.reserve_nt_headers(16);
+    writer.reserve_section_headers(0);
+    writer.write_empty_dos_header().unwrap();
+    writer.write_nt_headers(nt_headers());
+    writer.write_section_headers();
+
+    let file = PeFile64::parse(&*buffer).unwrap();
+    assert_eq!(file.timestamp(), 0);
+    assert!(file.debug_directories().unwrap().is_empty());
+    assert!(!file.is_reproducible_build().unwrap());
+}
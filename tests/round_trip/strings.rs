@@ -0,0 +1,36 @@
+use object::read::{Object, ObjectSection, SectionKindMask, StringEncoding};
+use object::{read, write};
+use object::{Architecture, BinaryFormat, Endianness};
+
+#[test]
+fn extract_strings() {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+
+    let text = object.section_id(write::StandardSection::Text);
+    object.append_section_data(text, b"\x90\x90abc\x90", 1);
+    let rodata = object.section_id(write::StandardSection::ReadOnlyData);
+    object.append_section_data(rodata, b"\x01hello world\0ab\x01x\0y\0z\0w\0\0\0", 1);
+
+    let bytes = object.write().unwrap();
+    let object = read::File::parse(&*bytes).unwrap();
+    let rodata = object.section_by_name(".rodata").unwrap();
+
+    let strings = object
+        .extract_strings(4, SectionKindMask::READ_ONLY_DATA)
+        .collect::<Vec<_>>();
+    assert_eq!(strings.len(), 2);
+    assert_eq!(strings[0].data, b"hello world");
+    assert_eq!(strings[0].encoding, StringEncoding::Ascii);
+    assert_eq!(strings[0].section, rodata.index());
+    assert_eq!(strings[0].offset, 1);
+    assert_eq!(strings[1].data, b"x\0y\0z\0w\0");
+    assert_eq!(strings[1].encoding, StringEncoding::Utf16Le);
+    assert_eq!(strings[1].offset, 16);
+
+    let strings = object
+        .extract_strings(3, SectionKindMask::TEXT | SectionKindMask::READ_ONLY_DATA)
+        .map(|s| s.data)
+        .collect::<Vec<_>>();
+    assert_eq!(strings, [&b"abc"[..], b"hello world", b"x\0y\0z\0w\0"]);
+}
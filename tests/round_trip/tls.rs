@@ -314,3 +314,61 @@ fn macho_x86_64_tls() {
     );
     assert_eq!(relocation.addend(), 0);
 }
+
+#[test]
+fn tls_symbols() {
+    for &format in &[BinaryFormat::Coff, BinaryFormat::Elf, BinaryFormat::MachO] {
+        let mut object = write::Object::new(format, Architecture::X86_64, Endianness::Little);
+
+        let section = object.section_id(write::StandardSection::Tls);
+        for (name, size) in &[(&b"tls1"[..], 8), (&b"tls2"[..], 16)] {
+            let symbol = object.add_symbol(write::Symbol {
+                name: name.to_vec(),
+                value: 0,
+                size: 0,
+                kind: SymbolKind::Tls,
+                scope: SymbolScope::Linkage,
+                weak: false,
+                section: write::SymbolSection::Undefined,
+                flags: SymbolFlags::None,
+            });
+            object.add_symbol_data(symbol, section, &vec![1; *size], 8);
+        }
+        let text = object.section_id(write::StandardSection::Text);
+        let func = object.add_symbol(write::Symbol {
+            name: b"func".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: write::SymbolSection::Undefined,
+            flags: SymbolFlags::None,
+        });
+        object.add_symbol_data(func, text, &[0xc3], 1);
+
+        let bytes = object.write().unwrap();
+        let object = read::File::parse(&*bytes).unwrap();
+        let tls = object.tls_symbols().unwrap();
+        let names = tls.iter().map(|symbol| symbol.name()).collect::<Vec<_>>();
+        match format {
+            BinaryFormat::MachO => {
+                // Only the descriptors in `__thread_vars`, which have a relocated offset.
+                assert_eq!(names, [&b"_tls1"[..], &b"_tls2"[..]]);
+                assert!(tls.iter().all(|symbol| symbol.tls_offset() == 0));
+            }
+            _ => {
+                assert_eq!(names, [&b"tls1"[..], &b"tls2"[..]]);
+                let offsets = tls
+                    .iter()
+                    .map(|symbol| symbol.tls_offset())
+                    .collect::<Vec<_>>();
+                assert_eq!(offsets, [0, 8]);
+            }
+        }
+        for symbol in &tls {
+            let name = object.symbol_by_index(symbol.index()).unwrap().name_bytes();
+            assert_eq!(name.unwrap(), symbol.name());
+        }
+    }
+}